@@ -27,6 +27,19 @@ from utils.app_paths import get_bundle_dir, is_frozen
 logger = logging.getLogger(__name__)
 
 
+def is_capability_disabled(capability: str) -> bool:
+    """
+    能力是否被用户在桌面端关闭
+
+    NODE_DISABLED_CAPABILITIES 由桌面端同步，逗号分隔，
+    条目可以是完整能力名（camera.snap）或分组前缀（camera）
+    """
+    disabled = [
+        d.strip() for d in os.getenv("NODE_DISABLED_CAPABILITIES", "").split(",") if d.strip()
+    ]
+    return any(capability == d or capability.startswith(f"{d}.") for d in disabled)
+
+
 class LocalNodeBase(ABC):
     """
     本地节点基类
@@ -121,10 +134,11 @@ class LocalNodeBase(ABC):
         """获取节点信息"""
         return NodeInfo(
             node_id=self.node_id,
-            display_name=self.display_name,
+            # 桌面端设置的设备名称（如 "Work MacBook"）优先
+            display_name=os.getenv("NODE_DISPLAY_NAME") or self.display_name,
             platform=self.platform,
             status=NodeStatus.ONLINE if self._initialized else NodeStatus.UNKNOWN,
-            capabilities=self.capabilities,
+            capabilities=[c for c in self.capabilities if not is_capability_disabled(c)],
             last_seen=datetime.now(),
         )
 
@@ -148,6 +162,9 @@ class LocalNodeBase(ABC):
         try:
             logger.debug(f"处理节点调用: command={command}, params={params}")
 
+            if is_capability_disabled(command):
+                raise PermissionError(f"能力已被用户关闭: {command}")
+
             # 路由到具体处理器
             if command == NodeCommand.SYSTEM_RUN.value:
                 result = await self._handle_system_run(params)
@@ -82,10 +82,13 @@ class NodeInfo:
     status: NodeStatus = NodeStatus.UNKNOWN
     capabilities: List[str] = field(default_factory=list)
     last_seen: Optional[datetime] = None
+    # 稳定的机器标识（客户端加盐哈希），数据目录重建后 node_id 会变化，按此去重
+    machine_id: Optional[str] = None
 
     def to_dict(self) -> Dict[str, Any]:
         return {
             "node_id": self.node_id,
+            "machine_id": self.machine_id,
             "display_name": self.display_name,
             "platform": self.platform,
             "status": self.status.value,
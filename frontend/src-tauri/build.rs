@@ -1,3 +1,56 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 构建元数据（git 提交、构建时间），供 app_info::get_app_info 读取
+fn emit_build_metadata() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=XIAODAZI_GIT_COMMIT={}", commit);
+
+    // 支持 SOURCE_DATE_EPOCH，保证可复现构建
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+    println!("cargo:rustc-env=XIAODAZI_BUILD_TIMESTAMP={}", timestamp);
+
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+    println!("cargo:rerun-if-changed=../../.git/refs/heads");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
+
+/// 发布构建要求 tools.lock.json 中的每个构建都已固定 SHA-256（含压缩包内可执行文件的 executable_sha256）
+///
+/// 未固定校验和的构建不会被下载（见 tools.rs），发布前需运行 scripts/update_tools_lock.py。
+fn check_tools_lock() {
+    println!("cargo:rerun-if-changed=tools.lock.json");
+    if std::env::var("PROFILE").as_deref() != Ok("release") {
+        return;
+    }
+    let lock = std::fs::read_to_string("tools.lock.json").unwrap_or_default();
+    // 同时匹配 "sha256" 与 "executable_sha256"
+    let missing = lock.matches("sha256\": \"\"").count();
+    if missing > 0 {
+        panic!(
+            "tools.lock.json has {} empty checksum(s); \
+             run `python scripts/update_tools_lock.py` and commit the result",
+            missing
+        );
+    }
+}
+
 fn main() {
+    emit_build_metadata();
+    check_tools_lock();
     tauri_build::build()
 }
@@ -0,0 +1,41 @@
+// ============================================================================
+// "关于"窗口
+// ============================================================================
+//
+// 独立的小窗口（前端路由 /about），显示 get_app_info 的版本与构建信息，
+// 提供复制版本信息、导出诊断信息（见 diagnostics.rs）的按钮。
+// 从 macOS 应用菜单与托盘菜单打开；已打开时直接聚焦。
+
+use tauri::Manager;
+
+/// 窗口标签
+pub const ABOUT_WINDOW_LABEL: &str = "about";
+
+/// 打开"关于"窗口
+pub fn show(app: &tauri::AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(ABOUT_WINDOW_LABEL) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+    tauri::WebviewWindowBuilder::new(
+        app,
+        ABOUT_WINDOW_LABEL,
+        tauri::WebviewUrl::App("about".into()),
+    )
+    .title(crate::i18n::t("menu.about"))
+    .inner_size(380.0, 440.0)
+    .resizable(false)
+    .minimizable(false)
+    .maximizable(false)
+    .center()
+    .build()
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// 打开"关于"窗口
+#[tauri::command]
+pub async fn show_about(app: tauri::AppHandle) -> Result<(), String> {
+    show(&app)
+}
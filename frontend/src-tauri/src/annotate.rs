@@ -0,0 +1,243 @@
+// ============================================================================
+// 截图标注：发送给 Agent 前裁剪、遮盖敏感区域、画箭头
+// ============================================================================
+//
+// annotate_image 依次执行 ops，结果写入临时 PNG 并以分块传输返回（见 transfers.rs）：
+// - crop：裁剪，之后的操作坐标相对裁剪后的图片
+// - redact：遮盖矩形区域，pixelate（大块马赛克，默认）或 fill（纯黑）
+// - arrow：从 from 指向 to 的箭头
+// 坐标单位为图片像素。来源为 capture_screen 的传输时，原始截图随之关闭并删除，
+// 未遮盖的截图不会离开截图流程。
+
+use crate::transfers::{self, TransferInfo};
+use crate::{debug_log, permissions, policy};
+use image::{Rgba, RgbaImage};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// 默认箭头颜色与线宽
+const ARROW_COLOR: Rgba<u8> = Rgba([0xE5, 0x39, 0x35, 0xFF]);
+const ARROW_WIDTH: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactMode {
+    #[default]
+    Pixelate,
+    Fill,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnnotateOp {
+    Crop {
+        #[serde(flatten)]
+        rect: Rect,
+    },
+    Redact {
+        #[serde(flatten)]
+        rect: Rect,
+        #[serde(default)]
+        mode: RedactMode,
+    },
+    Arrow {
+        from: (f32, f32),
+        to: (f32, f32),
+        /// "#RRGGBB"
+        color: Option<String>,
+        width: Option<f32>,
+    },
+}
+
+/// 把矩形限制在图片范围内，超出范围或为空时返回 None
+fn clamp(img: &RgbaImage, rect: Rect) -> Option<Rect> {
+    let x = rect.x.min(img.width());
+    let y = rect.y.min(img.height());
+    let width = rect.width.min(img.width() - x);
+    let height = rect.height.min(img.height() - y);
+    (width > 0 && height > 0).then_some(Rect {
+        x,
+        y,
+        width,
+        height,
+    })
+}
+
+fn parse_color(value: &str) -> Result<Rgba<u8>, String> {
+    let hex = value.trim_start_matches('#');
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .ok_or_else(|| format!("Invalid color: {}", value))
+    };
+    if hex.len() != 6 {
+        return Err(format!("Invalid color: {}", value));
+    }
+    Ok(Rgba([channel(0)?, channel(2)?, channel(4)?, 0xFF]))
+}
+
+/// 马赛克：块足够大，遮盖后无法辨认文字
+fn pixelate(img: &mut RgbaImage, rect: Rect) {
+    let block = (rect.width.min(rect.height) / 4).max(12);
+    for by in (rect.y..rect.y + rect.height).step_by(block as usize) {
+        for bx in (rect.x..rect.x + rect.width).step_by(block as usize) {
+            let x_end = (bx + block).min(rect.x + rect.width);
+            let y_end = (by + block).min(rect.y + rect.height);
+            let mut sum = [0u64; 4];
+            for y in by..y_end {
+                for x in bx..x_end {
+                    for (s, c) in sum.iter_mut().zip(img.get_pixel(x, y).0) {
+                        *s += c as u64;
+                    }
+                }
+            }
+            let count = ((x_end - bx) * (y_end - by)) as u64;
+            let average = Rgba(sum.map(|s| (s / count) as u8));
+            for y in by..y_end {
+                for x in bx..x_end {
+                    img.put_pixel(x, y, average);
+                }
+            }
+        }
+    }
+}
+
+fn fill(img: &mut RgbaImage, rect: Rect, color: Rgba<u8>) {
+    for y in rect.y..rect.y + rect.height {
+        for x in rect.x..rect.x + rect.width {
+            img.put_pixel(x, y, color);
+        }
+    }
+}
+
+/// 画一条粗线段（到线段距离不超过线宽一半的像素）
+fn draw_segment(
+    img: &mut RgbaImage,
+    from: (f32, f32),
+    to: (f32, f32),
+    width: f32,
+    color: Rgba<u8>,
+) {
+    let half = width / 2.0;
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length_sq = (dx * dx + dy * dy).max(f32::EPSILON);
+    let min_x = (from.0.min(to.0) - half).floor().max(0.0) as u32;
+    let min_y = (from.1.min(to.1) - half).floor().max(0.0) as u32;
+    let max_x = ((from.0.max(to.0) + half).ceil().max(0.0) as u32).min(img.width());
+    let max_y = ((from.1.max(to.1) + half).ceil().max(0.0) as u32).min(img.height());
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let t = (((px - from.0) * dx + (py - from.1) * dy) / length_sq).clamp(0.0, 1.0);
+            let (cx, cy) = (from.0 + t * dx, from.1 + t * dy);
+            if (px - cx).powi(2) + (py - cy).powi(2) <= half * half {
+                img.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+fn draw_arrow(img: &mut RgbaImage, from: (f32, f32), to: (f32, f32), width: f32, color: Rgba<u8>) {
+    draw_segment(img, from, to, width, color);
+    // 箭头两翼与主干成 30°
+    let angle = (from.1 - to.1).atan2(from.0 - to.0);
+    let head = (width * 4.0).max(16.0);
+    for side in [-1.0f32, 1.0] {
+        let wing = angle + side * std::f32::consts::FRAC_PI_6;
+        let end = (to.0 + head * wing.cos(), to.1 + head * wing.sin());
+        draw_segment(img, to, end, width, color);
+    }
+}
+
+fn apply(mut img: RgbaImage, ops: &[AnnotateOp]) -> Result<RgbaImage, String> {
+    for op in ops {
+        match op {
+            AnnotateOp::Crop { rect } => {
+                let rect = clamp(&img, *rect).ok_or("Crop area is outside the image")?;
+                img = image::imageops::crop_imm(&img, rect.x, rect.y, rect.width, rect.height)
+                    .to_image();
+            }
+            AnnotateOp::Redact { rect, mode } => {
+                let Some(rect) = clamp(&img, *rect) else {
+                    continue;
+                };
+                match mode {
+                    RedactMode::Pixelate => pixelate(&mut img, rect),
+                    RedactMode::Fill => fill(&mut img, rect, Rgba([0, 0, 0, 0xFF])),
+                }
+            }
+            AnnotateOp::Arrow {
+                from,
+                to,
+                color,
+                width,
+            } => {
+                let color = color
+                    .as_deref()
+                    .map(parse_color)
+                    .transpose()?
+                    .unwrap_or(ARROW_COLOR);
+                draw_arrow(&mut img, *from, *to, width.unwrap_or(ARROW_WIDTH), color);
+            }
+        }
+    }
+    Ok(img)
+}
+
+fn annotate_file(source: &Path, target: &Path, ops: &[AnnotateOp]) -> Result<(), String> {
+    let img = image::open(source)
+        .map_err(|e| format!("无法读取图片: {}", e))?
+        .into_rgba8();
+    apply(img, ops)?
+        .save_with_format(target, image::ImageFormat::Png)
+        .map_err(|e| format!("保存图片失败: {}", e))
+}
+
+/// 标注图片，结果以临时文件分块读取（关闭传输后删除）
+///
+/// 来源二选一：path（本地文件，需要 fs.read）或 transfer_id（capture_screen 返回的传输，
+/// 标注完成后原传输关闭）
+#[tauri::command]
+pub async fn annotate_image(
+    app: tauri::AppHandle,
+    path: Option<String>,
+    transfer_id: Option<String>,
+    ops: Vec<AnnotateOp>,
+) -> Result<TransferInfo, String> {
+    let source = match (&path, &transfer_id) {
+        (Some(path), None) => {
+            permissions::check_capability(&app, "fs.read").map_err(|e| e.to_string())?;
+            let path = PathBuf::from(path);
+            policy::check_path(&path)?;
+            path
+        }
+        (None, Some(id)) => transfers::path_of(&app, id)?,
+        _ => return Err("Exactly one of path and transfer_id is required".to_string()),
+    };
+
+    let target =
+        std::env::temp_dir().join(format!("xiaodazi-annotated-{}.png", uuid::Uuid::new_v4()));
+    let output = target.clone();
+    let result =
+        tauri::async_runtime::spawn_blocking(move || annotate_file(&source, &output, &ops))
+            .await
+            .map_err(|e| e.to_string())?;
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&target);
+        return Err(e);
+    }
+
+    if let Some(id) = &transfer_id {
+        transfers::close(&app, id)?;
+    }
+    debug_log(&format!("[annotate] 已标注图片: {}", target.display()));
+    transfers::register(&app, target, true)
+}
@@ -0,0 +1,70 @@
+// ============================================================================
+// 应用版本与构建信息
+// ============================================================================
+//
+// 版本号、git 提交、构建时间（由 build.rs 注入）、Tauri / WebView 版本以及后端
+// 运行模式，供"关于"对话框与诊断信息使用。其它模块统一使用 APP_VERSION。
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+/// 应用版本（Cargo.toml）
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 构建时的 git 提交（非 git 工作区构建时为空）
+const GIT_COMMIT: &str = env!("XIAODAZI_GIT_COMMIT");
+
+/// 构建时间（Unix 秒）
+const BUILD_TIMESTAMP: &str = env!("XIAODAZI_BUILD_TIMESTAMP");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppInfo {
+    pub name: String,
+    pub version: String,
+    pub git_commit: Option<String>,
+    /// 构建时间（RFC 3339）
+    pub build_date: Option<String>,
+    /// debug / release
+    pub build_profile: String,
+    pub tauri_version: String,
+    /// 系统 WebView 版本（WebKit / WebView2 / WebKitGTK）
+    pub webview_version: Option<String>,
+    pub os: String,
+    pub os_version: Option<String>,
+    pub arch: String,
+    /// 后端运行模式：sidecar（打包模式）/ dev（连接开发中的后端）
+    pub backend_mode: String,
+}
+
+fn build_date() -> Option<String> {
+    let secs = BUILD_TIMESTAMP.parse::<i64>().ok().filter(|s| *s > 0)?;
+    chrono::DateTime::from_timestamp(secs, 0).map(|t| t.to_rfc3339())
+}
+
+pub fn collect(app: &tauri::AppHandle) -> AppInfo {
+    let is_sidecar = app.state::<crate::BackendState>().info().is_sidecar;
+    AppInfo {
+        name: app.package_info().name.clone(),
+        version: APP_VERSION.to_string(),
+        git_commit: (!GIT_COMMIT.is_empty()).then(|| GIT_COMMIT.to_string()),
+        build_date: build_date(),
+        build_profile: if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        }
+        .to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+        webview_version: tauri::webview_version().ok(),
+        os: std::env::consts::OS.to_string(),
+        os_version: sysinfo::System::long_os_version(),
+        arch: std::env::consts::ARCH.to_string(),
+        backend_mode: if is_sidecar { "sidecar" } else { "dev" }.to_string(),
+    }
+}
+
+/// 获取应用版本与构建信息
+#[tauri::command]
+pub async fn get_app_info(app: tauri::AppHandle) -> Result<AppInfo, String> {
+    Ok(collect(&app))
+}
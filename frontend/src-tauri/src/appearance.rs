@@ -0,0 +1,514 @@
+// ============================================================================
+// 系统外观：深色/浅色主题、强调色与辅助功能偏好
+// ============================================================================
+//
+// get_system_appearance 返回前端无法直接获取的系统设置：强调色、减弱动态效果、
+// 降低透明度、系统文字缩放与屏幕阅读器是否开启。有变化时发出 `appearance-changed` 事件；
+// 屏幕阅读器开关另外发出 `screen-reader-changed` 事件，通知子系统据此改为朗读（见 notifications.rs）。
+// 变化由一个常驻的监听进程报告（不反复启动脚本），监听进程退出后延迟重启；
+// 另外每 APPEARANCE_FALLBACK_POLL 完整读取一次作为兜底。
+// - macOS: NSColor.controlAccentColor 与 NSWorkspace 的辅助功能属性（osascript JXA），
+//   系统没有全局文字缩放，font_scale 固定为 1.0；监听脚本保持运行循环以接收系统颜色变化
+// - Windows: 注册表（DWM AccentColor、EnableTransparency、MinAnimate、TextScaleFactor）
+//   与 SystemParametersInfo(SPI_GETSCREENREADER)；监听脚本只编译一次 Add-Type，
+//   收到 UserPreferenceChanged（WM_SETTINGCHANGE）时重新读取
+// - Linux: GNOME gsettings（accent-color、enable-animations、text-scaling-factor、
+//   screen-reader-enabled），通过 gsettings monitor 监听
+// 托盘图标跟随任务栏/菜单栏的颜色而不是应用窗口主题（Windows 的任务栏可以与应用主题不同，
+// 且窗口不存在时也需要主题），因此同时读取 taskbar_dark，变化时刷新托盘图标（见 health.rs）：
+// macOS 菜单栏跟随 AppleInterfaceStyle，Windows 为 SystemUsesLightTheme，
+// Linux 上 GNOME Shell 的顶栏始终为深色，其他桌面按 color-scheme / GTK 主题名判断。
+// 监听脚本在主程序退出后自行结束（检查 XIAODAZI_PARENT_PID），正常退出时由 stop_appearance_monitor 结束。
+
+use crate::debug_log;
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+use std::process::{Child, Command as SysCommand, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// 兜底的完整读取间隔（变化通常由监听进程报告）
+const APPEARANCE_FALLBACK_POLL: Duration = Duration::from_secs(600);
+
+/// 监听进程退出后的重启延迟
+const WATCHER_RESTART_DELAY: Duration = Duration::from_secs(60);
+
+/// 最近一次检测到的屏幕阅读器状态
+static SCREEN_READER: AtomicBool = AtomicBool::new(false);
+
+/// 最近一次读取的系统外观
+static LAST: Mutex<Option<SystemAppearance>> = Mutex::new(None);
+
+/// 最近一次读取的任务栏/菜单栏是否为深色（读取失败时为 None）
+static TASKBAR_DARK: Mutex<Option<bool>> = Mutex::new(None);
+
+/// 正在运行的监听进程
+static WATCHERS: Mutex<Vec<Child>> = Mutex::new(Vec::new());
+
+/// 应用退出后不再重启监听进程
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemAppearance {
+    /// "light" / "dark"
+    pub theme: String,
+    /// 系统强调色（"#RRGGBB"），无法获取时为 None
+    pub accent_color: Option<String>,
+    pub reduce_motion: bool,
+    pub reduce_transparency: bool,
+    /// 系统文字缩放比例（1.0 为默认大小）
+    pub font_scale: f64,
+    /// 屏幕阅读器（VoiceOver / 讲述人等）已开启
+    pub screen_reader: bool,
+}
+
+/// 平台读取的辅助功能偏好（不含主题）
+#[derive(Debug, Clone, Deserialize)]
+struct Preferences {
+    accent_color: Option<String>,
+    reduce_motion: bool,
+    reduce_transparency: bool,
+    font_scale: f64,
+    screen_reader: bool,
+    /// 任务栏/菜单栏为深色（无法判断时为 None）
+    #[serde(default)]
+    taskbar_dark: Option<bool>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            accent_color: None,
+            reduce_motion: false,
+            reduce_transparency: false,
+            font_scale: 1.0,
+            screen_reader: false,
+            taskbar_dark: None,
+        }
+    }
+}
+
+/// 将 Tauri 主题转换为前端使用的字符串（"light" / "dark"）
+pub fn theme_name(theme: tauri::Theme) -> &'static str {
+    match theme {
+        tauri::Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+/// 系统主题切换时通知前端（由主窗口的 ThemeChanged 事件触发）
+pub fn on_theme_changed(app: &tauri::AppHandle, theme: tauri::Theme) {
+    debug_log(&format!("[appearance] 系统主题切换: {}", theme_name(theme)));
+    let _ = app.emit("theme-changed", theme_name(theme));
+    crate::health::refresh_tray_icon(app);
+    let updated = LAST
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .map(|last| SystemAppearance {
+            theme: theme_name(theme).to_string(),
+            ..last.clone()
+        });
+    if let Some(updated) = updated {
+        publish(app, updated);
+    }
+}
+
+#[cfg(target_os = "macos")]
+const PREFERENCES_SCRIPT: &str = r#"
+ObjC.import('AppKit');
+function readPreferences() {
+  const ws = $.NSWorkspace.sharedWorkspace;
+  const color = $.NSColor.controlAccentColor.colorUsingColorSpace($.NSColorSpace.sRGBColorSpace);
+  const hex = (v) => Math.round(v * 255).toString(16).padStart(2, '0');
+  return JSON.stringify({
+    accent_color: color.isNil() ? null : '#' + hex(color.redComponent) + hex(color.greenComponent) + hex(color.blueComponent),
+    reduce_motion: ws.accessibilityDisplayShouldReduceMotion,
+    reduce_transparency: ws.accessibilityDisplayShouldReduceTransparency,
+    font_scale: 1.0,
+    screen_reader: ws.voiceOverEnabled,
+    taskbar_dark: $.NSUserDefaults.standardUserDefaults.stringForKey('AppleInterfaceStyle').js === 'Dark'
+  });
+}
+function run() {
+  const env = $.NSProcessInfo.processInfo.environment;
+  if (env.objectForKey('XIAODAZI_WATCH').isNil()) return readPreferences();
+  // 常驻监听：保持运行循环以接收系统颜色与辅助功能变化，变化时输出一行 JSON
+  const parent = parseInt(env.objectForKey('XIAODAZI_PARENT_PID').js, 10);
+  const out = $.NSFileHandle.fileHandleWithStandardOutput;
+  const loop = $.NSRunLoop.currentRunLoop;
+  loop.addPortForMode($.NSMachPort.port, $.NSDefaultRunLoopMode);
+  let last = '';
+  while (!$.NSRunningApplication.runningApplicationWithProcessIdentifier(parent).isNil()) {
+    const json = readPreferences();
+    if (json !== last) {
+      out.writeData($(json + '\n').dataUsingEncoding($.NSUTF8StringEncoding));
+      last = json;
+    }
+    loop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(2));
+  }
+}
+"#;
+
+#[cfg(target_os = "windows")]
+const PREFERENCES_SCRIPT: &str = r#"
+function Get-Value($path, $name) { try { Get-ItemPropertyValue -Path $path -Name $name -ErrorAction Stop } catch { $null } }
+Add-Type -Namespace A -Name S -MemberDefinition '[DllImport("user32.dll")] public static extern bool SystemParametersInfo(int action, int param, ref bool value, int flags);'
+function Read-Preferences {
+  $accent = Get-Value 'HKCU:\Software\Microsoft\Windows\DWM' 'AccentColor'
+  $color = $null
+  if ($accent -ne $null) { $c = [uint32]$accent; $color = '#{0:x2}{1:x2}{2:x2}' -f ($c -band 0xff), (($c -shr 8) -band 0xff), (($c -shr 16) -band 0xff) }
+  $scale = Get-Value 'HKCU:\Software\Microsoft\Accessibility' 'TextScaleFactor'
+  $reader = $false; [A.S]::SystemParametersInfo(0x46, 0, [ref]$reader, 0) | Out-Null
+  ConvertTo-Json -Compress @{
+    accent_color = $color
+    reduce_motion = ((Get-Value 'HKCU:\Control Panel\Desktop\WindowMetrics' 'MinAnimate') -eq '0')
+    reduce_transparency = ((Get-Value 'HKCU:\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize' 'EnableTransparency') -eq 0)
+    font_scale = $(if ($scale) { [double]$scale / 100 } else { 1.0 })
+    screen_reader = $reader
+    taskbar_dark = ((Get-Value 'HKCU:\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize' 'SystemUsesLightTheme') -ne 1)
+  }
+}
+if (-not $env:XIAODAZI_WATCH) { Read-Preferences; exit }
+$parent = [int]$env:XIAODAZI_PARENT_PID
+Register-ObjectEvent -InputObject ([Microsoft.Win32.SystemEvents]) -EventName UserPreferenceChanged -SourceIdentifier Preferences | Out-Null
+$last = ''
+while (Get-Process -Id $parent -ErrorAction SilentlyContinue) {
+  $json = Read-Preferences
+  if ($json -ne $last) { [Console]::Out.WriteLine($json); [Console]::Out.Flush(); $last = $json }
+  if (Wait-Event -SourceIdentifier Preferences -Timeout 30) { Remove-Event -SourceIdentifier Preferences }
+}
+"#;
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn read_preferences() -> Result<Preferences, String> {
+    #[cfg(target_os = "macos")]
+    let output = SysCommand::new("osascript")
+        .args(["-l", "JavaScript", "-e", PREFERENCES_SCRIPT])
+        .output();
+    #[cfg(target_os = "windows")]
+    let output = SysCommand::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            PREFERENCES_SCRIPT,
+        ])
+        .output();
+
+    let output = output.map_err(|e| format!("读取系统外观失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "读取系统外观失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("解析系统外观失败: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+fn read_preferences() -> Result<Preferences, String> {
+    let get_in = |schema: &str, key: &str| -> Option<String> {
+        let output = SysCommand::new("gsettings")
+            .args(["get", schema, key])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())?;
+        Some(
+            String::from_utf8_lossy(&output.stdout)
+                .trim()
+                .trim_matches('\'')
+                .to_string(),
+        )
+    };
+    let get = |key: &str| get_in("org.gnome.desktop.interface", key);
+    // GNOME 47+ 的强调色为颜色名称，按 libadwaita 的取值转换
+    let accent_color = get("accent-color").and_then(|name| {
+        let hex = match name.as_str() {
+            "blue" => "#3584e4",
+            "teal" => "#2190a4",
+            "green" => "#3a944a",
+            "yellow" => "#c88800",
+            "orange" => "#ed5b00",
+            "red" => "#e62d42",
+            "pink" => "#d56199",
+            "purple" => "#9141ac",
+            "slate" => "#6f8396",
+            _ => return None,
+        };
+        Some(hex.to_string())
+    });
+    // GNOME Shell 的顶栏不随配色方案变化，始终为深色
+    let gnome_shell = std::env::var("XDG_CURRENT_DESKTOP")
+        .is_ok_and(|desktop| desktop.to_ascii_uppercase().contains("GNOME"));
+    let taskbar_dark = if gnome_shell {
+        Some(true)
+    } else {
+        match get("color-scheme").as_deref() {
+            Some("prefer-dark") => Some(true),
+            Some("prefer-light") => Some(false),
+            _ => get("gtk-theme").map(|theme| theme.to_ascii_lowercase().contains("dark")),
+        }
+    };
+    Ok(Preferences {
+        accent_color,
+        reduce_motion: get("enable-animations").as_deref() == Some("false"),
+        reduce_transparency: false,
+        font_scale: get("text-scaling-factor")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1.0),
+        screen_reader: get_in(
+            "org.gnome.desktop.a11y.applications",
+            "screen-reader-enabled",
+        )
+        .as_deref()
+            == Some("true"),
+        taskbar_dark,
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn read_preferences() -> Result<Preferences, String> {
+    Ok(Preferences::default())
+}
+
+/// 常驻监听进程的命令行（每行输出表示外观可能变化；macOS / Windows 直接输出偏好 JSON）
+#[cfg(target_os = "macos")]
+const WATCHER_COMMANDS: &[&[&str]] =
+    &[&["osascript", "-l", "JavaScript", "-e", PREFERENCES_SCRIPT]];
+#[cfg(target_os = "windows")]
+const WATCHER_COMMANDS: &[&[&str]] = &[&[
+    "powershell",
+    "-NoProfile",
+    "-NonInteractive",
+    "-Command",
+    PREFERENCES_SCRIPT,
+]];
+#[cfg(target_os = "linux")]
+const WATCHER_COMMANDS: &[&[&str]] = &[
+    &["gsettings", "monitor", "org.gnome.desktop.interface"],
+    &[
+        "gsettings",
+        "monitor",
+        "org.gnome.desktop.a11y.applications",
+    ],
+];
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+const WATCHER_COMMANDS: &[&[&str]] = &[];
+
+fn main_window_theme(app: &tauri::AppHandle) -> Result<tauri::Theme, String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Main window not found")?;
+    window.theme().map_err(|e| e.to_string())
+}
+
+fn appearance(theme: tauri::Theme, preferences: Preferences) -> SystemAppearance {
+    SystemAppearance {
+        theme: theme_name(theme).to_string(),
+        accent_color: preferences.accent_color,
+        reduce_motion: preferences.reduce_motion,
+        reduce_transparency: preferences.reduce_transparency,
+        font_scale: preferences.font_scale,
+        screen_reader: preferences.screen_reader,
+    }
+}
+
+fn current_appearance(app: &tauri::AppHandle) -> Result<SystemAppearance, String> {
+    let preferences = read_preferences().unwrap_or_else(|e| {
+        debug_log(&format!("[appearance] {}", e));
+        Preferences::default()
+    });
+    update_taskbar(app, preferences.taskbar_dark);
+    let theme = main_window_theme(app)?;
+    Ok(appearance(theme, preferences))
+}
+
+/// 记录任务栏颜色，有变化时刷新托盘图标（读取失败时保留上次的结果）
+fn update_taskbar(app: &tauri::AppHandle, dark: Option<bool>) {
+    let Some(dark) = dark else {
+        return;
+    };
+    let previous = TASKBAR_DARK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .replace(dark);
+    if previous != Some(dark) {
+        debug_log(&format!(
+            "[appearance] 任务栏颜色: {}",
+            if dark { "dark" } else { "light" }
+        ));
+        crate::health::refresh_tray_icon(app);
+    }
+}
+
+/// 任务栏/菜单栏的主题（尚未读取到时为 None）
+pub fn taskbar_theme() -> Option<tauri::Theme> {
+    let dark = *TASKBAR_DARK.lock().unwrap_or_else(PoisonError::into_inner);
+    dark.map(|dark| {
+        if dark {
+            tauri::Theme::Dark
+        } else {
+            tauri::Theme::Light
+        }
+    })
+}
+
+/// 记录最新的系统外观，有变化时发出事件（首次读取只记录，不发出 appearance-changed）
+fn publish(app: &tauri::AppHandle, current: SystemAppearance) {
+    if SCREEN_READER.swap(current.screen_reader, Ordering::Relaxed) != current.screen_reader {
+        debug_log(&format!(
+            "[appearance] 屏幕阅读器{}",
+            if current.screen_reader {
+                "已开启"
+            } else {
+                "已关闭"
+            }
+        ));
+        let _ = app.emit("screen-reader-changed", current.screen_reader);
+    }
+    let mut last = LAST.lock().unwrap_or_else(PoisonError::into_inner);
+    if last.as_ref().is_some_and(|last| *last != current) {
+        debug_log(&format!("[appearance] 系统外观变化: {:?}", current));
+        let _ = app.emit("appearance-changed", &current);
+    }
+    *last = Some(current);
+}
+
+/// 运行一个监听进程直到其退出，每行输出触发一次更新
+fn run_watcher(app: &tauri::AppHandle, argv: &[&str]) -> Result<(), String> {
+    let mut child = SysCommand::new(argv[0])
+        .args(&argv[1..])
+        .env("XIAODAZI_WATCH", "1")
+        .env("XIAODAZI_PARENT_PID", std::process::id().to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("启动外观监听失败: {}", e))?;
+    let id = child.id();
+    let stdout = child.stdout.take().ok_or("外观监听没有输出")?;
+    WATCHERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(child);
+
+    for line in std::io::BufReader::new(stdout).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let preferences = match serde_json::from_str::<Preferences>(&line) {
+            Ok(preferences) => preferences,
+            Err(_) => match read_preferences() {
+                Ok(preferences) => preferences,
+                Err(e) => {
+                    debug_log(&format!("[appearance] {}", e));
+                    continue;
+                }
+            },
+        };
+        update_taskbar(app, preferences.taskbar_dark);
+        if let Ok(theme) = main_window_theme(app) {
+            publish(app, appearance(theme, preferences));
+        }
+    }
+
+    let child = {
+        let mut watchers = WATCHERS.lock().unwrap_or_else(PoisonError::into_inner);
+        let index = watchers.iter().position(|c| c.id() == id);
+        index.map(|index| watchers.swap_remove(index))
+    };
+    if let Some(mut child) = child {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    Ok(())
+}
+
+/// 屏幕阅读器是否开启（后台检测的最近结果）
+pub fn screen_reader_active() -> bool {
+    SCREEN_READER.load(Ordering::Relaxed)
+}
+
+/// 监听系统外观，有变化时发出 `appearance-changed` 事件
+pub fn start_appearance_monitor(app: tauri::AppHandle) {
+    for argv in WATCHER_COMMANDS {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            while !STOPPED.load(Ordering::SeqCst) {
+                if let Err(e) = run_watcher(&app, argv) {
+                    debug_log(&format!("[appearance] {}", e));
+                }
+                if STOPPED.load(Ordering::SeqCst) {
+                    break;
+                }
+                debug_log(&format!(
+                    "[appearance] 外观监听已退出，{}s 后重启",
+                    WATCHER_RESTART_DELAY.as_secs()
+                ));
+                std::thread::sleep(WATCHER_RESTART_DELAY);
+            }
+        });
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let handle = app.clone();
+            let current = tauri::async_runtime::spawn_blocking(move || current_appearance(&handle))
+                .await
+                .ok()
+                .and_then(Result::ok);
+            if let Some(current) = current {
+                publish(&app, current);
+            }
+            tokio::time::sleep(APPEARANCE_FALLBACK_POLL).await;
+        }
+    });
+}
+
+/// 结束外观监听进程（应用退出时调用）
+pub fn stop_appearance_monitor() {
+    STOPPED.store(true, Ordering::SeqCst);
+    let watchers: Vec<Child> = WATCHERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .drain(..)
+        .collect();
+    for mut child in watchers {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// 获取当前系统主题
+#[tauri::command]
+pub async fn get_system_theme(app: tauri::AppHandle) -> Result<String, String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Main window not found")?;
+    let theme = window.theme().map_err(|e| e.to_string())?;
+    Ok(theme_name(theme).to_string())
+}
+
+/// 获取系统外观（主题、强调色、辅助功能偏好与文字缩放）
+#[tauri::command]
+pub async fn get_system_appearance(app: tauri::AppHandle) -> Result<SystemAppearance, String> {
+    if let Some(last) = LAST.lock().unwrap_or_else(PoisonError::into_inner).clone() {
+        return Ok(last);
+    }
+    tauri::async_runtime::spawn_blocking(move || current_appearance(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 屏幕阅读器是否开启
+#[tauri::command]
+pub async fn is_screen_reader_active() -> Result<bool, String> {
+    let preferences = tauri::async_runtime::spawn_blocking(read_preferences)
+        .await
+        .map_err(|e| e.to_string())??;
+    SCREEN_READER.store(preferences.screen_reader, Ordering::Relaxed);
+    Ok(preferences.screen_reader)
+}
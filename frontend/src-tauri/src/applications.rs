@@ -0,0 +1,219 @@
+// ============================================================================
+// 已安装应用：枚举与启动
+// ============================================================================
+//
+// - macOS: 扫描 /Applications、/System/Applications、~/Applications 下的 .app
+// - Windows: 扫描开始菜单中的 .lnk 快捷方式
+// - Linux: 扫描 .desktop 文件
+// 启动时只接受枚举结果中存在的应用，避免被当作任意命令执行入口。
+
+use crate::debug_log;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command as SysCommand;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationEntry {
+    /// 显示名称
+    pub name: String,
+    /// 唯一标识（.app/.lnk/.desktop 文件的完整路径）
+    pub identifier: String,
+}
+
+/// 应用搜索目录
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let home = std::env::var("HOME").ok().map(PathBuf::from);
+
+    #[cfg(target_os = "macos")]
+    {
+        dirs.push(PathBuf::from("/Applications"));
+        dirs.push(PathBuf::from("/Applications/Utilities"));
+        dirs.push(PathBuf::from("/System/Applications"));
+        dirs.push(PathBuf::from("/System/Applications/Utilities"));
+        if let Some(h) = &home {
+            dirs.push(h.join("Applications"));
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = &home;
+        if let Ok(p) = std::env::var("ProgramData") {
+            dirs.push(PathBuf::from(p).join("Microsoft\\Windows\\Start Menu\\Programs"));
+        }
+        if let Ok(p) = std::env::var("APPDATA") {
+            dirs.push(PathBuf::from(p).join("Microsoft\\Windows\\Start Menu\\Programs"));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        dirs.push(PathBuf::from("/usr/share/applications"));
+        dirs.push(PathBuf::from("/usr/local/share/applications"));
+        dirs.push(PathBuf::from("/var/lib/flatpak/exports/share/applications"));
+        if let Some(h) = &home {
+            dirs.push(h.join(".local/share/applications"));
+        }
+    }
+
+    dirs
+}
+
+/// 解析 .desktop 文件，返回 Name（NoDisplay/Hidden 的条目返回 None）
+#[cfg(target_os = "linux")]
+fn parse_desktop_name(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let mut name = None;
+    let mut in_entry = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        if line == "NoDisplay=true" || line == "Hidden=true" {
+            return None;
+        }
+        if name.is_none() {
+            if let Some(n) = line.strip_prefix("Name=") {
+                name = Some(n.to_string());
+            }
+        }
+    }
+    name
+}
+
+/// 根据文件判断是否为应用条目，返回显示名称
+fn application_name(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    let stem = path.file_stem()?.to_string_lossy().to_string();
+
+    #[cfg(target_os = "macos")]
+    {
+        (ext == "app").then_some(stem)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        (ext == "lnk" && !stem.to_lowercase().contains("uninstall")).then_some(stem)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = stem;
+        if ext == "desktop" {
+            parse_desktop_name(path)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (ext, stem);
+        None
+    }
+}
+
+/// 扫描目录（.app 包本身是目录，不再深入；开始菜单需要递归子目录）
+fn scan_dir(dir: &Path, depth: u32, out: &mut Vec<ApplicationEntry>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if let Some(name) = application_name(&path) {
+            out.push(ApplicationEntry {
+                name,
+                identifier: path.to_string_lossy().to_string(),
+            });
+        } else if path.is_dir() && depth < 3 {
+            scan_dir(&path, depth + 1, out);
+        }
+    }
+}
+
+/// 枚举已安装应用（按名称排序、去重）
+pub fn enumerate_applications() -> Vec<ApplicationEntry> {
+    let mut apps = Vec::new();
+    for dir in application_dirs() {
+        scan_dir(&dir, 0, &mut apps);
+    }
+    apps.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    apps.dedup_by(|a, b| a.name.eq_ignore_ascii_case(&b.name));
+    apps
+}
+
+/// 列出已安装应用
+#[tauri::command]
+pub async fn list_applications() -> Result<Vec<ApplicationEntry>, String> {
+    tauri::async_runtime::spawn_blocking(enumerate_applications)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 构造启动命令
+fn launch_command(entry: &ApplicationEntry, args: &[String]) -> Result<SysCommand, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let mut cmd = SysCommand::new("open");
+        cmd.args(["-a", &entry.identifier]);
+        if !args.is_empty() {
+            cmd.arg("--args").args(args);
+        }
+        Ok(cmd)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut cmd = SysCommand::new("cmd");
+        cmd.args(["/C", "start", "", &entry.identifier]).args(args);
+        Ok(cmd)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let id = Path::new(&entry.identifier)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut cmd = SysCommand::new("gtk-launch");
+        cmd.arg(id).args(args);
+        Ok(cmd)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (entry, args);
+        Err("Launching applications not supported on this platform".to_string())
+    }
+}
+
+/// 启动应用
+///
+/// `identifier` 可以是 list_applications 返回的标识，也可以是应用名称（不区分大小写）。
+#[tauri::command]
+pub async fn launch_application(
+    identifier: String,
+    args: Option<Vec<String>>,
+) -> Result<ApplicationEntry, String> {
+    let apps = list_applications().await?;
+    let entry = apps
+        .iter()
+        .find(|a| a.identifier == identifier)
+        .or_else(|| apps.iter().find(|a| a.name.eq_ignore_ascii_case(&identifier)))
+        .cloned()
+        .ok_or_else(|| format!("未找到应用: {}", identifier))?;
+
+    launch_command(&entry, &args.unwrap_or_default())?
+        .spawn()
+        .map_err(|e| format!("启动应用失败: {}", e))?;
+
+    debug_log(&format!("[apps] 启动应用: {} ({})", entry.name, entry.identifier));
+    Ok(entry)
+}
@@ -0,0 +1,347 @@
+// ============================================================================
+// Agent 操作审批队列
+// ============================================================================
+//
+// 有风险的操作执行前调用 request_approval：
+// - 加入待审批队列，发出 `approval-requested` 事件，发送系统通知并请求用户注意（Dock 跳动 / 任务栏闪烁）
+// - 阻塞直到前端调用 approve(id) / deny(id)，或超时（APPROVAL_TIMEOUT_SECS）视为拒绝；
+//   等待期间应用被锁定过时不算超时，解锁后重新发出 `approval-requested` 并重新计时
+// - 结果发出 `approval-resolved` 事件，并写入审计日志
+// 当前需要审批的操作：远程节点执行 Shell 命令、提权运行命令（sudo / pkexec / runas 等）、
+// 截取屏幕、在允许目录外写入文件。允许目录为托管策略的 allowed_paths（未配置时为应用数据目录），
+// 加上已登记的项目目录。
+
+use crate::{
+    audit, badge, debug_log, events, i18n, lock, notifications, policy, projects, settings,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::oneshot;
+
+/// 等待审批的最长时间（秒）
+const APPROVAL_TIMEOUT_SECS: u64 = 120;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub id: String,
+    /// 操作类型（如 "remote.system.run"）
+    pub action: String,
+    /// 展示给用户的说明
+    pub summary: String,
+    pub details: serde_json::Value,
+    pub requested_at: String,
+    pub expires_at: String,
+}
+
+struct PendingApproval {
+    request: ApprovalRequest,
+    reply: oneshot::Sender<bool>,
+}
+
+/// 待审批的请求
+#[derive(Default)]
+pub struct ApprovalState {
+    pending: Mutex<HashMap<String, PendingApproval>>,
+}
+
+fn expires_at(from: chrono::DateTime<chrono::Local>) -> String {
+    (from + chrono::Duration::seconds(APPROVAL_TIMEOUT_SECS as i64)).to_rfc3339()
+}
+
+/// 发出审批请求事件，并通过系统通知与 Dock / 任务栏提醒用户
+fn prompt(app: &tauri::AppHandle, request: &ApprovalRequest) {
+    events::emit(app, "approval-requested", request);
+    notifications::notify(
+        app,
+        &i18n::t("approval.notify.title"),
+        &request.summary,
+        true,
+    );
+    badge::attention_main(app, true);
+}
+
+/// 请求用户审批，批准时返回 Ok，拒绝或超时返回 Err
+pub async fn request_approval(
+    app: &tauri::AppHandle,
+    action: &str,
+    summary: String,
+    details: serde_json::Value,
+) -> Result<(), String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Local::now();
+    let mut request = ApprovalRequest {
+        id: id.clone(),
+        action: action.to_string(),
+        summary: summary.clone(),
+        details: details.clone(),
+        requested_at: now.to_rfc3339(),
+        expires_at: expires_at(now),
+    };
+
+    let (reply, mut decision) = oneshot::channel();
+    {
+        let state = app.state::<ApprovalState>();
+        let mut pending = state.pending.lock().map_err(|e| e.to_string())?;
+        pending.insert(
+            id.clone(),
+            PendingApproval {
+                request: request.clone(),
+                reply,
+            },
+        );
+    }
+    debug_log(&format!("[approvals] 等待审批 {}: {}", id, summary));
+    prompt(app, &request);
+
+    let mut generation = lock::lock_generation(app);
+    let decision = loop {
+        let timeout = Duration::from_secs(APPROVAL_TIMEOUT_SECS);
+        match tokio::time::timeout(timeout, &mut decision).await {
+            Ok(Ok(true)) => break "approved",
+            Ok(Ok(false)) => break "denied",
+            Err(_) if lock::is_locked(app) || lock::lock_generation(app) != generation => {
+                // 锁定期间用户看不到审批，解锁后重新提示并重新计时
+                debug_log(&format!(
+                    "[approvals] {} 在锁定期间到期，解锁后重新提示",
+                    id
+                ));
+                lock::wait_unlocked(app).await;
+                generation = lock::lock_generation(app);
+                request.expires_at = expires_at(chrono::Local::now());
+                if let Ok(mut pending) = app.state::<ApprovalState>().pending.lock() {
+                    if let Some(pending) = pending.get_mut(&id) {
+                        pending.request = request.clone();
+                    }
+                }
+                prompt(app, &request);
+            }
+            _ => break "expired",
+        }
+    };
+    if let Ok(mut pending) = app.state::<ApprovalState>().pending.lock() {
+        pending.remove(&id);
+    }
+
+    debug_log(&format!("[approvals] {} -> {}", id, decision));
+    audit::record(
+        app,
+        "approval.decision",
+        decision == "approved",
+        serde_json::json!({
+            "id": &id,
+            "action": action,
+            "summary": &summary,
+            "details": details,
+            "decision": decision,
+        }),
+    );
+    events::emit(
+        app,
+        "approval-resolved",
+        serde_json::json!({ "id": &id, "decision": decision }),
+    );
+
+    match decision {
+        "approved" => Ok(()),
+        "denied" => Err(format!("Denied by user: {}", summary)),
+        _ => Err(format!("Approval timed out: {}", summary)),
+    }
+}
+
+/// 提权运行的程序（需要审批）
+const ELEVATION_PROGRAMS: &[&str] = &["sudo", "pkexec", "doas", "su", "runas", "gsudo"];
+
+/// 把参数中的命令字符串交给解释器执行的 shell
+const SHELLS: &[&str] = &[
+    "sh",
+    "bash",
+    "zsh",
+    "dash",
+    "fish",
+    "cmd",
+    "powershell",
+    "pwsh",
+];
+
+/// 程序名（去掉目录与 .exe，小写）
+fn program_name(arg: &str) -> String {
+    let name = arg
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(arg)
+        .to_ascii_lowercase();
+    match name.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => name,
+    }
+}
+
+/// 命令是否提权运行：任一参数是提权程序（含 env / nohup 等包装），
+/// 或 shell 执行的命令字符串中出现提权程序（含 PowerShell 的 -Verb RunAs）
+pub fn is_elevated(command: &[String]) -> bool {
+    let is_elevation = |word: &str| ELEVATION_PROGRAMS.contains(&program_name(word).as_str());
+    if command.iter().any(|arg| is_elevation(arg)) {
+        return true;
+    }
+    let Some(program) = command.first() else {
+        return false;
+    };
+    if !SHELLS.contains(&program_name(program).as_str()) {
+        return false;
+    }
+    command[1..].iter().any(|arg| {
+        arg.split(|c: char| c.is_whitespace() || ";|&()`$'\"".contains(c))
+            .any(is_elevation)
+    })
+}
+
+/// 提权运行的命令需要审批（source 为发起方标识，记入审批详情）
+pub async fn approve_elevated(
+    app: &tauri::AppHandle,
+    command: &[String],
+    source: Option<&str>,
+) -> Result<(), String> {
+    if !is_elevated(command) {
+        return Ok(());
+    }
+    request_approval(
+        app,
+        "system.run.elevated",
+        i18n::tf("approval.run_elevated", &[&command.join(" ")]),
+        serde_json::json!({ "command": command, "source": source }),
+    )
+    .await
+}
+
+/// 允许直接写入的目录：托管策略配置了 allowed_paths 时使用策略，否则为应用数据目录；
+/// 另外加上已登记的项目目录（见 projects.rs）
+fn writable_roots(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    let allowed = policy::current().allowed_paths;
+    let mut roots: Vec<PathBuf> = if allowed.is_empty() {
+        vec![settings::app_data_dir()]
+    } else {
+        allowed
+            .iter()
+            .filter_map(|dir| match dir.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir().map(|h| h.join(rest)),
+                None => Some(PathBuf::from(dir)),
+            })
+            .collect()
+    };
+    roots.extend(projects::roots(app));
+    roots
+}
+
+/// 写入路径不在允许目录内时请求审批
+pub async fn approve_write(app: &tauri::AppHandle, action: &str, path: &str) -> Result<(), String> {
+    // 目标可能尚不存在：取最近的已存在上级目录解析符号链接
+    let target = Path::new(path);
+    let resolved = target
+        .ancestors()
+        .find_map(|p| std::fs::canonicalize(p).ok().map(|c| (p, c)))
+        .map(|(existing, canonical)| {
+            canonical.join(target.strip_prefix(existing).unwrap_or(Path::new("")))
+        });
+    let inside = resolved.is_some_and(|resolved| {
+        writable_roots(app)
+            .iter()
+            .any(|root| std::fs::canonicalize(root).is_ok_and(|root| resolved.starts_with(root)))
+    });
+    if inside {
+        return Ok(());
+    }
+    request_approval(
+        app,
+        action,
+        i18n::tf("approval.write_outside", &[&path]),
+        serde_json::json!({ "path": path }),
+    )
+    .await
+}
+
+fn resolve(app: &tauri::AppHandle, id: &str, approved: bool) -> Result<(), String> {
+    let pending = app
+        .state::<ApprovalState>()
+        .pending
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(id)
+        .ok_or_else(|| format!("Approval not found: {}", id))?;
+    let _ = pending.reply.send(approved);
+    Ok(())
+}
+
+/// 列出待审批的请求
+#[tauri::command]
+pub async fn list_pending_approvals(app: tauri::AppHandle) -> Result<Vec<ApprovalRequest>, String> {
+    let state = app.state::<ApprovalState>();
+    let pending = state.pending.lock().map_err(|e| e.to_string())?;
+    let mut requests: Vec<ApprovalRequest> = pending.values().map(|p| p.request.clone()).collect();
+    requests.sort_by(|a, b| a.requested_at.cmp(&b.requested_at));
+    Ok(requests)
+}
+
+/// 批准请求
+#[tauri::command]
+pub async fn approve(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    resolve(&app, &id, true)
+}
+
+/// 拒绝请求
+#[tauri::command]
+pub async fn deny(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    resolve(&app, &id, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_direct_elevation() {
+        assert!(is_elevated(&argv(&["sudo", "rm", "-rf", "/tmp/x"])));
+        assert!(is_elevated(&argv(&["/usr/bin/pkexec", "true"])));
+        assert!(is_elevated(&argv(&["env", "FOO=1", "sudo", "ls"])));
+        assert!(is_elevated(&argv(&[
+            "C:\\Windows\\System32\\runas.exe",
+            "/user:admin",
+            "cmd"
+        ])));
+    }
+
+    #[test]
+    fn detects_elevation_inside_shell_strings() {
+        assert!(is_elevated(&argv(&[
+            "/bin/sh",
+            "-c",
+            "cd /tmp && sudo make install"
+        ])));
+        assert!(is_elevated(&argv(&["bash", "-c", "echo x;doas reboot"])));
+        assert!(is_elevated(&argv(&[
+            "powershell",
+            "-Command",
+            "Start-Process cmd -Verb RunAs",
+        ])));
+    }
+
+    #[test]
+    fn ignores_ordinary_commands() {
+        assert!(!is_elevated(&argv(&["ls", "-la"])));
+        assert!(!is_elevated(&argv(&["/bin/sh", "-c", "echo pseudo-sudo"])));
+        assert!(!is_elevated(&argv(&[
+            "git",
+            "commit",
+            "-m",
+            "mention sudo docs"
+        ])));
+        assert!(!is_elevated(&[]));
+    }
+}
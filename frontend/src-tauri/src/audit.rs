@@ -0,0 +1,624 @@
+// ============================================================================
+// 审计日志：记录 Agent 在本机执行的敏感操作
+// ============================================================================
+//
+// 追加写入数据目录下的 audit.log（JSON Lines，每行一条记录）。
+// 记录之间哈希链接：每条记录包含上一条的哈希，修改、删除或插入任意一条都会使
+// 之后的链条校验失败（verify_audit_log）。启用哈希链之前写入的旧记录不参与校验。
+// 哈希链只能发现中间的改动，截掉末尾若干条后剩下的链仍然完整，因此每次写入后把链末端
+// （链上的记录数与最后一条的哈希）连同 HMAC 保存到 audit.head，密钥为每次安装随机生成的
+// audit.key（仅当前用户可读）；校验时链末端必须与日志一致。
+// 找不到有效的链末端时（升级自旧版本，或 audit.head 被删除 / 篡改）从日志重建，
+// 并写入一条 audit.head_reset 记录，重建本身留在链上。
+// export_history 按时间范围把记录（含 run_command 的执行历史 system.run）导出为 CSV / JSON，
+// 用于合规审查或费用核对。
+
+use crate::store::data_file_path;
+use crate::{approvals, debug_log};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// 审计日志文件名
+pub const AUDIT_FILE: &str = "audit.log";
+
+/// 链末端文件（记录数、最后一条的哈希与 HMAC）
+const AUDIT_HEAD_FILE: &str = "audit.head";
+
+/// 链末端 HMAC 的密钥文件
+const AUDIT_KEY_FILE: &str = "audit.key";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 哈希链第一条记录的 prev_hash
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// 记录时间（RFC 3339）
+    pub timestamp: String,
+    /// 操作类型（如 "process.kill"）
+    pub action: String,
+    /// 是否执行成功（被拒绝/取消的操作也会记录）
+    pub success: bool,
+    pub details: serde_json::Value,
+    /// 上一条记录的哈希（第一条为 GENESIS_HASH；旧记录为空）
+    #[serde(default)]
+    pub prev_hash: String,
+    /// 本条记录的哈希：SHA-256(hash 为空时的 JSON)
+    #[serde(default)]
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditVerification {
+    pub valid: bool,
+    /// 参与校验的记录数
+    pub entries: usize,
+    /// 启用哈希链之前的旧记录数
+    pub legacy_entries: usize,
+    /// 第一条校验失败的记录行号（从 1 开始；链末端不一致时为 None）
+    pub invalid_line: Option<usize>,
+    pub error: Option<String>,
+    /// 是否对照了链末端（尚未生成密钥时为 false）
+    pub anchored: bool,
+}
+
+/// 哈希链末端
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ChainHead {
+    /// 链上的记录数（不含旧记录）
+    entries: usize,
+    /// 最后一条记录的哈希（没有记录时为 GENESIS_HASH）
+    hash: String,
+}
+
+impl ChainHead {
+    fn genesis() -> Self {
+        Self {
+            entries: 0,
+            hash: GENESIS_HASH.to_string(),
+        }
+    }
+}
+
+/// 保存在 audit.head 中的链末端
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignedHead {
+    #[serde(flatten)]
+    head: ChainHead,
+    /// HMAC-SHA256(key, "entries:hash")，十六进制
+    mac: String,
+}
+
+fn head_hmac(key: &[u8], head: &ChainHead) -> HmacSha256 {
+    let mut mac =
+        <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}:{}", head.entries, head.hash).as_bytes());
+    mac
+}
+
+fn sign_head(key: &[u8], head: ChainHead) -> SignedHead {
+    let mac = hex(&head_hmac(key, &head).finalize().into_bytes());
+    SignedHead { head, mac }
+}
+
+/// 校验 HMAC，通过时返回链末端
+fn verified_head(key: &[u8], signed: &SignedHead) -> Option<ChainHead> {
+    let bytes = (0..signed.mac.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(signed.mac.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    head_hmac(key, &signed.head)
+        .verify_slice(&bytes)
+        .is_ok()
+        .then(|| signed.head.clone())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn entry_hash(entry: &AuditEntry) -> String {
+    let unsigned = AuditEntry {
+        hash: String::new(),
+        ..entry.clone()
+    };
+    let json = serde_json::to_string(&unsigned).unwrap_or_default();
+    hex(&Sha256::digest(json.as_bytes()))
+}
+
+/// 从日志统计链末端（旧记录不计入）
+fn scan_head(content: &str) -> ChainHead {
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|entry| !entry.hash.is_empty())
+        .fold(ChainHead::genesis(), |head, entry| ChainHead {
+            entries: head.entries + 1,
+            hash: entry.hash,
+        })
+}
+
+/// 读取链末端 HMAC 密钥，create 为 true 时不存在则生成
+fn load_key(app: &tauri::AppHandle, create: bool) -> Option<Vec<u8>> {
+    let path = data_file_path(app, AUDIT_KEY_FILE);
+    if let Ok(key) = std::fs::read_to_string(&path) {
+        return Some(key.trim().as_bytes().to_vec());
+    }
+    if !create {
+        return None;
+    }
+    let key = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    match options
+        .open(&path)
+        .and_then(|mut f| f.write_all(key.as_bytes()))
+    {
+        Ok(()) => Some(key.into_bytes()),
+        Err(e) => {
+            debug_log(&format!("[audit] 无法生成链末端密钥: {}", e));
+            None
+        }
+    }
+}
+
+fn load_signed_head(app: &tauri::AppHandle) -> Option<SignedHead> {
+    let content = std::fs::read_to_string(data_file_path(app, AUDIT_HEAD_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 保存链末端（先写临时文件再替换，避免写入中断留下半个文件）
+fn save_head(app: &tauri::AppHandle, key: &[u8], head: &ChainHead) {
+    let path = data_file_path(app, AUDIT_HEAD_FILE);
+    let tmp = path.with_extension("head.tmp");
+    let json = serde_json::to_string(&sign_head(key, head.clone())).unwrap_or_default();
+    if let Err(e) = std::fs::write(&tmp, json).and_then(|()| std::fs::rename(&tmp, &path)) {
+        debug_log(&format!("[audit] 保存链末端失败: {}", e));
+    }
+}
+
+/// 写入状态：链末端与 HMAC 密钥（无法生成密钥时为 None，只维护哈希链）
+struct ChainState {
+    head: ChainHead,
+    key: Option<Vec<u8>>,
+}
+
+/// 写入状态（首次写入时初始化），写入期间持有锁以保证链条顺序
+fn chain_state() -> &'static Mutex<Option<ChainState>> {
+    static STATE: OnceLock<Mutex<Option<ChainState>>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(None))
+}
+
+/// 初始化写入状态：优先使用通过校验的链末端，否则从日志重建并记录重建
+fn init_state(app: &tauri::AppHandle, path: &Path) -> ChainState {
+    let key = load_key(app, true);
+    let signed = load_signed_head(app);
+    if let Some(head) = key
+        .as_deref()
+        .zip(signed.as_ref())
+        .and_then(|(key, signed)| verified_head(key, signed))
+    {
+        return ChainState { head, key };
+    }
+
+    let head = scan_head(&std::fs::read_to_string(path).unwrap_or_default());
+    let mut state = ChainState { head, key };
+    if state.key.is_some() && state.head.entries > 0 {
+        let reason = if signed.is_some() {
+            "invalid"
+        } else {
+            "missing"
+        };
+        debug_log(&format!(
+            "[audit] 链末端{}，从日志重建（{} 条记录）",
+            if signed.is_some() {
+                "校验失败"
+            } else {
+                "不存在"
+            },
+            state.head.entries
+        ));
+        append(
+            app,
+            path,
+            &mut state,
+            "audit.head_reset",
+            false,
+            serde_json::json!({ "reason": reason, "entries": state.head.entries }),
+        );
+    }
+    state
+}
+
+/// 在链末端追加一条记录并更新 audit.head
+fn append(
+    app: &tauri::AppHandle,
+    path: &Path,
+    state: &mut ChainState,
+    action: &str,
+    success: bool,
+    details: serde_json::Value,
+) {
+    let mut entry = AuditEntry {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        action: action.to_string(),
+        success,
+        details,
+        prev_hash: state.head.hash.clone(),
+        hash: String::new(),
+    };
+    entry.hash = entry_hash(&entry);
+    let line = match serde_json::to_string(&entry) {
+        Ok(l) => l,
+        Err(e) => {
+            debug_log(&format!("[audit] 序列化失败: {}", e));
+            return;
+        }
+    };
+
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| writeln!(f, "{}", line));
+    if let Err(e) = result {
+        debug_log(&format!("[audit] 写入失败: {}", e));
+        return;
+    }
+    state.head = ChainHead {
+        entries: state.head.entries + 1,
+        hash: entry.hash,
+    };
+    if let Some(key) = &state.key {
+        save_head(app, key, &state.head);
+    }
+}
+
+/// 追加一条审计记录（写入失败只记调试日志，不影响调用方）
+pub fn record(app: &tauri::AppHandle, action: &str, success: bool, details: serde_json::Value) {
+    let path = data_file_path(app, AUDIT_FILE);
+    let mut state = chain_state()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let state = state.get_or_insert_with(|| init_state(app, &path));
+    append(app, &path, state, action, success, details);
+}
+
+/// 校验审计日志的哈希链与链末端
+#[tauri::command]
+pub async fn verify_audit_log(app: tauri::AppHandle) -> Result<AuditVerification, String> {
+    // 持有写入锁，避免读到写了一半的记录或尚未更新的链末端
+    let _state = chain_state()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let content = match std::fs::read_to_string(data_file_path(&app, AUDIT_FILE)) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("读取审计日志失败: {}", e)),
+    };
+    let key = load_key(&app, false);
+    let signed = load_signed_head(&app);
+    let result = verify(&content, key.as_deref(), signed.as_ref());
+    if let Some(error) = &result.error {
+        debug_log(&format!(
+            "[audit] 校验失败（行 {:?}）: {}",
+            result.invalid_line, error
+        ));
+    }
+    Ok(result)
+}
+
+/// 校验日志内容；key 为 None（尚未生成密钥）时只校验哈希链
+fn verify(content: &str, key: Option<&[u8]>, signed: Option<&SignedHead>) -> AuditVerification {
+    let mut result = AuditVerification {
+        valid: true,
+        entries: 0,
+        legacy_entries: 0,
+        invalid_line: None,
+        error: None,
+        anchored: key.is_some(),
+    };
+    let mut expected_prev: Option<String> = None;
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let error = match serde_json::from_str::<AuditEntry>(line) {
+            Err(e) => Some(format!("无法解析: {}", e)),
+            Ok(entry) if entry.hash.is_empty() => {
+                if expected_prev.is_none() {
+                    result.legacy_entries += 1;
+                    continue;
+                }
+                Some("缺少哈希".to_string())
+            }
+            Ok(entry) => {
+                let prev = expected_prev.as_deref().unwrap_or(GENESIS_HASH);
+                if entry.prev_hash != prev {
+                    Some("与上一条记录不连续（记录被删除或插入）".to_string())
+                } else if entry_hash(&entry) != entry.hash {
+                    Some("哈希不匹配（记录被修改）".to_string())
+                } else {
+                    result.entries += 1;
+                    expected_prev = Some(entry.hash);
+                    None
+                }
+            }
+        };
+        if let Some(error) = error {
+            result.valid = false;
+            result.invalid_line = Some(index + 1);
+            result.error = Some(error);
+            return result;
+        }
+    }
+
+    let Some(key) = key else {
+        return result;
+    };
+    let actual = ChainHead {
+        entries: result.entries,
+        hash: expected_prev.unwrap_or_else(|| GENESIS_HASH.to_string()),
+    };
+    let error = match signed.map(|signed| verified_head(key, signed)) {
+        None if actual.entries > 0 => Some("缺少链末端记录（audit.head 被删除）"),
+        None => None,
+        Some(None) => Some("链末端记录校验失败（audit.head 被修改）"),
+        Some(Some(head)) if head != actual => {
+            Some("日志与链末端记录不一致（末尾的记录被删除，或在应用之外追加）")
+        }
+        Some(Some(_)) => None,
+    };
+    if let Some(error) = error {
+        result.valid = false;
+        result.error = Some(error.to_string());
+    }
+    result
+}
+
+/// 导出的时间范围（RFC 3339 或 YYYY-MM-DD 本地日期，含两端），不传的一端不限制
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryExport {
+    pub path: String,
+    /// 导出的记录数
+    pub entries: usize,
+}
+
+type Timestamp = chrono::DateTime<chrono::FixedOffset>;
+
+/// 解析范围边界；日期按本地时间取当天开始（from）或次日开始（to，不含）
+pub fn parse_bound(value: &str, end: bool) -> Result<Timestamp, String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(if end {
+            time + chrono::Duration::nanoseconds(1)
+        } else {
+            time
+        });
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid time: {}", value))?;
+    let date = if end {
+        date.succ_opt().unwrap_or(date)
+    } else {
+        date
+    };
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(chrono::Local).earliest())
+        .map(|t| t.fixed_offset())
+        .ok_or_else(|| format!("Invalid time: {}", value))
+}
+
+/// CSV 字段转义
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(entries: &[AuditEntry]) -> String {
+    let mut out = String::from("timestamp,action,success,details,hash\n");
+    for entry in entries {
+        let row = [
+            csv_field(&entry.timestamp),
+            csv_field(&entry.action),
+            entry.success.to_string(),
+            csv_field(&entry.details.to_string()),
+            csv_field(&entry.hash),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// 导出审计日志（含命令执行历史）
+///
+/// format: csv / json；path 不传时导出到下载目录
+#[tauri::command]
+pub async fn export_history(
+    app: tauri::AppHandle,
+    format: String,
+    range: Option<HistoryRange>,
+    path: Option<String>,
+) -> Result<HistoryExport, String> {
+    if format != "csv" && format != "json" {
+        return Err(format!("Unsupported format: {}", format));
+    }
+    let range = range.unwrap_or_default();
+    let from = range
+        .from
+        .as_deref()
+        .map(|v| parse_bound(v, false))
+        .transpose()?;
+    let to = range
+        .to
+        .as_deref()
+        .map(|v| parse_bound(v, true))
+        .transpose()?;
+
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .ok_or("无法确定导出目录")?
+            .join(format!(
+                "xiaodazi-history-{}.{}",
+                chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                format
+            )),
+    };
+    approvals::approve_write(&app, "audit.export", &path.display().to_string()).await?;
+
+    let content = match std::fs::read_to_string(data_file_path(&app, AUDIT_FILE)) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("读取审计日志失败: {}", e)),
+    };
+    let entries: Vec<AuditEntry> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|entry| {
+            let Ok(time) = chrono::DateTime::parse_from_rfc3339(&entry.timestamp) else {
+                return false;
+            };
+            !from.is_some_and(|from| time < from) && !to.is_some_and(|to| time >= to)
+        })
+        .collect();
+
+    let output = if format == "csv" {
+        to_csv(&entries)
+    } else {
+        serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?
+    };
+    std::fs::write(&path, output).map_err(|e| format!("写入导出文件失败: {}", e))?;
+
+    record(
+        &app,
+        "audit.export",
+        true,
+        serde_json::json!({ "path": path, "format": format, "entries": entries.len() }),
+    );
+    debug_log(&format!(
+        "[audit] 已导出 {} 条记录: {}",
+        entries.len(),
+        path.display()
+    ));
+    Ok(HistoryExport {
+        path: path.display().to_string(),
+        entries: entries.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"test-key";
+
+    /// 生成 n 条链接好的记录，返回日志内容与链末端
+    fn chain(n: usize) -> (Vec<String>, ChainHead) {
+        let mut head = ChainHead::genesis();
+        let mut lines = Vec::new();
+        for i in 0..n {
+            let mut entry = AuditEntry {
+                timestamp: format!("2024-01-01T00:00:{:02}+08:00", i),
+                action: "system.run".to_string(),
+                success: true,
+                details: serde_json::json!({ "i": i }),
+                prev_hash: head.hash.clone(),
+                hash: String::new(),
+            };
+            entry.hash = entry_hash(&entry);
+            head = ChainHead {
+                entries: head.entries + 1,
+                hash: entry.hash.clone(),
+            };
+            lines.push(serde_json::to_string(&entry).unwrap());
+        }
+        (lines, head)
+    }
+
+    fn check(lines: &[String], head: &ChainHead) -> AuditVerification {
+        let signed = sign_head(KEY, head.clone());
+        verify(&(lines.join("\n") + "\n"), Some(KEY), Some(&signed))
+    }
+
+    #[test]
+    fn intact_log_is_valid() {
+        let (lines, head) = chain(3);
+        let result = check(&lines, &head);
+        assert!(result.valid, "{:?}", result.error);
+        assert_eq!(result.entries, 3);
+        assert!(result.anchored);
+    }
+
+    #[test]
+    fn detects_modified_entry() {
+        let (mut lines, head) = chain(3);
+        lines[1] = lines[1].replace("\"i\":1", "\"i\":9");
+        let result = check(&lines, &head);
+        assert!(!result.valid);
+        assert_eq!(result.invalid_line, Some(2));
+    }
+
+    #[test]
+    fn detects_inserted_entry() {
+        let (mut lines, head) = chain(3);
+        let (extra, _) = chain(1);
+        lines.insert(1, extra[0].clone());
+        let result = check(&lines, &head);
+        assert!(!result.valid);
+        assert_eq!(result.invalid_line, Some(2));
+    }
+
+    #[test]
+    fn detects_truncated_tail() {
+        let (lines, head) = chain(3);
+        let result = check(&lines[..2], &head);
+        assert!(!result.valid);
+        assert_eq!(result.invalid_line, None);
+        // 全部删除同样能发现
+        assert!(!check(&[], &head).valid);
+    }
+
+    #[test]
+    fn detects_forged_or_missing_head() {
+        let (lines, _) = chain(3);
+        // 截断后按剩余日志伪造链末端，但没有密钥
+        let (_, truncated) = chain(2);
+        let forged = sign_head(b"other-key", truncated);
+        let content = lines[..2].join("\n");
+        assert!(!verify(&content, Some(KEY), Some(&forged)).valid);
+        assert!(!verify(&content, Some(KEY), None).valid);
+    }
+
+    #[test]
+    fn scan_head_skips_legacy_entries() {
+        let (lines, head) = chain(2);
+        let legacy = r#"{"timestamp":"t","action":"a","success":true,"details":{}}"#;
+        let content = format!("{}\n{}\n", legacy, lines.join("\n"));
+        assert_eq!(scan_head(&content), head);
+        let result = verify(&content, Some(KEY), Some(&sign_head(KEY, head)));
+        assert!(result.valid);
+        assert_eq!(result.legacy_entries, 1);
+    }
+}
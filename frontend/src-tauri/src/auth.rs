@@ -0,0 +1,227 @@
+// ============================================================================
+// 系统身份验证（Touch ID / Windows Hello）
+// ============================================================================
+//
+// 敏感命令执行前调用 require_auth(scope)，通过系统身份验证后才继续：
+// - macOS: LocalAuthentication（Touch ID，不可用时回退到登录密码）
+// - Windows: Windows Hello（UserConsentVerifier，PIN / 指纹 / 面部）
+// - Linux: polkit（pkexec 弹出管理员密码框）
+// 验证成功后在宽限期（设置 auth_grace_period_secs）内同一 scope 不再重复验证。
+// 宽限期只能通过 set_auth_grace_period 修改，延长时需要重新验证（update_settings 拒绝该字段）。
+// 系统不支持身份验证时拒绝执行（安全闸门不降级放行）。
+
+use crate::{audit, debug_log, i18n, policy, settings};
+use std::collections::HashMap;
+use std::process::Command as SysCommand;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+/// 宽限期上限（秒）
+const MAX_GRACE_PERIOD_SECS: u64 = 3600;
+
+/// 各 scope 最近一次验证成功的时间
+#[derive(Default)]
+pub struct AuthState {
+    verified: Mutex<HashMap<String, Instant>>,
+}
+
+#[cfg(target_os = "macos")]
+const LOCAL_AUTH_SCRIPT: &str = r#"
+ObjC.import('Foundation');
+ObjC.import('LocalAuthentication');
+function run(argv) {
+  const ctx = $.LAContext.alloc.init;
+  // LAPolicyDeviceOwnerAuthentication：Touch ID 不可用时允许输入登录密码
+  const POLICY = 2;
+  const err = Ref();
+  if (!ctx.canEvaluatePolicyError(POLICY, err)) {
+    return JSON.stringify({ ok: false, error: 'unavailable' });
+  }
+  let done = false, ok = false, code = 0;
+  ctx.evaluatePolicyLocalizedReasonReply(POLICY, argv[0], function (success, error) {
+    ok = success;
+    if (!success && error) code = error.code;
+    done = true;
+  });
+  const deadline = $.NSDate.dateWithTimeIntervalSinceNow(120);
+  while (!done && $.NSDate.date.compare(deadline) < 0) {
+    $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.1));
+  }
+  // LAErrorUserCancel = -2, LAErrorSystemCancel = -4, LAErrorAppCancel = -9
+  const cancelled = code === -2 || code === -4 || code === -9;
+  return JSON.stringify({ ok: ok, error: ok ? null : (cancelled ? 'cancelled' : (done ? 'failed' : 'timeout')) });
+}
+"#;
+
+#[cfg(target_os = "windows")]
+const WINDOWS_HELLO_SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Runtime.WindowsRuntime
+$asTask = ([System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object { $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1' })[0]
+function Await($op, [Type]$t) { $task = $asTask.MakeGenericMethod($t).Invoke($null, @($op)); $task.Wait(-1) | Out-Null; $task.Result }
+[Windows.Security.Credentials.UI.UserConsentVerifier, Windows.Security.Credentials.UI, ContentType = WindowsRuntime] | Out-Null
+$available = Await ([Windows.Security.Credentials.UI.UserConsentVerifier]::CheckAvailabilityAsync()) ([Windows.Security.Credentials.UI.UserConsentVerifierAvailability])
+if ($available -ne 'Available') { 'unavailable'; exit }
+Await ([Windows.Security.Credentials.UI.UserConsentVerifier]::RequestVerificationAsync($env:XDZ_AUTH_REASON)) ([Windows.Security.Credentials.UI.UserConsentVerificationResult])
+"#;
+
+/// 调用系统身份验证（阻塞，直到用户完成或取消）
+#[cfg(target_os = "macos")]
+fn os_authenticate(reason: &str) -> Result<(), String> {
+    let output = SysCommand::new("osascript")
+        .args(["-l", "JavaScript", "-e", LOCAL_AUTH_SCRIPT, reason])
+        .output()
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|_| {
+        format!(
+            "身份验证失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    })?;
+    if result["ok"].as_bool() == Some(true) {
+        return Ok(());
+    }
+    match result["error"].as_str() {
+        Some("cancelled") => Err("Authentication cancelled".to_string()),
+        Some("unavailable") => Err("OS authentication is not available".to_string()),
+        _ => Err("Authentication failed".to_string()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn os_authenticate(reason: &str) -> Result<(), String> {
+    let output = SysCommand::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            WINDOWS_HELLO_SCRIPT,
+        ])
+        .env("XDZ_AUTH_REASON", reason)
+        .output()
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "Verified" => Ok(()),
+        "Canceled" => Err("Authentication cancelled".to_string()),
+        "unavailable" => Err("Windows Hello is not set up on this device".to_string()),
+        _ => Err("Authentication failed".to_string()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn os_authenticate(_reason: &str) -> Result<(), String> {
+    // pkexec 无法自定义提示文案；退出码 126 表示用户取消，127 表示未授权
+    let status = SysCommand::new("pkexec")
+        .arg("/bin/true")
+        .status()
+        .map_err(|_| "OS authentication is not available (pkexec not found)".to_string())?;
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(126) => Err("Authentication cancelled".to_string()),
+        _ => Err("Authentication failed".to_string()),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn os_authenticate(_reason: &str) -> Result<(), String> {
+    Err("OS authentication not supported on this platform".to_string())
+}
+
+/// 系统验证框中显示的原因
+fn reason(scope: &str) -> String {
+    let key = format!("auth.scope.{}", scope);
+    let label = i18n::t(&key);
+    let label = if label == key {
+        scope.to_string()
+    } else {
+        label
+    };
+    i18n::tf("auth.reason", &[&label])
+}
+
+/// 不经过宽限期，直接调用系统身份验证
+pub async fn authenticate_now(app: &tauri::AppHandle, scope: &str) -> Result<(), String> {
+    let text = reason(scope);
+    let result = tauri::async_runtime::spawn_blocking(move || os_authenticate(&text))
+        .await
+        .map_err(|e| e.to_string())?;
+    audit::record(
+        app,
+        "auth.verify",
+        result.is_ok(),
+        serde_json::json!({ "scope": scope, "error": result.as_ref().err() }),
+    );
+    match &result {
+        Ok(()) => {
+            debug_log(&format!("[auth] 身份验证通过: {}", scope));
+            if let Ok(mut verified) = app.state::<AuthState>().verified.lock() {
+                verified.insert(scope.to_string(), Instant::now());
+            }
+        }
+        Err(e) => debug_log(&format!("[auth] 身份验证未通过 ({}): {}", scope, e)),
+    }
+    result
+}
+
+/// 敏感操作前要求系统身份验证（宽限期内同一 scope 直接通过）
+pub async fn require_auth(app: &tauri::AppHandle, scope: &str) -> Result<(), String> {
+    let grace = Duration::from_secs(settings::current(app).auth_grace_period_secs);
+    let recent = app
+        .state::<AuthState>()
+        .verified
+        .lock()
+        .ok()
+        .and_then(|v| v.get(scope).copied())
+        .is_some_and(|at| at.elapsed() < grace);
+    if recent {
+        return Ok(());
+    }
+    authenticate_now(app, scope).await
+}
+
+/// 清除所有 scope 的验证状态（锁定后需重新验证）
+pub fn revoke_all(app: &tauri::AppHandle) {
+    if let Ok(mut verified) = app.state::<AuthState>().verified.lock() {
+        verified.clear();
+    }
+}
+
+/// 供前端在敏感操作（如查看密钥）前请求身份验证
+#[tauri::command]
+pub async fn authenticate(app: tauri::AppHandle, scope: String) -> Result<(), String> {
+    require_auth(&app, &scope).await
+}
+
+/// 修改身份验证宽限期（秒，0 表示每次都验证），延长时需要先通过系统身份验证
+#[tauri::command]
+pub async fn set_auth_grace_period(app: tauri::AppHandle, secs: u64) -> Result<u64, String> {
+    if policy::is_locked_setting("auth_grace_period_secs") {
+        return Err("Setting locked by policy: auth_grace_period_secs".to_string());
+    }
+    if secs > MAX_GRACE_PERIOD_SECS {
+        return Err(format!(
+            "Grace period cannot exceed {} seconds",
+            MAX_GRACE_PERIOD_SECS
+        ));
+    }
+    let previous = settings::current(&app).auth_grace_period_secs;
+    if secs > previous {
+        authenticate_now(&app, "auth_settings").await?;
+    }
+    let updated = {
+        let state = app.state::<Mutex<settings::AppSettings>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        guard.auth_grace_period_secs = secs;
+        settings::save_settings(&guard)?;
+        guard.clone()
+    };
+    audit::record(
+        &app,
+        "settings.auth_grace_period",
+        true,
+        serde_json::json!({ "previous": previous, "secs": secs }),
+    );
+    debug_log(&format!("[auth] 身份验证宽限期改为 {} 秒", secs));
+    let _ = app.emit("settings-changed", &updated);
+    Ok(secs)
+}
@@ -0,0 +1,685 @@
+// ============================================================================
+// 自动化规则：文件夹变化 / 定时 / 全局快捷键 → 执行动作
+// ============================================================================
+//
+// 规则持久化到 automations.json，由 Rust 壳层直接执行，前端未运行时同样生效。
+// 动作与定时任务相同（见 scheduler::TaskAction）；文件夹触发时，动作参数中的
+// `{path}` 会替换为新文件的完整路径，例如把 ~/Downloads 中新出现的 PDF 发给 Agent。
+// 每条规则可在托盘菜单"自动化"子菜单中启用 / 停用。快捷键触发在注册前检查与其它快捷键的
+// 冲突，并列入快捷键登记（见 shortcuts.rs）。
+
+use crate::scheduler::{self, TaskAction, TaskRunResult};
+use crate::store::{load_json, save_json};
+use crate::{debug_log, i18n, shortcuts};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+/// 规则持久化文件
+const AUTOMATIONS_FILE: &str = "automations.json";
+
+/// 托盘菜单项 ID 前缀
+pub const TRAY_ITEM_PREFIX: &str = "automation:";
+
+/// 定时触发检查间隔
+const SCHEDULE_TICK: Duration = Duration::from_secs(5);
+
+/// 同一文件的重复事件在此时间内只触发一次（下载过程中会产生多次写入）
+const FS_DEBOUNCE: Duration = Duration::from_secs(3);
+
+/// 未完成下载的临时文件扩展名（未指定匹配规则时忽略）
+const PARTIAL_EXTENSIONS: &[&str] = &["crdownload", "part", "download", "tmp", "partial"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AutomationTrigger {
+    /// 文件夹中出现新文件（新建或移入）
+    Fs {
+        /// 监视的目录（支持 ~ 开头）
+        path: String,
+        /// 文件名匹配规则（支持 * 与 ?，不区分大小写），如 "*.pdf"
+        #[serde(default)]
+        pattern: Option<String>,
+        #[serde(default)]
+        recursive: bool,
+    },
+    /// cron 表达式（写法同 schedule_task）
+    Schedule { cron: String },
+    /// 全局快捷键，如 "CmdOrCtrl+Shift+K"
+    Hotkey { shortcut: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Automation {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub trigger: AutomationTrigger,
+    pub action: TaskAction,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub created_at: String,
+    #[serde(default)]
+    pub last_run: Option<TaskRunResult>,
+    /// 定时触发的下一次执行时间（仅 schedule 触发）
+    #[serde(default)]
+    pub next_run: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Automation {
+    /// 托盘中显示的名称（未命名时按触发条件生成）
+    fn label(&self) -> String {
+        if let Some(name) = self.name.as_deref().filter(|n| !n.is_empty()) {
+            return name.to_string();
+        }
+        match &self.trigger {
+            AutomationTrigger::Fs { path, pattern, .. } => i18n::tf(
+                "automation.label.fs",
+                &[&path, &pattern.as_deref().unwrap_or("*")],
+            ),
+            AutomationTrigger::Schedule { cron } => i18n::tf("automation.label.schedule", &[cron]),
+            AutomationTrigger::Hotkey { shortcut } => {
+                i18n::tf("automation.label.hotkey", &[shortcut])
+            }
+        }
+    }
+}
+
+/// 自动化规则与其运行时资源（文件监视器、最近触发记录）
+#[derive(Default)]
+pub struct AutomationState {
+    rules: Mutex<Vec<Automation>>,
+    watchers: Mutex<HashMap<String, notify::RecommendedWatcher>>,
+    recent: Mutex<HashMap<(String, PathBuf), Instant>>,
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest.trim_start_matches(['/', '\\'])))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// 把通配符规则转换为正则（整名匹配，不区分大小写）
+fn pattern_regex(pattern: &str) -> Result<Regex, String> {
+    let escaped = regex::escape(pattern)
+        .replace(r"\*", ".*")
+        .replace(r"\?", ".");
+    Regex::new(&format!("(?i)^{}$", escaped)).map_err(|e| format!("无效的匹配规则: {}", e))
+}
+
+fn file_matches(path: &Path, pattern: Option<&Regex>) -> bool {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy()) else {
+        return false;
+    };
+    match pattern {
+        Some(re) => re.is_match(&name),
+        None => {
+            let partial = path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .is_some_and(|ext| PARTIAL_EXTENSIONS.contains(&ext.as_str()));
+            !name.starts_with('.') && !partial
+        }
+    }
+}
+
+/// 把动作参数中的 `{path}` 替换为触发文件路径
+fn substitute_path(action: &TaskAction, path: &Path) -> TaskAction {
+    let escaped = serde_json::to_string(&path.to_string_lossy()).unwrap_or_default();
+    let escaped = escaped.trim_matches('"');
+    serde_json::to_string(action)
+        .ok()
+        .map(|json| json.replace("{path}", escaped))
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(|| action.clone())
+}
+
+fn persist(app: &tauri::AppHandle, rules: &[Automation]) {
+    if let Err(e) = save_json(app, AUTOMATIONS_FILE, &rules) {
+        debug_log(&format!("[automation] 保存自动化规则失败: {}", e));
+    }
+}
+
+/// 执行规则的动作（规则不存在或已停用时忽略）
+fn fire(app: &tauri::AppHandle, id: &str, path: Option<PathBuf>) {
+    let rule = {
+        let state = app.state::<AutomationState>();
+        let rules = state.rules.lock().unwrap_or_else(PoisonError::into_inner);
+        match rules.iter().find(|r| r.id == id && r.enabled) {
+            Some(r) => r.clone(),
+            None => return,
+        }
+    };
+    let action = match &path {
+        Some(p) => substitute_path(&rule.action, p),
+        None => rule.action.clone(),
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        debug_log(&format!("[automation] 触发自动化规则 (id={})", rule.id));
+        let source = scheduler::ActionSource::Automation(&rule.id);
+        let result = scheduler::run_action(&app, source, &action).await;
+        if !result.success {
+            debug_log(&format!(
+                "[automation] 规则执行失败 (id={}): {}",
+                rule.id, result.output
+            ));
+        }
+        {
+            let state = app.state::<AutomationState>();
+            let mut rules = state.rules.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(r) = rules.iter_mut().find(|r| r.id == rule.id) {
+                r.last_run = Some(result.clone());
+                persist(&app, &rules);
+            }
+        }
+        let _ = app.emit(
+            "automation-triggered",
+            serde_json::json!({
+                "id": rule.id,
+                "path": path.map(|p| p.to_string_lossy().to_string()),
+                "result": result,
+            }),
+        );
+    });
+}
+
+/// 文件事件去抖：同一规则同一文件短时间内只处理一次
+fn debounced(app: &tauri::AppHandle, id: &str, path: &Path) -> bool {
+    let state = app.state::<AutomationState>();
+    let mut recent = state.recent.lock().unwrap_or_else(PoisonError::into_inner);
+    let now = Instant::now();
+    recent.retain(|_, at| now.duration_since(*at) < FS_DEBOUNCE);
+    let key = (id.to_string(), path.to_path_buf());
+    if recent.contains_key(&key) {
+        return true;
+    }
+    recent.insert(key, now);
+    false
+}
+
+fn watch_folder(
+    app: &tauri::AppHandle,
+    id: &str,
+    path: &str,
+    pattern: Option<&str>,
+    recursive: bool,
+) -> Result<notify::RecommendedWatcher, String> {
+    use notify::event::{CreateKind, ModifyKind};
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let dir = expand_home(path);
+    if !dir.is_dir() {
+        return Err(format!("Folder not found: {}", dir.display()));
+    }
+    let pattern = pattern.map(pattern_regex).transpose()?;
+
+    let app = app.clone();
+    let id = id.to_string();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        // 新建文件，或从别处移入 / 下载完成后改名
+        let relevant = matches!(
+            event.kind,
+            EventKind::Create(CreateKind::File | CreateKind::Any)
+                | EventKind::Modify(ModifyKind::Name(_))
+        );
+        if !relevant {
+            return;
+        }
+        for file in event.paths {
+            if file.is_file()
+                && file_matches(&file, pattern.as_ref())
+                && !debounced(&app, &id, &file)
+            {
+                fire(&app, &id, Some(file));
+            }
+        }
+    })
+    .map_err(|e| format!("创建文件监视失败: {}", e))?;
+
+    let mode = if recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher
+        .watch(&dir, mode)
+        .map_err(|e| format!("监视目录失败 {}: {}", dir.display(), e))?;
+    Ok(watcher)
+}
+
+/// 启用规则的触发条件（文件监视 / 快捷键；定时触发由调度循环处理）
+fn activate(app: &tauri::AppHandle, rule: &Automation) -> Result<(), String> {
+    match &rule.trigger {
+        AutomationTrigger::Fs {
+            path,
+            pattern,
+            recursive,
+        } => {
+            let watcher = watch_folder(app, &rule.id, path, pattern.as_deref(), *recursive)?;
+            app.state::<AutomationState>()
+                .watchers
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(rule.id.clone(), watcher);
+        }
+        AutomationTrigger::Schedule { .. } => {}
+        AutomationTrigger::Hotkey { shortcut } => {
+            let id = rule.id.clone();
+            app.global_shortcut()
+                .on_shortcut(shortcut.as_str(), move |app, _shortcut, event| {
+                    if event.state() == ShortcutState::Pressed {
+                        fire(app, &id, None);
+                    }
+                })
+                .map_err(|e| format!("注册快捷键失败 {}: {}", shortcut, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 停用规则的触发条件
+fn deactivate(app: &tauri::AppHandle, rule: &Automation) {
+    match &rule.trigger {
+        AutomationTrigger::Fs { .. } => {
+            // 监视器 drop 时停止监视
+            app.state::<AutomationState>()
+                .watchers
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&rule.id);
+        }
+        AutomationTrigger::Schedule { .. } => {}
+        AutomationTrigger::Hotkey { shortcut } => {
+            if let Err(e) = app.global_shortcut().unregister(shortcut.as_str()) {
+                debug_log(&format!("[automation] 注销快捷键失败 {}: {}", shortcut, e));
+            }
+        }
+    }
+}
+
+/// 取出已到期的定时规则，并推进下一次执行时间
+fn take_due(app: &tauri::AppHandle) -> Vec<String> {
+    let now = chrono::Local::now();
+    let state = app.state::<AutomationState>();
+    let mut rules = state.rules.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut due = Vec::new();
+    for rule in rules.iter_mut().filter(|r| r.enabled) {
+        let AutomationTrigger::Schedule { cron } = &rule.trigger else {
+            continue;
+        };
+        let is_due = rule
+            .next_run
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .is_some_and(|t| t <= now);
+        if is_due {
+            due.push(rule.id.clone());
+            rule.next_run = scheduler::next_run_after_now(cron);
+        }
+    }
+    due
+}
+
+fn next_run_for(rule: &Automation) -> Option<String> {
+    match &rule.trigger {
+        AutomationTrigger::Schedule { cron } if rule.enabled => scheduler::next_run_after_now(cron),
+        _ => None,
+    }
+}
+
+/// 托盘菜单"自动化"子菜单中的规则列表：(菜单项 ID, 名称, 是否启用)
+pub fn tray_items<R: tauri::Runtime, M: Manager<R>>(manager: &M) -> Vec<(String, String, bool)> {
+    let Some(state) = manager.try_state::<AutomationState>() else {
+        return Vec::new();
+    };
+    let rules = state.rules.lock().unwrap_or_else(PoisonError::into_inner);
+    rules
+        .iter()
+        .map(|r| {
+            (
+                format!("{}{}", TRAY_ITEM_PREFIX, r.id),
+                r.label(),
+                r.enabled,
+            )
+        })
+        .collect()
+}
+
+/// 快捷键触发的规则：(规则 ID, 名称, 快捷键, 是否启用)
+pub fn hotkeys(app: &tauri::AppHandle) -> Vec<(String, String, String, bool)> {
+    let Some(state) = app.try_state::<AutomationState>() else {
+        return Vec::new();
+    };
+    let rules = state.rules.lock().unwrap_or_else(PoisonError::into_inner);
+    rules
+        .iter()
+        .filter_map(|r| match &r.trigger {
+            AutomationTrigger::Hotkey { shortcut } => {
+                Some((r.id.clone(), r.label(), shortcut.clone(), r.enabled))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// 改绑快捷键触发规则的快捷键（冲突检查由调用方完成），新按键注册失败时恢复原按键
+pub fn set_hotkey(app: &tauri::AppHandle, id: &str, shortcut: &str) -> Result<Automation, String> {
+    let previous = {
+        let state = app.state::<AutomationState>();
+        let rules = state.rules.lock().map_err(|e| e.to_string())?;
+        rules
+            .iter()
+            .find(|r| r.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Automation not found: {}", id))?
+    };
+    if !matches!(previous.trigger, AutomationTrigger::Hotkey { .. }) {
+        return Err(format!("Automation has no hotkey trigger: {}", id));
+    }
+    let mut rule = previous.clone();
+    rule.trigger = AutomationTrigger::Hotkey {
+        shortcut: shortcut.to_string(),
+    };
+    if rule.enabled {
+        deactivate(app, &previous);
+        if let Err(e) = activate(app, &rule) {
+            if let Err(restore) = activate(app, &previous) {
+                debug_log(&format!("[automation] 恢复快捷键失败: {}", restore));
+            }
+            return Err(e);
+        }
+    }
+
+    {
+        let state = app.state::<AutomationState>();
+        let mut rules = state.rules.lock().map_err(|e| e.to_string())?;
+        if let Some(r) = rules.iter_mut().find(|r| r.id == id) {
+            r.trigger = rule.trigger.clone();
+        }
+        save_json(app, AUTOMATIONS_FILE, &*rules)?;
+    }
+    refresh_tray(app);
+    let _ = app.emit("automations-changed", ());
+    Ok(rule)
+}
+
+fn refresh_tray(app: &tauri::AppHandle) {
+    if let Err(e) = i18n::refresh_tray_menu(app) {
+        debug_log(&format!("[automation] 刷新托盘菜单失败: {}", e));
+    }
+}
+
+/// 启用 / 停用规则并持久化（触发条件启用失败时保持停用）
+fn set_enabled(app: &tauri::AppHandle, id: &str, enabled: bool) -> Result<Automation, String> {
+    let mut rule = {
+        let state = app.state::<AutomationState>();
+        let rules = state.rules.lock().map_err(|e| e.to_string())?;
+        rules
+            .iter()
+            .find(|r| r.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Automation not found: {}", id))?
+    };
+    if rule.enabled == enabled {
+        return Ok(rule);
+    }
+    rule.enabled = enabled;
+    rule.next_run = next_run_for(&rule);
+    if enabled {
+        activate(app, &rule)?;
+    } else {
+        deactivate(app, &rule);
+    }
+
+    {
+        let state = app.state::<AutomationState>();
+        let mut rules = state.rules.lock().map_err(|e| e.to_string())?;
+        if let Some(r) = rules.iter_mut().find(|r| r.id == id) {
+            r.enabled = enabled;
+            r.next_run = rule.next_run.clone();
+        }
+        save_json(app, AUTOMATIONS_FILE, &*rules)?;
+    }
+    debug_log(&format!(
+        "[automation] 规则 {} 已{}",
+        rule.id,
+        if enabled { "启用" } else { "停用" }
+    ));
+    refresh_tray(app);
+    let _ = app.emit("automations-changed", ());
+    Ok(rule)
+}
+
+/// 托盘菜单中切换规则的启用状态
+pub fn toggle_from_tray(app: &tauri::AppHandle, menu_id: &str) {
+    let Some(id) = menu_id.strip_prefix(TRAY_ITEM_PREFIX) else {
+        return;
+    };
+    let enabled = {
+        let state = app.state::<AutomationState>();
+        let rules = state.rules.lock().unwrap_or_else(PoisonError::into_inner);
+        match rules.iter().find(|r| r.id == id) {
+            Some(r) => r.enabled,
+            None => return,
+        }
+    };
+    if let Err(e) = set_enabled(app, id, !enabled) {
+        debug_log(&format!("[automation] {}", e));
+    }
+}
+
+/// 加载持久化的规则，启用其触发条件并启动定时触发循环
+pub fn start(app: tauri::AppHandle) {
+    let mut saved: Vec<Automation> = load_json(&app, AUTOMATIONS_FILE);
+    for rule in saved.iter_mut() {
+        rule.next_run = next_run_for(rule);
+    }
+    if !saved.is_empty() {
+        debug_log(&format!("[automation] 恢复 {} 条自动化规则", saved.len()));
+    }
+    for rule in saved.iter().filter(|r| r.enabled) {
+        if let Err(e) = activate(&app, rule) {
+            debug_log(&format!("[automation] 规则 {} 启用失败: {}", rule.id, e));
+        }
+    }
+    *app.state::<AutomationState>()
+        .rules
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = saved;
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            for id in take_due(&app) {
+                fire(&app, &id, None);
+            }
+            tokio::time::sleep(SCHEDULE_TICK).await;
+        }
+    });
+}
+
+/// 添加自动化规则，返回登记的规则（同 ID 会覆盖已有规则）
+#[tauri::command]
+pub async fn add_automation(
+    app: tauri::AppHandle,
+    trigger: AutomationTrigger,
+    action: TaskAction,
+    name: Option<String>,
+    id: Option<String>,
+) -> Result<Automation, String> {
+    scheduler::validate_action(&action)?;
+    scheduler::check_action_capability(&app, &action)?;
+    let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    match &trigger {
+        AutomationTrigger::Fs { pattern, .. } => {
+            if let Some(p) = pattern {
+                pattern_regex(p)?;
+            }
+        }
+        AutomationTrigger::Schedule { cron } => {
+            scheduler::parse_cron(cron)?;
+        }
+        AutomationTrigger::Hotkey { shortcut } if shortcut.trim().is_empty() => {
+            return Err("Shortcut cannot be empty".to_string());
+        }
+        AutomationTrigger::Hotkey { shortcut } => {
+            shortcuts::check_conflict(&app, &shortcuts::automation_id(&id), shortcut)?;
+        }
+    }
+
+    let mut rule = Automation {
+        id,
+        name,
+        trigger,
+        action,
+        enabled: true,
+        created_at: chrono::Local::now().to_rfc3339(),
+        last_run: None,
+        next_run: None,
+    };
+    rule.next_run = next_run_for(&rule);
+
+    // 替换同 ID 的旧规则：先停用其触发条件
+    let previous = {
+        let state = app.state::<AutomationState>();
+        let rules = state.rules.lock().map_err(|e| e.to_string())?;
+        rules.iter().find(|r| r.id == rule.id).cloned()
+    };
+    if let Some(old) = previous.filter(|r| r.enabled) {
+        deactivate(&app, &old);
+    }
+    activate(&app, &rule)?;
+
+    {
+        let state = app.state::<AutomationState>();
+        let mut rules = state.rules.lock().map_err(|e| e.to_string())?;
+        rules.retain(|r| r.id != rule.id);
+        rules.push(rule.clone());
+        save_json(&app, AUTOMATIONS_FILE, &*rules)?;
+    }
+    debug_log(&format!("[automation] 已添加自动化规则 (id={})", rule.id));
+    refresh_tray(&app);
+    let _ = app.emit("automations-changed", ());
+    Ok(rule)
+}
+
+/// 列出全部自动化规则
+#[tauri::command]
+pub async fn list_automations(app: tauri::AppHandle) -> Result<Vec<Automation>, String> {
+    let state = app.state::<AutomationState>();
+    let rules = state.rules.lock().map_err(|e| e.to_string())?;
+    Ok(rules.clone())
+}
+
+/// 删除自动化规则，返回是否存在
+#[tauri::command]
+pub async fn delete_automation(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+    let removed = {
+        let state = app.state::<AutomationState>();
+        let mut rules = state.rules.lock().map_err(|e| e.to_string())?;
+        let removed = rules
+            .iter()
+            .position(|r| r.id == id)
+            .map(|i| rules.remove(i));
+        if removed.is_some() {
+            save_json(&app, AUTOMATIONS_FILE, &*rules)?;
+        }
+        removed
+    };
+    let Some(rule) = removed else {
+        return Ok(false);
+    };
+    if rule.enabled {
+        deactivate(&app, &rule);
+    }
+    refresh_tray(&app);
+    let _ = app.emit("automations-changed", ());
+    Ok(true)
+}
+
+/// 启用 / 停用自动化规则
+#[tauri::command]
+pub async fn set_automation_enabled(
+    app: tauri::AppHandle,
+    id: String,
+    enabled: bool,
+) -> Result<Automation, String> {
+    set_enabled(&app, &id, enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pattern_regex_matches_whole_name_case_insensitively() {
+        let re = pattern_regex("*.PDF").unwrap();
+        assert!(re.is_match("report.pdf"));
+        assert!(re.is_match("a.b.Pdf"));
+        assert!(!re.is_match("report.pdf.tmp"));
+
+        let re = pattern_regex("IMG_????.jpg").unwrap();
+        assert!(re.is_match("img_0001.JPG"));
+        assert!(!re.is_match("IMG_01.jpg"));
+    }
+
+    #[test]
+    fn pattern_regex_escapes_other_characters() {
+        let re = pattern_regex("a+b (1).txt").unwrap();
+        assert!(re.is_match("a+b (1).txt"));
+        assert!(!re.is_match("aab (1).txt"));
+        assert!(!re.is_match("a+b (1)xtxt"));
+    }
+
+    #[test]
+    fn substitute_path_escapes_path_in_every_field() {
+        let path = Path::new(r#"/tmp/say "hi"\x.pdf"#);
+        let action = TaskAction::RunCommand {
+            command: vec![
+                "convert".to_string(),
+                "{path}".to_string(),
+                "{path}.png".to_string(),
+            ],
+            cwd: None,
+            env: None,
+            timeout_ms: None,
+        };
+        let substituted = serde_json::to_value(substitute_path(&action, path)).unwrap();
+        assert_eq!(
+            substituted["command"],
+            json!([
+                "convert",
+                r#"/tmp/say "hi"\x.pdf"#,
+                r#"/tmp/say "hi"\x.pdf.png"#
+            ])
+        );
+
+        let action = TaskAction::BackendRequest {
+            method: "POST".to_string(),
+            path: "/api/v1/files".to_string(),
+            body: Some(json!({ "file": "{path}" })),
+        };
+        let substituted = serde_json::to_value(substitute_path(&action, path)).unwrap();
+        assert_eq!(
+            substituted["body"],
+            json!({ "file": r#"/tmp/say "hi"\x.pdf"# })
+        );
+    }
+}
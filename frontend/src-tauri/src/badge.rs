@@ -0,0 +1,122 @@
+// ============================================================================
+// 窗口徽标、任务栏 / Dock 进度与提醒
+// ============================================================================
+//
+// 长任务在窗口隐藏时也能在系统层面看到状态：
+// - set_badge_count(n)：macOS Dock 徽标、Linux（Unity）启动器计数；
+//   Windows 任务栏叠加图标只显示红点（叠加图标不含数字）
+// - set_progress(value)：任务栏 / Dock 进度条，value 为 0~1，不传时清除
+// - request_attention(critical)：Dock 图标跳动 / 任务栏按钮闪烁（窗口已聚焦时不提醒）
+// 作用于调用命令的窗口。
+
+use crate::debug_log;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::Manager;
+
+/// Windows 叠加图标的边长
+#[cfg(windows)]
+const OVERLAY_SIZE: u32 = 16;
+
+/// 生成红色圆点叠加图标（RGBA）
+#[cfg(windows)]
+fn overlay_dot() -> tauri::image::Image<'static> {
+    let size = OVERLAY_SIZE;
+    let center = (size as f32 - 1.0) / 2.0;
+    let radius = size as f32 / 2.0;
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            let alpha = ((radius - distance).clamp(0.0, 1.0) * 255.0) as u8;
+            rgba.extend_from_slice(&[0xE5, 0x39, 0x35, alpha]);
+        }
+    }
+    tauri::image::Image::new_owned(rgba, size, size)
+}
+
+/// 设置徽标计数，0 或不传时清除
+#[tauri::command]
+pub async fn set_badge_count(
+    window: tauri::WebviewWindow,
+    count: Option<u32>,
+) -> Result<(), String> {
+    let count = count.filter(|n| *n > 0);
+    debug_log(&format!(
+        "[badge] 窗口 {} 徽标: {:?}",
+        window.label(),
+        count
+    ));
+
+    #[cfg(windows)]
+    {
+        window
+            .set_overlay_icon(count.map(|_| overlay_dot()))
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(windows))]
+    {
+        window
+            .set_badge_count(count.map(i64::from))
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// 设置任务栏 / Dock 进度
+///
+/// state: normal（默认）/ indeterminate / paused / error
+#[tauri::command]
+pub async fn set_progress(
+    window: tauri::WebviewWindow,
+    value: Option<f64>,
+    state: Option<String>,
+) -> Result<(), String> {
+    let status = match (value, state.as_deref()) {
+        (None, None) => ProgressBarStatus::None,
+        (_, None | Some("normal")) => ProgressBarStatus::Normal,
+        (_, Some("indeterminate")) => ProgressBarStatus::Indeterminate,
+        (_, Some("paused")) => ProgressBarStatus::Paused,
+        (_, Some("error")) => ProgressBarStatus::Error,
+        (_, Some(other)) => return Err(format!("Unknown progress state: {}", other)),
+    };
+    let progress = value.map(|v| (v.clamp(0.0, 1.0) * 100.0).round() as u64);
+    window
+        .set_progress_bar(ProgressBarState {
+            status: Some(status),
+            progress,
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// 窗口不在前台时请求用户注意（critical 时持续提醒直到窗口获得焦点）
+pub fn attention(window: &tauri::WebviewWindow, critical: bool) -> Result<(), String> {
+    if window.is_focused().unwrap_or(false) {
+        return Ok(());
+    }
+    let kind = if critical {
+        tauri::UserAttentionType::Critical
+    } else {
+        tauri::UserAttentionType::Informational
+    };
+    window
+        .request_user_attention(Some(kind))
+        .map_err(|e| e.to_string())
+}
+
+/// 主窗口请求用户注意（Rust 内部使用，如等待审批）
+pub fn attention_main(app: &tauri::AppHandle, critical: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = attention(&window, critical) {
+            debug_log(&format!("[badge] 请求用户注意失败: {}", e));
+        }
+    }
+}
+
+/// 请求用户注意：macOS Dock 图标跳动，Windows 任务栏按钮闪烁
+#[tauri::command]
+pub async fn request_attention(
+    window: tauri::WebviewWindow,
+    critical: Option<bool>,
+) -> Result<(), String> {
+    attention(&window, critical.unwrap_or(false))
+}
@@ -0,0 +1,173 @@
+// ============================================================================
+// 与后端之间的二进制通道
+// ============================================================================
+//
+// 截图、文件分块等大体积数据不经过 JSON / base64：
+// - 前端 ↔ Rust：原始字节 invoke（请求体为 ArrayBuffer / Uint8Array，返回 ArrayBuffer）
+// - Rust ↔ 后端：沿用当前传输（TCP 或 Unix socket），请求体 / 响应体为 application/octet-stream
+// backend_upload_file / backend_download_file 在 Rust 中直接读写本地文件，数据完全不经过 webview。
+// 后端重启期间的请求排队等待恢复后重试（见 queue.rs）。
+
+use crate::queue::SendError;
+use crate::{approvals, debug_log, policy, queue, timeline};
+use std::time::Duration;
+use tauri::Manager;
+
+/// 单次二进制请求的超时
+const BINARY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// backend_binary 从 invoke 请求头读取的参数
+const PATH_HEADER: &str = "x-backend-path";
+const METHOD_HEADER: &str = "x-backend-method";
+
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// 向后端发送二进制请求，返回状态码与响应体
+pub async fn request(
+    app: &tauri::AppHandle,
+    method: &str,
+    path: &str,
+    content_type: &str,
+    body: Vec<u8>,
+) -> Result<(u16, Vec<u8>), String> {
+    if !path.starts_with('/') {
+        return Err("path must start with /".to_string());
+    }
+    debug_log(&format!(
+        "[binary] {} {} ({} 字节)",
+        method,
+        path,
+        body.len()
+    ));
+    queue::send(app, || {
+        request_once(app, method, path, content_type, body.clone())
+    })
+    .await
+}
+
+/// 发送一次请求（不重试）
+async fn request_once(
+    app: &tauri::AppHandle,
+    method: &str,
+    path: &str,
+    content_type: &str,
+    body: Vec<u8>,
+) -> Result<(u16, Vec<u8>), SendError> {
+    let crate::BackendInfo {
+        port, socket_path, ..
+    } = app.state::<crate::BackendState>().info();
+
+    if let Some(socket) = socket_path {
+        let headers = [
+            ("Content-Type".to_string(), content_type.to_string()),
+            ("Accept".to_string(), OCTET_STREAM.to_string()),
+        ];
+        let resp =
+            crate::uds::http_request(&socket, method, path, &headers, &body, BINARY_TIMEOUT)
+                .await
+                .map_err(SendError::from_uds)?;
+        return Ok((resp.status, resp.body));
+    }
+
+    let method = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|e| SendError::Failed(format!("无效的请求方法: {}", e)))?;
+    let resp = crate::http::client()
+        .request(method, format!("http://127.0.0.1:{}{}", port, path))
+        .header("Content-Type", content_type)
+        .header("Accept", OCTET_STREAM)
+        .timeout(BINARY_TIMEOUT)
+        .body(body)
+        .send()
+        .await
+        .map_err(SendError::from_reqwest)?;
+    let status = resp.status().as_u16();
+    let bytes = resp
+        .bytes()
+        .await
+        .map_err(|e| SendError::Failed(e.to_string()))?;
+    Ok((status, bytes.to_vec()))
+}
+
+/// 非 2xx 响应转为错误
+fn check_status(status: u16, body: &[u8]) -> Result<(), String> {
+    if (200..300).contains(&status) {
+        return Ok(());
+    }
+    Err(format!(
+        "HTTP {}: {}",
+        status,
+        String::from_utf8_lossy(&body[..body.len().min(512)])
+    ))
+}
+
+/// 原始字节请求：请求体为 invoke 的原始数据，后端路径与方法通过请求头
+/// x-backend-path / x-backend-method（默认 POST）传入，响应体以 ArrayBuffer 返回
+#[tauri::command]
+pub async fn backend_binary(
+    app: tauri::AppHandle,
+    request: tauri::ipc::Request<'_>,
+) -> Result<tauri::ipc::Response, String> {
+    let header = |name: &str| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let path = header(PATH_HEADER).ok_or_else(|| format!("missing {} header", PATH_HEADER))?;
+    let method = header(METHOD_HEADER).unwrap_or_else(|| "POST".to_string());
+    let content_type = header("content-type").unwrap_or_else(|| OCTET_STREAM.to_string());
+    let body = match request.body() {
+        tauri::ipc::InvokeBody::Raw(bytes) => bytes.clone(),
+        tauri::ipc::InvokeBody::Json(serde_json::Value::Null) => Vec::new(),
+        tauri::ipc::InvokeBody::Json(_) => {
+            return Err("request body must be an ArrayBuffer or Uint8Array".to_string())
+        }
+    };
+
+    let (status, body) = self::request(&app, &method, &path, &content_type, body).await?;
+    check_status(status, &body)?;
+    Ok(tauri::ipc::Response::new(body))
+}
+
+/// 把本地文件作为请求体发送给后端，返回响应文本
+#[tauri::command]
+pub async fn backend_upload_file(
+    app: tauri::AppHandle,
+    path: String,
+    file_path: String,
+    method: Option<String>,
+) -> Result<String, String> {
+    policy::check_path(std::path::Path::new(&file_path))?;
+    let data = tokio::fs::read(&file_path)
+        .await
+        .map_err(|e| format!("无法读取文件: {}", e))?;
+    let method = method.unwrap_or_else(|| "POST".to_string());
+    let (status, body) = request(&app, &method, &path, OCTET_STREAM, data).await?;
+    check_status(status, &body)?;
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+/// 把后端响应体直接写入本地文件，返回写入的字节数
+#[tauri::command]
+pub async fn backend_download_file(
+    app: tauri::AppHandle,
+    path: String,
+    file_path: String,
+) -> Result<u64, String> {
+    approvals::approve_write(&app, "fs.download", &file_path).await?;
+    let (status, body) = request(&app, "GET", &path, OCTET_STREAM, Vec::new()).await?;
+    check_status(status, &body)?;
+    let result = tokio::fs::write(&file_path, &body)
+        .await
+        .map_err(|e| format!("无法写入文件: {}", e));
+    timeline::record(
+        &app,
+        timeline::TimelineKind::FileWrite,
+        "fs.download",
+        result.is_ok(),
+        serde_json::json!({ "path": file_path, "source": path, "bytes": body.len() }),
+    );
+    result?;
+    Ok(body.len() as u64)
+}
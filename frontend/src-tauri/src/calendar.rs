@@ -0,0 +1,280 @@
+// ============================================================================
+// 系统日历读取（calendar.read）
+// ============================================================================
+//
+// macOS: 通过 osascript (JXA) 调用 EventKit，读取系统日历（含 iCloud / Exchange /
+// Google 等已在"日历"App 中登录的账户），无需再向 Agent 提供云端日历凭据。
+// 首次读取时弹出系统授权框；被拒绝后需在"系统设置 → 隐私与安全性 → 日历"中开启
+// （open_system_preferences("calendars")）。其它平台暂不支持。
+
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "macos")]
+use std::process::Command as SysCommand;
+
+/// 单次查询允许的最大时间跨度（天）
+const MAX_RANGE_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarInfo {
+    pub id: String,
+    pub title: String,
+    /// 所属账户（如 "iCloud"、"Exchange"）
+    pub source: Option<String>,
+    pub writable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub id: String,
+    pub title: String,
+    /// 开始 / 结束时间（RFC 3339，本地时区）
+    pub start: String,
+    pub end: String,
+    pub all_day: bool,
+    pub location: Option<String>,
+    pub notes: Option<String>,
+    pub url: Option<String>,
+    pub calendar_id: String,
+    pub calendar: String,
+}
+
+/// JXA 输出的原始事件（时间为 Unix 秒）
+#[derive(Deserialize)]
+struct RawEvent {
+    id: String,
+    title: Option<String>,
+    start: f64,
+    end: f64,
+    all_day: bool,
+    location: Option<String>,
+    notes: Option<String>,
+    url: Option<String>,
+    calendar_id: String,
+    calendar: String,
+}
+
+#[cfg(target_os = "macos")]
+const EVENTKIT_SCRIPT: &str = r#"
+ObjC.import('Foundation');
+ObjC.import('EventKit');
+const EVENT = 0;
+const STATUS = ['not_determined', 'restricted', 'denied', 'authorized', 'write_only'];
+function str(v) {
+  try { const s = v.js; return (s === undefined || s === null || s === '') ? null : s; } catch (e) { return null; }
+}
+function calendars(store, wanted) {
+  const all = store.calendarsForEntityType(EVENT);
+  const out = [];
+  for (let i = 0; i < all.count; i++) {
+    const c = all.objectAtIndex(i);
+    if (wanted.length === 0 || wanted.indexOf(c.calendarIdentifier.js) >= 0) out.push(c);
+  }
+  return out;
+}
+function run(argv) {
+  const mode = argv[0];
+  if (mode === 'status') {
+    return JSON.stringify(STATUS[$.EKEventStore.authorizationStatusForEntityType(EVENT)] || 'unknown');
+  }
+  const store = $.EKEventStore.alloc.init;
+  if (mode === 'request') {
+    let done = false, granted = false;
+    const handler = function (ok, err) { granted = ok; done = true; };
+    try {
+      store.requestFullAccessToEventsWithCompletion(handler);
+    } catch (e) {
+      store.requestAccessToEntityTypeCompletion(EVENT, handler);
+    }
+    const deadline = $.NSDate.dateWithTimeIntervalSinceNow(300);
+    while (!done && $.NSDate.date.compare(deadline) < 0) {
+      $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.2));
+    }
+    return JSON.stringify(granted);
+  }
+  const wanted = argv.length > 3 && argv[3].length > 0 ? JSON.parse(argv[3]) : [];
+  const cals = calendars(store, wanted);
+  if (mode === 'calendars') {
+    return JSON.stringify(cals.map(c => ({
+      id: c.calendarIdentifier.js,
+      title: c.title.js,
+      source: c.source ? str(c.source.title) : null,
+      writable: c.allowsContentModifications
+    })));
+  }
+  if (cals.length === 0) return '[]';
+  const start = $.NSDate.dateWithTimeIntervalSince1970(Number(argv[1]));
+  const end = $.NSDate.dateWithTimeIntervalSince1970(Number(argv[2]));
+  const events = store.eventsMatchingPredicate(
+    store.predicateForEventsWithStartDateEndDateCalendars(start, end, $(cals)));
+  const out = [];
+  for (let i = 0; i < events.count; i++) {
+    const e = events.objectAtIndex(i);
+    out.push({
+      id: e.eventIdentifier.js,
+      title: str(e.title),
+      start: e.startDate.timeIntervalSince1970,
+      end: e.endDate.timeIntervalSince1970,
+      all_day: e.allDay,
+      location: str(e.location),
+      notes: str(e.notes),
+      url: e.URL ? str(e.URL.absoluteString) : null,
+      calendar_id: e.calendar.calendarIdentifier.js,
+      calendar: e.calendar.title.js
+    });
+  }
+  return JSON.stringify(out);
+}
+"#;
+
+/// 执行 EventKit 脚本，返回 stdout（JSON）
+#[cfg(target_os = "macos")]
+fn run_eventkit(args: &[&str]) -> Result<String, String> {
+    let output = SysCommand::new("osascript")
+        .args(["-l", "JavaScript", "-e", EVENTKIT_SCRIPT])
+        .args(args)
+        .output()
+        .map_err(|e| format!("读取日历失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "读取日历失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn run_eventkit(_args: &[&str]) -> Result<String, String> {
+    Err("Calendar access not supported on this platform".to_string())
+}
+
+fn run_json<T: serde::de::DeserializeOwned>(args: &[&str]) -> Result<T, String> {
+    let output = run_eventkit(args)?;
+    serde_json::from_str(output.trim()).map_err(|e| format!("解析日历数据失败: {}", e))
+}
+
+/// 确保已获得日历权限（未询问过时弹出系统授权框）
+fn ensure_access() -> Result<(), String> {
+    let status: String = run_json(&["status"])?;
+    match status.as_str() {
+        "authorized" => Ok(()),
+        "not_determined" => {
+            let granted: bool = run_json(&["request"])?;
+            if granted {
+                Ok(())
+            } else {
+                Err("Calendar access was not granted".to_string())
+            }
+        }
+        "write_only" => Err(
+            "Calendar access is write-only; grant full access in System Settings > Privacy & Security > Calendars"
+                .to_string(),
+        ),
+        _ => Err(
+            "Calendar access denied; enable it in System Settings > Privacy & Security > Calendars"
+                .to_string(),
+        ),
+    }
+}
+
+fn to_local(secs: f64) -> String {
+    let millis = (secs * 1000.0).round() as i64;
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default()
+}
+
+fn parse_time(value: &str) -> Result<DateTime<chrono::FixedOffset>, String> {
+    DateTime::parse_from_rfc3339(value).map_err(|e| format!("无效的时间格式: {}", e))
+}
+
+/// 查询日历权限状态
+///
+/// 返回 not_determined / restricted / denied / authorized / write_only，
+/// 不支持的平台返回 unsupported。
+#[tauri::command]
+pub async fn get_calendar_permission() -> Result<String, String> {
+    if !cfg!(target_os = "macos") {
+        return Ok("unsupported".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(|| run_json::<String>(&["status"]))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 请求日历权限（已询问过时直接返回当前是否可读）
+#[tauri::command]
+pub async fn request_calendar_access() -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(|| Ok(ensure_access().is_ok()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 列出系统日历
+#[tauri::command]
+pub async fn list_calendars() -> Result<Vec<CalendarInfo>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        ensure_access()?;
+        run_json(&["calendars", "", "", ""])
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 列出时间范围内的日程（按开始时间排序）
+///
+/// `start` / `end` 为 RFC 3339 时间，跨度不超过 366 天；
+/// `calendar_ids` 为空时查询全部日历。
+#[tauri::command]
+pub async fn list_events(
+    start: String,
+    end: String,
+    calendar_ids: Option<Vec<String>>,
+) -> Result<Vec<CalendarEvent>, String> {
+    let (from, to) = (parse_time(&start)?, parse_time(&end)?);
+    if to <= from {
+        return Err("End time must be after start time".to_string());
+    }
+    if to - from > chrono::Duration::days(MAX_RANGE_DAYS) {
+        return Err(format!("Time range cannot exceed {} days", MAX_RANGE_DAYS));
+    }
+    let calendars =
+        serde_json::to_string(&calendar_ids.unwrap_or_default()).map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        ensure_access()?;
+        let raw: Vec<RawEvent> = run_json(&[
+            "events",
+            &from.timestamp().to_string(),
+            &to.timestamp().to_string(),
+            &calendars,
+        ])?;
+        let mut events: Vec<(f64, CalendarEvent)> = raw
+            .into_iter()
+            .map(|e| {
+                (
+                    e.start,
+                    CalendarEvent {
+                        id: e.id,
+                        title: e.title.unwrap_or_default(),
+                        start: to_local(e.start),
+                        end: to_local(e.end),
+                        all_day: e.all_day,
+                        location: e.location,
+                        notes: e.notes,
+                        url: e.url,
+                        calendar_id: e.calendar_id,
+                        calendar: e.calendar,
+                    },
+                )
+            })
+            .collect();
+        events.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(events.into_iter().map(|(_, e)| e).collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,303 @@
+// ============================================================================
+// 节点能力开关
+// ============================================================================
+//
+// 设置 disabled_capabilities 中的能力会从 NodeInfo.capabilities 中移除，
+// 并由 permissions 在调用层直接拒绝对应的 Tauri 命令（前端、远程调用、计划任务均无法绕过）。
+// 条目可以是完整能力名（"camera.snap"）或分组前缀（"camera" 关闭全部 camera.*）。
+// 由后端执行的能力（system.run、摄像头、录屏等）同步给后端，由后端拒绝调用：启动 sidecar 时
+// 通过环境变量 NODE_DISABLED_CAPABILITIES 传入，开关变化时通过后端设置接口更新。同步的是
+// 用户关闭、安全模式与托管策略关闭的能力合集。
+// 安全模式（设置 safe_mode）额外关闭 policy::SAFE_MODE_DISABLED_CAPABILITIES，关闭后恢复原有开关。
+// 重新开启能力与关闭安全模式都需要系统身份验证。
+
+use crate::{audit, auth, debug_log, events, http, policy, settings};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityState {
+    pub capability: String,
+    pub enabled: bool,
+    /// 被托管策略关闭，不能在应用内开启
+    pub locked: bool,
+}
+
+/// 本平台支持的全部能力
+pub fn supported() -> Vec<String> {
+    let mut capabilities = vec![
+        "system.run".to_string(),
+        "system.which".to_string(),
+        "system.notify".to_string(),
+        "system.speak".to_string(),
+        "system.processes".to_string(),
+        "system.apps".to_string(),
+        "system.volume".to_string(),
+        "system.print".to_string(),
+        "fs.read".to_string(),
+        "fs.write".to_string(),
+        "git.read".to_string(),
+        "git.write".to_string(),
+        "system.power".to_string(),
+        "system.display".to_string(),
+        "system.network".to_string(),
+        "system.usb".to_string(),
+        "system.recent".to_string(),
+        "remote.control".to_string(),
+        "remote.invoke".to_string(),
+        "webhook.serve".to_string(),
+        "backend.proxy".to_string(),
+        "tools.manage".to_string(),
+        "projects.manage".to_string(),
+    ];
+
+    #[cfg(target_os = "macos")]
+    {
+        capabilities.push("camera.snap".to_string());
+        capabilities.push("camera.list".to_string());
+        capabilities.push("screen.record".to_string());
+        capabilities.push("location.get".to_string());
+        capabilities.push("screen.ocr".to_string());
+        capabilities.push("calendar.read".to_string());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        capabilities.push("camera.snap".to_string());
+        capabilities.push("camera.list".to_string());
+        capabilities.push("screen.ocr".to_string());
+    }
+
+    // Canvas capabilities (all platforms)
+    capabilities.push("canvas.present".to_string());
+    capabilities.push("canvas.hide".to_string());
+    capabilities.push("canvas.navigate".to_string());
+    capabilities.push("canvas.eval".to_string());
+    capabilities.push("canvas.snapshot".to_string());
+
+    capabilities
+}
+
+/// 能力是否被禁用（完整名称或分组前缀匹配）
+fn is_disabled(disabled: &[String], capability: &str) -> bool {
+    disabled.iter().any(|d| {
+        capability == d
+            || capability
+                .strip_prefix(d.as_str())
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+/// 被关闭的能力：用户关闭的能力，加上安全模式与托管策略关闭的能力
+fn disabled(settings: &settings::AppSettings) -> Vec<String> {
+    let mut disabled = settings.disabled_capabilities.clone();
+    if settings.safe_mode {
+        disabled.extend(
+            policy::SAFE_MODE_DISABLED_CAPABILITIES
+                .iter()
+                .map(|c| c.to_string()),
+        );
+    }
+    disabled.extend(policy::disabled_capabilities(&policy::current()));
+    let mut seen = std::collections::HashSet::new();
+    disabled.retain(|c| seen.insert(c.clone()));
+    disabled
+}
+
+/// 后端环境变量 NODE_DISABLED_CAPABILITIES 的值（启动 sidecar 时传入）
+pub fn backend_disabled(settings: &settings::AppSettings) -> String {
+    disabled(settings).join(",")
+}
+
+/// 把当前生效的能力开关同步给后端（后端未就绪时只记录日志，下次启动时由环境变量带入）
+async fn sync_backend(app: &tauri::AppHandle) {
+    let body = serde_json::json!({
+        "node": { "NODE_DISABLED_CAPABILITIES": backend_disabled(&settings::current(app)) }
+    });
+    let (success, output) = http::call_backend(
+        app,
+        "PUT",
+        "/api/v1/settings",
+        Some(&body),
+        http::BACKEND_TIMEOUT,
+    )
+    .await;
+    if !success {
+        debug_log(&format!(
+            "[capabilities] 同步能力开关到后端失败: {}",
+            output
+        ));
+    }
+}
+
+/// 过滤掉被禁用的能力
+pub fn enabled(settings: &settings::AppSettings) -> Vec<String> {
+    let disabled = disabled(settings);
+    supported()
+        .into_iter()
+        .filter(|c| !is_disabled(&disabled, c))
+        .collect()
+}
+
+/// 能力当前是否可用
+pub fn is_enabled(app: &tauri::AppHandle, capability: &str) -> bool {
+    !is_disabled(&disabled(&settings::current(app)), capability)
+}
+
+/// 列出本平台支持的能力及其开关状态
+#[tauri::command]
+pub async fn list_capabilities(app: tauri::AppHandle) -> Result<Vec<CapabilityState>, String> {
+    let disabled = disabled(&settings::current(&app));
+    let locked = policy::disabled_capabilities(&policy::current());
+    Ok(supported()
+        .into_iter()
+        .map(|capability| CapabilityState {
+            enabled: !is_disabled(&disabled, &capability),
+            locked: is_disabled(&locked, &capability),
+            capability,
+        })
+        .collect())
+}
+
+/// 开启或关闭能力（可传分组前缀，如 "camera"），返回关闭的能力列表
+///
+/// 开启前需要系统身份验证；修改后重新广播 mDNS，并同步给后端（后端未就绪时只记录日志）。
+#[tauri::command]
+pub async fn set_capability_enabled(
+    app: tauri::AppHandle,
+    capability: String,
+    enabled: bool,
+) -> Result<Vec<String>, String> {
+    let capability = capability.trim().to_string();
+    let known = supported()
+        .iter()
+        .any(|c| c == &capability || is_disabled(&[capability.clone()], c));
+    if !known {
+        return Err(format!("Unknown capability: {}", capability));
+    }
+    let locked = policy::disabled_capabilities(&policy::current());
+    if enabled && is_disabled(&locked, &capability) {
+        return Err(format!("Capability locked by policy: {}", capability));
+    }
+    if enabled {
+        auth::require_auth(&app, "capabilities").await?;
+    }
+
+    let updated = {
+        let state = app.state::<Mutex<settings::AppSettings>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        let mut updated = guard.clone();
+        if enabled {
+            let mut next = Vec::new();
+            for d in &updated.disabled_capabilities {
+                if is_disabled(&[capability.clone()], d) {
+                    // 该能力本身，或开启分组时其下的单项
+                    continue;
+                }
+                if is_disabled(&[d.clone()], &capability) {
+                    // 覆盖该能力的分组：展开为分组内其余能力，保持它们关闭
+                    next.extend(supported().into_iter().filter(|c| {
+                        is_disabled(&[d.clone()], c) && !is_disabled(&[capability.clone()], c)
+                    }));
+                    continue;
+                }
+                next.push(d.clone());
+            }
+            updated.disabled_capabilities = next;
+        } else if !is_disabled(&updated.disabled_capabilities, &capability) {
+            updated.disabled_capabilities.push(capability.clone());
+        }
+        // 开启分组时保留策略关闭的单项
+        policy::apply(&mut updated);
+        settings::save_settings(&updated)?;
+        *guard = updated.clone();
+        updated
+    };
+    debug_log(&format!(
+        "[capabilities] {} {}",
+        capability,
+        if enabled { "已开启" } else { "已关闭" }
+    ));
+    let _ = app.emit("settings-changed", &updated);
+
+    crate::discovery::refresh_advertising(&app);
+    sync_backend(&app).await;
+
+    Ok(updated.disabled_capabilities)
+}
+
+/// 开启或关闭安全模式（关闭 policy::SAFE_MODE_DISABLED_CAPABILITIES），返回当前状态
+///
+/// 托管策略强制安全模式时不能关闭；关闭前需要系统身份验证。
+#[tauri::command]
+pub async fn set_safe_mode(app: tauri::AppHandle, enabled: bool) -> Result<bool, String> {
+    if settings::current(&app).safe_mode == enabled {
+        return Ok(enabled);
+    }
+    if !enabled {
+        if policy::is_locked_setting("safe_mode") {
+            return Err("Setting locked by policy: safe_mode".to_string());
+        }
+        auth::require_auth(&app, "safe_mode").await?;
+    }
+    let updated = {
+        let state = app.state::<Mutex<settings::AppSettings>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        if guard.safe_mode == enabled {
+            return Ok(enabled);
+        }
+        guard.safe_mode = enabled;
+        settings::save_settings(&guard)?;
+        guard.clone()
+    };
+    debug_log(&format!(
+        "[capabilities] 安全模式{}",
+        if enabled { "已开启" } else { "已关闭" }
+    ));
+    audit::record(
+        &app,
+        "settings.safe_mode",
+        true,
+        serde_json::json!({ "enabled": enabled }),
+    );
+    events::emit(
+        &app,
+        "safe-mode-changed",
+        serde_json::json!({ "enabled": enabled }),
+    );
+    let _ = app.emit("settings-changed", &updated);
+    crate::discovery::refresh_advertising(&app);
+    #[cfg(target_os = "macos")]
+    crate::menu::refresh(&app);
+    sync_backend(&app).await;
+    Ok(enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn is_disabled_matches_full_name_and_group_prefix() {
+        let disabled = names(&["screen", "system.run"]);
+        assert!(is_disabled(&disabled, "screen"));
+        assert!(is_disabled(&disabled, "screen.record"));
+        assert!(is_disabled(&disabled, "system.run"));
+        assert!(is_disabled(&disabled, "system.run.elevated"));
+    }
+
+    #[test]
+    fn is_disabled_ignores_partial_names() {
+        let disabled = names(&["screen", "system.run"]);
+        assert!(!is_disabled(&disabled, "screenshot"));
+        assert!(!is_disabled(&disabled, "system"));
+        assert!(!is_disabled(&disabled, "system.runner"));
+        assert!(!is_disabled(&disabled, "system.notify"));
+        assert!(!is_disabled(&[], "screen.record"));
+    }
+}
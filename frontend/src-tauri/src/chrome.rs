@@ -0,0 +1,131 @@
+// ============================================================================
+// 窗口外观：毛玻璃材质、透明圆角背景、红绿灯按钮位置
+// ============================================================================
+//
+// 前端只声明想要的外观（设置 window_chrome），平台细节由这里处理：
+// - vibrancy：macOS NSVisualEffectView 材质 / Windows 11 Mica、Acrylic，窗口背景透明
+// - corner_radius：macOS 毛玻璃背景的圆角
+// - traffic_light_inset：macOS 隐藏标题栏、内容延伸到标题栏下方，红绿灯按钮移到指定位置
+// 材质与圆角可即时切换；透明背景与标题栏样式只能在创建窗口时设置，变化后需重启应用。
+// 作用于主窗口（启动时创建）以及传入 label 的其它窗口（如状态浮窗）。
+
+use crate::{debug_log, settings};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::window::{Effect, EffectState, EffectsBuilder};
+use tauri::Manager;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowChrome {
+    /// 材质：macOS sidebar / under_window / hud / popover / menu / titlebar / content / window；
+    /// Windows mica / acrylic / blur / tabbed；None 表示不使用
+    pub vibrancy: Option<String>,
+    /// 毛玻璃背景圆角（逻辑像素，仅 macOS）
+    pub corner_radius: Option<f64>,
+    /// 红绿灯按钮位置（逻辑像素，仅 macOS），None 表示系统默认标题栏
+    pub traffic_light_inset: Option<(f64, f64)>,
+}
+
+impl WindowChrome {
+    /// 需要在创建窗口时确定的部分（透明背景、标题栏样式）是否相同
+    fn same_window_config(&self, other: &Self) -> bool {
+        self.vibrancy.is_some() == other.vibrancy.is_some()
+            && self.traffic_light_inset == other.traffic_light_inset
+    }
+}
+
+fn effect(name: &str) -> Option<Effect> {
+    Some(match name {
+        "sidebar" => Effect::Sidebar,
+        "under_window" => Effect::UnderWindowBackground,
+        "hud" => Effect::HudWindow,
+        "popover" => Effect::Popover,
+        "menu" => Effect::Menu,
+        "titlebar" => Effect::Titlebar,
+        "content" => Effect::ContentBackground,
+        "window" => Effect::WindowBackground,
+        "mica" => Effect::Mica,
+        "acrylic" => Effect::Acrylic,
+        "blur" => Effect::Blur,
+        "tabbed" => Effect::Tabbed,
+        _ => return None,
+    })
+}
+
+/// 创建窗口前应用透明背景与标题栏样式
+pub fn configure<'a, R: tauri::Runtime, M: Manager<R>>(
+    builder: tauri::WebviewWindowBuilder<'a, R, M>,
+    chrome: &WindowChrome,
+) -> tauri::WebviewWindowBuilder<'a, R, M> {
+    let builder = builder.transparent(chrome.vibrancy.is_some());
+
+    #[cfg(target_os = "macos")]
+    if let Some((x, y)) = chrome.traffic_light_inset {
+        return builder
+            .title_bar_style(tauri::TitleBarStyle::Overlay)
+            .hidden_title(true)
+            .traffic_light_position(tauri::LogicalPosition::new(x, y));
+    }
+
+    builder
+}
+
+/// 应用材质与圆角（可在窗口创建后随时调用）
+pub fn apply_effects(window: &tauri::WebviewWindow, chrome: &WindowChrome) -> Result<(), String> {
+    let effects = match &chrome.vibrancy {
+        Some(name) => {
+            let effect =
+                effect(name).ok_or_else(|| format!("Unknown vibrancy material: {}", name))?;
+            let mut builder = EffectsBuilder::new()
+                .effect(effect)
+                .state(EffectState::Active);
+            if let Some(radius) = chrome.corner_radius {
+                builder = builder.radius(radius);
+            }
+            Some(builder.build())
+        }
+        None => None,
+    };
+    window.set_effects(effects).map_err(|e| e.to_string())
+}
+
+/// 获取窗口外观设置
+#[tauri::command]
+pub async fn get_window_chrome(app: tauri::AppHandle) -> Result<WindowChrome, String> {
+    Ok(settings::current(&app).window_chrome)
+}
+
+/// 修改窗口外观，返回是否需要重启应用才能完全生效
+#[tauri::command]
+pub async fn set_window_chrome(
+    app: tauri::AppHandle,
+    chrome: WindowChrome,
+    label: Option<String>,
+) -> Result<bool, String> {
+    if let Some(name) = &chrome.vibrancy {
+        effect(name).ok_or_else(|| format!("Unknown vibrancy material: {}", name))?;
+    }
+    let label = label.unwrap_or_else(|| "main".to_string());
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window not found: {}", label))?;
+
+    // 只有主窗口的外观保存到设置
+    let restart_required = if label == "main" {
+        let state = app.state::<Mutex<settings::AppSettings>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        let previous = std::mem::replace(&mut guard.window_chrome, chrome.clone());
+        settings::save_settings(&guard)?;
+        !previous.same_window_config(&chrome)
+    } else {
+        false
+    };
+
+    apply_effects(&window, &chrome)?;
+    debug_log(&format!(
+        "[chrome] 窗口 {} 外观: {:?}（需重启: {}）",
+        label, chrome, restart_required
+    ));
+    Ok(restart_required)
+}
@@ -0,0 +1,301 @@
+// ============================================================================
+// 崩溃报告：Rust panic 与 sidecar 异常退出
+// ============================================================================
+//
+// 崩溃时在数据目录的 crash-reports/ 下写入一份 JSON 报告（错误信息、调用栈、
+// 最近 200 行日志、应用与系统版本）。下次启动时询问用户是否发送，
+// 只有用户同意（或在设置中选择"总是发送"）才会上传。
+// panic 可能发生在 Tauri 应用创建之前，因此报告目录与日志缓冲不依赖 AppHandle。
+
+use crate::settings::{self, CrashUploadConsent};
+use crate::{debug_log, i18n};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+
+/// 报告目录（数据目录下）
+const CRASH_DIR: &str = "crash-reports";
+
+/// 报告中附带的最近日志行数
+const LOG_TAIL_LINES: usize = 200;
+
+/// 本地最多保留的报告数
+const MAX_REPORTS: usize = 20;
+
+/// 上传超时
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    /// Rust 壳层 panic
+    Panic,
+    /// sidecar 后端异常退出
+    Sidecar,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: CrashKind,
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub os_version: Option<String>,
+    pub arch: String,
+    pub message: String,
+    /// panic 发生的位置（file:line）
+    pub location: Option<String>,
+    pub backtrace: Option<String>,
+    /// sidecar 退出码 / 信号
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub log_tail: Vec<String>,
+    /// 已询问过用户（无论是否同意）
+    #[serde(default)]
+    pub prompted: bool,
+    #[serde(default)]
+    pub uploaded: bool,
+}
+
+fn log_tail_buffer() -> &'static Mutex<VecDeque<String>> {
+    static BUFFER: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
+    BUFFER.get_or_init(|| Mutex::new(VecDeque::with_capacity(LOG_TAIL_LINES)))
+}
+
+/// 记录一行日志到内存缓冲（由 debug_log 调用）
+pub fn remember_log_line(line: &str) {
+    let mut buffer = log_tail_buffer()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if buffer.len() >= LOG_TAIL_LINES {
+        buffer.pop_front();
+    }
+    buffer.push_back(crate::logging::redact(line).into_owned());
+}
+
+/// 最近的日志（panic 时若缓冲正被占用则返回空，避免死锁）
+fn log_tail() -> Vec<String> {
+    match log_tail_buffer().try_lock() {
+        Ok(buffer) => buffer.iter().cloned().collect(),
+        Err(std::sync::TryLockError::Poisoned(p)) => p.into_inner().iter().cloned().collect(),
+        Err(std::sync::TryLockError::WouldBlock) => Vec::new(),
+    }
+}
+
+fn crash_dir() -> PathBuf {
+    settings::app_data_dir().join(CRASH_DIR)
+}
+
+fn report_path(id: &str) -> PathBuf {
+    crash_dir().join(format!("{}.json", id))
+}
+
+fn new_report(kind: CrashKind, message: String) -> CrashReport {
+    let now = chrono::Local::now();
+    CrashReport {
+        id: format!(
+            "{}-{}",
+            now.format("%Y%m%d-%H%M%S"),
+            &uuid::Uuid::new_v4().to_string()[..8]
+        ),
+        kind,
+        created_at: now.to_rfc3339(),
+        app_version: crate::app_info::APP_VERSION.to_string(),
+        os: std::env::consts::OS.to_string(),
+        os_version: sysinfo::System::long_os_version(),
+        arch: std::env::consts::ARCH.to_string(),
+        message,
+        location: None,
+        backtrace: None,
+        exit_code: None,
+        signal: None,
+        log_tail: log_tail(),
+        prompted: false,
+        uploaded: false,
+    }
+}
+
+fn save_report(report: &CrashReport) -> Result<(), String> {
+    std::fs::create_dir_all(crash_dir()).map_err(|e| format!("创建崩溃报告目录失败: {}", e))?;
+    let content = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    std::fs::write(report_path(&report.id), content).map_err(|e| format!("写入崩溃报告失败: {}", e))
+}
+
+/// 读取全部报告（按时间倒序）
+fn load_reports() -> Vec<CrashReport> {
+    let Ok(entries) = std::fs::read_dir(crash_dir()) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|c| serde_json::from_str(&c).ok())
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    reports
+}
+
+/// 只保留最近 MAX_REPORTS 份报告
+fn prune_reports() {
+    for report in load_reports().into_iter().skip(MAX_REPORTS) {
+        let _ = std::fs::remove_file(report_path(&report.id));
+    }
+}
+
+/// 安装 panic hook（在 main 开头调用），写入报告后交给默认 hook 继续处理
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+
+        let message = crate::logging::redact(&message).into_owned();
+        let mut report = new_report(CrashKind::Panic, message);
+        report.location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()));
+        report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+        if let Err(e) = save_report(&report) {
+            eprintln!("[crash] {}", e);
+        }
+
+        default_hook(info);
+    }));
+}
+
+/// 记录 sidecar 异常退出
+pub fn record_sidecar_crash(exit_code: Option<i32>, signal: Option<i32>, reason: &str) {
+    let mut report = new_report(CrashKind::Sidecar, crate::logging::redact(reason).into_owned());
+    report.exit_code = exit_code;
+    report.signal = signal;
+    match save_report(&report) {
+        Ok(()) => debug_log(&format!("[crash] 已记录后端崩溃报告 {}", report.id)),
+        Err(e) => debug_log(&format!("[crash] {}", e)),
+    }
+    prune_reports();
+}
+
+async fn upload(url: &str, report: &CrashReport) -> Result<(), String> {
+    reqwest::Client::new()
+        .post(url)
+        .timeout(UPLOAD_TIMEOUT)
+        .json(report)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("上传崩溃报告失败: {}", e))?;
+    Ok(())
+}
+
+/// 上传一份报告并标记为已上传
+async fn send(app: &tauri::AppHandle, mut report: CrashReport) -> Result<(), String> {
+    let url = settings::current(app)
+        .crash_report_url
+        .ok_or("Crash report upload is not configured")?;
+    upload(&url, &report).await?;
+    report.uploaded = true;
+    report.prompted = true;
+    save_report(&report)?;
+    debug_log(&format!("[crash] 已上传崩溃报告 {}", report.id));
+    Ok(())
+}
+
+fn mark_prompted(reports: &[CrashReport]) {
+    for report in reports {
+        let mut report = report.clone();
+        report.prompted = true;
+        let _ = save_report(&report);
+    }
+}
+
+/// 启动时处理上次运行留下的报告：按设置询问 / 直接上传 / 忽略
+pub fn handle_pending_reports(app: &tauri::AppHandle) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    prune_reports();
+    let pending: Vec<CrashReport> = load_reports()
+        .into_iter()
+        .filter(|r| !r.prompted && !r.uploaded)
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+
+    let s = settings::current(app);
+    if s.crash_report_url.is_none() {
+        // 没有配置上传地址，报告仅保留在本地
+        return;
+    }
+    debug_log(&format!(
+        "[crash] 发现 {} 份未发送的崩溃报告",
+        pending.len()
+    ));
+
+    let send_all = {
+        let app = app.clone();
+        move |reports: Vec<CrashReport>| {
+            tauri::async_runtime::spawn(async move {
+                for report in reports {
+                    if let Err(e) = send(&app, report).await {
+                        debug_log(&format!("[crash] {}", e));
+                    }
+                }
+            });
+        }
+    };
+
+    match s.crash_upload_consent {
+        CrashUploadConsent::Never => mark_prompted(&pending),
+        CrashUploadConsent::Always => send_all(pending),
+        CrashUploadConsent::Ask => {
+            app.dialog()
+                .message(i18n::tf("dialog.crash_report.body", &[&pending.len()]))
+                .title(i18n::t("dialog.crash_report.title"))
+                .kind(MessageDialogKind::Info)
+                .buttons(MessageDialogButtons::OkCancelCustom(
+                    i18n::t("dialog.crash_report.send"),
+                    i18n::t("dialog.crash_report.dont_send"),
+                ))
+                .show(move |confirmed| {
+                    if confirmed {
+                        send_all(pending);
+                    } else {
+                        mark_prompted(&pending);
+                    }
+                });
+        }
+    }
+}
+
+/// 列出本地崩溃报告
+#[tauri::command]
+pub async fn list_crash_reports() -> Result<Vec<CrashReport>, String> {
+    Ok(load_reports())
+}
+
+/// 发送指定的崩溃报告（用户在界面中手动确认）
+#[tauri::command]
+pub async fn send_crash_report(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let report = load_reports()
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("Crash report not found: {}", id))?;
+    send(&app, report).await
+}
+
+/// 删除指定的崩溃报告
+#[tauri::command]
+pub async fn delete_crash_report(id: String) -> Result<(), String> {
+    if id.contains(['/', '\\']) || id.contains("..") {
+        return Err("Invalid crash report id".to_string());
+    }
+    std::fs::remove_file(report_path(&id)).map_err(|e| format!("删除崩溃报告失败: {}", e))
+}
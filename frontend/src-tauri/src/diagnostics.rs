@@ -0,0 +1,89 @@
+// ============================================================================
+// 诊断信息导出
+// ============================================================================
+//
+// export_diagnostics 把排查问题需要的信息导出到一个目录，用户可直接打包发给支持人员：
+// app_info.json、settings.json、self_test.json、backend_health.json、crash_reports.json、
+// 以及 logs/ 下的日志。所有文件导出前都经过脱敏（logging::redact / redact_json）：
+// 日志写入时虽已脱敏，但开启 redact_home_path 之前写入的内容仍含主目录与用户名。
+// 由"关于"窗口的按钮调用（见 about.rs）。
+
+use crate::{app_info, approvals, crash, debug_log, health, logging, selftest, settings};
+use std::path::Path;
+
+/// 脱敏后写入 JSON
+fn write_json<T: serde::Serialize>(dir: &Path, name: &str, value: &T) -> Result<(), String> {
+    let value = logging::redact_json(serde_json::to_value(value).map_err(|e| e.to_string())?);
+    let content = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(name), content).map_err(|e| format!("写入 {} 失败: {}", name, e))
+}
+
+/// 脱敏后复制日志
+fn copy_log(from: &Path, to: &Path) -> std::io::Result<()> {
+    let content = std::fs::read(from)?;
+    std::fs::write(
+        to,
+        logging::redact(&String::from_utf8_lossy(&content)).as_bytes(),
+    )
+}
+
+/// 导出诊断信息到 dir（默认下载目录）下的新目录，返回该目录路径
+#[tauri::command]
+pub async fn export_diagnostics(
+    app: tauri::AppHandle,
+    dir: Option<String>,
+) -> Result<String, String> {
+    let parent = match dir {
+        Some(dir) => dir.into(),
+        None => dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .ok_or("无法确定导出目录")?,
+    };
+    let target = parent.join(format!(
+        "xiaodazi-diagnostics-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    approvals::approve_write(&app, "diagnostics.export", &target.display().to_string()).await?;
+    std::fs::create_dir_all(&target).map_err(|e| format!("创建导出目录失败: {}", e))?;
+
+    write_json(&target, "app_info.json", &app_info::collect(&app))?;
+    write_json(&target, "settings.json", &settings::current(&app))?;
+    // 单项失败时记录错误，不影响其它内容导出
+    let self_test = selftest::run_self_test(app.clone()).await.map_or_else(
+        |e| serde_json::json!({ "error": e }),
+        |r| serde_json::json!(r),
+    );
+    write_json(&target, "self_test.json", &self_test)?;
+    let health = health::get_backend_health_history(app.clone())
+        .await
+        .map_or_else(
+            |e| serde_json::json!({ "error": e }),
+            |h| serde_json::json!(h),
+        );
+    write_json(&target, "backend_health.json", &health)?;
+    write_json(
+        &target,
+        "crash_reports.json",
+        &crash::list_crash_reports().await.unwrap_or_default(),
+    )?;
+
+    let logs = target.join(logging::LOG_DIR);
+    std::fs::create_dir_all(&logs).map_err(|e| format!("创建导出目录失败: {}", e))?;
+    for file in logging::log_files() {
+        if let Some(name) = file.file_name() {
+            if let Err(e) = copy_log(&file, &logs.join(name)) {
+                debug_log(&format!(
+                    "[diagnostics] 复制日志失败 {}: {}",
+                    file.display(),
+                    e
+                ));
+            }
+        }
+    }
+
+    debug_log(&format!(
+        "[diagnostics] 已导出诊断信息: {}",
+        target.display()
+    ));
+    Ok(target.display().to_string())
+}
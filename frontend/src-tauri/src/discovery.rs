@@ -0,0 +1,201 @@
+// ============================================================================
+// 局域网节点发现（mDNS / Bonjour）
+// ============================================================================
+//
+// 每个节点以 _zenflux._tcp 服务广播自身（node_id、端口、能力列表），
+// discover_nodes 浏览同类服务，返回局域网内的其他节点。
+
+use crate::debug_log;
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+/// mDNS 服务类型
+const SERVICE_TYPE: &str = "_zenflux._tcp.local.";
+
+/// discover_nodes 默认浏览时长（毫秒）
+const DEFAULT_DISCOVERY_MS: u64 = 3000;
+
+/// mDNS 守护进程（广播与浏览共用）
+#[derive(Default)]
+pub struct Discovery {
+    daemon: Mutex<Option<ServiceDaemon>>,
+    /// 当前广播的端口（节点信息变化时用于重新广播）
+    port: Mutex<Option<u16>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerNode {
+    pub node_id: String,
+    pub machine_id: Option<String>,
+    pub display_name: String,
+    pub platform: String,
+    pub version: String,
+    pub addresses: Vec<String>,
+    pub port: u16,
+    pub capabilities: Vec<String>,
+}
+
+/// 获取（必要时创建）mDNS 守护进程
+fn daemon(app: &tauri::AppHandle) -> Result<ServiceDaemon, String> {
+    let state = app.state::<Discovery>();
+    let mut guard = state.daemon.lock().map_err(|e| e.to_string())?;
+    if let Some(d) = guard.as_ref() {
+        return Ok(d.clone());
+    }
+    let d = ServiceDaemon::new().map_err(|e| format!("mDNS 初始化失败: {}", e))?;
+    *guard = Some(d.clone());
+    Ok(d)
+}
+
+/// 在局域网广播本节点
+pub fn start_advertising(app: &tauri::AppHandle, port: u16) {
+    if let Ok(mut guard) = app.state::<Discovery>().port.lock() {
+        *guard = Some(port);
+    }
+    let info = crate::collect_node_info();
+    let daemon = match daemon(app) {
+        Ok(d) => d,
+        Err(e) => {
+            debug_log(&format!("[discovery] {}", e));
+            return;
+        }
+    };
+
+    let capabilities = info.capabilities.join(",");
+    let properties = [
+        ("node_id", info.node_id.as_str()),
+        ("machine_id", info.machine_id.as_deref().unwrap_or("")),
+        ("display_name", info.display_name.as_str()),
+        ("platform", info.platform.as_str()),
+        ("version", info.version.as_str()),
+        ("capabilities", capabilities.as_str()),
+    ];
+    let host_name = format!("{}.local.", info.node_id);
+
+    let service = match ServiceInfo::new(
+        SERVICE_TYPE,
+        &info.node_id,
+        &host_name,
+        "",
+        port,
+        &properties[..],
+    ) {
+        Ok(s) => s.enable_addr_auto(),
+        Err(e) => {
+            debug_log(&format!("[discovery] 构造 mDNS 服务失败: {}", e));
+            return;
+        }
+    };
+
+    match daemon.register(service) {
+        Ok(_) => debug_log(&format!(
+            "[discovery] 已广播节点 {} (port={})",
+            info.node_id, port
+        )),
+        Err(e) => debug_log(&format!("[discovery] mDNS 广播失败: {}", e)),
+    }
+}
+
+/// 节点信息（如显示名称）变化后重新广播（尚未开始广播时忽略）
+pub fn refresh_advertising(app: &tauri::AppHandle) {
+    let port = app.state::<Discovery>().port.lock().ok().and_then(|g| *g);
+    if let Some(port) = port {
+        start_advertising(app, port);
+    }
+}
+
+/// 停止 mDNS（应用退出时调用，发送下线通告）
+pub fn shutdown(app: &tauri::AppHandle) {
+    let taken = app
+        .state::<Discovery>()
+        .daemon
+        .lock()
+        .ok()
+        .and_then(|mut g| g.take());
+    if let Some(d) = taken {
+        let _ = d.shutdown();
+    }
+}
+
+/// 将解析到的服务转换为节点信息
+fn peer_from_service(info: &ServiceInfo) -> Option<PeerNode> {
+    let prop = |key: &str| info.get_property_val_str(key).unwrap_or("").to_string();
+    let node_id = prop("node_id");
+    if node_id.is_empty() {
+        return None;
+    }
+    let mut addresses: Vec<String> = info.get_addresses().iter().map(|a| a.to_string()).collect();
+    addresses.sort();
+
+    let machine_id = Some(prop("machine_id")).filter(|m| !m.is_empty());
+
+    Some(PeerNode {
+        node_id,
+        machine_id,
+        display_name: prop("display_name"),
+        platform: prop("platform"),
+        version: prop("version"),
+        addresses,
+        port: info.get_port(),
+        capabilities: prop("capabilities")
+            .split(',')
+            .filter(|c| !c.is_empty())
+            .map(|c| c.to_string())
+            .collect(),
+    })
+}
+
+/// 浏览局域网内的其他节点
+pub fn browse_peers(app: &tauri::AppHandle, timeout: Duration) -> Result<Vec<PeerNode>, String> {
+    let daemon = daemon(app)?;
+    let receiver = daemon
+        .browse(SERVICE_TYPE)
+        .map_err(|e| format!("mDNS 浏览失败: {}", e))?;
+
+    let own_id = crate::node_id();
+    let own_machine = crate::machine_id::machine_id();
+    let deadline = Instant::now() + timeout;
+    let mut peers: HashMap<String, PeerNode> = HashMap::new();
+
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match receiver.recv_timeout(remaining) {
+            Ok(ServiceEvent::ServiceResolved(info)) => {
+                if let Some(peer) = peer_from_service(&info) {
+                    // 同一台机器上重启（node_id 变化）的节点按 machine_id 去重
+                    let is_self = peer.node_id == own_id
+                        || (own_machine.is_some() && peer.machine_id.as_deref() == own_machine);
+                    if !is_self {
+                        let key = peer
+                            .machine_id
+                            .clone()
+                            .unwrap_or_else(|| peer.node_id.clone());
+                        peers.insert(key, peer);
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+    let _ = daemon.stop_browse(SERVICE_TYPE);
+
+    let mut list: Vec<PeerNode> = peers.into_values().collect();
+    list.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    Ok(list)
+}
+
+/// 发现局域网内的其他 ZenFlux 节点
+#[tauri::command]
+pub async fn discover_nodes(
+    app: tauri::AppHandle,
+    timeout_ms: Option<u64>,
+) -> Result<Vec<PeerNode>, String> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_DISCOVERY_MS).min(30_000));
+    tauri::async_runtime::spawn_blocking(move || browse_peers(&app, timeout))
+        .await
+        .map_err(|e| e.to_string())?
+}
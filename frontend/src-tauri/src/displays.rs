@@ -0,0 +1,287 @@
+// ============================================================================
+// 显示器信息、窗口跨屏移动与内建屏幕亮度
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    /// 显示器标识（系统名称，无名称时为 "display-<序号>"）
+    pub id: String,
+    pub name: Option<String>,
+    /// 物理像素坐标
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+fn display_id(index: usize, monitor: &tauri::Monitor) -> String {
+    monitor
+        .name()
+        .cloned()
+        .unwrap_or_else(|| format!("display-{}", index))
+}
+
+/// 枚举显示器
+pub fn list_displays(app: &tauri::AppHandle) -> Result<Vec<DisplayInfo>, String> {
+    let primary = app.primary_monitor().map_err(|e| e.to_string())?;
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+
+    Ok(monitors
+        .into_iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let is_primary = primary
+                .as_ref()
+                .map(|p| p.position() == m.position() && p.size() == m.size())
+                .unwrap_or(i == 0);
+            DisplayInfo {
+                id: display_id(i, &m),
+                name: m.name().cloned(),
+                x: m.position().x,
+                y: m.position().y,
+                width: m.size().width,
+                height: m.size().height,
+                scale_factor: m.scale_factor(),
+                is_primary,
+            }
+        })
+        .collect())
+}
+
+/// 获取所有显示器信息
+#[tauri::command]
+pub async fn get_displays(app: tauri::AppHandle) -> Result<Vec<DisplayInfo>, String> {
+    list_displays(&app)
+}
+
+/// 将窗口移动到指定显示器并居中
+#[tauri::command]
+pub async fn move_window_to_display(
+    app: tauri::AppHandle,
+    label: String,
+    display_id: String,
+) -> Result<DisplayInfo, String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window not found: {}", label))?;
+    let info = list_displays(&app)?
+        .into_iter()
+        .find(|d| d.id == display_id)
+        .ok_or_else(|| format!("Display not found: {}", display_id))?;
+
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let x = info.x + (info.width.saturating_sub(size.width) / 2) as i32;
+    let y = info.y + (info.height.saturating_sub(size.height) / 2) as i32;
+    window
+        .set_position(tauri::PhysicalPosition::new(x, y))
+        .map_err(|e| e.to_string())?;
+
+    Ok(info)
+}
+
+// ============================================================================
+// 内建屏幕亮度
+// ============================================================================
+//
+// - macOS: 运行时加载私有框架 DisplayServices（外接显示器不支持）
+// - Windows: WMI WmiMonitorBrightness（仅笔记本等内建屏幕）
+// - Linux: /sys/class/backlight（写入需要相应权限）
+// 亮度统一为 0-100。
+
+#[cfg(target_os = "macos")]
+mod brightness {
+    use std::ffi::{c_void, CStr};
+    use std::sync::OnceLock;
+
+    type GetBrightness = unsafe extern "C" fn(u32, *mut f32) -> i32;
+    type SetBrightness = unsafe extern "C" fn(u32, f32) -> i32;
+
+    const DISPLAY_SERVICES: &CStr =
+        c"/System/Library/PrivateFrameworks/DisplayServices.framework/DisplayServices";
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGGetOnlineDisplayList(max: u32, displays: *mut u32, count: *mut u32) -> i32;
+        fn CGDisplayIsBuiltin(display: u32) -> u32;
+    }
+
+    struct DisplayServices {
+        get: GetBrightness,
+        set: SetBrightness,
+    }
+
+    fn symbol(handle: *mut c_void, name: &CStr) -> Option<*mut c_void> {
+        let ptr = unsafe { libc::dlsym(handle, name.as_ptr()) };
+        (!ptr.is_null()).then_some(ptr)
+    }
+
+    fn display_services() -> Result<&'static DisplayServices, String> {
+        static LIB: OnceLock<Option<DisplayServices>> = OnceLock::new();
+        LIB.get_or_init(|| {
+            let handle = unsafe { libc::dlopen(DISPLAY_SERVICES.as_ptr(), libc::RTLD_LAZY) };
+            if handle.is_null() {
+                return None;
+            }
+            let get = symbol(handle, c"DisplayServicesGetBrightness")?;
+            let set = symbol(handle, c"DisplayServicesSetBrightness")?;
+            // SAFETY: 符号签名与 DisplayServices 导出的 C 函数一致
+            Some(unsafe {
+                DisplayServices {
+                    get: std::mem::transmute::<*mut c_void, GetBrightness>(get),
+                    set: std::mem::transmute::<*mut c_void, SetBrightness>(set),
+                }
+            })
+        })
+        .as_ref()
+        .ok_or_else(|| "DisplayServices is not available".to_string())
+    }
+
+    /// 内建屏幕的 CGDirectDisplayID
+    fn builtin_display() -> Result<u32, String> {
+        let mut displays = [0u32; 16];
+        let mut count = 0u32;
+        let err = unsafe {
+            CGGetOnlineDisplayList(displays.len() as u32, displays.as_mut_ptr(), &mut count)
+        };
+        if err != 0 {
+            return Err(format!("获取显示器列表失败: {}", err));
+        }
+        displays[..count as usize]
+            .iter()
+            .copied()
+            .find(|&d| unsafe { CGDisplayIsBuiltin(d) } != 0)
+            .ok_or_else(|| "No built-in display found".to_string())
+    }
+
+    pub fn get() -> Result<u8, String> {
+        let lib = display_services()?;
+        let mut value = 0f32;
+        let err = unsafe { (lib.get)(builtin_display()?, &mut value) };
+        if err != 0 {
+            return Err(format!("读取亮度失败: {}", err));
+        }
+        Ok((value * 100.0).round().clamp(0.0, 100.0) as u8)
+    }
+
+    pub fn set(level: u8) -> Result<(), String> {
+        let lib = display_services()?;
+        let err = unsafe { (lib.set)(builtin_display()?, level as f32 / 100.0) };
+        if err != 0 {
+            return Err(format!("设置亮度失败: {}", err));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod brightness {
+    use std::process::Command as SysCommand;
+
+    fn powershell(script: &str) -> Result<String, String> {
+        let output = SysCommand::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", script])
+            .output()
+            .map_err(|e| format!("亮度控制失败: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "亮度控制失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn get() -> Result<u8, String> {
+        powershell(
+            "(Get-CimInstance -Namespace root/WMI -ClassName WmiMonitorBrightness -ErrorAction Stop \
+             | Select-Object -First 1).CurrentBrightness",
+        )?
+        .parse()
+        .map_err(|_| "No built-in display found".to_string())
+    }
+
+    pub fn set(level: u8) -> Result<(), String> {
+        powershell(&format!(
+            "Get-CimInstance -Namespace root/WMI -ClassName WmiMonitorBrightnessMethods -ErrorAction Stop \
+             | Select-Object -First 1 \
+             | Invoke-CimMethod -MethodName WmiSetBrightness -Arguments @{{Timeout = 0; Brightness = {}}} | Out-Null",
+            level
+        ))
+        .map(|_| ())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod brightness {
+    use std::path::PathBuf;
+
+    /// 第一个背光设备目录
+    fn backlight() -> Result<PathBuf, String> {
+        std::fs::read_dir("/sys/class/backlight")
+            .ok()
+            .and_then(|mut entries| entries.find_map(|e| e.ok()).map(|e| e.path()))
+            .ok_or_else(|| "No built-in display found".to_string())
+    }
+
+    fn read_value(path: PathBuf) -> Result<u32, String> {
+        std::fs::read_to_string(&path)
+            .map_err(|e| format!("读取亮度失败: {}", e))?
+            .trim()
+            .parse()
+            .map_err(|e| format!("读取亮度失败: {}", e))
+    }
+
+    pub fn get() -> Result<u8, String> {
+        let dir = backlight()?;
+        let max = read_value(dir.join("max_brightness"))?.max(1);
+        let current = read_value(dir.join("brightness"))?;
+        Ok((current as f64 * 100.0 / max as f64).round().min(100.0) as u8)
+    }
+
+    pub fn set(level: u8) -> Result<(), String> {
+        let dir = backlight()?;
+        let max = read_value(dir.join("max_brightness"))?;
+        let value = (max as f64 * level as f64 / 100.0).round() as u32;
+        std::fs::write(dir.join("brightness"), value.to_string())
+            .map_err(|e| format!("设置亮度失败: {}", e))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod brightness {
+    pub fn get() -> Result<u8, String> {
+        Err("Brightness control not supported on this platform".to_string())
+    }
+
+    pub fn set(_level: u8) -> Result<(), String> {
+        Err("Brightness control not supported on this platform".to_string())
+    }
+}
+
+/// 获取内建屏幕亮度（0-100）
+#[tauri::command]
+pub async fn get_brightness() -> Result<u8, String> {
+    tauri::async_runtime::spawn_blocking(brightness::get)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 设置内建屏幕亮度（0-100），返回设置后的亮度
+#[tauri::command]
+pub async fn set_brightness(level: u8) -> Result<u8, String> {
+    if level > 100 {
+        return Err("Brightness level must be between 0 and 100".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        brightness::set(level)?;
+        crate::debug_log(&format!("[displays] 设置屏幕亮度为 {}", level));
+        brightness::get()
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
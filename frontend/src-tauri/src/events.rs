@@ -0,0 +1,82 @@
+// ============================================================================
+// 可重放的状态事件
+// ============================================================================
+//
+// `backend-ready`、`sidecar-status` 等状态事件可能在 webview 加载完成、前端注册监听之前发出。
+// 通过 events::emit 发出的事件会带序号保存在内存环形缓冲区中，前端加载（或刷新）后调用
+// get_recent_events(since) 补取错过的事件。高频事件（下载进度、日志等）不经过这里。
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+/// 缓冲区保留的事件数
+const MAX_BUFFERED_EVENTS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BufferedEvent {
+    /// 递增序号（从 1 开始），前端记录已处理的最大序号用于下次补取
+    pub seq: u64,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub timestamp: String,
+}
+
+#[derive(Default)]
+struct Buffer {
+    last_seq: u64,
+    events: VecDeque<BufferedEvent>,
+}
+
+/// 最近发出的状态事件
+#[derive(Default)]
+pub struct EventBuffer {
+    inner: Mutex<Buffer>,
+}
+
+/// 发出事件并保存到缓冲区
+pub fn emit<S: Serialize + Clone>(app: &tauri::AppHandle, event: &str, payload: S) {
+    if let Ok(value) = serde_json::to_value(&payload) {
+        if let Ok(mut buffer) = app.state::<EventBuffer>().inner.lock() {
+            buffer.last_seq += 1;
+            let seq = buffer.last_seq;
+            if buffer.events.len() >= MAX_BUFFERED_EVENTS {
+                buffer.events.pop_front();
+            }
+            buffer.events.push_back(BufferedEvent {
+                seq,
+                event: event.to_string(),
+                payload: value,
+                timestamp: chrono::Local::now().to_rfc3339(),
+            });
+        }
+    }
+    let _ = app.emit(event, payload);
+}
+
+/// 当前最新的事件序号
+pub fn last_seq(app: &tauri::AppHandle) -> u64 {
+    app.state::<EventBuffer>()
+        .inner
+        .lock()
+        .map(|b| b.last_seq)
+        .unwrap_or(0)
+}
+
+/// 获取序号大于 since 的事件（不传时返回缓冲区中的全部事件）
+#[tauri::command]
+pub async fn get_recent_events(
+    app: tauri::AppHandle,
+    since: Option<u64>,
+) -> Result<Vec<BufferedEvent>, String> {
+    let state = app.state::<EventBuffer>();
+    let buffer = state.inner.lock().map_err(|e| e.to_string())?;
+    let since = since.unwrap_or(0);
+    Ok(buffer
+        .events
+        .iter()
+        .filter(|e| e.seq > since)
+        .cloned()
+        .collect())
+}
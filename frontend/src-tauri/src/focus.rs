@@ -0,0 +1,78 @@
+// ============================================================================
+// 专注模式 / 勿扰模式检测
+// ============================================================================
+//
+// - macOS 12+: 读取 ~/Library/DoNotDisturb/DB/Assertions.json，存在有效断言即专注模式开启；
+//   旧版本回退到 com.apple.notificationcenterui doNotDisturb 偏好
+// - Windows: 直接调用 shell32 SHQueryUserNotificationState（专注助手、演示模式、全屏应用）
+// - 其他平台视为未开启
+
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "macos")]
+use std::process::Command as SysCommand;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FocusState {
+    /// 专注/勿扰模式是否开启
+    pub active: bool,
+    /// 检测来源（"macos-focus" / "macos-dnd" / "windows-quns" / "unsupported"）
+    pub source: String,
+    /// 因专注模式或手动暂停而延迟发送的通知数量
+    pub deferred_notifications: usize,
+    /// 手动暂停通知的结束时间（RFC 3339），未暂停时为 None
+    pub snoozed_until: Option<String>,
+}
+
+/// 检测专注模式是否开启，返回 (是否开启, 检测来源)
+#[cfg(target_os = "macos")]
+pub fn detect_focus() -> (bool, &'static str) {
+    if let Ok(home) = std::env::var("HOME") {
+        let path = format!("{}/Library/DoNotDisturb/DB/Assertions.json", home);
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            let active = serde_json::from_str::<serde_json::Value>(&content)
+                .ok()
+                .and_then(|v| {
+                    v["data"].as_array().map(|items| {
+                        items.iter().any(|item| {
+                            item["storeAssertionRecords"]
+                                .as_array()
+                                .map(|r| !r.is_empty())
+                                .unwrap_or(false)
+                        })
+                    })
+                })
+                .unwrap_or(false);
+            return (active, "macos-focus");
+        }
+    }
+
+    let active = SysCommand::new("defaults")
+        .args([
+            "-currentHost",
+            "read",
+            "com.apple.notificationcenterui",
+            "doNotDisturb",
+        ])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "1")
+        .unwrap_or(false);
+    (active, "macos-dnd")
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect_focus() -> (bool, &'static str) {
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHQueryUserNotificationState(state: *mut i32) -> i32;
+    }
+    let mut state = 0;
+    // 返回 HRESULT，S_OK = 0
+    let ok = unsafe { SHQueryUserNotificationState(&mut state) } == 0;
+    // QUNS_ACCEPTS_NOTIFICATIONS = 5，其余状态（忙碌、全屏、演示、安静时间）均视为专注
+    (ok && state != 5 && state != 0, "windows-quns")
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn detect_focus() -> (bool, &'static str) {
+    (false, "unsupported")
+}
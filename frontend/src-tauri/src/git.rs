@@ -0,0 +1,541 @@
+// ============================================================================
+// Git 操作
+// ============================================================================
+//
+// 编码类任务常用的 git 操作直接返回结构化结果，不再解析 run_command 的原始输出：
+// - git_status(repo)：分支、上游、领先 / 落后提交数与变更文件（git.read）
+// - git_diff(repo, staged, paths)：补丁文本与每个文件的增删行数（git.read）
+// - git_commit(repo, message, paths, all)：暂存并提交，返回提交哈希（git.write）
+// - git_clone(url, dest, branch, depth)：克隆仓库（git.write）
+// 读取操作要求仓库在托管策略允许的目录内（policy::check_path），写入操作与写文件相同，
+// 目标不在允许目录内时请求审批（approvals::approve_write）。提交与克隆记入审计日志与活动时间线。
+// 使用 git 可执行文件（查找顺序见 tools.rs），禁止交互式输入。
+// 克隆源只允许 https / ssh 远程仓库与本机路径（含 file://）；本机路径与读取操作一样
+// 需要在允许的目录内，其它协议（http、git://、ext:: 等传输）一律拒绝。
+
+use crate::timeline::{self, TimelineKind};
+use crate::{approvals, audit, debug_log, policy, tools};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 本地操作超时
+const LOCAL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 克隆超时
+const CLONE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// git_diff 返回的补丁最大长度（字节），超出时截断
+const MAX_DIFF_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitFileStatus {
+    pub path: String,
+    /// 重命名 / 复制前的路径
+    pub orig_path: Option<String>,
+    /// 暂存区状态（porcelain 的 X，如 "M"、"A"、"."），未跟踪为 "?"
+    pub index: String,
+    /// 工作区状态（porcelain 的 Y）
+    pub worktree: String,
+    /// 存在合并冲突
+    pub conflicted: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GitStatus {
+    /// 当前分支，分离 HEAD 时为 None
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+    pub files: Vec<GitFileStatus>,
+    pub clean: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitDiffFile {
+    pub path: String,
+    /// 二进制文件为 None
+    pub additions: Option<u32>,
+    pub deletions: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitDiff {
+    pub files: Vec<GitDiffFile>,
+    pub patch: String,
+    /// 补丁超过 MAX_DIFF_BYTES 被截断
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitCommit {
+    pub commit: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitClone {
+    pub path: String,
+    pub commit: String,
+}
+
+/// 运行 git，返回 stdout
+async fn run_git(
+    app: &tauri::AppHandle,
+    repo: Option<&Path>,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<String, String> {
+    let git = tools::find_tool(app, "git")?;
+    let mut cmd = tokio::process::Command::new(git);
+    if let Some(repo) = repo {
+        cmd.arg("-C").arg(repo);
+    }
+    cmd.args([
+        "-c",
+        "protocol.ext.allow=never",
+        "-c",
+        "core.quotePath=false",
+    ])
+    .args(args)
+    .env("GIT_TERMINAL_PROMPT", "0")
+    .env("GIT_OPTIONAL_LOCKS", "0")
+    .stdin(std::process::Stdio::null())
+    .kill_on_drop(true);
+    let output = tokio::time::timeout(timeout, cmd.output())
+        .await
+        .map_err(|_| format!("git {} timed out", args.first().unwrap_or(&"")))?
+        .map_err(|e| format!("启动 git 失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} 失败: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 校验仓库目录在允许范围内
+fn check_repo(repo: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(repo);
+    if !path.is_dir() {
+        return Err(format!("不是目录: {}", repo));
+    }
+    policy::check_path(&path)?;
+    Ok(path)
+}
+
+/// 克隆源允许的 URL 协议
+const CLONE_SCHEMES: &[&str] = &["https", "ssh", "git+ssh", "ssh+git"];
+
+/// 解析克隆源：远程仓库返回 None，本机仓库返回其路径，不支持的协议返回错误
+fn clone_source(url: &str) -> Result<Option<PathBuf>, String> {
+    let unsupported = || Err(format!("Unsupported repository URL: {}", url));
+    if url.is_empty() || url.starts_with('-') || url.contains("::") {
+        return unsupported();
+    }
+    if let Some((scheme, rest)) = url.split_once("://") {
+        let scheme = scheme.to_ascii_lowercase();
+        return match scheme.as_str() {
+            s if CLONE_SCHEMES.contains(&s) => Ok(None),
+            "file" => Ok(Some(PathBuf::from(rest))),
+            _ => unsupported(),
+        };
+    }
+    // scp 写法（user@host:path）：第一个冒号之前没有路径分隔符；单个字母为 Windows 盘符
+    if let Some((host, _)) = url.split_once(':') {
+        if host.len() > 1 && !host.contains(['/', '\\']) {
+            return Ok(None);
+        }
+    }
+    Ok(Some(PathBuf::from(url)))
+}
+
+/// 解析 `git status --porcelain=v2 --branch -z`
+fn parse_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut entries = output.split('\0').filter(|e| !e.is_empty());
+    while let Some(entry) = entries.next() {
+        if let Some(header) = entry.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for part in value.split(' ') {
+                        if let Some(n) = part.strip_prefix('+') {
+                            status.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = part.strip_prefix('-') {
+                            status.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        // 各类条目在路径前的字段数：1 → 8，2 → 9（路径后另有原路径条目），u → 10
+        if !entry.is_char_boundary(1) {
+            continue;
+        }
+        let (kind, rest) = entry.split_at(1);
+        let fields = match kind {
+            "1" => 8,
+            "2" => 9,
+            "u" => 10,
+            "?" => {
+                status.files.push(GitFileStatus {
+                    path: rest.strip_prefix(' ').unwrap_or(rest).to_string(),
+                    orig_path: None,
+                    index: "?".to_string(),
+                    worktree: "?".to_string(),
+                    conflicted: false,
+                });
+                continue;
+            }
+            _ => continue,
+        };
+        let parts: Vec<&str> = entry.splitn(fields + 1, ' ').collect();
+        let (Some(xy), Some(path)) = (parts.get(1), parts.get(fields)) else {
+            continue;
+        };
+        let mut xy = xy.chars();
+        status.files.push(GitFileStatus {
+            path: path.to_string(),
+            orig_path: if kind == "2" {
+                entries.next().map(str::to_string)
+            } else {
+                None
+            },
+            index: xy.next().map(String::from).unwrap_or_default(),
+            worktree: xy.next().map(String::from).unwrap_or_default(),
+            conflicted: kind == "u",
+        });
+    }
+    status.clean = status.files.is_empty();
+    status
+}
+
+/// 解析 `git diff --numstat -z`
+fn parse_numstat(output: &str) -> Vec<GitDiffFile> {
+    let mut files = Vec::new();
+    let mut entries = output.split('\0');
+    while let Some(entry) = entries.next() {
+        let mut parts = entry.splitn(3, '\t');
+        let (Some(additions), Some(deletions), Some(path)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        // 重命名时路径为空，其后依次是原路径与新路径
+        let path = if path.is_empty() {
+            entries.next();
+            entries.next().unwrap_or_default().to_string()
+        } else {
+            path.to_string()
+        };
+        files.push(GitDiffFile {
+            path,
+            additions: additions.parse().ok(),
+            deletions: deletions.parse().ok(),
+        });
+    }
+    files
+}
+
+/// 获取仓库状态
+#[tauri::command]
+pub async fn git_status(app: tauri::AppHandle, repo: String) -> Result<GitStatus, String> {
+    let path = check_repo(&repo)?;
+    let output = run_git(
+        &app,
+        Some(&path),
+        &["status", "--porcelain=v2", "--branch", "-z"],
+        LOCAL_TIMEOUT,
+    )
+    .await?;
+    Ok(parse_status(&output))
+}
+
+/// 获取变更（staged 为 true 时为已暂存的变更），可限定路径
+#[tauri::command]
+pub async fn git_diff(
+    app: tauri::AppHandle,
+    repo: String,
+    staged: Option<bool>,
+    paths: Option<Vec<String>>,
+) -> Result<GitDiff, String> {
+    let path = check_repo(&repo)?;
+    let mut base = vec!["diff", "--no-color", "--no-ext-diff"];
+    if staged.unwrap_or(false) {
+        base.push("--cached");
+    }
+    let paths = paths.unwrap_or_default();
+    let with_paths = |extra: &[&'static str]| -> Vec<&str> {
+        let mut args = base.clone();
+        args.extend_from_slice(extra);
+        args.push("--");
+        args.extend(paths.iter().map(String::as_str));
+        args
+    };
+
+    let numstat = run_git(
+        &app,
+        Some(&path),
+        &with_paths(&["--numstat", "-z"]),
+        LOCAL_TIMEOUT,
+    )
+    .await?;
+    let mut patch = run_git(&app, Some(&path), &with_paths(&[]), LOCAL_TIMEOUT).await?;
+    let truncated = patch.len() > MAX_DIFF_BYTES;
+    if truncated {
+        let mut end = MAX_DIFF_BYTES;
+        while !patch.is_char_boundary(end) {
+            end -= 1;
+        }
+        patch.truncate(end);
+    }
+    Ok(GitDiff {
+        files: parse_numstat(&numstat),
+        patch,
+        truncated,
+    })
+}
+
+/// 提交变更：paths 非空时先暂存这些路径，all 为 true 时提交全部已跟踪文件的修改
+#[tauri::command]
+pub async fn git_commit(
+    app: tauri::AppHandle,
+    repo: String,
+    message: String,
+    paths: Option<Vec<String>>,
+    all: Option<bool>,
+) -> Result<GitCommit, String> {
+    if message.trim().is_empty() {
+        return Err("Commit message is empty".to_string());
+    }
+    let path = check_repo(&repo)?;
+    approvals::approve_write(&app, "git.commit", &repo).await?;
+
+    let paths = paths.unwrap_or_default();
+    if !paths.is_empty() {
+        let mut args = vec!["add", "--"];
+        args.extend(paths.iter().map(String::as_str));
+        run_git(&app, Some(&path), &args, LOCAL_TIMEOUT).await?;
+    }
+    let mut args = vec!["commit", "-m", message.as_str()];
+    if all.unwrap_or(false) {
+        args.push("--all");
+    }
+    let result = run_git(&app, Some(&path), &args, LOCAL_TIMEOUT).await;
+    let commit = match &result {
+        Ok(_) => run_git(&app, Some(&path), &["rev-parse", "HEAD"], LOCAL_TIMEOUT)
+            .await
+            .map(|s| s.trim().to_string()),
+        Err(e) => Err(e.clone()),
+    };
+    let details = serde_json::json!({
+        "repo": repo,
+        "commit": commit.as_ref().ok(),
+        "paths": paths,
+    });
+    audit::record(&app, "git.commit", commit.is_ok(), details.clone());
+    timeline::record(
+        &app,
+        TimelineKind::FileWrite,
+        "git.commit",
+        commit.is_ok(),
+        details,
+    );
+    let commit = commit?;
+    debug_log(&format!("[git] 已提交 {} ({})", commit, repo));
+    Ok(GitCommit {
+        commit,
+        summary: message.lines().next().unwrap_or_default().to_string(),
+    })
+}
+
+/// 克隆仓库到 dest（dest 不能已存在且非空）
+#[tauri::command]
+pub async fn git_clone(
+    app: tauri::AppHandle,
+    url: String,
+    dest: String,
+    branch: Option<String>,
+    depth: Option<u32>,
+) -> Result<GitClone, String> {
+    if let Some(source) = clone_source(&url)? {
+        policy::check_path(&source)?;
+    }
+    approvals::approve_write(&app, "git.clone", &dest).await?;
+
+    let depth = depth.map(|d| d.to_string());
+    let mut args = vec!["clone"];
+    if let Some(depth) = &depth {
+        args.extend(["--depth", depth.as_str()]);
+    }
+    if let Some(branch) = &branch {
+        args.extend(["--branch", branch.as_str()]);
+    }
+    args.extend(["--", url.as_str(), dest.as_str()]);
+
+    debug_log(&format!("[git] 克隆 {} → {}", url, dest));
+    let result = run_git(&app, None, &args, CLONE_TIMEOUT).await;
+    let commit = match &result {
+        Ok(_) => run_git(
+            &app,
+            Some(Path::new(&dest)),
+            &["rev-parse", "HEAD"],
+            LOCAL_TIMEOUT,
+        )
+        .await
+        .map(|s| s.trim().to_string()),
+        Err(e) => Err(e.clone()),
+    };
+    let details = serde_json::json!({ "url": url, "dest": dest, "branch": branch });
+    audit::record(&app, "git.clone", commit.is_ok(), details.clone());
+    timeline::record(
+        &app,
+        TimelineKind::FileWrite,
+        "git.clone",
+        commit.is_ok(),
+        details,
+    );
+    Ok(GitClone {
+        path: dest,
+        commit: commit?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_status_reads_branch_and_entries() {
+        let output = [
+            "# branch.oid 1234abcd",
+            "# branch.head main",
+            "# branch.upstream origin/main",
+            "# branch.ab +2 -1",
+            "1 .M N... 100644 100644 100644 aaaa bbbb src/my file.rs",
+            "2 R. N... 100644 100644 100644 aaaa bbbb R100 new.rs",
+            "old.rs",
+            "u UU N... 100644 100644 100644 100644 aaaa bbbb cccc conflict.txt",
+            "? untracked.txt",
+            "",
+        ]
+        .join("\0");
+        let status = parse_status(&output);
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert!(!status.clean);
+
+        let files: Vec<_> = status
+            .files
+            .iter()
+            .map(|f| {
+                (
+                    f.path.as_str(),
+                    f.orig_path.as_deref(),
+                    f.index.as_str(),
+                    f.worktree.as_str(),
+                    f.conflicted,
+                )
+            })
+            .collect();
+        assert_eq!(
+            files,
+            [
+                ("src/my file.rs", None, ".", "M", false),
+                ("new.rs", Some("old.rs"), "R", ".", false),
+                ("conflict.txt", None, "U", "U", true),
+                ("untracked.txt", None, "?", "?", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_status_handles_detached_clean_repo() {
+        let status = parse_status("# branch.oid 1234abcd\0# branch.head (detached)\0");
+        assert_eq!(status.branch, None);
+        assert_eq!(status.upstream, None);
+        assert!(status.files.is_empty());
+        assert!(status.clean);
+    }
+
+    #[test]
+    fn parse_numstat_reads_counts_binaries_and_renames() {
+        let output = [
+            "3\t1\tsrc/a.rs",
+            "-\t-\timage.png",
+            // 重命名：路径字段为空，其后为原路径与新路径
+            "5\t0\t",
+            "old.rs",
+            "new.rs",
+            "",
+        ]
+        .join("\0");
+        let files: Vec<_> = parse_numstat(&output)
+            .into_iter()
+            .map(|f| (f.path, f.additions, f.deletions))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                ("src/a.rs".to_string(), Some(3), Some(1)),
+                ("image.png".to_string(), None, None),
+                ("new.rs".to_string(), Some(5), Some(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn clone_source_allows_https_and_ssh() {
+        for url in [
+            "https://github.com/user/repo.git",
+            "ssh://git@github.com/user/repo.git",
+            "git@github.com:user/repo.git",
+            "HTTPS://example.com/repo",
+        ] {
+            assert_eq!(clone_source(url), Ok(None), "{}", url);
+        }
+    }
+
+    #[test]
+    fn clone_source_returns_local_paths() {
+        assert_eq!(
+            clone_source("file:///home/user/repo"),
+            Ok(Some(PathBuf::from("/home/user/repo")))
+        );
+        assert_eq!(
+            clone_source("/home/user/repo"),
+            Ok(Some(PathBuf::from("/home/user/repo")))
+        );
+        assert_eq!(
+            clone_source("../repo:old"),
+            Ok(Some(PathBuf::from("../repo:old")))
+        );
+        assert_eq!(
+            clone_source("C:\\repos\\app"),
+            Ok(Some(PathBuf::from("C:\\repos\\app")))
+        );
+    }
+
+    #[test]
+    fn clone_source_rejects_other_transports() {
+        for url in [
+            "http://example.com/repo.git",
+            "git://example.com/repo.git",
+            "ext::sh -c touch% /tmp/pwned",
+            "fd::17",
+            "--upload-pack=touch /tmp/pwned",
+            "",
+        ] {
+            assert!(clone_source(url).is_err(), "{}", url);
+        }
+    }
+}
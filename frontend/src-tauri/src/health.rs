@@ -0,0 +1,299 @@
+// ============================================================================
+// 后端健康监视
+// ============================================================================
+//
+// 启动阶段由 main.rs 以指数退避轮询 /health；就绪后由这里低频巡检：
+// - 检查失败后改为退避重试，连续 UNHEALTHY_THRESHOLD 次失败 → 发出 `backend-unhealthy`，
+//   backend-ready 改为 false，托盘图标加红点；
+//   设置 backend_auto_restart 开启时重启 sidecar（RESTART_WINDOW 内最多 MAX_AUTO_RESTARTS 次）
+// - 失败后首次恢复 → 发出 `backend-recovered`，恢复托盘图标
+// 托盘图标随任务栏/菜单栏颜色切换（见 appearance::taskbar_theme）：macOS 使用模板图标（由系统着色），
+// Windows / Linux 不支持模板图标，深色任务栏用白色图标、浅色任务栏用黑色图标
+// （icons/tray-dark.png / tray-light.png，由 scripts/generate_tray_icons.py 生成）。
+// 每次巡检结果（含延迟）写入环形缓冲，供 get_backend_health_history 绘制可用率/延迟曲线。
+
+use crate::{debug_log, settings};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+/// 就绪后的巡检间隔
+const MONITOR_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 判定为不健康前允许的连续失败次数
+const UNHEALTHY_THRESHOLD: u32 = 3;
+
+/// 检查失败后重试的最小/最大间隔
+const RETRY_MIN: Duration = Duration::from_secs(2);
+const RETRY_MAX: Duration = Duration::from_secs(30);
+
+/// 单次检查超时
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 自动重启次数上限（RESTART_WINDOW 内），避免后端反复崩溃时无限重启
+const MAX_AUTO_RESTARTS: usize = 3;
+const RESTART_WINDOW: Duration = Duration::from_secs(600);
+
+/// 健康检查历史保留条数（按 30s 巡检约 1 小时）
+const HISTORY_CAPACITY: usize = 120;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthSample {
+    pub timestamp: String,
+    pub ok: bool,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthHistory {
+    pub samples: Vec<HealthSample>,
+    /// 历史窗口内检查通过的比例（0..1，无样本时为 None）
+    pub uptime_ratio: Option<f64>,
+    /// 通过的检查的平均延迟
+    pub avg_latency_ms: Option<u64>,
+    /// 当前连续通过的次数
+    pub current_streak: usize,
+}
+
+/// 健康检查历史（环形缓冲）
+#[derive(Default)]
+pub struct HealthHistoryState {
+    samples: Mutex<VecDeque<HealthSample>>,
+    /// 最近的自动重启时间
+    restarts: Mutex<VecDeque<Instant>>,
+}
+
+fn record_sample(app: &tauri::AppHandle, ok: bool, latency: Duration) {
+    let state = app.state::<HealthHistoryState>();
+    let mut samples = state.samples.lock().unwrap_or_else(PoisonError::into_inner);
+    if samples.len() >= HISTORY_CAPACITY {
+        samples.pop_front();
+    }
+    samples.push_back(HealthSample {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        ok,
+        latency_ms: latency.as_millis() as u64,
+    });
+}
+
+/// 执行一次健康检查并记入历史
+pub async fn probe(app: &tauri::AppHandle, port: u16, socket_path: Option<String>, timeout: Duration) -> bool {
+    let started = Instant::now();
+    let ok = crate::check_health(port, socket_path, timeout).await;
+    record_sample(app, ok, started.elapsed());
+    ok
+}
+
+/// 为等待时长加上 ±20% 的随机抖动，避免多个轮询同步
+pub fn with_jitter(delay: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    // 0.8 .. 1.2
+    let factor = 0.8 + (nanos % 1000) as f64 / 1000.0 * 0.4;
+    delay.mul_f64(factor)
+}
+
+/// 指数退避：下一次等待时长翻倍，不超过 max
+pub fn next_backoff(delay: Duration, max: Duration) -> Duration {
+    (delay * 2).min(max)
+}
+
+/// 当前后端的端口与 socket；sidecar 已被关闭时返回 None
+fn current_target(app: &tauri::AppHandle) -> Option<(u16, Option<String>)> {
+    let info = app.state::<crate::BackendState>().info();
+    if info.is_sidecar && info.pid.is_none() && !info.adopted {
+        return None;
+    }
+    Some((info.port, info.socket_path))
+}
+
+/// 托盘图标当前是否显示为健康状态（随系统主题刷新图标时沿用）
+static TRAY_HEALTHY: AtomicBool = AtomicBool::new(true);
+
+/// 在图标右下角画一个红点
+fn with_alert_dot(icon: &tauri::image::Image<'_>) -> tauri::image::Image<'static> {
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+    let radius = width.min(height) as f32 * 0.22;
+    let (cx, cy) = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+    for y in 0..height {
+        for x in 0..width {
+            let distance = ((x as f32 - cx).powi(2) + (y as f32 - cy).powi(2)).sqrt();
+            let coverage = (radius - distance).clamp(0.0, 1.0);
+            if coverage <= 0.0 {
+                continue;
+            }
+            let i = ((y * width + x) * 4) as usize;
+            for (c, dot) in [0xE5u8, 0x39, 0x35].into_iter().enumerate() {
+                rgba[i + c] = (rgba[i + c] as f32 * (1.0 - coverage) + dot as f32 * coverage) as u8;
+            }
+            rgba[i + 3] = rgba[i + 3].max((coverage * 255.0) as u8);
+        }
+    }
+    tauri::image::Image::new_owned(rgba, width, height)
+}
+
+/// 按任务栏颜色与后端健康状态设置托盘图标（启动时与任务栏/系统主题切换时调用）
+pub fn refresh_tray_icon(app: &tauri::AppHandle) {
+    let Some(tray) = app.tray_by_id(crate::i18n::TRAY_ID) else {
+        return;
+    };
+    let healthy = TRAY_HEALTHY.load(Ordering::SeqCst);
+    // 任务栏颜色未知时退回窗口主题；窗口也不存在时按深色处理（多数系统的任务栏默认为深色）
+    let theme = crate::appearance::taskbar_theme()
+        .or_else(|| {
+            app.get_webview_window("main")
+                .and_then(|window| window.theme().ok())
+        })
+        .unwrap_or(tauri::Theme::Dark);
+    // 模板图标只显示轮廓，红点需要关闭模板模式，此时按主题选择图标
+    let template = cfg!(target_os = "macos") && healthy;
+    let icon = match theme {
+        tauri::Theme::Dark if !template => crate::TRAY_ICON_DARK,
+        _ => crate::TRAY_ICON,
+    };
+    let icon = if healthy { icon } else { with_alert_dot(&icon) };
+    let _ = tray.set_icon(Some(icon));
+    let _ = tray.set_icon_as_template(template);
+}
+
+/// 按后端健康状态更新托盘图标与提示
+fn update_tray(app: &tauri::AppHandle, healthy: bool) {
+    let Some(tray) = app.tray_by_id(crate::i18n::TRAY_ID) else {
+        return;
+    };
+    TRAY_HEALTHY.store(healthy, Ordering::SeqCst);
+    refresh_tray_icon(app);
+    let tooltip = if healthy {
+        "xiaodazi".to_string()
+    } else {
+        format!("xiaodazi - {}", crate::i18n::t("tray.backend_unhealthy"))
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+/// 是否可以自动重启：设置开启、后端是本应用启动的 sidecar，且未超过次数上限
+fn allow_auto_restart(app: &tauri::AppHandle) -> bool {
+    let info = app.state::<crate::BackendState>().info();
+    if !settings::current(app).backend_auto_restart
+        || !info.is_sidecar
+        || info.adopted
+        || !crate::is_release_build()
+    {
+        return false;
+    }
+    let state = app.state::<HealthHistoryState>();
+    let mut restarts = state.restarts.lock().unwrap_or_else(PoisonError::into_inner);
+    while restarts
+        .front()
+        .is_some_and(|t| t.elapsed() > RESTART_WINDOW)
+    {
+        restarts.pop_front();
+    }
+    if restarts.len() >= MAX_AUTO_RESTARTS {
+        debug_log(&format!(
+            "[health] {}s 内已自动重启 {} 次，不再重启",
+            RESTART_WINDOW.as_secs(),
+            restarts.len()
+        ));
+        return false;
+    }
+    restarts.push_back(Instant::now());
+    true
+}
+
+/// 启动就绪后的健康巡检任务
+pub fn start_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut failures: u32 = 0;
+        let mut unhealthy = false;
+        let mut retry = RETRY_MIN;
+
+        loop {
+            let wait = if failures > 0 { retry } else { MONITOR_INTERVAL };
+            tokio::time::sleep(with_jitter(wait)).await;
+
+            let Some((port, socket_path)) = current_target(&app) else {
+                debug_log("[health] sidecar 已停止，结束健康巡检");
+                return;
+            };
+
+            if probe(&app, port, socket_path, CHECK_TIMEOUT).await {
+                if unhealthy {
+                    debug_log(&format!("[health] 后端已恢复 (连续失败 {} 次后)", failures));
+                    crate::events::emit(
+                        &app,
+                        "backend-recovered",
+                        serde_json::json!({ "failures": failures }),
+                    );
+                    crate::set_backend_ready(&app, true);
+                    update_tray(&app, true);
+                }
+                failures = 0;
+                unhealthy = false;
+                retry = RETRY_MIN;
+                continue;
+            }
+
+            failures += 1;
+            if failures > 1 {
+                retry = next_backoff(retry, RETRY_MAX);
+            }
+            if !unhealthy && failures >= UNHEALTHY_THRESHOLD {
+                unhealthy = true;
+                debug_log(&format!("[health] 后端连续 {} 次健康检查失败", failures));
+                crate::events::emit(
+                    &app,
+                    "backend-unhealthy",
+                    serde_json::json!({ "failures": failures }),
+                );
+                crate::set_backend_ready(&app, false);
+                update_tray(&app, false);
+
+                if allow_auto_restart(&app) {
+                    debug_log("[health] 自动重启后端");
+                    crate::events::emit(
+                        &app,
+                        "backend-restarting",
+                        serde_json::json!({ "failures": failures }),
+                    );
+                    // 重启后的 sidecar 就绪时会启动新的巡检任务
+                    crate::restart_sidecar(app.clone()).await;
+                    return;
+                }
+            }
+        }
+    });
+}
+
+/// 获取最近的健康检查历史与可用率统计
+#[tauri::command]
+pub async fn get_backend_health_history(app: tauri::AppHandle) -> Result<HealthHistory, String> {
+    let samples: Vec<HealthSample> = app
+        .state::<HealthHistoryState>()
+        .samples
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .cloned()
+        .collect();
+
+    let ok_latencies: Vec<u64> = samples.iter().filter(|s| s.ok).map(|s| s.latency_ms).collect();
+    let uptime_ratio =
+        (!samples.is_empty()).then(|| ok_latencies.len() as f64 / samples.len() as f64);
+    let avg_latency_ms = (!ok_latencies.is_empty())
+        .then(|| ok_latencies.iter().sum::<u64>() / ok_latencies.len() as u64);
+    let current_streak = samples.iter().rev().take_while(|s| s.ok).count();
+
+    Ok(HealthHistory {
+        samples,
+        uptime_ratio,
+        avg_latency_ms,
+        current_streak,
+    })
+}
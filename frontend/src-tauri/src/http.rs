@@ -0,0 +1,87 @@
+// ============================================================================
+// 共享异步 HTTP 客户端
+// ============================================================================
+//
+// 所有到后端的 HTTP 请求共用同一个 reqwest::Client（连接池复用），
+// 避免在异步命令中使用阻塞请求占用运行时线程。
+// backend_request / call_backend 按当前后端信息选择 Unix socket 或本机端口，
+// 供设置同步、计划任务、URL 快捷指令、Webhook 转发等共用。
+
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::Manager;
+
+/// 建立连接的超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 空闲连接保留时长
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// 获取共享客户端
+///
+/// 访问的都是本机后端，因此不走系统代理。单次请求的超时由调用方设置。
+pub fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .no_proxy()
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// 调用后端接口的默认超时（设置同步、状态查询等短请求）
+pub const BACKEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 调用后端接口（请求体为 JSON），返回 (状态码, 响应体)；请求未送达时返回 Err
+pub async fn backend_request(
+    app: &tauri::AppHandle,
+    method: &str,
+    path: &str,
+    body: Vec<u8>,
+    timeout: Duration,
+) -> Result<(u16, String), String> {
+    let crate::BackendInfo {
+        port, socket_path, ..
+    } = app.state::<crate::BackendState>().info();
+
+    if let Some(socket) = socket_path {
+        let headers = [("Content-Type".to_string(), "application/json".to_string())];
+        let resp =
+            crate::uds::http_request(&socket, method, path, &headers, &body, timeout).await?;
+        return Ok((resp.status, String::from_utf8_lossy(&resp.body).to_string()));
+    }
+
+    let method = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|e| format!("无效的请求方法: {}", e))?;
+    let url = format!("http://127.0.0.1:{}{}", port, path);
+    let resp = client()
+        .request(method, &url)
+        .timeout(timeout)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok((
+        resp.status().as_u16(),
+        resp.text().await.unwrap_or_default(),
+    ))
+}
+
+/// 调用后端接口，返回 (是否成功, 响应体或错误信息)
+pub async fn call_backend(
+    app: &tauri::AppHandle,
+    method: &str,
+    path: &str,
+    body: Option<&serde_json::Value>,
+    timeout: Duration,
+) -> (bool, String) {
+    let body = body.map(|b| b.to_string().into_bytes()).unwrap_or_default();
+    match backend_request(app, method, path, body, timeout).await {
+        Ok((status, body)) => ((200..300).contains(&status), body),
+        Err(e) => (false, e),
+    }
+}
@@ -0,0 +1,513 @@
+// ============================================================================
+// 界面文案本地化（托盘、对话框、通知、状态事件）
+// ============================================================================
+//
+// 默认跟随系统语言，可在设置中通过 language 覆盖（set_language 命令）。
+// 部分文案在 Tauri 应用创建之前就会用到，因此当前语言用进程级静态变量保存。
+// 缺失的翻译回退到中文，仍缺失时直接返回 key。
+
+use crate::{debug_log, settings};
+use serde::{Deserialize, Serialize};
+use std::sync::{PoisonError, RwLock};
+use tauri::{Emitter, Manager};
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Language {
+    /// 由 BCP 47 语言标签（如 "zh-Hans-CN"、"en-US"）解析，不支持的语言返回 None
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Some(Self::ZhCn),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::ZhCn => "zh-CN",
+            Self::En => "en",
+        }
+    }
+}
+
+/// 托盘图标 ID（切换语言时据此重建托盘菜单）
+pub const TRAY_ID: &str = "main";
+
+const ZH_CN: &[(&str, &str)] = &[
+    ("tray.show", "显示窗口"),
+    ("tray.quit", "退出"),
+    ("tray.advanced", "高级"),
+    ("tray.rollback", "回退到上一版本..."),
+    ("tray.automations", "自动化"),
+    ("tray.automations.empty", "暂无自动化规则"),
+    ("tray.snooze", "暂停通知"),
+    ("tray.snooze.until", "通知已暂停至 {0}"),
+    ("tray.snooze.resume", "恢复通知"),
+    ("tray.snooze.minutes", "{0} 分钟"),
+    ("tray.snooze.hours", "{0} 小时"),
+    ("automation.label.fs", "监视 {0}（{1}）"),
+    ("automation.label.schedule", "定时 {0}"),
+    ("automation.label.hotkey", "快捷键 {0}"),
+    ("sidecar.starting", "正在启动服务..."),
+    ("sidecar.ready", "准备就绪"),
+    ("sidecar.failed", "服务启动失败"),
+    ("sidecar.timeout", "启动超时，请重试"),
+    ("sidecar.adopted", "已连接到运行中的服务"),
+    ("sidecar.port_conflict", "端口被占用，无法启动服务"),
+    ("sidecar.stage.import", "正在加载模块..."),
+    ("sidecar.stage.imported", "模块加载完成"),
+    ("sidecar.stage.resilience", "正在加载配置..."),
+    ("sidecar.stage.local_store", "正在初始化数据..."),
+    ("sidecar.stage.capabilities", "正在加载工具..."),
+    ("sidecar.stage.agents", "正在加载 Agent..."),
+    ("sidecar.stage.chat_service", "正在预热对话服务..."),
+    ("sidecar.stage.knowledge", "正在索引知识库..."),
+    ("backend.health_failed", "后端健康检查失败"),
+    ("backend.ports_blocked", "端口均被占用: {0}"),
+    ("backend.exited", "后端进程已退出 (code={0}, signal={1})"),
+    ("backend.timeout", "后端启动超时 ({0}s)"),
+    ("backend.spawn_failed", "后端进程启动失败: {0}"),
+    ("backend.command_failed", "后端命令创建失败: {0}"),
+    ("notify.port_conflict.title", "无法启动服务"),
+    (
+        "notify.port_conflict.body",
+        "端口均被占用: {0}，请关闭占用端口的程序后重启应用",
+    ),
+    ("notify.security.title", "安全提醒"),
+    ("dialog.port_exposed.title", "后端端口暴露"),
+    (
+        "dialog.port_exposed.body",
+        "{0}\n\n局域网内的其他设备可能访问到本机 Agent，请检查防火墙设置或重启应用。",
+    ),
+    ("dialog.kill_process.title", "确认终止进程"),
+    (
+        "dialog.kill_process.body",
+        "Agent 请求终止进程「{0}」(PID {1})，该进程并非由本应用启动。是否继续？",
+    ),
+    ("notify.update_available.title", "发现新版本"),
+    (
+        "notify.update_available.body",
+        "新版本 {0} 已发布，可在设置中更新",
+    ),
+    ("notify.update_on_quit.title", "更新已就绪"),
+    (
+        "notify.update_on_quit.body",
+        "新版本 {0} 已下载，将在退出应用时安装",
+    ),
+    ("dialog.rollback.title", "回退版本"),
+    (
+        "dialog.rollback.body",
+        "将回退最近一次更新并重启应用，进行中的任务会被中断。是否继续？",
+    ),
+    ("dialog.rollback_failed.body", "回退失败: {0}"),
+    ("dialog.crash_report.title", "发送崩溃报告"),
+    (
+        "dialog.crash_report.body",
+        "应用上次运行时发生了 {0} 次崩溃。是否发送崩溃报告帮助我们改进？报告包含错误信息和最近的日志。",
+    ),
+    ("dialog.crash_report.send", "发送"),
+    ("dialog.crash_report.dont_send", "不发送"),
+    ("notify.snooze_summary.title", "暂停期间的通知"),
+    ("notify.snooze_summary.body", "共 {0} 条：{1}"),
+    ("notify.low_disk.title", "磁盘空间不足"),
+    (
+        "notify.low_disk.body",
+        "数据所在磁盘仅剩 {0} MB，可能导致任务失败，请清理磁盘空间",
+    ),
+    ("notify.scheduled_task.succeeded", "计划任务已完成"),
+    ("notify.scheduled_task.failed", "计划任务执行失败"),
+    ("dialog.intent.title", "快捷指令请求"),
+    ("dialog.intent.allow", "允许"),
+    ("dialog.intent.deny", "拒绝"),
+    ("dialog.intent.ask_body", "其它应用请求把以下内容交给小搭子处理：\n\n{0}"),
+    ("dialog.intent.run_body", "其它应用请求运行以下命令：\n\n{0}\n\n请确认命令来源可信。"),
+    ("dialog.intent.ocr_body", "其它应用请求截取整个屏幕并识别其中的文字，识别结果会显示在小搭子中。"),
+    (
+        "dialog.intent.ocr_ask_body",
+        "其它应用请求截取整个屏幕，并把识别出的文字连同以下问题交给小搭子处理：\n\n{0}",
+    ),
+    ("dialog.print.title", "确认打印"),
+    (
+        "dialog.print.body",
+        "Agent 请求打印「{0}」（约 {1} 页 × {2} 份），是否继续？",
+    ),
+    ("auth.reason", "验证身份以{0}"),
+    ("auth.scope.remote_control", "开启远程控制"),
+    ("auth.scope.unlock", "解锁小搭子"),
+    ("auth.scope.safe_mode", "关闭安全模式"),
+    ("auth.scope.capabilities", "开启已关闭的能力"),
+    ("auth.scope.auth_settings", "延长身份验证宽限期"),
+    ("auth.scope.security_settings", "修改安全设置"),
+    ("approval.notify.title", "Agent 操作等待批准"),
+    ("approval.write_outside", "写入允许目录之外的路径：{0}"),
+    ("approval.remote_run", "远程节点 {0} 请求运行命令：{1}"),
+    ("approval.run_elevated", "以管理员权限运行命令：{0}"),
+    ("approval.capture_screen", "截取整个屏幕"),
+    ("menu.about", "关于小搭子"),
+    ("menu.preferences", "偏好设置…"),
+    ("menu.check_updates", "检查更新…"),
+    ("menu.safe_mode", "安全模式"),
+    ("menu.services", "服务"),
+    ("menu.hide", "隐藏小搭子"),
+    ("menu.hide_others", "隐藏其他"),
+    ("menu.show_all", "全部显示"),
+    ("menu.quit", "退出小搭子"),
+    ("menu.edit", "编辑"),
+    ("menu.window", "窗口"),
+    ("menu.show_main", "显示主窗口"),
+    ("shortcut.zoom_in", "放大"),
+    ("shortcut.zoom_out", "缩小"),
+    ("shortcut.zoom_reset", "实际大小"),
+    ("shortcut.undo", "撤销"),
+    ("shortcut.redo", "重做"),
+    ("shortcut.cut", "剪切"),
+    ("shortcut.copy", "复制"),
+    ("shortcut.paste", "粘贴"),
+    ("shortcut.select_all", "全选"),
+    ("shortcut.minimize", "最小化"),
+    ("shortcut.fullscreen", "进入全屏幕"),
+    ("shortcut.close_window", "关闭窗口"),
+    ("tray.about", "关于小搭子"),
+    ("sidecar.onboarding", "完成初始设置后启动服务"),
+    ("backend.dev_unreachable", "开发后端未运行 (localhost:{0})，正在等待启动"),
+    ("tray.backend_unhealthy", "服务无响应"),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("tray.show", "Show Window"),
+    ("tray.quit", "Quit"),
+    ("tray.advanced", "Advanced"),
+    ("tray.rollback", "Roll Back to Previous Version..."),
+    ("tray.automations", "Automations"),
+    ("tray.automations.empty", "No Automations"),
+    ("tray.snooze", "Pause Notifications"),
+    ("tray.snooze.until", "Notifications Paused Until {0}"),
+    ("tray.snooze.resume", "Resume Notifications"),
+    ("tray.snooze.minutes", "{0} Minutes"),
+    ("tray.snooze.hours", "{0} Hour(s)"),
+    ("automation.label.fs", "Watch {0} ({1})"),
+    ("automation.label.schedule", "Schedule {0}"),
+    ("automation.label.hotkey", "Hotkey {0}"),
+    ("sidecar.starting", "Starting service..."),
+    ("sidecar.ready", "Ready"),
+    ("sidecar.failed", "Service failed to start"),
+    ("sidecar.timeout", "Startup timed out, please retry"),
+    ("sidecar.adopted", "Connected to the running service"),
+    ("sidecar.port_conflict", "Ports are in use, cannot start the service"),
+    ("sidecar.stage.import", "Loading modules..."),
+    ("sidecar.stage.imported", "Modules loaded"),
+    ("sidecar.stage.resilience", "Loading configuration..."),
+    ("sidecar.stage.local_store", "Initializing data..."),
+    ("sidecar.stage.capabilities", "Loading tools..."),
+    ("sidecar.stage.agents", "Loading agents..."),
+    ("sidecar.stage.chat_service", "Warming up chat service..."),
+    ("sidecar.stage.knowledge", "Indexing knowledge base..."),
+    ("backend.health_failed", "Backend health check failed"),
+    ("backend.ports_blocked", "All ports are in use: {0}"),
+    ("backend.exited", "Backend process exited (code={0}, signal={1})"),
+    ("backend.timeout", "Backend startup timed out ({0}s)"),
+    ("backend.spawn_failed", "Failed to start backend process: {0}"),
+    ("backend.command_failed", "Failed to create backend command: {0}"),
+    ("notify.port_conflict.title", "Cannot start service"),
+    (
+        "notify.port_conflict.body",
+        "All ports are in use: {0}. Close the programs using them and restart the app.",
+    ),
+    ("notify.security.title", "Security Alert"),
+    ("dialog.port_exposed.title", "Backend Port Exposed"),
+    (
+        "dialog.port_exposed.body",
+        "{0}\n\nOther devices on your network may be able to reach this Agent. Check your firewall settings or restart the app.",
+    ),
+    ("dialog.kill_process.title", "Confirm Process Termination"),
+    (
+        "dialog.kill_process.body",
+        "The Agent wants to terminate \"{0}\" (PID {1}), which was not started by this app. Continue?",
+    ),
+    ("notify.update_available.title", "Update Available"),
+    ("notify.update_available.body", "Version {0} is available. You can update it from Settings."),
+    ("notify.update_on_quit.title", "Update Ready"),
+    ("notify.update_on_quit.body", "Version {0} has been downloaded and will be installed when you quit."),
+    ("dialog.rollback.title", "Roll Back"),
+    (
+        "dialog.rollback.body",
+        "The most recent update will be rolled back and the app will restart. Running tasks will be interrupted. Continue?",
+    ),
+    ("dialog.rollback_failed.body", "Rollback failed: {0}"),
+    ("dialog.crash_report.title", "Send Crash Report"),
+    (
+        "dialog.crash_report.body",
+        "The app crashed {0} time(s) during its last run. Send a crash report to help us improve? Reports include the error and recent logs.",
+    ),
+    ("dialog.crash_report.send", "Send"),
+    ("dialog.crash_report.dont_send", "Don't Send"),
+    ("notify.snooze_summary.title", "Notifications While Paused"),
+    ("notify.snooze_summary.body", "{0} notifications: {1}"),
+    ("notify.low_disk.title", "Low Disk Space"),
+    (
+        "notify.low_disk.body",
+        "Only {0} MB left on the disk holding your data. Tasks may fail; please free up some space.",
+    ),
+    ("notify.scheduled_task.succeeded", "Scheduled Task Completed"),
+    ("notify.scheduled_task.failed", "Scheduled Task Failed"),
+    ("dialog.intent.title", "Shortcut Request"),
+    ("dialog.intent.allow", "Allow"),
+    ("dialog.intent.deny", "Deny"),
+    ("dialog.intent.ask_body", "Another app wants to send the following to xiaodazi:\n\n{0}"),
+    ("dialog.intent.run_body", "Another app wants to run the following command:\n\n{0}\n\nOnly allow it if you trust the source."),
+    ("dialog.intent.ocr_body", "Another app wants to capture your entire screen and recognize its text. The result will be shown in xiaodazi."),
+    (
+        "dialog.intent.ocr_ask_body",
+        "Another app wants to capture your entire screen and send the recognized text to xiaodazi with the following question:\n\n{0}",
+    ),
+    ("dialog.print.title", "Confirm Printing"),
+    (
+        "dialog.print.body",
+        "The agent wants to print \"{0}\" (about {1} pages × {2} copies). Continue?",
+    ),
+    ("auth.reason", "authenticate to {0}"),
+    ("auth.scope.remote_control", "enable remote control"),
+    ("auth.scope.unlock", "unlock xiaodazi"),
+    ("auth.scope.safe_mode", "turn off safe mode"),
+    ("auth.scope.capabilities", "re-enable a capability"),
+    ("auth.scope.auth_settings", "extend the authentication grace period"),
+    ("auth.scope.security_settings", "change security settings"),
+    ("approval.notify.title", "Agent action awaiting approval"),
+    ("approval.write_outside", "Write outside the allowed folders: {0}"),
+    ("approval.remote_run", "Remote node {0} wants to run: {1}"),
+    ("approval.run_elevated", "Run a command with administrator rights: {0}"),
+    ("approval.capture_screen", "Capture the entire screen"),
+    ("menu.about", "About xiaodazi"),
+    ("menu.preferences", "Preferences…"),
+    ("menu.check_updates", "Check for Updates…"),
+    ("menu.safe_mode", "Safe Mode"),
+    ("menu.services", "Services"),
+    ("menu.hide", "Hide xiaodazi"),
+    ("menu.hide_others", "Hide Others"),
+    ("menu.show_all", "Show All"),
+    ("menu.quit", "Quit xiaodazi"),
+    ("menu.edit", "Edit"),
+    ("menu.window", "Window"),
+    ("menu.show_main", "Show Main Window"),
+    ("shortcut.zoom_in", "Zoom In"),
+    ("shortcut.zoom_out", "Zoom Out"),
+    ("shortcut.zoom_reset", "Actual Size"),
+    ("shortcut.undo", "Undo"),
+    ("shortcut.redo", "Redo"),
+    ("shortcut.cut", "Cut"),
+    ("shortcut.copy", "Copy"),
+    ("shortcut.paste", "Paste"),
+    ("shortcut.select_all", "Select All"),
+    ("shortcut.minimize", "Minimize"),
+    ("shortcut.fullscreen", "Enter Full Screen"),
+    ("shortcut.close_window", "Close Window"),
+    ("tray.about", "About xiaodazi"),
+    ("sidecar.onboarding", "The service starts after setup is complete"),
+    ("backend.dev_unreachable", "Dev backend is not running (localhost:{0}), waiting for it to start"),
+    ("tray.backend_unhealthy", "Service not responding"),
+];
+
+fn current_lang() -> &'static RwLock<Language> {
+    static LANG: RwLock<Language> = RwLock::new(Language::ZhCn);
+    &LANG
+}
+
+/// 当前界面语言
+pub fn language() -> Language {
+    *current_lang()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+fn set_current(lang: Language) {
+    *current_lang()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = lang;
+}
+
+/// 系统语言（无法识别时为中文）
+pub fn system_language() -> Language {
+    sys_locale::get_locale()
+        .and_then(|tag| Language::from_tag(&tag))
+        .unwrap_or_default()
+}
+
+/// 根据设置确定启动时的语言（设置覆盖 > 系统语言）
+pub fn init(settings: &settings::AppSettings) {
+    let lang = settings
+        .language
+        .as_deref()
+        .and_then(Language::from_tag)
+        .unwrap_or_else(system_language);
+    set_current(lang);
+    debug_log(&format!("[i18n] 界面语言: {}", lang.tag()));
+}
+
+fn lookup(lang: Language, key: &str) -> Option<&'static str> {
+    let table = match lang {
+        Language::ZhCn => ZH_CN,
+        Language::En => EN,
+    };
+    table.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+/// 翻译文案
+pub fn t(key: &str) -> String {
+    lookup(language(), key)
+        .or_else(|| lookup(Language::ZhCn, key))
+        .map(str::to_string)
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// 翻译文案并按位置替换 {0}、{1}…
+pub fn tf(key: &str, args: &[&dyn std::fmt::Display]) -> String {
+    args.iter().enumerate().fold(t(key), |s, (i, arg)| {
+        s.replace(&format!("{{{}}}", i), &arg.to_string())
+    })
+}
+
+/// 翻译 sidecar 上报的启动阶段，没有对应翻译时使用后端自带的文案
+pub fn stage_message(stage: &str, fallback: &str) -> String {
+    lookup(language(), &format!("sidecar.stage.{}", stage))
+        .map(str::to_string)
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// 按当前语言构建托盘菜单
+pub fn tray_menu<R: tauri::Runtime, M: Manager<R>>(
+    manager: &M,
+) -> tauri::Result<tauri::menu::Menu<R>> {
+    use tauri::menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+
+    let show_item = MenuItemBuilder::with_id("show", t("tray.show")).build(manager)?;
+
+    // 自动化规则：勾选表示已启用，点击切换
+    let mut automations = SubmenuBuilder::new(manager, t("tray.automations"));
+    let rules = crate::automation::tray_items(manager);
+    if rules.is_empty() {
+        let empty = MenuItemBuilder::new(t("tray.automations.empty"))
+            .enabled(false)
+            .build(manager)?;
+        automations = automations.item(&empty);
+    }
+    for (id, label, enabled) in rules {
+        let item = CheckMenuItemBuilder::with_id(id, label)
+            .checked(enabled)
+            .build(manager)?;
+        automations = automations.item(&item);
+    }
+    let automations = automations.build()?;
+
+    // 暂停通知：暂停期间标题显示结束时间，并提供"恢复通知"
+    let snoozed_until = crate::notifications::snoozed_until(manager);
+    let mut snooze = SubmenuBuilder::new(
+        manager,
+        match snoozed_until {
+            Some(until) => tf("tray.snooze.until", &[&until.format("%H:%M")]),
+            None => t("tray.snooze"),
+        },
+    );
+    if snoozed_until.is_some() {
+        let resume = MenuItemBuilder::with_id(
+            format!("{}resume", crate::notifications::SNOOZE_ITEM_PREFIX),
+            t("tray.snooze.resume"),
+        )
+        .build(manager)?;
+        snooze = snooze.item(&resume).separator();
+    }
+    for &minutes in crate::notifications::SNOOZE_PRESETS {
+        let label = if minutes % 60 == 0 {
+            tf("tray.snooze.hours", &[&(minutes / 60)])
+        } else {
+            tf("tray.snooze.minutes", &[&minutes])
+        };
+        let item = MenuItemBuilder::with_id(
+            format!("{}{}", crate::notifications::SNOOZE_ITEM_PREFIX, minutes),
+            label,
+        )
+        .build(manager)?;
+        snooze = snooze.item(&item);
+    }
+    let snooze = snooze.build()?;
+
+    let about_item = MenuItemBuilder::with_id("about", t("tray.about")).build(manager)?;
+    let rollback_item = MenuItemBuilder::with_id("rollback", t("tray.rollback")).build(manager)?;
+    let advanced = SubmenuBuilder::new(manager, t("tray.advanced"))
+        .item(&rollback_item)
+        .build()?;
+    let quit_item = MenuItemBuilder::with_id("quit", t("tray.quit")).build(manager)?;
+    MenuBuilder::new(manager)
+        .item(&show_item)
+        .separator()
+        .item(&automations)
+        .item(&snooze)
+        .item(&advanced)
+        .separator()
+        .item(&about_item)
+        .item(&quit_item)
+        .build()
+}
+
+/// 重建托盘菜单（语言切换或菜单内容变化后调用）
+pub fn refresh_tray_menu(app: &tauri::AppHandle) -> Result<(), String> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let menu = tray_menu(app).map_err(|e| e.to_string())?;
+        tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 获取当前界面语言
+#[tauri::command]
+pub async fn get_language() -> Result<String, String> {
+    Ok(language().tag().to_string())
+}
+
+/// 切换界面语言（传入 "system" 或空值恢复跟随系统），返回生效的语言
+#[tauri::command]
+pub async fn set_language(
+    app: tauri::AppHandle,
+    language: Option<String>,
+) -> Result<String, String> {
+    if crate::policy::is_locked_setting("language") {
+        return Err("Setting locked by policy: language".to_string());
+    }
+    let requested = language.filter(|l| !l.is_empty() && l != "system");
+    let lang = match requested.as_deref() {
+        Some(tag) => {
+            Language::from_tag(tag).ok_or_else(|| format!("Unsupported language: {}", tag))?
+        }
+        None => system_language(),
+    };
+
+    {
+        let state = app.state::<std::sync::Mutex<settings::AppSettings>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        guard.language = requested.map(|_| lang.tag().to_string());
+        settings::save_settings(&guard)?;
+    }
+    set_current(lang);
+
+    refresh_tray_menu(&app)?;
+    #[cfg(target_os = "macos")]
+    crate::menu::refresh(&app);
+
+    debug_log(&format!("[i18n] 界面语言切换为 {}", lang.tag()));
+    let _ = app.emit("language-changed", lang.tag());
+    let _ = app.emit("settings-changed", settings::current(&app));
+    Ok(lang.tag().to_string())
+}
@@ -0,0 +1,351 @@
+// ============================================================================
+// 系统快捷指令入口（xiaodazi:// URL）
+// ============================================================================
+//
+// macOS 快捷指令（Shortcuts）通过"打开 URL"动作调用，其它应用与脚本同样可用：
+//   xiaodazi://ask?text=...[&conversation_id=...]   把一段文字交给 Agent
+//   xiaodazi://ocr-screen[?ask=...]                  截取屏幕并识别文字，可附带提问交给 Agent（每次都需确认）
+//   xiaodazi://run?command=...                       运行快捷命令（每次执行前都需用户确认）
+// 可附带 x-success / x-error 回调 URL（x-callback-url 约定），结果以 result / error 参数回传。
+// 任何网页都能打开自定义 URL，因此 ask 默认也需要确认（设置 confirm_url_intents）；
+// 识别出的屏幕文字只发给本应用前端，不会写入回调 URL，避免网页借回调窃取屏幕内容。
+// macOS 服务菜单（Services）入口见 services.rs，同样经由这里执行。
+
+use crate::{debug_log, http, i18n, lock, permissions, scheduler, settings};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// URL scheme（与 tauri.conf.json 中 plugins.deep-link 保持一致）
+pub const URL_SCHEME: &str = "xiaodazi";
+
+/// 快捷指令使用的用户 ID（与前端本地模式一致）
+const LOCAL_USER_ID: &str = "local";
+
+/// 快捷命令的超时
+const QUICK_COMMAND_TIMEOUT_MS: u64 = 60_000;
+
+/// 快捷提问等待后端回复的超时
+const CHAT_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Intent {
+    Ask {
+        text: String,
+        conversation_id: Option<String>,
+    },
+    OcrScreen {
+        ask: Option<String>,
+    },
+    RunCommand {
+        command: String,
+    },
+}
+
+impl Intent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Ask { .. } => "ask",
+            Self::OcrScreen { .. } => "ocr-screen",
+            Self::RunCommand { .. } => "run",
+        }
+    }
+
+    /// 结果是否可以写入 x-success 回调（屏幕文字不能交给外部 URL）
+    fn exposes_result(&self) -> bool {
+        !matches!(self, Self::OcrScreen { ask: None })
+    }
+}
+
+/// 解析快捷指令 URL，返回 (意图, 查询参数)
+fn parse(url: &url::Url) -> Result<(Intent, HashMap<String, String>), String> {
+    if url.scheme() != URL_SCHEME {
+        return Err(format!("Unsupported URL scheme: {}", url.scheme()));
+    }
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let param = |key: &str| params.get(key).filter(|v| !v.trim().is_empty()).cloned();
+
+    // xiaodazi://ask?... 中 ask 是 host；xiaodazi:///ask 或 xiaodazi:ask 中是 path
+    let action = url
+        .host_str()
+        .filter(|h| !h.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| url.path().trim_matches('/').to_string());
+
+    let intent = match action.as_str() {
+        "ask" => Intent::Ask {
+            text: param("text").ok_or("Missing parameter: text")?,
+            conversation_id: param("conversation_id"),
+        },
+        "ocr-screen" => Intent::OcrScreen { ask: param("ask") },
+        "run" => Intent::RunCommand {
+            command: param("command").ok_or("Missing parameter: command")?,
+        },
+        other => return Err(format!("Unknown intent: {}", other)),
+    };
+    Ok((intent, params))
+}
+
+/// 弹出确认框（在阻塞线程中等待用户选择）
+async fn confirm(app: &tauri::AppHandle, body: String) -> bool {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        app.dialog()
+            .message(body)
+            .title(i18n::t("dialog.intent.title"))
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                i18n::t("dialog.intent.allow"),
+                i18n::t("dialog.intent.deny"),
+            ))
+            .blocking_show()
+    })
+    .await
+    .unwrap_or(false)
+}
+
+/// 把文字交给 Agent（后端同步模式立即返回任务信息，回复在应用中查看）
+async fn ask_agent(
+    app: &tauri::AppHandle,
+    text: &str,
+    conversation_id: Option<&str>,
+) -> Result<String, String> {
+    let mut body = serde_json::json!({
+        "message": text,
+        "userId": LOCAL_USER_ID,
+        "stream": false,
+    });
+    if let Some(id) = conversation_id {
+        body["conversationId"] = serde_json::Value::String(id.to_string());
+    }
+    match http::call_backend(app, "POST", "/api/v1/chat", Some(&body), CHAT_TIMEOUT).await {
+        (true, output) => Ok(output),
+        (false, output) => Err(output),
+    }
+}
+
+/// 截取整个屏幕保存到 path（仅 macOS）
+pub(crate) fn capture_screen(path: &std::path::Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        // 未授权时 screencapture 仍会成功，但其他应用的窗口是空白的
+        if !crate::screen_capture::screen_capture_granted() {
+            return Err(
+                "Screen recording permission not granted (see preflight_screen_capture)"
+                    .to_string(),
+            );
+        }
+        let status = std::process::Command::new("screencapture")
+            .arg("-x")
+            .arg(path)
+            .status()
+            .map_err(|e| format!("截图失败: {}", e))?;
+        if !status.success() || !path.is_file() {
+            // 未授予屏幕录制权限时 screencapture 不会生成文件
+            return Err("Screen capture failed (screen recording permission required)".to_string());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = path;
+        Err("Screen capture is only supported on macOS".to_string())
+    }
+}
+
+/// 截取整个屏幕并识别文字
+async fn capture_and_ocr(app: &tauri::AppHandle) -> Result<String, String> {
+    let dir = settings::app_data_dir().join("screenshots");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建截图目录失败: {}", e))?;
+    let path = dir.join(format!(
+        "intent-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let captured = capture_screen(&path);
+    crate::timeline::record(
+        app,
+        crate::timeline::TimelineKind::Capture,
+        "screen.capture",
+        captured.is_ok(),
+        serde_json::json!({ "path": path, "source": "intent.ocr_screen" }),
+    );
+    captured?;
+    let result = crate::ocr::ocr_image(path.to_string_lossy().to_string(), None).await?;
+    Ok(result.text)
+}
+
+/// 通过系统 shell 运行快捷命令
+async fn run_quick_command(app: &tauri::AppHandle, command: &str) -> Result<String, String> {
+    #[cfg(windows)]
+    let argv = vec!["cmd".to_string(), "/C".to_string(), command.to_string()];
+    #[cfg(not(windows))]
+    let argv = vec!["/bin/sh".to_string(), "-c".to_string(), command.to_string()];
+
+    let result = crate::run_shell_command(
+        app,
+        Some("intent:run"),
+        argv,
+        dirs::home_dir().map(|p| p.to_string_lossy().to_string()),
+        None,
+        Some(QUICK_COMMAND_TIMEOUT_MS),
+        None,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    if result.success {
+        Ok(scheduler::truncate_output(result.stdout))
+    } else {
+        Err(scheduler::truncate_output(result.stderr))
+    }
+}
+
+fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+async fn execute(app: &tauri::AppHandle, intent: &Intent) -> Result<String, String> {
+    // 外部 URL 不是用户事先登记的动作，锁定期间一律拒绝
+    if lock::is_locked(app) {
+        return Err("App is locked".to_string());
+    }
+    let require_confirm = settings::current(app).confirm_url_intents;
+    match intent {
+        Intent::Ask {
+            text,
+            conversation_id,
+        } => {
+            if require_confirm && !confirm(app, i18n::tf("dialog.intent.ask_body", &[text])).await {
+                return Err("Cancelled by user".to_string());
+            }
+            let response = ask_agent(app, text, conversation_id.as_deref()).await?;
+            show_main_window(app);
+            Ok(response)
+        }
+        Intent::OcrScreen { ask } => {
+            // 截图前检查能力（包括应用锁定），并且无论设置如何都需要确认
+            for capability in ["screen.record", "screen.ocr"] {
+                permissions::check_capability(app, capability).map_err(|e| e.message)?;
+            }
+            let body = match ask {
+                Some(question) => i18n::tf("dialog.intent.ocr_ask_body", &[question]),
+                None => i18n::t("dialog.intent.ocr_body"),
+            };
+            if !confirm(app, body).await {
+                return Err("Cancelled by user".to_string());
+            }
+            let text = capture_and_ocr(app).await?;
+            let Some(question) = ask else {
+                show_main_window(app);
+                return Ok(text);
+            };
+            let prompt = format!("{}\n\n---\n{}", question, text);
+            let response = ask_agent(app, &prompt, None).await?;
+            show_main_window(app);
+            Ok(response)
+        }
+        Intent::RunCommand { command } => {
+            // 确认前检查能力（包括应用锁定）；命令来自外部 URL，无论设置如何都需要确认
+            permissions::check_capability(app, "system.run").map_err(|e| e.message)?;
+            if !confirm(app, i18n::tf("dialog.intent.run_body", &[command])).await {
+                return Err("Cancelled by user".to_string());
+            }
+            run_quick_command(app, command).await
+        }
+    }
+}
+
+/// 打开 x-success / x-error 回调（expose_result 为 false 时成功回调不附带结果）
+fn open_callback(
+    params: &HashMap<String, String>,
+    result: &Result<String, String>,
+    expose_result: bool,
+) {
+    let (key, param, value) = match result {
+        Ok(v) => ("x-success", "result", v),
+        Err(e) => ("x-error", "error", e),
+    };
+    let Some(mut callback) = params.get(key).and_then(|u| url::Url::parse(u).ok()) else {
+        return;
+    };
+    // 回调只能跳转到其它 App，不允许回到本应用（避免循环触发）
+    if callback.scheme() == URL_SCHEME || matches!(callback.scheme(), "http" | "https" | "file") {
+        debug_log(&format!(
+            "[intents] 忽略不安全的回调 URL: {}",
+            callback.scheme()
+        ));
+        return;
+    }
+    if result.is_err() || expose_result {
+        callback.query_pairs_mut().append_pair(param, value);
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Err(e) = std::process::Command::new("open")
+        .arg(callback.as_str())
+        .spawn()
+    {
+        debug_log(&format!("[intents] 打开回调 URL 失败: {}", e));
+    }
+}
+
+/// 执行意图并把结果发给前端（URL 与服务菜单共用）
+pub(crate) async fn dispatch(app: &tauri::AppHandle, intent: &Intent) -> Result<String, String> {
+    debug_log(&format!("[intents] 收到快捷指令: {}", intent.name()));
+    let result = execute(app, intent).await;
+    if let Err(e) = &result {
+        debug_log(&format!("[intents] 快捷指令 {} 失败: {}", intent.name(), e));
+    }
+    let _ = app.emit(
+        "intent-completed",
+        serde_json::json!({
+            "intent": intent.name(),
+            "success": result.is_ok(),
+            "result": result.as_ref().ok(),
+            "error": result.as_ref().err(),
+        }),
+    );
+    result
+}
+
+/// 处理一个快捷指令 URL
+async fn handle_url(app: tauri::AppHandle, url: url::Url) {
+    let (intent, params) = match parse(&url) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            debug_log(&format!("[intents] 无法识别的快捷指令: {}", e));
+            return;
+        }
+    };
+    let result = dispatch(&app, &intent).await;
+    open_callback(&params, &result, intent.exposes_result());
+}
+
+/// 注册 URL scheme 回调（包括通过快捷指令 URL 启动应用的情况）
+pub fn start(app: &tauri::AppHandle) {
+    // Windows / Linux 需在运行时注册 scheme（macOS 由打包时写入的 Info.plist 注册）
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        debug_log(&format!("[intents] 注册 URL scheme 失败: {}", e));
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            tauri::async_runtime::spawn(handle_url(handle.clone(), url));
+        }
+    });
+
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            tauri::async_runtime::spawn(handle_url(app.clone(), url));
+        }
+    }
+}
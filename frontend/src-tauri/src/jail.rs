@@ -0,0 +1,237 @@
+// ============================================================================
+// 任务工作区隔离：限制 Agent 任务启动的命令只能访问任务工作区
+// ============================================================================
+//
+// run_command 传入 task_id 且设置 task_command_jail 开启时：
+// - 工作目录固定在任务工作区（数据目录/workspace/scratchpad/<task_id>）内，相对 cwd 基于工作区解析
+// - 参数中的绝对路径（含 --opt=/path 形式）与通过 ".." 跳出工作区的相对路径，
+//   必须位于工作区或托管策略 allowed_paths 内，否则拒绝执行并返回 JailViolation
+// 这是参数层面的约束，不是操作系统沙箱：命令本身仍可在运行时访问其它路径。
+
+use crate::{policy, settings};
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+/// 任务工作区根目录（相对数据目录，与后端 get_scratchpad_dir 一致）
+const TASK_WORKSPACE_DIR: &str = "workspace/scratchpad";
+
+/// 不视为文件访问的特殊路径
+const ALWAYS_ALLOWED_PATHS: &[&str] = &["/dev/null", "NUL"];
+
+/// 命令参数越出任务工作区时返回的错误
+#[derive(Debug, Clone, Serialize)]
+pub struct JailViolation {
+    /// invalid_task_id / cwd_outside_workspace / path_outside_workspace
+    pub code: &'static str,
+    pub task_id: String,
+    pub workspace: String,
+    /// 越界的路径
+    pub path: Option<String>,
+    pub message: String,
+}
+
+impl std::fmt::Display for JailViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// 按路径组件规范化（处理 "." 与 ".."，不访问文件系统）
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// 解析路径：取最近的已存在上级目录解析符号链接，其余部分按组件规范化
+fn resolve(path: &Path) -> PathBuf {
+    let path = normalize(path);
+    path.ancestors()
+        .find_map(|p| std::fs::canonicalize(p).ok().map(|c| (p, c)))
+        .map(|(existing, canonical)| {
+            canonical.join(path.strip_prefix(existing).unwrap_or(Path::new("")))
+        })
+        .unwrap_or(path)
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|h| h.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// 任务命令隔离
+pub struct Jail {
+    task_id: String,
+    workspace: PathBuf,
+    allowed: Vec<PathBuf>,
+}
+
+impl Jail {
+    /// 设置关闭隔离时返回 None
+    pub fn for_task(task_id: &str) -> Result<Option<Self>, JailViolation> {
+        if !settings::load_settings().task_command_jail {
+            return Ok(None);
+        }
+        let violation = |message: String| JailViolation {
+            code: "invalid_task_id",
+            task_id: task_id.to_string(),
+            workspace: String::new(),
+            path: None,
+            message,
+        };
+        let valid = !task_id.is_empty()
+            && task_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(violation(format!("Invalid task id: {}", task_id)));
+        }
+        let workspace = settings::app_data_dir()
+            .join(TASK_WORKSPACE_DIR)
+            .join(task_id);
+        std::fs::create_dir_all(&workspace)
+            .map_err(|e| violation(format!("创建任务工作区失败: {}", e)))?;
+        let workspace = std::fs::canonicalize(&workspace).unwrap_or(workspace);
+        let mut allowed = vec![workspace.clone()];
+        allowed.extend(
+            policy::current()
+                .allowed_paths
+                .iter()
+                .map(|p| resolve(&expand_home(p))),
+        );
+        Ok(Some(Self {
+            task_id: task_id.to_string(),
+            workspace,
+            allowed,
+        }))
+    }
+
+    fn violation(&self, code: &'static str, path: &str) -> JailViolation {
+        JailViolation {
+            code,
+            task_id: self.task_id.clone(),
+            workspace: self.workspace.display().to_string(),
+            path: Some(path.to_string()),
+            message: format!("Path outside task workspace: {}", path),
+        }
+    }
+
+    /// 解析并校验工作目录（未传入时为工作区根目录）
+    pub fn cwd(&self, cwd: Option<&str>) -> Result<PathBuf, JailViolation> {
+        let Some(cwd) = cwd else {
+            return Ok(self.workspace.clone());
+        };
+        let resolved = resolve(&self.workspace.join(expand_home(cwd)));
+        if resolved.starts_with(&self.workspace) {
+            Ok(resolved)
+        } else {
+            Err(self.violation("cwd_outside_workspace", cwd))
+        }
+    }
+
+    /// 校验命令参数中的路径（第一个元素为可执行文件，不校验）
+    pub fn check_args(&self, command: &[String], cwd: &Path) -> Result<(), JailViolation> {
+        for arg in command.iter().skip(1) {
+            // --output=/tmp/x 形式取等号后的部分
+            let value = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with('-') => value,
+                _ => arg.as_str(),
+            };
+            if value.is_empty() || ALWAYS_ALLOWED_PATHS.contains(&value) {
+                continue;
+            }
+            let path = expand_home(value);
+            let escapes_by_parent = path.components().any(|c| matches!(c, Component::ParentDir));
+            if !path.is_absolute() && !escapes_by_parent {
+                continue;
+            }
+            let resolved = resolve(&cwd.join(&path));
+            if !self.allowed.iter().any(|root| resolved.starts_with(root)) {
+                return Err(self.violation("path_outside_workspace", value));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    /// 在临时目录下建立任务工作区（workspace）与额外允许的目录（shared），返回隔离与根目录
+    fn jail(name: &str) -> (Jail, PathBuf) {
+        let root =
+            std::env::temp_dir().join(format!("xiaodazi-jail-{}-{}", name, std::process::id()));
+        for dir in ["workspace", "shared", "other"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let root = std::fs::canonicalize(&root).unwrap();
+        let jail = Jail {
+            task_id: "task-1".to_string(),
+            workspace: root.join("workspace"),
+            allowed: vec![root.join("workspace"), root.join("shared")],
+        };
+        (jail, root)
+    }
+
+    #[test]
+    fn check_args_allows_paths_inside_allowed_roots() {
+        let (jail, root) = jail("inside");
+        let cwd = root.join("workspace");
+        let inside = cwd.join("out.txt").display().to_string();
+        let shared = root.join("shared").join("data.csv").display().to_string();
+        for args in [
+            argv(&["cat", "notes.txt", "sub/../notes.txt", "-n"]),
+            argv(&["cp", "notes.txt", &inside]),
+            argv(&["tool", &format!("--output={}", inside)]),
+            argv(&["cat", &shared]),
+            argv(&["tool", "/dev/null", "--log="]),
+            // 第一个元素为可执行文件，不校验
+            argv(&["/usr/bin/env"]),
+        ] {
+            assert!(jail.check_args(&args, &cwd).is_ok(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn check_args_rejects_paths_outside_allowed_roots() {
+        let (jail, root) = jail("outside");
+        let cwd = root.join("workspace");
+        let other = root.join("other").join("secret.txt").display().to_string();
+        for (args, path) in [
+            (argv(&["cat", &other]), other.clone()),
+            (
+                argv(&["cat", "../other/secret.txt"]),
+                "../other/secret.txt".to_string(),
+            ),
+            (
+                argv(&["cat", "sub/../../other"]),
+                "sub/../../other".to_string(),
+            ),
+            (
+                argv(&["tool", &format!("--output={}", other)]),
+                other.clone(),
+            ),
+        ] {
+            let violation = jail.check_args(&args, &cwd).unwrap_err();
+            assert_eq!(violation.code, "path_outside_workspace");
+            assert_eq!(violation.path.as_deref(), Some(path.as_str()));
+        }
+    }
+}
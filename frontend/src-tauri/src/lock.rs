@@ -0,0 +1,215 @@
+// ============================================================================
+// 空闲自动锁定
+// ============================================================================
+//
+// 用户（系统级）空闲超过设置 auto_lock_minutes 后进入锁定状态：
+// - 发出 `app-locked`，前端显示锁屏遮罩
+// - 除锁屏所需的少数命令外，前端与远程调用的命令一律拒绝（见 permissions::guard）
+// - 清除身份验证宽限期，解锁需通过系统身份验证（auth::authenticate_now）
+// 计划任务与自动化规则不受影响（执行时只检查能力开关，见 permissions::check_unattended_capability），
+// Agent 可继续在无人值守时运行。
+// 锁定期间到期的待审批请求不会被视为超时，解锁后重新提示并重新计时（见 approvals::request_approval）。
+
+use crate::{auth, debug_log, events, settings};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::Notify;
+
+/// 空闲检查间隔（秒）
+const LOCK_POLL_SECS: u64 = 30;
+
+/// 锁定期间仍允许调用的命令（锁屏界面、前端日志与后端连接所需，均为只读）
+const ALLOWED_WHILE_LOCKED: &[&str] = &[
+    "get_lock_state",
+    "unlock_app",
+    "lock_app",
+    "get_backend_url",
+    "get_backend_ws_url",
+    "is_backend_ready",
+    "get_sidecar_status",
+    "get_app_info",
+    "get_language",
+    "get_system_theme",
+    "log_from_frontend",
+];
+
+#[derive(Default)]
+pub struct LockState {
+    locked: AtomicBool,
+    /// 累计锁定次数（用于判断某段时间内是否锁定过）
+    generation: AtomicU64,
+    /// 解锁时通知等待者
+    unlocked: Notify,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockStatus {
+    pub locked: bool,
+    /// 自动锁定时长（分钟），0 表示关闭
+    pub auto_lock_minutes: u64,
+    /// 当前系统空闲时长（无法检测时为 None）
+    pub idle_secs: Option<u64>,
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn system_idle_secs() -> Option<u64> {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+    // kCGEventSourceStateCombinedSessionState = 0, kCGAnyInputEventType = ~0
+    let secs = unsafe { CGEventSourceSecondsSinceLastEventType(0, u32::MAX) };
+    (secs.is_finite() && secs >= 0.0).then_some(secs as u64)
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn system_idle_secs() -> Option<u64> {
+    #[repr(C)]
+    struct LastInputInfo {
+        cb_size: u32,
+        dw_time: u32,
+    }
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetLastInputInfo(plii: *mut LastInputInfo) -> i32;
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTickCount() -> u32;
+    }
+    let mut info = LastInputInfo {
+        cb_size: std::mem::size_of::<LastInputInfo>() as u32,
+        dw_time: 0,
+    };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    let idle_ms = unsafe { GetTickCount() }.wrapping_sub(info.dw_time);
+    Some(idle_ms as u64 / 1000)
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn system_idle_secs() -> Option<u64> {
+    use std::process::Command as SysCommand;
+
+    let run = |program: &str, args: &[&str]| -> Option<String> {
+        let output = SysCommand::new(program).args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+    // X11: xprintidle 输出毫秒
+    if let Some(ms) = run("xprintidle", &[]).and_then(|s| s.trim().parse::<u64>().ok()) {
+        return Some(ms / 1000);
+    }
+    // GNOME (Wayland)：输出形如 "(uint64 12345,)"
+    let out = run(
+        "gdbus",
+        &[
+            "call",
+            "--session",
+            "--dest",
+            "org.gnome.Mutter.IdleMonitor",
+            "--object-path",
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            "--method",
+            "org.gnome.Mutter.IdleMonitor.GetIdletime",
+        ],
+    )?;
+    out.split_whitespace()
+        .nth(1)
+        .and_then(|s| s.trim_end_matches([',', ')']).parse::<u64>().ok())
+        .map(|ms| ms / 1000)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub(crate) fn system_idle_secs() -> Option<u64> {
+    None
+}
+
+/// 当前是否处于锁定状态
+pub fn is_locked(app: &tauri::AppHandle) -> bool {
+    app.state::<LockState>().locked.load(Ordering::SeqCst)
+}
+
+/// 累计锁定次数，与之前读取的值不同说明期间锁定过
+pub fn lock_generation(app: &tauri::AppHandle) -> u64 {
+    app.state::<LockState>().generation.load(Ordering::SeqCst)
+}
+
+/// 等待解锁（未锁定时立即返回）
+pub async fn wait_unlocked(app: &tauri::AppHandle) {
+    let state = app.state::<LockState>();
+    let unlocked = state.unlocked.notified();
+    if !state.locked.load(Ordering::SeqCst) {
+        return;
+    }
+    unlocked.await;
+}
+
+/// 锁定期间该命令是否被拒绝
+pub fn is_blocked(app: &tauri::AppHandle, command: &str) -> bool {
+    is_locked(app) && !ALLOWED_WHILE_LOCKED.contains(&command)
+}
+
+fn lock(app: &tauri::AppHandle, reason: &str) {
+    let state = app.state::<LockState>();
+    if state.locked.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    auth::revoke_all(app);
+    debug_log(&format!("[lock] 应用已锁定 ({})", reason));
+    events::emit(app, "app-locked", serde_json::json!({ "reason": reason }));
+}
+
+/// 启动空闲监视线程
+pub fn start_idle_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(LOCK_POLL_SECS));
+        let minutes = settings::current(&app).auto_lock_minutes;
+        if minutes == 0 || is_locked(&app) {
+            continue;
+        }
+        if system_idle_secs().is_some_and(|idle| idle >= minutes * 60) {
+            lock(&app, "idle");
+        }
+    });
+}
+
+/// 获取锁定状态
+#[tauri::command]
+pub async fn get_lock_state(app: tauri::AppHandle) -> Result<LockStatus, String> {
+    Ok(LockStatus {
+        locked: is_locked(&app),
+        auto_lock_minutes: settings::current(&app).auto_lock_minutes,
+        idle_secs: tauri::async_runtime::spawn_blocking(system_idle_secs)
+            .await
+            .map_err(|e| e.to_string())?,
+    })
+}
+
+/// 立即锁定
+#[tauri::command]
+pub async fn lock_app(app: tauri::AppHandle) -> Result<(), String> {
+    lock(&app, "manual");
+    Ok(())
+}
+
+/// 通过系统身份验证解锁
+#[tauri::command]
+pub async fn unlock_app(app: tauri::AppHandle) -> Result<(), String> {
+    if !is_locked(&app) {
+        return Ok(());
+    }
+    auth::authenticate_now(&app, "unlock").await?;
+    let state = app.state::<LockState>();
+    state.locked.store(false, Ordering::SeqCst);
+    state.unlocked.notify_waiters();
+    debug_log("[lock] 应用已解锁");
+    events::emit(&app, "app-unlocked", ());
+    Ok(())
+}
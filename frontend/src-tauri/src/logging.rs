@@ -0,0 +1,331 @@
+// ============================================================================
+// 统一日志文件（Rust 壳层、sidecar 输出、前端日志）
+// ============================================================================
+//
+// 所有来源写入数据目录下的 logs/xiaodazi.log，按时间顺序形成同一条时间线。
+// 文件超过 MAX_LOG_BYTES 时轮转为 xiaodazi.1.log、xiaodazi.2.log…，最多保留 MAX_ROTATED 份。
+// 日志在 Tauri 应用创建之前就会写入，因此路径不依赖 AppHandle。
+//
+// 写入前会脱敏：Bearer token、API key 等凭据替换为 [REDACTED]，
+// 并可按设置（redact_home_path）把用户主目录替换为 ~、用户名替换为 <user>，
+// 方便用户直接分享日志。
+
+use crate::settings;
+use regex::Regex;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// 日志目录（数据目录下）
+pub const LOG_DIR: &str = "logs";
+
+/// 当前日志文件名（不含扩展名）
+const LOG_STEM: &str = "xiaodazi";
+
+/// 单个日志文件上限
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// 保留的历史日志份数
+const MAX_ROTATED: usize = 3;
+
+/// 前端单条日志的最大长度（字节）
+const MAX_FRONTEND_MESSAGE_BYTES: usize = 8 * 1024;
+
+/// 脱敏后的占位符
+const REDACTED: &str = "[REDACTED]";
+
+/// 是否把主目录 / 用户名替换掉（由设置控制）
+static REDACT_HOME_PATH: AtomicBool = AtomicBool::new(true);
+
+struct LogWriter {
+    file: Option<File>,
+    size: u64,
+}
+
+fn writer() -> &'static Mutex<LogWriter> {
+    static WRITER: OnceLock<Mutex<LogWriter>> = OnceLock::new();
+    WRITER.get_or_init(|| Mutex::new(LogWriter { file: None, size: 0 }))
+}
+
+pub fn log_dir() -> PathBuf {
+    settings::app_data_dir().join(LOG_DIR)
+}
+
+fn log_path(index: usize) -> PathBuf {
+    if index == 0 {
+        log_dir().join(format!("{}.log", LOG_STEM))
+    } else {
+        log_dir().join(format!("{}.{}.log", LOG_STEM, index))
+    }
+}
+
+/// 正在写入的日志文件
+pub fn current_log_path() -> PathBuf {
+    log_path(0)
+}
+
+/// 当前日志及历史日志的路径（由新到旧，仅包含已存在的文件）
+pub fn log_files() -> Vec<PathBuf> {
+    (0..=MAX_ROTATED)
+        .map(log_path)
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+fn open_current() -> Option<(File, u64)> {
+    std::fs::create_dir_all(log_dir()).ok()?;
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path(0))
+        .ok()?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Some((file, size))
+}
+
+/// 轮转：xiaodazi.log → xiaodazi.1.log → … ，最旧的一份被覆盖
+fn rotate() {
+    for i in (0..MAX_ROTATED).rev() {
+        let from = log_path(i);
+        if from.is_file() {
+            let _ = std::fs::rename(&from, log_path(i + 1));
+        }
+    }
+}
+
+/// 凭据匹配规则：(正则, 替换)
+fn secret_patterns() -> &'static [(Regex, &'static str)] {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // Authorization: Bearer xxx
+            (r"(?i)\b(bearer)\s+[A-Za-z0-9\-._~+/]+=*", "${1} [REDACTED]"),
+            // api_key=xxx / "token": "xxx" / password: xxx
+            (
+                r#"(?i)(["']?\b(?:api[_-]?key|x-api-key|access[_-]?token|refresh[_-]?token|auth[_-]?token|token|secret|client[_-]?secret|password|passwd)["']?\s*[:=]\s*["']?)[^"'\s,&;}]+"#,
+                "${1}[REDACTED]",
+            ),
+            // 常见服务商的 key 前缀
+            (
+                r"\b(?:sk-(?:ant-)?[A-Za-z0-9_\-]{16,}|gh[pousr]_[A-Za-z0-9]{20,}|xox[abpr]-[A-Za-z0-9\-]{10,}|AKIA[0-9A-Z]{16})\b",
+                REDACTED,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(pattern, replacement)| Some((Regex::new(pattern).ok()?, replacement)))
+        .collect()
+    })
+}
+
+/// 主目录与用户名（无法获取时为 None）
+fn home_and_user() -> &'static Option<(String, String)> {
+    static HOME: OnceLock<Option<(String, String)>> = OnceLock::new();
+    HOME.get_or_init(|| {
+        let home = dirs::home_dir()?.to_string_lossy().to_string();
+        let user = std::path::Path::new(&home)
+            .file_name()?
+            .to_string_lossy()
+            .to_string();
+        (home.len() > 1 && !user.is_empty()).then_some((home, user))
+    })
+}
+
+/// 按设置更新脱敏选项
+pub fn configure(settings: &settings::AppSettings) {
+    REDACT_HOME_PATH.store(settings.redact_home_path, Ordering::Relaxed);
+}
+
+/// 脱敏：替换凭据，并按设置替换主目录与用户名
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut out = Cow::Borrowed(text);
+    for (re, replacement) in secret_patterns() {
+        if re.is_match(&out) {
+            out = Cow::Owned(re.replace_all(&out, *replacement).into_owned());
+        }
+    }
+
+    if REDACT_HOME_PATH.load(Ordering::Relaxed) {
+        if let Some((home, user)) = home_and_user() {
+            if out.contains(home.as_str()) {
+                out = Cow::Owned(out.replace(home.as_str(), "~"));
+            }
+            // 用户名较短时容易误伤普通单词，只替换路径中的用户名
+            for sep in ['/', '\\'] {
+                let needle = format!("{sep}{user}{sep}");
+                if out.contains(&needle) {
+                    out = Cow::Owned(out.replace(&needle, &format!("{sep}<user>{sep}")));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// 凭据类字段名（JSON 中这些字段的值整体替换）
+fn secret_key_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)(?:api[_-]?key|token|secret|password|passwd)$").expect("valid regex")
+    })
+}
+
+/// 对 JSON 脱敏：凭据类字段的值替换为 [REDACTED]，其余字符串（含字段名）按 redact 处理
+///
+/// 按值处理而不是处理序列化后的文本，Windows 路径在 JSON 中的反斜杠转义不影响匹配。
+pub fn redact_json(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::String(s) => Value::String(redact(&s).into_owned()),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact_json).collect()),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(_) if secret_key_pattern().is_match(&key) => {
+                            Value::String(REDACTED.to_string())
+                        }
+                        value => redact_json(value),
+                    };
+                    (redact(&key).into_owned(), value)
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// 追加一行日志（写入前脱敏；写入失败时静默忽略，不能影响调用方）
+pub fn append(line: &str) {
+    let line = redact(line);
+    let mut w = writer().lock().unwrap_or_else(PoisonError::into_inner);
+
+    if w.file.is_none() || w.size >= MAX_LOG_BYTES {
+        if w.size >= MAX_LOG_BYTES {
+            w.file = None;
+            rotate();
+        }
+        match open_current() {
+            Some((file, size)) => {
+                w.file = Some(file);
+                w.size = size;
+            }
+            None => return,
+        }
+    }
+
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    let entry = format!("[{}] {}\n", now, line);
+    let written = w
+        .file
+        .as_mut()
+        .map(|f| f.write_all(entry.as_bytes()).is_ok())
+        .unwrap_or(false);
+    if written {
+        w.size += entry.len() as u64;
+    }
+}
+
+/// 截断过长的文本（保证在字符边界上）
+fn truncate(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// 写入前端（webview）日志
+///
+/// level 为 console 的级别（error / warn / info / debug / log），
+/// context 为附加信息（如来源文件、堆栈、路由），序列化为单行 JSON 附在消息后。
+#[tauri::command]
+pub async fn log_from_frontend(
+    level: String,
+    message: String,
+    context: Option<serde_json::Value>,
+) -> Result<(), String> {
+    let level = match level.to_ascii_lowercase().as_str() {
+        "error" => "error",
+        "warn" | "warning" => "warn",
+        "debug" | "trace" => "debug",
+        _ => "info",
+    };
+    let message = truncate(&message, MAX_FRONTEND_MESSAGE_BYTES).replace('\n', "\\n");
+    let line = match context.filter(|c| !c.is_null()) {
+        Some(ctx) => format!("[webview:{}] {} {}", level, message, ctx),
+        None => format!("[webview:{}] {}", level, message),
+    };
+    if level == "error" {
+        eprintln!("{}", line);
+    }
+    crate::crash::remember_log_line(&line);
+    append(&line);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redact_replaces_credentials() {
+        assert_eq!(
+            redact("Authorization: Bearer abc.def-ghi"),
+            "Authorization: Bearer [REDACTED]"
+        );
+        assert_eq!(
+            redact("GET /x?api_key=s3cr3t&page=2"),
+            "GET /x?api_key=[REDACTED]&page=2"
+        );
+        assert_eq!(
+            redact(r#"{"password": "hunter2", "user": "bob"}"#),
+            r#"{"password": "[REDACTED]", "user": "bob"}"#
+        );
+        assert_eq!(
+            redact("key sk-ant-REDACTED used"),
+            "key [REDACTED] used"
+        );
+    }
+
+    #[test]
+    fn redact_leaves_plain_text_borrowed() {
+        assert!(matches!(
+            redact("backend ready on port 18900"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn redact_json_replaces_secret_fields_recursively() {
+        let value = json!({
+            "config": {
+                "api_key": "abc",
+                "providers": [{ "access_token": "t", "name": "openai" }],
+                "max_tokens": "100"
+            },
+            "note": "Bearer xyz",
+            "count": 3,
+            "password": null
+        });
+        assert_eq!(
+            redact_json(value),
+            json!({
+                "config": {
+                    "api_key": "[REDACTED]",
+                    "providers": [{ "access_token": "[REDACTED]", "name": "openai" }],
+                    "max_tokens": "100"
+                },
+                "note": "Bearer [REDACTED]",
+                "count": 3,
+                "password": null
+            })
+        );
+    }
+}
@@ -0,0 +1,88 @@
+// ============================================================================
+// 稳定的机器标识（节点身份）
+// ============================================================================
+//
+// node_id 每次启动随机生成，数据目录被删除重建后后端无法识别是同一台机器。
+// 这里读取系统级标识，加盐哈希后作为 machine_id 暴露（不泄露原始硬件 ID）：
+// - macOS: IOPlatformUUID（ioreg）
+// - Windows: HKLM\SOFTWARE\Microsoft\Cryptography\MachineGuid
+// - Linux: /etc/machine-id（或 /var/lib/dbus/machine-id）
+
+use crate::debug_log;
+use sha2::{Digest, Sha256};
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::Command as SysCommand;
+
+/// 哈希盐（同一台机器在其它应用中的标识无法与本应用关联）
+const MACHINE_ID_SALT: &str = "xiaodazi.node.machine-id.v1";
+
+/// machine_id 长度（十六进制字符）
+const MACHINE_ID_LEN: usize = 32;
+
+#[cfg(target_os = "macos")]
+fn raw_machine_id() -> Option<String> {
+    // 输出示例：    "IOPlatformUUID" = "564D8E2C-...."
+    let output = SysCommand::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|l| l.contains("\"IOPlatformUUID\""))
+        .and_then(|l| l.split('"').nth(3))
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "windows")]
+fn raw_machine_id() -> Option<String> {
+    // 输出示例：    MachineGuid    REG_SZ    5d1c...
+    let output = SysCommand::new("reg")
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Cryptography",
+            "/v",
+            "MachineGuid",
+        ])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|l| l.trim_start().starts_with("MachineGuid"))
+        .and_then(|l| l.split_whitespace().last())
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "linux")]
+fn raw_machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|p| std::fs::read_to_string(p).ok())
+        .map(|s| s.trim().to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn raw_machine_id() -> Option<String> {
+    None
+}
+
+/// 加盐哈希后的机器标识（进程内缓存；无法读取系统标识时为 None）
+pub fn machine_id() -> Option<&'static str> {
+    static MACHINE_ID: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+    MACHINE_ID
+        .get_or_init(|| {
+            let Some(raw) = raw_machine_id().filter(|s| !s.is_empty()) else {
+                debug_log("[machine_id] 无法读取系统机器标识");
+                return None;
+            };
+            let mut hasher = Sha256::new();
+            hasher.update(MACHINE_ID_SALT.as_bytes());
+            hasher.update(raw.to_ascii_lowercase().as_bytes());
+            let hex: String = hasher
+                .finalize()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            Some(hex[..MACHINE_ID_LEN].to_string())
+        })
+        .as_deref()
+}
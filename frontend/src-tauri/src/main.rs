@@ -3,31 +3,90 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::io::Write;
 use std::process::Command as SysCommand;
-use std::sync::Mutex;
+use std::sync::{Mutex, PoisonError};
 use std::time::{Duration, Instant};
 use tauri::{Emitter, Manager};
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 
-/// 写入调试日志文件（用于诊断 open/Spotlight 启动问题）
+mod appearance;
+mod power;
+mod speech;
+mod ocr;
+mod store;
+mod notification_history;
+mod notifications;
+mod timeline;
+mod focus;
+mod processes;
+mod audit;
+mod applications;
+mod displays;
+mod network;
+mod discovery;
+mod remote;
+mod webhook;
+mod settings;
+mod uds;
+mod selftest;
+mod http;
+mod health;
+mod startup;
+mod ports;
+mod i18n;
+mod updater;
+mod sidecar_update;
+mod crash;
+mod logging;
+mod storage;
+mod retention;
+mod scheduler;
+mod automation;
+mod intents;
+mod calendar;
+mod volume;
+mod usb;
+mod printing;
+mod app_info;
+mod machine_id;
+mod capabilities;
+mod auth;
+mod lock;
+mod policy;
+mod permissions;
+mod approvals;
+mod jail;
+mod events;
+mod streams;
+mod binary;
+mod transfers;
+mod badge;
+mod overlay;
+mod chrome;
+mod zoom;
+mod about;
+mod diagnostics;
+mod onboarding;
+mod queue;
+mod annotate;
+mod thumbnail;
+mod video;
+mod tools;
+mod git;
+mod projects;
+mod recent;
+mod screen_capture;
+mod shortcuts;
+#[cfg(target_os = "macos")]
+mod menu;
+#[cfg(target_os = "macos")]
+mod services;
+
+/// 写入调试日志（stderr + 数据目录下的轮转日志文件，用于诊断 open/Spotlight 启动问题）
 fn debug_log(msg: &str) {
     eprintln!("{}", msg);
-    if let Ok(data_dir) = std::env::var("HOME") {
-        let log_path = format!(
-            "{}/Library/Application Support/com.zenflux.agent/sidecar-debug.log",
-            data_dir
-        );
-        if let Ok(mut f) = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)
-        {
-            let now = chrono::Local::now().format("%H:%M:%S%.3f");
-            let _ = writeln!(f, "[{}] {}", now, msg);
-        }
-    }
+    crash::remember_log_line(msg);
+    logging::append(msg);
 }
 
 // ============================================================================
@@ -43,12 +102,34 @@ const SIDECAR_PORT_RANGE: u16 = 10;
 /// 开发模式下后端默认端口
 const DEV_PORT: u16 = 8000;
 
+/// 托盘图标：浅色任务栏用黑色图标（也是 macOS 的模板图标），深色任务栏用白色图标
+/// （由 scripts/generate_tray_icons.py 生成；后端不健康时由 health.rs 加上红点）
+const TRAY_ICON: tauri::image::Image<'static> = tauri::include_image!("./icons/tray-light.png");
+const TRAY_ICON_DARK: tauri::image::Image<'static> = tauri::include_image!("./icons/tray-dark.png");
+
 /// 后端启动超时（秒）
 /// 首次启动需要 LLM 生成 prompt_results（~60s），加上 embedding 预热（~15s）
 const BACKEND_STARTUP_TIMEOUT_SECS: u64 = 120;
 
-/// 健康检查轮询间隔（毫秒）
-const BACKEND_HEALTH_POLL_MS: u64 = 500;
+/// 启动阶段健康检查轮询间隔（毫秒）：从最小值开始指数退避，不超过最大值
+const BACKEND_HEALTH_POLL_MIN_MS: u64 = 200;
+const BACKEND_HEALTH_POLL_MAX_MS: u64 = 3000;
+
+/// 前端单次可请求延长的启动等待上限（秒）
+const MAX_STARTUP_EXTENSION_SECS: u64 = 600;
+
+/// 终止 sidecar 的最长等待时间（毫秒），避免退出流程卡住 UI 线程
+const SIDECAR_SHUTDOWN_TIMEOUT_MS: u64 = 3000;
+
+/// run_command 默认禁止传入的环境变量（可在设置 blocked_env_keys / allowed_env_keys 中调整）
+const DEFAULT_BLOCKED_ENV_KEYS: &[&str] = &[
+    "NODE_OPTIONS",
+    "PYTHONHOME",
+    "PYTHONPATH",
+    "LD_PRELOAD",
+    "DYLD_*",
+    "LD_*",
+];
 
 // ============================================================================
 // 数据结构定义
@@ -57,6 +138,8 @@ const BACKEND_HEALTH_POLL_MS: u64 = 500;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeInfo {
     pub node_id: String,
+    /// 稳定的机器标识（加盐哈希），数据目录重建后保持不变，用于后端对节点去重
+    pub machine_id: Option<String>,
     pub display_name: String,
     pub platform: String,
     pub version: String,
@@ -73,6 +156,35 @@ pub struct ShellResult {
     pub timed_out: bool,
 }
 
+/// run_command 的错误（普通错误序列化为字符串，越出任务工作区时为结构化对象）
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum RunCommandError {
+    Message(String),
+    Jail(jail::JailViolation),
+}
+
+impl From<String> for RunCommandError {
+    fn from(message: String) -> Self {
+        Self::Message(message)
+    }
+}
+
+impl std::fmt::Display for RunCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Message(message) => f.write_str(message),
+            Self::Jail(violation) => violation.fmt(f),
+        }
+    }
+}
+
+impl From<jail::JailViolation> for RunCommandError {
+    fn from(violation: jail::JailViolation) -> Self {
+        Self::Jail(violation)
+    }
+}
+
 // ============================================================================
 // 本地工作区：文件/目录操作
 // ============================================================================
@@ -161,33 +273,238 @@ fn read_dir_entries(
     Ok(entries)
 }
 
-/// 后端运行状态
-struct BackendState {
-    /// sidecar 进程（仅打包模式）
-    child: Option<tauri_plugin_shell::process::CommandChild>,
+/// 后端状态快照（读多写少，通过 watch 通道发布）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct BackendInfo {
     /// 后端实际运行端口
     port: u16,
     /// 是否为 sidecar 模式（打包模式）
     is_sidecar: bool,
+    /// UDS 模式下后端监听的 socket 路径（TCP 模式为 None）
+    socket_path: Option<String>,
+    /// sidecar 进程 PID（未启动或已终止为 None）
+    pid: Option<u32>,
+    /// 最近一次健康检查是否通过
+    ready: bool,
+    /// 最近一次 sidecar 启动进度
+    status: String,
+    /// 后端启动超时（秒），启动过程中可由前端延长
+    startup_timeout_secs: u64,
+    /// 是否接管了上次残留的后端（没有进程句柄，退出时按 PID 终止）
+    adopted: bool,
+    /// 后端进程启动（或被接管）的时间
+    started_at: Option<String>,
 }
 
-/// 在指定范围内寻找可用端口
+/// 后端运行状态
 ///
-/// 从 preferred 端口开始，依次尝试绑定 preferred..preferred+range，
-/// 返回第一个可用的端口。如果全部被占用，返回 preferred（sidecar 启动时会报错）。
-fn find_available_port(preferred: u16, range: u16) -> u16 {
-    for port in preferred..preferred.saturating_add(range) {
-        if std::net::TcpListener::bind(("127.0.0.1", port)).is_ok() {
-            return port;
+/// 端口、就绪状态等只读数据放在 watch 通道中，读取无需加锁；
+/// 进程句柄单独加锁，且锁中毒时仍可继续使用，不会导致所有命令失败。
+struct BackendState {
+    info: tokio::sync::watch::Sender<BackendInfo>,
+    /// sidecar 进程（仅打包模式）
+    child: Mutex<Option<tauri_plugin_shell::process::CommandChild>>,
+    /// 启动阶段健康检查的最大轮询间隔（毫秒），重启 sidecar 时沿用
+    health_poll_max_ms: u64,
+}
+
+impl BackendState {
+    fn new(port: u16, startup_timeout_secs: u64, health_poll_max_ms: u64) -> Self {
+        let (info, _) = tokio::sync::watch::channel(BackendInfo {
+            port,
+            is_sidecar: false,
+            socket_path: None,
+            pid: None,
+            ready: false,
+            status: String::new(),
+            startup_timeout_secs,
+            adopted: false,
+            started_at: None,
+        });
+        Self {
+            info,
+            child: Mutex::new(None),
+            health_poll_max_ms,
         }
     }
-    debug_log(&format!(
-        "[sidecar] 端口 {}..{} 全部被占用，使用默认端口 {}",
-        preferred,
-        preferred.saturating_add(range),
-        preferred
-    ));
-    preferred
+
+    /// 当前状态快照
+    fn info(&self) -> BackendInfo {
+        self.info.borrow().clone()
+    }
+
+    /// 修改状态并通知订阅者
+    fn update(&self, f: impl FnOnce(&mut BackendInfo)) {
+        self.info.send_if_modified(|info| {
+            let before = info.clone();
+            f(info);
+            *info != before
+        });
+    }
+
+    /// 订阅状态变化
+    fn subscribe(&self) -> tokio::sync::watch::Receiver<BackendInfo> {
+        self.info.subscribe()
+    }
+
+    /// 保存 sidecar 进程句柄
+    fn set_child(&self, child: tauri_plugin_shell::process::CommandChild, socket_path: Option<String>) {
+        let pid = child.pid();
+        *self.child.lock().unwrap_or_else(PoisonError::into_inner) = Some(child);
+        self.update(|info| {
+            info.is_sidecar = true;
+            info.pid = Some(pid);
+            info.socket_path = socket_path;
+            info.started_at = Some(chrono::Local::now().to_rfc3339());
+        });
+    }
+
+    /// 取出 sidecar 进程句柄
+    fn take_child(&self) -> Option<tauri_plugin_shell::process::CommandChild> {
+        let child = self.child.lock().unwrap_or_else(PoisonError::into_inner).take();
+        if child.is_some() {
+            self.update(|info| {
+                info.pid = None;
+                info.ready = false;
+            });
+        }
+        child
+    }
+}
+
+impl BackendInfo {
+    /// API 基础 URL（UDS 模式经自定义协议由 Rust 转发）
+    fn base_url(&self) -> String {
+        if self.socket_path.is_some() {
+            format!("{}://localhost/api", uds::URI_SCHEME)
+        } else {
+            format!("http://127.0.0.1:{}/api", self.port)
+        }
+    }
+
+    /// WebSocket URL（UDS 模式下没有可直连的地址）
+    fn ws_url(&self) -> Option<String> {
+        if self.socket_path.is_some() {
+            None
+        } else {
+            Some(format!("ws://127.0.0.1:{}/api", self.port))
+        }
+    }
+}
+
+/// `backend-ready` 事件负载
+#[derive(Debug, Clone, Serialize)]
+struct BackendReadyPayload {
+    ready: bool,
+    port: u16,
+    base_url: String,
+    ws_url: Option<String>,
+    pid: Option<u32>,
+    started_at: Option<String>,
+    /// 未就绪的原因
+    reason: Option<String>,
+}
+
+/// `sidecar-status` 事件负载
+#[derive(Debug, Clone, Serialize)]
+struct SidecarStatusPayload {
+    message: String,
+    /// 后端上报的启动阶段（如 "local_store"），壳层自身的状态为 None
+    stage: Option<String>,
+    /// 后端上报的启动进度（0-100）
+    percent: Option<u8>,
+    /// 距应用启动的毫秒数
+    elapsed_ms: u64,
+}
+
+/// sidecar stdout 中启动进度行的前缀（与 main.py 的 _PROGRESS_MARKER 一致）
+const SIDECAR_PROGRESS_MARKER: &str = "[xiaodazi:progress]";
+
+/// sidecar 上报的启动进度
+#[derive(Debug, Deserialize)]
+struct SidecarProgress {
+    stage: String,
+    percent: u8,
+    message: String,
+}
+
+/// 解析 stdout 中的启动进度行，不是进度行时返回 None
+fn parse_sidecar_progress(line: &str) -> Option<SidecarProgress> {
+    let json = line.strip_prefix(SIDECAR_PROGRESS_MARKER)?;
+    serde_json::from_str(json.trim()).ok()
+}
+
+/// `backend-stopped` 事件负载
+#[derive(Debug, Clone, Serialize)]
+struct BackendStoppedPayload {
+    pid: Option<u32>,
+    exit_code: Option<i32>,
+    signal: Option<i32>,
+    reason: String,
+}
+
+/// 更新 sidecar 启动进度并通知前端（webview 晚于 sidecar 创建，可通过 get_sidecar_status 补取）
+fn set_sidecar_status(app: &tauri::AppHandle, status: &str) {
+    emit_sidecar_status(app, status, None, None);
+}
+
+/// 转发 sidecar 上报的真实启动进度
+fn set_sidecar_progress(app: &tauri::AppHandle, progress: SidecarProgress) {
+    startup::mark(&format!("sidecar_{}", progress.stage));
+    emit_sidecar_status(
+        app,
+        &i18n::stage_message(&progress.stage, &progress.message),
+        Some(progress.stage),
+        Some(progress.percent.min(100)),
+    );
+}
+
+fn emit_sidecar_status(
+    app: &tauri::AppHandle,
+    status: &str,
+    stage: Option<String>,
+    percent: Option<u8>,
+) {
+    app.state::<BackendState>().update(|info| info.status = status.to_string());
+    events::emit(
+        app,
+        "sidecar-status",
+        SidecarStatusPayload {
+            message: status.to_string(),
+            stage,
+            percent,
+            elapsed_ms: startup::elapsed_ms(),
+        },
+    );
+}
+
+fn emit_backend_ready(app: &tauri::AppHandle, ready: bool, reason: Option<&str>) {
+    let state = app.state::<BackendState>();
+    state.update(|info| info.ready = ready);
+    let info = state.info();
+    events::emit(
+        app,
+        "backend-ready",
+        BackendReadyPayload {
+            ready,
+            port: info.port,
+            base_url: info.base_url(),
+            ws_url: info.ws_url(),
+            pid: info.pid,
+            started_at: info.started_at.clone(),
+            reason: reason.map(|r| r.to_string()),
+        },
+    );
+}
+
+/// 更新后端就绪状态并通知前端
+fn set_backend_ready(app: &tauri::AppHandle, ready: bool) {
+    emit_backend_ready(app, ready, (!ready).then(|| i18n::t("backend.health_failed")).as_deref());
+}
+
+/// 后端启动失败，附带原因通知前端
+fn set_backend_failed(app: &tauri::AppHandle, reason: &str) {
+    emit_backend_ready(app, false, Some(reason));
 }
 
 // ============================================================================
@@ -208,13 +525,27 @@ fn health_url(port: u16) -> String {
     format!("http://127.0.0.1:{}/health", port)
 }
 
+/// 检查后端健康状态（UDS 模式走 socket，否则走 TCP 端口）
+async fn check_health(port: u16, socket_path: Option<String>, timeout: Duration) -> bool {
+    match socket_path {
+        Some(socket) => matches!(
+            uds::http_request(&socket, "GET", "/health", &[], &[], timeout).await,
+            Ok(resp) if resp.status == 200
+        ),
+        None => matches!(
+            http::client().get(health_url(port)).timeout(timeout).send().await,
+            Ok(resp) if resp.status().is_success()
+        ),
+    }
+}
+
 /// 等待后端健康检查通过（备用，首次启动向导等场景可能需要）
 #[allow(dead_code)]
-fn wait_for_backend_ready(port: u16) -> bool {
+async fn wait_for_backend_ready(port: u16) -> bool {
     let start = Instant::now();
     let timeout = Duration::from_secs(BACKEND_STARTUP_TIMEOUT_SECS);
-    let poll_interval = Duration::from_millis(BACKEND_HEALTH_POLL_MS);
-    let url = health_url(port);
+    let max_interval = Duration::from_millis(BACKEND_HEALTH_POLL_MAX_MS);
+    let mut poll_interval = Duration::from_millis(BACKEND_HEALTH_POLL_MIN_MS);
 
     eprintln!("[sidecar] 等待后端就绪 (port={})...", port);
 
@@ -224,19 +555,13 @@ fn wait_for_backend_ready(port: u16) -> bool {
             return false;
         }
 
-        match ureq::get(&url)
-            .timeout(Duration::from_secs(2))
-            .call()
-        {
-            Ok(resp) if resp.status() == 200 => {
-                let elapsed_ms = start.elapsed().as_millis();
-                eprintln!("[sidecar] 后端就绪 ({}ms)", elapsed_ms);
-                return true;
-            }
-            _ => {
-                std::thread::sleep(poll_interval);
-            }
+        if check_health(port, None, Duration::from_secs(2)).await {
+            let elapsed_ms = start.elapsed().as_millis();
+            eprintln!("[sidecar] 后端就绪 ({}ms)", elapsed_ms);
+            return true;
         }
+        tokio::time::sleep(health::with_jitter(poll_interval)).await;
+        poll_interval = health::next_backoff(poll_interval, max_interval);
     }
 }
 
@@ -246,35 +571,152 @@ fn wait_for_backend_ready(port: u16) -> bool {
 
 /// 获取后端 API 基础 URL
 #[tauri::command]
-async fn get_backend_url(state: tauri::State<'_, Mutex<BackendState>>) -> Result<String, String> {
-    let port = state.lock().map_err(|e| e.to_string())?.port;
-    Ok(format!("http://127.0.0.1:{}/api", port))
+async fn get_backend_url(state: tauri::State<'_, BackendState>) -> Result<String, String> {
+    Ok(state.info().base_url())
 }
 
 /// 获取后端 WebSocket URL
+///
+/// UDS 模式下没有可直连的 WebSocket 地址，需改用 ws_bridge_connect。
 #[tauri::command]
-async fn get_backend_ws_url(state: tauri::State<'_, Mutex<BackendState>>) -> Result<String, String> {
-    let port = state.lock().map_err(|e| e.to_string())?.port;
-    Ok(format!("ws://127.0.0.1:{}/api", port))
+async fn get_backend_ws_url(state: tauri::State<'_, BackendState>) -> Result<String, String> {
+    state
+        .info()
+        .ws_url()
+        .ok_or_else(|| "backend uses unix socket transport, use ws_bridge_connect".to_string())
+}
+
+/// 前端加载时一次性获取的应用状态（避免依赖可能错过的事件）
+#[derive(Debug, Clone, Serialize)]
+struct AppStateSnapshot {
+    backend: BackendInfo,
+    base_url: String,
+    ws_url: Option<String>,
+    pending_approvals: Vec<approvals::ApprovalRequest>,
+    scheduled_tasks: Vec<scheduler::ScheduledTask>,
+    /// 主窗口是否已隐藏到托盘
+    in_tray: bool,
+    locked: bool,
+    settings: settings::AppSettings,
+    /// 当前最新的事件序号，之后可用 get_recent_events(since) 增量补取
+    last_event_seq: u64,
+}
+
+/// 获取应用状态快照（前端加载或刷新时调用）
+#[tauri::command]
+async fn get_app_state_snapshot(app: tauri::AppHandle) -> Result<AppStateSnapshot, String> {
+    let backend = app.state::<BackendState>().info();
+    let in_tray = app
+        .get_webview_window("main")
+        .and_then(|w| w.is_visible().ok())
+        .map(|visible| !visible)
+        .unwrap_or(false);
+    Ok(AppStateSnapshot {
+        base_url: backend.base_url(),
+        ws_url: backend.ws_url(),
+        backend,
+        pending_approvals: approvals::list_pending_approvals(app.clone()).await?,
+        scheduled_tasks: scheduler::list_schedules(app.clone()).await?,
+        in_tray,
+        locked: lock::is_locked(&app),
+        settings: settings::current(&app),
+        last_event_seq: events::last_seq(&app),
+    })
 }
 
 /// 检查后端是否就绪
 #[tauri::command]
-async fn is_backend_ready(state: tauri::State<'_, Mutex<BackendState>>) -> Result<bool, String> {
-    let port = state.lock().map_err(|e| e.to_string())?.port;
-    let url = health_url(port);
-    match ureq::get(&url)
-        .timeout(Duration::from_secs(2))
-        .call()
-    {
-        Ok(resp) if resp.status() == 200 => Ok(true),
-        _ => Ok(false),
-    }
+async fn is_backend_ready(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, BackendState>,
+) -> Result<bool, String> {
+    let info = state.info();
+    let ready = health::probe(&app, info.port, info.socket_path, Duration::from_secs(2)).await;
+    state.update(|info| info.ready = ready);
+    Ok(ready)
+}
+
+/// 延长后端启动等待时间，返回新的超时秒数
+///
+/// 首次启动解压模型等耗时场景下由前端调用；启动已超时后调用无效。
+#[tauri::command]
+async fn extend_backend_startup(
+    state: tauri::State<'_, BackendState>,
+    extra_secs: u64,
+) -> Result<u64, String> {
+    let extra = extra_secs.min(MAX_STARTUP_EXTENSION_SECS);
+    state.update(|info| {
+        info.startup_timeout_secs = info.startup_timeout_secs.saturating_add(extra);
+    });
+    let timeout = state.info().startup_timeout_secs;
+    debug_log(&format!("[sidecar] 启动超时延长至 {}s", timeout));
+    Ok(timeout)
+}
+
+/// 获取最近一次 sidecar 启动进度（用于启动画面挂载前错过的 sidecar-status 事件）
+#[tauri::command]
+async fn get_sidecar_status(state: tauri::State<'_, BackendState>) -> Result<String, String> {
+    Ok(state.info().status)
 }
 
 /// 执行 Shell 命令
+///
+/// 传入 `task_id` 时命令被限制在该任务的工作区内（见 jail.rs）。
+/// 每次执行记入审计日志（system.run），可通过 export_history 导出。
+/// 程序名为已安装的托管工具（如 "rg"）时使用托管版本（见 tools.rs）。
+/// 未指定 cwd 时在默认项目目录中执行（见 projects.rs）。
 #[tauri::command]
 async fn run_command(
+    app: tauri::AppHandle,
+    command: Vec<String>,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+    timeout_ms: Option<u64>,
+    task_id: Option<String>,
+) -> Result<ShellResult, RunCommandError> {
+    run_shell_command(&app, None, command, cwd, env, timeout_ms, task_id).await
+}
+
+/// run_command 与计划任务、自动化共用的执行路径（权限检查由调用方负责）
+///
+/// 应用任务工作区隔离、托管工具解析与默认项目目录，并写入审计日志与时间线。
+/// `source` 为非交互发起方标识（如 "task:<id>"），记入审计详情。
+pub(crate) async fn run_shell_command(
+    app: &tauri::AppHandle,
+    source: Option<&str>,
+    command: Vec<String>,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+    timeout_ms: Option<u64>,
+    task_id: Option<String>,
+) -> Result<ShellResult, RunCommandError> {
+    let cwd = match task_id.as_deref().map(jail::Jail::for_task).transpose()?.flatten() {
+        Some(jail) => {
+            let dir = jail.cwd(cwd.as_deref())?;
+            jail.check_args(&command, &dir)?;
+            Some(dir.to_string_lossy().to_string())
+        }
+        None => cwd.or_else(|| projects::default_cwd(app)),
+    };
+    approvals::approve_elevated(app, &command, source).await?;
+    let resolved = tools::resolve_command(app, command.clone());
+    let result = execute_command(resolved, cwd.clone(), env, timeout_ms).await;
+    let success = result.as_ref().is_ok_and(|r| r.success);
+    let details = serde_json::json!({
+        "command": command,
+        "cwd": cwd,
+        "task_id": task_id,
+        "source": source,
+        "exit_code": result.as_ref().ok().map(|r| r.exit_code),
+        "elapsed_ms": result.as_ref().ok().map(|r| r.elapsed_ms),
+    });
+    audit::record(app, "system.run", success, details.clone());
+    timeline::record(app, timeline::TimelineKind::Command, "system.run", success, details);
+    Ok(result?)
+}
+
+/// 执行 Shell 命令（远程调用等内部入口，权限检查由调用方负责）
+async fn execute_command(
     command: Vec<String>,
     cwd: Option<String>,
     env: Option<HashMap<String, String>>,
@@ -285,9 +727,9 @@ async fn run_command(
     }
 
     let start = Instant::now();
-    let _timeout = timeout_ms.unwrap_or(60000);
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(60000));
 
-    let mut cmd = SysCommand::new(&command[0]);
+    let mut cmd = tokio::process::Command::new(&command[0]);
     if command.len() > 1 {
         cmd.args(&command[1..]);
     }
@@ -297,48 +739,128 @@ async fn run_command(
     }
 
     if let Some(env_vars) = env {
+        let settings = settings::load_settings();
         for (key, value) in env_vars {
-            if !is_blocked_env_key(&key) {
-                cmd.env(key, value);
+            if is_blocked_env_key(&key, &settings) {
+                debug_log(&format!("[run_command] 已过滤环境变量: {}", key));
+                continue;
             }
+            cmd.env(key, value);
         }
     }
 
-    match cmd.output() {
-        Ok(output) => {
-            let elapsed_ms = start.elapsed().as_millis() as u64;
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let mut child = cmd
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to execute command: {}", e))?;
+    let stdout = OutputReader::spawn(child.stdout.take());
+    let stderr = OutputReader::spawn(child.stderr.take());
+
+    let (status, timed_out) = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => (
+            status.map_err(|e| format!("Failed to execute command: {}", e))?,
+            false,
+        ),
+        Err(_) => {
+            debug_log(&format!(
+                "[run_command] 超时（{} ms），终止进程: {}",
+                timeout.as_millis(),
+                command[0]
+            ));
+            let _ = child.kill().await;
+            let status = child
+                .wait()
+                .await
+                .map_err(|e| format!("Failed to execute command: {}", e))?;
+            (status, true)
+        }
+    };
 
-            let max_len = 200000;
-            let stdout = if stdout.len() > max_len {
-                format!("{}...(truncated)", &stdout[..max_len])
-            } else {
-                stdout
-            };
-            let stderr = if stderr.len() > max_len {
-                format!("{}...(truncated)", &stderr[..max_len])
-            } else {
-                stderr
+    let (stdout, stderr) = tokio::join!(stdout.finish(), stderr.finish());
+
+    Ok(ShellResult {
+        success: status.success() && !timed_out,
+        stdout,
+        stderr,
+        exit_code: status.code().unwrap_or(-1),
+        elapsed_ms: start.elapsed().as_millis() as u64,
+        timed_out,
+    })
+}
+
+/// stdout / stderr 各自保留的最大字节数
+const MAX_OUTPUT_BYTES: usize = 200000;
+
+/// 进程退出后等待输出管道关闭的时长（后台子进程可能仍持有管道）
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 子进程输出的读取任务，超出 MAX_OUTPUT_BYTES 的部分读出后丢弃（避免子进程因管道写满而阻塞）
+struct OutputReader {
+    buffer: std::sync::Arc<Mutex<(Vec<u8>, bool)>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl OutputReader {
+    fn spawn<R>(reader: Option<R>) -> Self
+    where
+        R: tokio::io::AsyncRead + Unpin + Send + 'static,
+    {
+        use tokio::io::AsyncReadExt;
+        let buffer = std::sync::Arc::new(Mutex::new((Vec::new(), false)));
+        let shared = buffer.clone();
+        let task = tokio::spawn(async move {
+            let Some(mut reader) = reader else {
+                return;
             };
+            let mut chunk = [0u8; 8192];
+            loop {
+                match reader.read(&mut chunk).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let mut guard = shared.lock().unwrap_or_else(PoisonError::into_inner);
+                        let (buf, truncated) = &mut *guard;
+                        let room = MAX_OUTPUT_BYTES - buf.len();
+                        *truncated |= n > room;
+                        buf.extend_from_slice(&chunk[..n.min(room)]);
+                    }
+                }
+            }
+        });
+        Self { buffer, task }
+    }
 
-            Ok(ShellResult {
-                success: output.status.success(),
-                stdout,
-                stderr,
-                exit_code: output.status.code().unwrap_or(-1),
-                elapsed_ms,
-                timed_out: false,
-            })
+    /// 等待读取结束（最多 OUTPUT_DRAIN_TIMEOUT），返回已读到的文本
+    async fn finish(mut self) -> String {
+        if tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, &mut self.task)
+            .await
+            .is_err()
+        {
+            self.task.abort();
         }
-        Err(e) => Err(format!("Failed to execute command: {}", e)),
+        let guard = self.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+        output_text(&guard.0, guard.1)
     }
 }
 
+/// 输出转为文本；被截断时去掉末尾不完整的 UTF-8 字符并追加标记
+fn output_text(bytes: &[u8], truncated: bool) -> String {
+    if !truncated {
+        return String::from_utf8_lossy(bytes).to_string();
+    }
+    let end = match std::str::from_utf8(bytes) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => bytes.len(),
+    };
+    format!("{}...(truncated)", String::from_utf8_lossy(&bytes[..end]))
+}
+
 #[tauri::command]
 async fn which_command(executable: String) -> Result<Option<String>, String> {
     let result =
-        run_command(vec!["which".to_string(), executable], None, None, Some(5000)).await?;
+        execute_command(vec!["which".to_string(), executable], None, None, Some(5000)).await?;
     if result.success {
         Ok(Some(result.stdout.trim().to_string()))
     } else {
@@ -346,12 +868,28 @@ async fn which_command(executable: String) -> Result<Option<String>, String> {
     }
 }
 
-#[tauri::command]
-async fn get_node_info() -> Result<NodeInfo, String> {
-    let node_id = format!("node-{}", &uuid::Uuid::new_v4().to_string()[..8]);
-    let hostname = hostname::get()
-        .map(|h| h.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "Unknown".to_string());
+/// 本次运行的节点 ID（进程内保持不变，mDNS 广播与 get_node_info 共用）
+fn node_id() -> &'static str {
+    static NODE_ID: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    NODE_ID.get_or_init(|| format!("node-{}", &uuid::Uuid::new_v4().to_string()[..8]))
+}
+
+/// 节点显示名称的最大长度（字符）
+const NODE_DISPLAY_NAME_MAX_CHARS: usize = 64;
+
+/// 采集节点信息
+fn collect_node_info() -> NodeInfo {
+    let node_id = node_id().to_string();
+    let app_settings = settings::load_settings();
+    // 用户自定义名称优先，未设置时使用主机名
+    let display_name = app_settings
+        .node_display_name
+        .clone()
+        .unwrap_or_else(|| {
+            hostname::get()
+                .map(|h| h.to_string_lossy().to_string())
+                .unwrap_or_else(|_| "Unknown".to_string())
+        });
 
     let platform = if cfg!(target_os = "macos") {
         "darwin"
@@ -363,40 +901,70 @@ async fn get_node_info() -> Result<NodeInfo, String> {
         "unknown"
     };
 
-    let mut capabilities = vec![
-        "system.run".to_string(),
-        "system.which".to_string(),
-        "system.notify".to_string(),
-    ];
-
-    #[cfg(target_os = "macos")]
-    {
-        capabilities.push("camera.snap".to_string());
-        capabilities.push("camera.list".to_string());
-        capabilities.push("screen.record".to_string());
-        capabilities.push("location.get".to_string());
+    NodeInfo {
+        node_id,
+        machine_id: machine_id::machine_id().map(str::to_string),
+        display_name,
+        platform: platform.to_string(),
+        version: app_info::APP_VERSION.to_string(),
+        capabilities: capabilities::enabled(&app_settings),
     }
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        capabilities.push("camera.snap".to_string());
-        capabilities.push("camera.list".to_string());
-    }
+#[tauri::command]
+async fn get_node_info() -> Result<NodeInfo, String> {
+    Ok(collect_node_info())
+}
 
-    // Canvas capabilities (all platforms)
-    capabilities.push("canvas.present".to_string());
-    capabilities.push("canvas.hide".to_string());
-    capabilities.push("canvas.navigate".to_string());
-    capabilities.push("canvas.eval".to_string());
-    capabilities.push("canvas.snapshot".to_string());
+/// 设置节点显示名称（空字符串恢复为主机名）
+///
+/// 保存到设置后重新广播 mDNS，并同步给后端（后端未就绪时只记录日志）。
+#[tauri::command]
+async fn set_display_name(app: tauri::AppHandle, name: String) -> Result<NodeInfo, String> {
+    if policy::is_locked_setting("node_display_name") {
+        return Err("Setting locked by policy: node_display_name".to_string());
+    }
+    let name = name.trim().to_string();
+    if name.chars().count() > NODE_DISPLAY_NAME_MAX_CHARS {
+        return Err(format!(
+            "Display name cannot exceed {} characters",
+            NODE_DISPLAY_NAME_MAX_CHARS
+        ));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("Display name contains invalid characters".to_string());
+    }
+    let display_name = (!name.is_empty()).then(|| name.clone());
+
+    let updated = {
+        let state = app.state::<Mutex<settings::AppSettings>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        let mut updated = guard.clone();
+        updated.node_display_name = display_name;
+        settings::save_settings(&updated)?;
+        *guard = updated.clone();
+        updated
+    };
+    debug_log(&format!("[node] 显示名称已更新: {:?}", updated.node_display_name));
+    let _ = app.emit("settings-changed", &updated);
+
+    discovery::refresh_advertising(&app);
+
+    // 同步给后端（空字符串表示清除）
+    let body = serde_json::json!({ "node": { "NODE_DISPLAY_NAME": name } });
+    let (success, output) = http::call_backend(
+        &app,
+        "PUT",
+        "/api/v1/settings",
+        Some(&body),
+        http::BACKEND_TIMEOUT,
+    )
+    .await;
+    if !success {
+        debug_log(&format!("[node] 同步显示名称到后端失败: {}", output));
+    }
 
-    Ok(NodeInfo {
-        node_id,
-        display_name: hostname,
-        platform: platform.to_string(),
-        version: env!("CARGO_PKG_VERSION").to_string(),
-        capabilities,
-    })
+    Ok(collect_node_info())
 }
 
 // ============================================================================
@@ -406,6 +974,7 @@ async fn get_node_info() -> Result<NodeInfo, String> {
 /// 读取本地目录（递归，带深度限制）
 #[tauri::command]
 async fn read_local_dir(path: String, max_depth: Option<u32>) -> Result<Vec<LocalFileEntry>, String> {
+    policy::check_path(std::path::Path::new(&path))?;
     let depth = max_depth.unwrap_or(3);
     read_dir_entries(&path, 0, depth).map_err(|e| format!("读取目录失败: {}", e))
 }
@@ -413,6 +982,7 @@ async fn read_local_dir(path: String, max_depth: Option<u32>) -> Result<Vec<Loca
 /// 读取本地文本文件内容
 #[tauri::command]
 async fn read_local_file_text(path: String, max_size: Option<u64>) -> Result<String, String> {
+    policy::check_path(std::path::Path::new(&path))?;
     let max = max_size.unwrap_or(2_000_000); // 默认 2MB 限制
 
     let metadata =
@@ -420,7 +990,7 @@ async fn read_local_file_text(path: String, max_size: Option<u64>) -> Result<Str
 
     if metadata.len() > max {
         return Err(format!(
-            "文件过大 ({:.1} MB)，超过 {:.0} MB 限制",
+            "文件过大 ({:.1} MB)，超过 {:.0} MB 限制，请改用 open_file_transfer 分块读取",
             metadata.len() as f64 / 1_000_000.0,
             max as f64 / 1_000_000.0
         ));
@@ -432,12 +1002,19 @@ async fn read_local_file_text(path: String, max_size: Option<u64>) -> Result<Str
 /// 检查路径是否为目录
 #[tauri::command]
 async fn check_is_directory(path: String) -> Result<bool, String> {
+    policy::check_path(std::path::Path::new(&path))?;
     Ok(std::path::Path::new(&path).is_dir())
 }
 
 /// 移动/重命名文件或目录
 #[tauri::command]
-async fn move_local_file(from_path: String, to_path: String) -> Result<(), String> {
+async fn move_local_file(
+    app: tauri::AppHandle,
+    from_path: String,
+    to_path: String,
+) -> Result<(), String> {
+    approvals::approve_write(&app, "fs.move", &from_path).await?;
+    approvals::approve_write(&app, "fs.move", &to_path).await?;
     // 确保目标父目录存在
     if let Some(parent) = std::path::Path::new(&to_path).parent() {
         if !parent.exists() {
@@ -449,29 +1026,51 @@ async fn move_local_file(from_path: String, to_path: String) -> Result<(), Strin
     if std::path::Path::new(&to_path).exists() {
         return Err("目标路径已存在同名文件或文件夹".to_string());
     }
-    std::fs::rename(&from_path, &to_path)
-        .map_err(|e| format!("移动失败: {}", e))
+    let result = std::fs::rename(&from_path, &to_path)
+        .map_err(|e| format!("移动失败: {}", e));
+    timeline::record(
+        &app,
+        timeline::TimelineKind::FileWrite,
+        "fs.move",
+        result.is_ok(),
+        serde_json::json!({ "from": from_path, "to": to_path }),
+    );
+    result
 }
 
 /// 删除文件或目录
 #[tauri::command]
-async fn delete_local_path(path: String) -> Result<(), String> {
+async fn delete_local_path(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    approvals::approve_write(&app, "fs.delete", &path).await?;
     let p = std::path::Path::new(&path);
     if !p.exists() {
         return Err("路径不存在".to_string());
     }
-    if p.is_dir() {
+    let result = if p.is_dir() {
         std::fs::remove_dir_all(&path)
             .map_err(|e| format!("删除目录失败: {}", e))
     } else {
         std::fs::remove_file(&path)
             .map_err(|e| format!("删除文件失败: {}", e))
-    }
+    };
+    timeline::record(
+        &app,
+        timeline::TimelineKind::FileWrite,
+        "fs.delete",
+        result.is_ok(),
+        serde_json::json!({ "path": path }),
+    );
+    result
 }
 
 /// 创建文件（可含初始内容）
 #[tauri::command]
-async fn create_local_file(path: String, content: Option<String>) -> Result<(), String> {
+async fn create_local_file(
+    app: tauri::AppHandle,
+    path: String,
+    content: Option<String>,
+) -> Result<(), String> {
+    approvals::approve_write(&app, "fs.create", &path).await?;
     if std::path::Path::new(&path).exists() {
         return Err("文件已存在".to_string());
     }
@@ -479,14 +1078,23 @@ async fn create_local_file(path: String, content: Option<String>) -> Result<(),
         std::fs::create_dir_all(parent)
             .map_err(|e| format!("创建父目录失败: {}", e))?;
     }
-    std::fs::write(&path, content.unwrap_or_default())
-        .map_err(|e| format!("创建文件失败: {}", e))
+    let result = std::fs::write(&path, content.unwrap_or_default())
+        .map_err(|e| format!("创建文件失败: {}", e));
+    timeline::record(
+        &app,
+        timeline::TimelineKind::FileWrite,
+        "fs.create",
+        result.is_ok(),
+        serde_json::json!({ "path": path }),
+    );
+    result
 }
 
-/// 读取本地文件为 base64 编码（支持二进制文件如图片、PDF 等）
+/// 读取本地文件为 base64 编码（支持二进制文件如图片、PDF 等；大文件使用 open_file_transfer）
 #[tauri::command]
 async fn read_local_file_binary(path: String, max_size: Option<u64>) -> Result<String, String> {
     use base64::Engine;
+    policy::check_path(std::path::Path::new(&path))?;
     let max = max_size.unwrap_or(10_000_000); // 默认 10MB 限制
 
     let metadata =
@@ -506,12 +1114,21 @@ async fn read_local_file_binary(path: String, max_size: Option<u64>) -> Result<S
 
 /// 创建目录
 #[tauri::command]
-async fn create_local_dir(path: String) -> Result<(), String> {
+async fn create_local_dir(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    approvals::approve_write(&app, "fs.create", &path).await?;
     if std::path::Path::new(&path).exists() {
         return Err("目录已存在".to_string());
     }
-    std::fs::create_dir_all(&path)
-        .map_err(|e| format!("创建目录失败: {}", e))
+    let result = std::fs::create_dir_all(&path)
+        .map_err(|e| format!("创建目录失败: {}", e));
+    timeline::record(
+        &app,
+        timeline::TimelineKind::FileWrite,
+        "fs.create_dir",
+        result.is_ok(),
+        serde_json::json!({ "path": path }),
+    );
+    result
 }
 
 /// 获取启动时传入的路径参数（拖拽文件夹到 exe 时系统传入）
@@ -548,6 +1165,9 @@ async fn open_system_preferences(pane: String) -> Result<(), String> {
             "accessibility" => {
                 "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
             }
+            "calendars" => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Calendars"
+            }
             _ => return Err(format!("Unknown preference pane: {}", pane)),
         };
 
@@ -752,47 +1372,351 @@ async fn canvas_snapshot(app: tauri::AppHandle) -> Result<serde_json::Value, Str
 // 辅助函数
 // ============================================================================
 
-fn is_blocked_env_key(key: &str) -> bool {
-    let blocked_keys = ["NODE_OPTIONS", "PYTHONHOME", "PYTHONPATH", "LD_PRELOAD"];
-    let blocked_prefixes = ["DYLD_", "LD_"];
+/// 环境变量名是否匹配模式（结尾的 `*` 表示前缀匹配）
+fn env_key_matches(key: &str, pattern: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => key == pattern,
+    }
+}
+
+/// 环境变量是否禁止传给子进程（allowed_env_keys 优先于 blocked_env_keys）
+fn is_blocked_env_key(key: &str, settings: &settings::AppSettings) -> bool {
+    let matches_any = |patterns: &[String]| patterns.iter().any(|p| env_key_matches(key, p));
+    matches_any(&settings.blocked_env_keys) && !matches_any(&settings.allowed_env_keys)
+}
 
-    if blocked_keys.contains(&key) {
-        return true;
+/// 启动 sidecar 后端并在后台等待就绪（打包模式；首次运行时推迟到引导完成后，见 onboarding.rs）
+fn spawn_sidecar(handle: tauri::AppHandle, actual_port: u16, health_poll_max_ms: u64) {
+    let data_dir = onboarding::backend_data_dir(&handle);
+
+    // 确保数据目录存在
+    let _ = std::fs::create_dir_all(&data_dir);
+
+    // 传输方式：设置为 UDS 且平台支持时改用 Unix socket
+    let socket_path = (settings::current(&handle).sidecar_transport
+        == settings::SidecarTransport::Uds
+        && uds::is_supported())
+    .then(|| {
+        std::path::Path::new(&data_dir)
+            .join(uds::SOCKET_FILE)
+            .to_string_lossy()
+            .to_string()
+    });
+    if let Some(socket) = &socket_path {
+        // 清理上次异常退出残留的 socket 文件
+        let _ = std::fs::remove_file(socket);
     }
 
-    for prefix in blocked_prefixes {
-        if key.starts_with(prefix) {
-            return true;
-        }
+    debug_log(&format!(
+        "[sidecar] 启动后端 sidecar (port={}, uds={:?}, data-dir={})",
+        actual_port, socket_path, data_dir
+    ));
+
+    // 使用 Tauri shell plugin 的 sidecar API
+    use tauri_plugin_shell::ShellExt;
+
+    // 优先使用单独下载的新版本后端，否则使用内置的 sidecar
+    let sidecar_result = match sidecar_update::prepare_spawn(&handle) {
+        Some(path) => Ok(handle.shell().command(path)),
+        None => handle.shell().sidecar("xiaodazi-backend"),
     }
+    .map(|cmd| match &socket_path {
+        Some(socket) => cmd.args(["--uds", socket, "--data-dir", &data_dir]),
+        None => cmd.args(["--port", &actual_port.to_string(), "--data-dir", &data_dir]),
+    });
 
-    false
+    match sidecar_result {
+        Ok(cmd) => run_backend(handle, cmd, actual_port, socket_path, health_poll_max_ms),
+        Err(e) => {
+            debug_log(&format!("[sidecar] sidecar 命令创建失败: {}", e));
+            set_backend_failed(&handle, &i18n::tf("backend.command_failed", &[&e]));
+        }
+    }
 }
 
-/// 终止 sidecar 后端进程
-fn kill_sidecar(app_handle: &tauri::AppHandle) {
-    let state = app_handle.state::<Mutex<BackendState>>();
-    let mut guard = match state.lock() {
-        Ok(g) => g,
-        Err(e) => {
-            eprintln!("[sidecar] 获取锁失败: {}", e);
+/// 开发模式：8000 端口上没有后端时执行设置 dev_backend_command（输出转发与健康检查同 sidecar）
+fn spawn_dev_backend(handle: tauri::AppHandle, command: String, health_poll_max_ms: u64) {
+    use tauri_plugin_shell::ShellExt;
+
+    tauri::async_runtime::spawn(async move {
+        if check_health(DEV_PORT, None, Duration::from_secs(3)).await {
+            eprintln!("[dev] 开发后端已在运行 (port={})，不再启动", DEV_PORT);
+            startup::mark("backend_ready");
+            set_backend_ready(&handle, true);
+            health::start_monitor(handle);
             return;
         }
-    };
 
-    if guard.is_sidecar {
-        if let Some(child) = guard.child.take() {
-            eprintln!("[sidecar] 正在终止后端进程 (port={})...", guard.port);
-            match child.kill() {
-                Ok(_) => eprintln!("[sidecar] 后端进程已终止"),
-                Err(e) => {
-                    eprintln!("[sidecar] kill 失败: {}", e);
+        #[cfg(target_os = "windows")]
+        let cmd = handle.shell().command("cmd").args(["/C", &command]);
+        #[cfg(not(target_os = "windows"))]
+        let cmd = handle.shell().command("/bin/sh").args(["-c", &command]);
+        let cwd = settings::current(&handle).dev_backend_cwd;
+        let cmd = match &cwd {
+            Some(dir) => cmd.current_dir(dir),
+            None => cmd,
+        };
+        eprintln!("[dev] 启动开发后端: {} (cwd={:?})", command, cwd);
+        debug_log(&format!("[dev] 启动开发后端: {} (cwd={:?})", command, cwd));
+        run_backend(handle, cmd, DEV_PORT, None, health_poll_max_ms);
+    });
+}
+
+/// 启动后端进程，转发输出并等待就绪（sidecar 与开发模式的后端命令共用）
+fn run_backend(
+    handle: tauri::AppHandle,
+    cmd: tauri_plugin_shell::process::Command,
+    actual_port: u16,
+    socket_path: Option<String>,
+    health_poll_max_ms: u64,
+) {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tauri_plugin_shell::process::CommandEvent;
+
+    // 后端执行的能力（system.run 等）按当前开关拒绝调用，之后的变化由 capabilities 同步；
+    // 其中包括托管策略关闭的能力（disable_run_command 关闭 system.run）
+    let cmd = cmd.env(
+        "NODE_DISABLED_CAPABILITIES",
+        capabilities::backend_disabled(&settings::current(&handle)),
+    );
+
+    match cmd.spawn() {
+        Ok((mut rx, child)) => {
+            debug_log("[sidecar] sidecar 进程已启动");
+            startup::mark("sidecar_spawned");
+
+            // 保存进程句柄
+            handle
+                .state::<BackendState>()
+                .set_child(child, socket_path.clone());
+
+            // 共享标志：sidecar 是否已退出
+            let sidecar_exited = Arc::new(AtomicBool::new(false));
+            let sidecar_exited_for_log = sidecar_exited.clone();
+            let sidecar_exited_for_health = sidecar_exited.clone();
+
+            // 在后台线程读取 sidecar 输出
+            let log_handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                while let Some(event) = rx.recv().await {
+                    match event {
+                        CommandEvent::Stdout(line) => {
+                            let line = String::from_utf8_lossy(&line);
+                            let trimmed = line.trim();
+                            eprintln!("[sidecar:stdout] {}", trimmed);
+                            debug_log(&format!("[sidecar:stdout] {}", trimmed));
+                            if let Some(progress) = parse_sidecar_progress(trimmed) {
+                                set_sidecar_progress(&log_handle, progress);
+                            }
+                        }
+                        CommandEvent::Stderr(line) => {
+                            let line = String::from_utf8_lossy(&line);
+                            let trimmed = line.trim();
+                            eprintln!("[sidecar:stderr] {}", trimmed);
+                            debug_log(&format!("[sidecar:stderr] {}", trimmed));
+                        }
+                        CommandEvent::Terminated(status) => {
+                            debug_log(&format!("[sidecar] 进程已退出: {:?}", status));
+                            sidecar_exited_for_log.store(true, Ordering::SeqCst);
+                            // 立即通知前端：sidecar 意外退出
+                            let reason = i18n::tf(
+                                "backend.exited",
+                                &[
+                                    &format!("{:?}", status.code),
+                                    &format!("{:?}", status.signal),
+                                ],
+                            );
+                            let pid = log_handle.state::<BackendState>().info().pid;
+                            set_backend_failed(&log_handle, &reason);
+                            // 进程句柄仍在说明不是主动终止，记录崩溃报告
+                            if pid.is_some() {
+                                crash::record_sidecar_crash(
+                                    status.code,
+                                    status.signal,
+                                    &reason,
+                                );
+                            }
+                            // 新版本后端启动即退出时回退并重启
+                            sidecar_update::on_startup_failed(&log_handle);
+                            let _ = log_handle.emit(
+                                "backend-stopped",
+                                BackendStoppedPayload {
+                                    pid,
+                                    exit_code: status.code,
+                                    signal: status.signal,
+                                    reason,
+                                },
+                            );
+                            break;
+                        }
+                        _ => {}
+                    }
                 }
-            }
+            });
+
+            // 在后台任务中等待后端就绪
+            tauri::async_runtime::spawn(async move {
+                let start = Instant::now();
+                let max_interval = Duration::from_millis(health_poll_max_ms);
+                let mut poll_interval = Duration::from_millis(BACKEND_HEALTH_POLL_MIN_MS);
+                debug_log(&format!("[sidecar] 等待后端就绪 (port={})...", actual_port));
+
+                // 向前端发送启动进度
+                // 之后的阶段进度由 sidecar 的 stdout 进度行驱动
+                set_sidecar_status(&handle, &i18n::t("sidecar.starting"));
+
+                loop {
+                    // 如果 sidecar 已经退出，立即失败
+                    if sidecar_exited_for_health.load(Ordering::SeqCst) {
+                        debug_log("[sidecar] sidecar 进程已退出，停止健康检查");
+                        set_sidecar_status(&handle, &i18n::t("sidecar.failed"));
+                        // backend-ready(false) 已由日志线程发出
+                        return;
+                    }
+
+                    // 超时时长每轮重新读取（前端可能已请求延长）
+                    let timeout_secs =
+                        handle.state::<BackendState>().info().startup_timeout_secs;
+                    if start.elapsed() > Duration::from_secs(timeout_secs) {
+                        debug_log(&format!("[sidecar] 后端启动超时 ({}s)", timeout_secs));
+                        set_sidecar_status(&handle, &i18n::t("sidecar.timeout"));
+                        set_backend_failed(
+                            &handle,
+                            &i18n::tf("backend.timeout", &[&timeout_secs]),
+                        );
+                        sidecar_update::on_startup_failed(&handle);
+                        return;
+                    }
+
+                    if check_health(
+                        actual_port,
+                        socket_path.clone(),
+                        Duration::from_secs(2),
+                    )
+                    .await
+                    {
+                        let elapsed_ms = start.elapsed().as_millis();
+                        debug_log(&format!("[sidecar] 后端就绪 ({}ms)", elapsed_ms));
+                        startup::mark("backend_ready");
+                        if socket_path.is_none() && is_release_build() {
+                            settings::remember_sidecar_port(&handle, actual_port);
+                        }
+                        sidecar_update::confirm_healthy(&handle);
+                        emit_sidecar_status(
+                            &handle,
+                            &i18n::t("sidecar.ready"),
+                            None,
+                            Some(100),
+                        );
+                        set_backend_ready(&handle, true);
+                        // 就绪后转入低频健康巡检
+                        health::start_monitor(handle.clone());
+                        // 确认后端未暴露到局域网
+                        let _ = tauri::async_runtime::spawn_blocking(move || {
+                            selftest::verify_backend_binding(&handle)
+                        })
+                        .await;
+                        return;
+                    }
+                    tokio::time::sleep(health::with_jitter(poll_interval)).await;
+                    poll_interval = health::next_backoff(poll_interval, max_interval);
+                }
+            });
+        }
+        Err(e) => {
+            debug_log(&format!("[sidecar] spawn 失败: {}", e));
+            set_backend_failed(&handle, &i18n::tf("backend.spawn_failed", &[&e]));
         }
     }
 }
 
+/// 重启 sidecar（健康巡检发现后端失去响应时调用，见 health.rs）
+async fn restart_sidecar(app_handle: tauri::AppHandle) {
+    shutdown_sidecar(app_handle.clone()).await;
+    let state = app_handle.state::<BackendState>();
+    let (port, health_poll_max_ms) = (state.info().port, state.health_poll_max_ms);
+    debug_log(&format!("[sidecar] 重启后端 (port={})", port));
+    spawn_sidecar(app_handle, port, health_poll_max_ms);
+}
+
+/// 取出 sidecar 进程句柄（终止过程中不持有锁）
+fn take_sidecar(
+    app_handle: &tauri::AppHandle,
+) -> Option<(tauri_plugin_shell::process::CommandChild, u16, Option<String>)> {
+    let state = app_handle.state::<BackendState>();
+    let child = state.take_child()?;
+    let info = state.info();
+    Some((child, info.port, info.socket_path))
+}
+
+/// 终止 sidecar 后端进程（最多等待 SIDECAR_SHUTDOWN_TIMEOUT_MS）
+async fn shutdown_sidecar(app_handle: tauri::AppHandle) {
+    let Some((child, port, socket_path)) = take_sidecar(&app_handle) else {
+        shutdown_adopted_backend(&app_handle).await;
+        return;
+    };
+    let pid = child.pid();
+    eprintln!("[sidecar] 正在终止后端进程 (port={}, pid={})...", port, pid);
+
+    let kill = tauri::async_runtime::spawn_blocking(move || child.kill());
+    match tokio::time::timeout(Duration::from_millis(SIDECAR_SHUTDOWN_TIMEOUT_MS), kill).await {
+        Ok(Ok(Ok(_))) => eprintln!("[sidecar] 后端进程已终止"),
+        Ok(Ok(Err(e))) => eprintln!("[sidecar] kill 失败: {}", e),
+        Ok(Err(e)) => eprintln!("[sidecar] kill 任务异常: {}", e),
+        Err(_) => eprintln!(
+            "[sidecar] 终止后端超时 ({}ms, pid={})",
+            SIDECAR_SHUTDOWN_TIMEOUT_MS, pid
+        ),
+    }
+
+    if let Some(socket) = socket_path {
+        let _ = std::fs::remove_file(socket);
+    }
+}
+
+/// 终止接管的残留后端（没有 CommandChild，按 PID 终止）
+async fn shutdown_adopted_backend(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<BackendState>();
+    let info = state.info();
+    let (true, Some(pid)) = (info.adopted, info.pid) else {
+        return;
+    };
+    state.update(|info| {
+        info.adopted = false;
+        info.pid = None;
+        info.ready = false;
+    });
+    eprintln!("[sidecar] 正在终止接管的后端进程 (port={}, pid={})...", info.port, pid);
+
+    let kill = tauri::async_runtime::spawn_blocking(move || {
+        let mut sys = sysinfo::System::new();
+        sys.refresh_processes();
+        sys.process(sysinfo::Pid::from_u32(pid))
+            .map(|p| p.kill())
+            .unwrap_or(false)
+    });
+    match tokio::time::timeout(Duration::from_millis(SIDECAR_SHUTDOWN_TIMEOUT_MS), kill).await {
+        Ok(Ok(true)) => eprintln!("[sidecar] 接管的后端进程已终止"),
+        Ok(_) => eprintln!("[sidecar] 接管的后端进程终止失败 (pid={})", pid),
+        Err(_) => eprintln!("[sidecar] 终止接管的后端超时 (pid={})", pid),
+    }
+}
+
+/// 同步终止 sidecar（仅用于应用退出流程，阻塞时长有上限）
+fn kill_sidecar(app_handle: &tauri::AppHandle) {
+    tauri::async_runtime::block_on(shutdown_sidecar(app_handle.clone()));
+}
+
+/// 读取命令行参数 `--name value` 的值
+fn cli_arg(name: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1).cloned())
+}
+
 /// 判断当前是否为 release 构建（打包模式）
 fn is_release_build() -> bool {
     // cfg!(debug_assertions) 在 debug 构建（cargo run / tauri dev）时为 true
@@ -805,12 +1729,29 @@ fn is_release_build() -> bool {
 // ============================================================================
 
 fn main() {
-    // 初始状态：dev 模式连 8000，release 模式动态分配端口
-    let initial_port = if is_release_build() {
-        find_available_port(SIDECAR_PORT, SIDECAR_PORT_RANGE)
+    startup::begin();
+    crash::install_panic_hook();
+
+    let app_settings = settings::load_settings();
+    logging::configure(&app_settings);
+    i18n::init(&app_settings);
+
+    // 初始状态：dev 模式连 8000，release 模式优先复用上次成功的端口，否则动态分配
+    let port_selection = if is_release_build() {
+        ports::select_sidecar_port(
+            app_settings.last_sidecar_port,
+            SIDECAR_PORT,
+            SIDECAR_PORT_RANGE,
+            &onboarding::data_dir_of(&app_settings),
+        )
+            .unwrap_or_else(|conflict| ports::PortSelection::conflicted(SIDECAR_PORT, conflict))
     } else {
-        DEV_PORT
+        ports::PortSelection::fixed(DEV_PORT)
     };
+    let initial_port = port_selection.port;
+    let adopted_backend = port_selection.adopted.then_some(port_selection.adopted_pid);
+    let port_conflict = port_selection.conflict.clone();
+    startup::mark("port_scan");
 
     debug_log(&format!(
         "[app] 启动模式: {} (后端端口: {})",
@@ -818,166 +1759,137 @@ fn main() {
         initial_port
     ));
 
+    // 启动超时与轮询间隔：命令行参数 > 设置 > 默认值
+    let startup_timeout_secs = cli_arg("--startup-timeout")
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(app_settings.backend_startup_timeout_secs)
+        .max(10);
+    let health_poll_max_ms = cli_arg("--health-poll-ms")
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(app_settings.backend_health_poll_ms)
+        .clamp(BACKEND_HEALTH_POLL_MIN_MS, 10_000);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_dialog::init())
-        .manage(Mutex::new(BackendState {
-            child: None,
-            port: initial_port,
-            is_sidecar: false,
-        }))
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            None,
+        ))
+        .manage(BackendState::new(initial_port, startup_timeout_secs, health_poll_max_ms))
+        .manage(health::HealthHistoryState::default())
+        .manage(events::EventBuffer::default())
+        .manage(port_selection)
+        .manage(Mutex::new(app_settings))
+        .manage(uds::WsBridges::default())
+        .manage(streams::StreamSubscriptions::default())
+        .manage(transfers::Transfers::default())
+        .manage(overlay::OverlayState::default())
+        .manage(onboarding::OnboardingGate::default())
+        .manage(queue::RequestQueue::default())
+        .manage(Mutex::new(power::SleepGuards::default()))
+        .manage(Mutex::new(speech::SpeechState::default()))
+        .manage(Mutex::new(notifications::NotificationScheduler::default()))
+        .manage(Mutex::new(notifications::DeferredNotifications::default()))
+        .manage(notification_history::NotificationHistory::default())
+        .manage(timeline::TimelineState::default())
+        .manage(Mutex::new(scheduler::TaskScheduler::default()))
+        .manage(automation::AutomationState::default())
+        .manage(projects::ProjectState::default())
+        .manage(network::Connectivity::default())
+        .manage(discovery::Discovery::default())
+        .manage(remote::RemoteControl::default())
+        .manage(auth::AuthState::default())
+        .manage(lock::LockState::default())
+        .manage(approvals::ApprovalState::default())
+        .manage(webhook::WebhookState::default())
+        .manage(updater::UpdaterState::default())
+        .register_asynchronous_uri_scheme_protocol(uds::URI_SCHEME, |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                responder.respond(uds::handle_scheme_request(&app, request).await);
+            });
+        })
         .setup(move |app| {
+            startup::mark("setup");
             let handle = app.handle().clone();
 
-            if is_release_build() {
-                // ============ 打包模式：启动 sidecar ============
-                let data_dir = get_app_data_dir(app.handle());
-                let actual_port = initial_port;
-
-                // 确保数据目录存在
-                let _ = std::fs::create_dir_all(&data_dir);
+            // Agent 活动时间线（需在任何通知、命令执行之前打开）
+            timeline::open(&handle);
 
+            // 后端状态变化时推送 `backend-status`
+            let mut status_rx = handle.state::<BackendState>().subscribe();
+            let status_handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                while status_rx.changed().await.is_ok() {
+                    let info = status_rx.borrow_and_update().clone();
+                    events::emit(&status_handle, "backend-status", &info);
+                }
+            });
+
+            if let Some(conflict) = port_conflict {
+                // ============ 打包模式：没有可用端口，不启动 sidecar ============
+                let ports_text = conflict
+                    .blocked
+                    .iter()
+                    .map(|b| match &b.owner_name {
+                        Some(name) => format!("{} ({})", b.port, name),
+                        None => b.port.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                debug_log(&format!("[sidecar] 端口全部被占用，未启动后端: {}", ports_text));
+                events::emit(&handle, "backend-port-conflict", &conflict);
+                set_sidecar_status(&handle, &i18n::t("sidecar.port_conflict"));
+                set_backend_failed(&handle, &i18n::tf("backend.ports_blocked", &[&ports_text]));
+                notifications::notify(
+                    &handle,
+                    &i18n::t("notify.port_conflict.title"),
+                    &i18n::tf("notify.port_conflict.body", &[&ports_text]),
+                    true,
+                );
+            } else if let Some(adopted_pid) = adopted_backend {
+                // ============ 打包模式：接管上次残留的后端 ============
                 debug_log(&format!(
-                    "[sidecar] 启动后端 sidecar (port={}, data-dir={})",
-                    actual_port, data_dir
+                    "[sidecar] 接管已在运行的后端 (port={}, pid={:?})",
+                    initial_port, adopted_pid
                 ));
-
-                // 使用 Tauri shell plugin 的 sidecar API
-                use tauri_plugin_shell::ShellExt;
-                use tauri_plugin_shell::process::CommandEvent;
-                use std::sync::Arc;
-                use std::sync::atomic::{AtomicBool, Ordering};
-
-                let sidecar_result = app.handle()
-                    .shell()
-                    .sidecar("xiaodazi-backend")
-                    .map(|cmd| {
-                        cmd.args([
-                            "--port",
-                            &actual_port.to_string(),
-                            "--data-dir",
-                            &data_dir,
-                        ])
-                    });
-
-                match sidecar_result {
-                    Ok(cmd) => {
-                        match cmd.spawn() {
-                            Ok((mut rx, child)) => {
-                                debug_log("[sidecar] sidecar 进程已启动");
-
-                                // 保存进程句柄
-                                if let Ok(mut guard) = handle.state::<Mutex<BackendState>>().lock() {
-                                    guard.child = Some(child);
-                                    guard.is_sidecar = true;
-                                }
-
-                                // 共享标志：sidecar 是否已退出
-                                let sidecar_exited = Arc::new(AtomicBool::new(false));
-                                let sidecar_exited_for_log = sidecar_exited.clone();
-                                let sidecar_exited_for_health = sidecar_exited.clone();
-
-                                // 在后台线程读取 sidecar 输出
-                                let log_handle = handle.clone();
-                                tauri::async_runtime::spawn(async move {
-                                    while let Some(event) = rx.recv().await {
-                                        match event {
-                                            CommandEvent::Stdout(line) => {
-                                                let line = String::from_utf8_lossy(&line);
-                                                let trimmed = line.trim();
-                                                eprintln!("[sidecar:stdout] {}", trimmed);
-                                                debug_log(&format!("[sidecar:stdout] {}", trimmed));
-                                            }
-                                            CommandEvent::Stderr(line) => {
-                                                let line = String::from_utf8_lossy(&line);
-                                                let trimmed = line.trim();
-                                                eprintln!("[sidecar:stderr] {}", trimmed);
-                                                debug_log(&format!("[sidecar:stderr] {}", trimmed));
-                                            }
-                                            CommandEvent::Terminated(status) => {
-                                                debug_log(&format!("[sidecar] 进程已退出: {:?}", status));
-                                                sidecar_exited_for_log.store(true, Ordering::SeqCst);
-                                                // 立即通知前端：sidecar 意外退出
-                                                let _ = log_handle.emit("backend-ready", false);
-                                                let _ = log_handle.emit("backend-stopped", true);
-                                                break;
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                });
-
-                                // 在后台线程等待后端就绪
-                                std::thread::spawn(move || {
-                                    let start = Instant::now();
-                                    let timeout = Duration::from_secs(BACKEND_STARTUP_TIMEOUT_SECS);
-                                    let poll_interval = Duration::from_millis(BACKEND_HEALTH_POLL_MS);
-                                    let url = health_url(actual_port);
-
-                                    debug_log(&format!("[sidecar] 等待后端就绪 (port={})...", actual_port));
-
-                                    // 向前端发送启动进度
-                                    let _ = handle.emit("sidecar-status", "正在启动服务...");
-                                    let mut poll_count: u32 = 0;
-
-                                    loop {
-                                        // 如果 sidecar 已经退出，立即失败
-                                        if sidecar_exited_for_health.load(Ordering::SeqCst) {
-                                            debug_log("[sidecar] sidecar 进程已退出，停止健康检查");
-                                            let _ = handle.emit("sidecar-status", "服务启动失败");
-                                            // backend-ready(false) 已由日志线程发出
-                                            return;
-                                        }
-
-                                        if start.elapsed() > timeout {
-                                            debug_log(&format!("[sidecar] 后端启动超时 ({}s)", BACKEND_STARTUP_TIMEOUT_SECS));
-                                            let _ = handle.emit("sidecar-status", "启动超时，请重试");
-                                            let _ = handle.emit("backend-ready", false);
-                                            return;
-                                        }
-
-                                        // 根据等待时长更新进度提示
-                                        poll_count += 1;
-                                        if poll_count == 4 {
-                                            let _ = handle.emit("sidecar-status", "正在加载模块...");
-                                        } else if poll_count == 10 {
-                                            let _ = handle.emit("sidecar-status", "正在初始化数据...");
-                                        } else if poll_count == 20 {
-                                            let _ = handle.emit("sidecar-status", "即将就绪...");
-                                        }
-
-                                        match ureq::get(&url)
-                                            .timeout(Duration::from_secs(2))
-                                            .call()
-                                        {
-                                            Ok(resp) if resp.status() == 200 => {
-                                                let elapsed_ms = start.elapsed().as_millis();
-                                                debug_log(&format!("[sidecar] 后端就绪 ({}ms)", elapsed_ms));
-                                                let _ = handle.emit("sidecar-status", "准备就绪");
-                                                let _ = handle.emit("backend-ready", true);
-                                                return;
-                                            }
-                                            _ => {
-                                                std::thread::sleep(poll_interval);
-                                            }
-                                        }
-                                    }
-                                });
-                            }
-                            Err(e) => {
-                                debug_log(&format!("[sidecar] spawn 失败: {}", e));
-                                let _ = handle.emit("backend-ready", false);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        debug_log(&format!("[sidecar] sidecar 命令创建失败: {}", e));
-                        let _ = handle.emit("backend-ready", false);
+                handle.state::<BackendState>().update(|info| {
+                    info.is_sidecar = true;
+                    info.adopted = true;
+                    info.pid = adopted_pid;
+                    info.started_at = Some(chrono::Local::now().to_rfc3339());
+                });
+                set_sidecar_status(&handle, &i18n::t("sidecar.adopted"));
+
+                tauri::async_runtime::spawn(async move {
+                    let ready = health::probe(&handle, initial_port, None, Duration::from_secs(3)).await;
+                    set_backend_ready(&handle, ready);
+                    if ready {
+                        startup::mark("backend_ready");
+                        health::start_monitor(handle);
                     }
+                });
+            } else if is_release_build() {
+                // ============ 打包模式：启动 sidecar（首次运行时等引导完成） ============
+                let spawn_handle = handle.clone();
+                let spawn = move || spawn_sidecar(spawn_handle, initial_port, health_poll_max_ms);
+                if onboarding::is_completed(&handle) {
+                    spawn();
+                } else {
+                    onboarding::defer_backend(&handle, spawn);
                 }
+            } else if let Some(command) = settings::current(&handle)
+                .dev_backend_command
+                .filter(|c| !c.trim().is_empty())
+            {
+                // ============ 开发模式：按设置的命令启动后端 ============
+                spawn_dev_backend(handle, command, health_poll_max_ms);
             } else {
                 // ============ 开发模式：假设后端已手动启动在 8000 端口 ============
                 eprintln!(
@@ -985,38 +1897,112 @@ fn main() {
                     DEV_PORT
                 );
 
-                // 在后台线程检查开发后端是否可用
-                std::thread::spawn(move || {
-                    let url = health_url(DEV_PORT);
-                    match ureq::get(&url)
-                        .timeout(Duration::from_secs(3))
-                        .call()
-                    {
-                        Ok(resp) if resp.status() == 200 => {
-                            eprintln!("[dev] 开发后端已就绪 (port={})", DEV_PORT);
-                            let _ = handle.emit("backend-ready", true);
-                        }
-                        _ => {
-                            eprintln!(
-                                "[dev] 警告: 开发后端未就绪 (port={})，请手动启动",
-                                DEV_PORT
-                            );
-                            // 仍然通知前端，让页面能显示
-                            let _ = handle.emit("backend-ready", true);
+                // 在后台任务中检查开发后端是否可用
+                tauri::async_runtime::spawn(async move {
+                    if !check_health(DEV_PORT, None, Duration::from_secs(3)).await {
+                        eprintln!(
+                            "[dev] 警告: 开发后端未就绪 (port={})，请手动启动",
+                            DEV_PORT
+                        );
+                        // 如实通知前端（可显示提示条），后台持续重试直到后端出现
+                        set_backend_failed(&handle, &i18n::tf("backend.dev_unreachable", &[&DEV_PORT]));
+                        let max_interval = Duration::from_millis(health_poll_max_ms);
+                        let mut poll_interval = Duration::from_millis(BACKEND_HEALTH_POLL_MIN_MS);
+                        loop {
+                            tokio::time::sleep(health::with_jitter(poll_interval)).await;
+                            if check_health(DEV_PORT, None, Duration::from_secs(2)).await {
+                                break;
+                            }
+                            poll_interval = health::next_backoff(poll_interval, max_interval);
                         }
                     }
+                    eprintln!("[dev] 开发后端已就绪 (port={})", DEV_PORT);
+                    startup::mark("backend_ready");
+                    set_backend_ready(&handle, true);
+                    health::start_monitor(handle);
                 });
             }
 
+            // ============ 主窗口 ============
+            // sidecar 已在上面启动，此时再创建 webview，两者的启动耗时并行
+            if let Some(window_config) = app.config().app.windows.iter().find(|w| w.label == "main") {
+                let chrome = settings::current(app.handle()).window_chrome;
+                let builder = tauri::WebviewWindowBuilder::from_config(app.handle(), window_config)?;
+                let window = chrome::configure(builder, &chrome).build()?;
+                if let Err(e) = chrome::apply_effects(&window, &chrome) {
+                    debug_log(&format!("[chrome] 应用窗口外观失败: {}", e));
+                }
+                startup::mark("window_created");
+            }
+
+            // macOS 应用菜单栏
+            #[cfg(target_os = "macos")]
+            menu::install(app.handle())?;
+
+            // 上次运行留下的崩溃报告（询问是否发送）
+            crash::handle_pending_reports(app.handle());
+
+            // 系统休眠/唤醒监视（唤醒后重新检查后端）
+            power::start_wake_monitor(app.handle().clone());
+
+            // 供电来源与电池电量监视
+            power::start_power_monitor(app.handle().clone());
+
+            // 定时通知调度（恢复持久化的提醒）
+            notifications::start_scheduler(app.handle().clone());
+
+            // 定时任务调度（cron，窗口隐藏时同样执行）
+            scheduler::start_scheduler(app.handle().clone());
+
+            // 自动化规则（文件夹监视 / 定时 / 全局快捷键）
+            automation::start(app.handle().clone());
+
+            // 已登记的项目目录（文件监视）
+            projects::start(app.handle());
+
+            // xiaodazi:// 快捷指令（macOS 快捷指令 App 等外部调用）
+            intents::start(app.handle());
+
+            // macOS 服务菜单（在其它应用中选中文字后调用）
+            #[cfg(target_os = "macos")]
+            services::install(app.handle());
+
+            // 专注/勿扰模式监视（延迟非关键通知）
+            notifications::start_focus_monitor(app.handle().clone());
+
+            // 系统强调色与辅助功能偏好变化监视
+            appearance::start_appearance_monitor(app.handle().clone());
+
+            // 网络变化与在线状态监视
+            network::start_network_monitor(app.handle().clone());
+
+            // 系统空闲自动锁定
+            lock::start_idle_monitor(app.handle().clone());
+
+            // USB 设备热插拔监视
+            usb::start_usb_monitor(app.handle().clone());
+
+            // 数据目录所在磁盘的剩余空间监视
+            storage::start_disk_monitor(app.handle().clone());
+
+            // 外接磁盘挂载 / 卸载监视
+            storage::start_volume_monitor(app.handle().clone());
+
+            // 定时清理旧日志与临时数据
+            retention::start_scheduler(app.handle().clone());
+
+            // 后台检查应用更新（按设置中的更新策略处理）
+            updater::start_scheduler(app.handle().clone());
+
+            // 局域网 mDNS 广播本节点
+            discovery::start_advertising(app.handle(), initial_port);
+
             // ============ 系统托盘 ============
-            let show_item = MenuItemBuilder::with_id("show", "显示窗口").build(app)?;
-            let quit_item = MenuItemBuilder::with_id("quit", "退出").build(app)?;
-            let tray_menu = MenuBuilder::new(app)
-                .items(&[&show_item, &quit_item])
-                .build()?;
-
-            let _tray = TrayIconBuilder::new()
-                .icon(tauri::include_image!("./icons/128x128@2x.png"))
+            // 菜单文案随界面语言切换（见 i18n::set_language）
+            let tray_menu = i18n::tray_menu(app)?;
+
+            let _tray = TrayIconBuilder::with_id(i18n::TRAY_ID)
+                .icon(TRAY_ICON)
                 .icon_as_template(true)
                 .menu(&tray_menu)
                 .show_menu_on_left_click(false)
@@ -1029,10 +2015,25 @@ fn main() {
                             let _ = window.set_focus();
                         }
                     }
+                    "about" => {
+                        if let Err(e) = about::show(app) {
+                            debug_log(&format!("[about] 打开关于窗口失败: {}", e));
+                        }
+                    }
+                    "rollback" => updater::rollback_from_tray(app),
+                    id if id.starts_with(automation::TRAY_ITEM_PREFIX) => {
+                        automation::toggle_from_tray(app, id)
+                    }
+                    id if id.starts_with(notifications::SNOOZE_ITEM_PREFIX) => {
+                        notifications::snooze_from_tray(app, id)
+                    }
                     "quit" => {
-                        // 真正退出：先终止 sidecar，再退出应用
-                        kill_sidecar(app);
-                        app.exit(0);
+                        // 真正退出：先终止 sidecar，再退出应用（在后台任务中进行，不阻塞托盘事件）
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            shutdown_sidecar(app.clone()).await;
+                            app.exit(0);
+                        });
                     }
                     _ => {}
                 })
@@ -1053,9 +2054,19 @@ fn main() {
                     }
                 })
                 .build(app)?;
+            // Windows / Linux 不支持模板图标，按当前系统主题换成单色图标
+            health::refresh_tray_icon(app.handle());
 
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            if payload.event() == tauri::webview::PageLoadEvent::Finished {
+                zoom::restore(webview);
+                if webview.label() == "main" {
+                    startup::mark("webview_ready");
+                }
+            }
+        })
         .on_window_event(|window, event| {
             match event {
                 // 仅拦截主窗口关闭 → 隐藏到托盘；其他窗口（如 canvas）正常关闭
@@ -1068,19 +2079,51 @@ fn main() {
                 // 主窗口销毁时终止 sidecar（第一层防护）
                 tauri::WindowEvent::Destroyed => {
                     if window.label() == "main" {
-                        kill_sidecar(window.app_handle());
+                        tauri::async_runtime::spawn(shutdown_sidecar(window.app_handle().clone()));
+                    }
+                }
+                // 应用窗口获得焦点期间启用缩放快捷键
+                tauri::WindowEvent::Focused(focused) => {
+                    zoom::on_focus_changed(window.app_handle(), *focused);
+                }
+                // 系统深色/浅色主题切换
+                tauri::WindowEvent::ThemeChanged(theme) => {
+                    if window.label() == "main" {
+                        appearance::on_theme_changed(window.app_handle(), *theme);
                     }
                 }
                 _ => {}
             }
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(permissions::guard(tauri::generate_handler![
             get_backend_url,
             get_backend_ws_url,
             is_backend_ready,
+            get_sidecar_status,
+            get_app_state_snapshot,
+            extend_backend_startup,
+            ports::get_port_selection,
+            startup::get_startup_timings,
+            health::get_backend_health_history,
             run_command,
             which_command,
             get_node_info,
+            set_display_name,
+            capabilities::list_capabilities,
+            capabilities::set_capability_enabled,
+            auth::authenticate,
+            auth::set_auth_grace_period,
+            lock::get_lock_state,
+            lock::lock_app,
+            lock::unlock_app,
+            policy::get_effective_policy,
+            audit::verify_audit_log,
+            audit::export_history,
+            approvals::list_pending_approvals,
+            approvals::approve,
+            approvals::deny,
+            events::get_recent_events,
+            app_info::get_app_info,
             open_system_preferences,
             read_local_dir,
             read_local_file_text,
@@ -1096,7 +2139,142 @@ fn main() {
             canvas_navigate,
             canvas_eval,
             canvas_snapshot,
-        ])
+            appearance::get_system_theme,
+            appearance::get_system_appearance,
+            appearance::is_screen_reader_active,
+            power::prevent_sleep,
+            power::allow_sleep,
+            power::get_power_state,
+            volume::get_volume,
+            volume::set_volume,
+            volume::set_muted,
+            speech::speak,
+            speech::stop_speaking,
+            ocr::ocr_image,
+            calendar::get_calendar_permission,
+            calendar::request_calendar_access,
+            calendar::list_calendars,
+            calendar::list_events,
+            notifications::schedule_notification,
+            notifications::cancel_notification,
+            notifications::list_scheduled_notifications,
+            notifications::send_notification,
+            notifications::snooze_notifications,
+            notifications::resume_notifications,
+            notification_history::get_notification_history,
+            notification_history::mark_notifications_read,
+            notification_history::clear_notification_history,
+            timeline::query_timeline,
+            notifications::get_focus_state,
+            scheduler::schedule_task,
+            scheduler::list_schedules,
+            scheduler::delete_schedule,
+            scheduler::run_command_at,
+            automation::add_automation,
+            automation::list_automations,
+            automation::delete_automation,
+            automation::set_automation_enabled,
+            processes::list_processes,
+            processes::get_process,
+            processes::kill_process,
+            applications::list_applications,
+            applications::launch_application,
+            displays::get_displays,
+            displays::move_window_to_display,
+            displays::get_brightness,
+            displays::set_brightness,
+            network::get_network_info,
+            network::get_wifi_info,
+            network::is_online,
+            usb::list_usb_devices,
+            printing::list_printers,
+            printing::print_file,
+            discovery::discover_nodes,
+            remote::enable_remote_control,
+            remote::disable_remote_control,
+            remote::get_remote_control_status,
+            remote::connect_node,
+            remote::invoke_remote,
+            remote::disconnect_node,
+            webhook::start_webhook_server,
+            webhook::stop_webhook_server,
+            webhook::get_webhook_status,
+            settings::get_settings,
+            settings::update_settings,
+            settings::update_security_settings,
+            i18n::get_language,
+            i18n::set_language,
+            updater::check_for_update,
+            updater::download_update,
+            updater::install_update,
+            updater::get_update_history,
+            updater::rollback_update,
+            crash::list_crash_reports,
+            crash::send_crash_report,
+            crash::delete_crash_report,
+            logging::log_from_frontend,
+            storage::get_storage_report,
+            storage::list_volumes,
+            retention::run_cleanup_now,
+            sidecar_update::check_backend_update,
+            sidecar_update::download_backend_update,
+            sidecar_update::get_backend_update_status,
+            sidecar_update::rollback_backend_update,
+            uds::ws_bridge_connect,
+            uds::ws_bridge_send,
+            uds::ws_bridge_close,
+            streams::subscribe_stream,
+            streams::unsubscribe_stream,
+            streams::replay_stream,
+            binary::backend_binary,
+            binary::backend_upload_file,
+            binary::backend_download_file,
+            transfers::open_file_transfer,
+            transfers::capture_screen,
+            screen_capture::preflight_screen_capture,
+            transfers::read_chunk,
+            transfers::close_transfer,
+            annotate::annotate_image,
+            thumbnail::make_thumbnail,
+            thumbnail::downscale_image,
+            video::trim_video,
+            video::compress_video,
+            tools::ensure_tool,
+            tools::list_managed_tools,
+            tools::get_tool_path,
+            tools::set_tool_enabled,
+            tools::remove_managed_tool,
+            tools::cleanup_tools,
+            git::git_status,
+            git::git_diff,
+            git::git_commit,
+            git::git_clone,
+            projects::add_project,
+            projects::list_projects,
+            projects::remove_project,
+            projects::set_default_project,
+            recent::get_recent_items,
+            badge::set_badge_count,
+            badge::set_progress,
+            badge::request_attention,
+            overlay::show_overlay,
+            overlay::hide_overlay,
+            overlay::update_overlay,
+            overlay::get_overlay_status,
+            chrome::get_window_chrome,
+            chrome::set_window_chrome,
+            zoom::set_zoom,
+            zoom::get_zoom,
+            shortcuts::list_shortcuts,
+            shortcuts::set_shortcut,
+            capabilities::set_safe_mode,
+            about::show_about,
+            diagnostics::export_diagnostics,
+            onboarding::get_onboarding_state,
+            onboarding::complete_onboarding_step,
+            onboarding::start_backend,
+            selftest::run_self_test,
+        ]))
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
@@ -1105,6 +2283,10 @@ fn main() {
                 tauri::RunEvent::Exit => {
                     eprintln!("[app] 应用退出，执行清理...");
                     kill_sidecar(app_handle);
+                    updater::install_on_quit(app_handle);
+                    power::release_all(app_handle);
+                    discovery::shutdown(app_handle);
+                    appearance::stop_appearance_monitor();
                 }
                 // macOS：点击 Dock 栏图标时唤醒隐藏的主窗口
                 #[cfg(target_os = "macos")]
@@ -1121,3 +2303,46 @@ fn main() {
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_text_keeps_untruncated_output() {
+        assert_eq!(output_text("你好\n".as_bytes(), false), "你好\n");
+    }
+
+    #[test]
+    fn output_text_drops_partial_char_when_truncated() {
+        let bytes = "ab你好".as_bytes();
+        // 截在“好”的第一、二个字节
+        assert_eq!(output_text(&bytes[..6], true), "ab你...(truncated)");
+        assert_eq!(output_text(&bytes[..7], true), "ab你...(truncated)");
+        assert_eq!(output_text(&bytes[..5], true), "ab你...(truncated)");
+        assert_eq!(output_text(&bytes[..8], true), "ab你好...(truncated)");
+    }
+
+    #[test]
+    fn default_blocked_env_keys_match_names_and_prefixes() {
+        let settings = settings::AppSettings::default();
+        assert!(is_blocked_env_key("NODE_OPTIONS", &settings));
+        assert!(is_blocked_env_key("LD_PRELOAD", &settings));
+        assert!(is_blocked_env_key("DYLD_INSERT_LIBRARIES", &settings));
+        assert!(!is_blocked_env_key("NODE_ENV", &settings));
+        assert!(!is_blocked_env_key("PATH", &settings));
+    }
+
+    #[test]
+    fn allowed_env_keys_override_blocked_env_keys() {
+        let settings = settings::AppSettings {
+            blocked_env_keys: vec!["LD_*".to_string(), "SECRET".to_string()],
+            allowed_env_keys: vec!["LD_LIBRARY_PATH".to_string()],
+            ..Default::default()
+        };
+        assert!(!is_blocked_env_key("LD_LIBRARY_PATH", &settings));
+        assert!(is_blocked_env_key("LD_PRELOAD", &settings));
+        assert!(is_blocked_env_key("SECRET", &settings));
+        assert!(!is_blocked_env_key("SECRET_NAME", &settings));
+    }
+}
@@ -0,0 +1,151 @@
+// ============================================================================
+// macOS 应用菜单栏
+// ============================================================================
+//
+// macOS 没有菜单栏时，复制粘贴等标准快捷键与"偏好设置 / 退出"入口都不可用。
+// 菜单项：
+// - 应用菜单：关于（打开 about.rs 的关于窗口）、偏好设置…（⌘,）、检查更新…、安全模式（勾选切换）、服务、隐藏、退出
+// - 编辑菜单：撤销、重做、剪切、复制、粘贴、全选（由系统处理）
+// - 窗口菜单：最小化、缩放、全屏、显示主窗口、关闭窗口
+// 偏好设置与检查更新发出 `menu-preferences` / `menu-check-updates` 事件，由前端处理；
+// 安全模式直接在这里切换。菜单文案随界面语言切换（见 i18n::set_language），
+// 偏好设置的快捷键可在设置中改绑（见 shortcuts.rs）。
+// Windows / Linux 的窗口内菜单栏与现有界面不协调，不设置菜单。
+
+use crate::i18n::t;
+use crate::{about, capabilities, debug_log, policy, settings, shortcuts};
+use tauri::menu::{
+    CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder,
+};
+use tauri::{Emitter, Manager};
+
+const ABOUT_ID: &str = "app-menu.about";
+const PREFERENCES_ID: &str = "app-menu.preferences";
+const CHECK_UPDATES_ID: &str = "app-menu.check-updates";
+const SAFE_MODE_ID: &str = "app-menu.safe-mode";
+const SHOW_MAIN_ID: &str = "app-menu.show-main";
+
+/// 按当前语言与设置构建菜单
+fn build(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let about = MenuItemBuilder::with_id(ABOUT_ID, t("menu.about")).build(app)?;
+    let preferences = MenuItemBuilder::with_id(PREFERENCES_ID, t("menu.preferences"))
+        .accelerator(shortcuts::accelerator(app, "menu.preferences"))
+        .build(app)?;
+    let check_updates =
+        MenuItemBuilder::with_id(CHECK_UPDATES_ID, t("menu.check_updates")).build(app)?;
+    let safe_mode = CheckMenuItemBuilder::with_id(SAFE_MODE_ID, t("menu.safe_mode"))
+        .checked(settings::current(app).safe_mode)
+        .enabled(!policy::is_locked_setting("safe_mode"))
+        .build(app)?;
+    let app_menu = SubmenuBuilder::new(app, "xiaodazi")
+        .item(&about)
+        .separator()
+        .item(&preferences)
+        .item(&check_updates)
+        .item(&safe_mode)
+        .separator()
+        .item(&PredefinedMenuItem::services(
+            app,
+            Some(t("menu.services").as_str()),
+        )?)
+        .separator()
+        .item(&PredefinedMenuItem::hide(
+            app,
+            Some(t("menu.hide").as_str()),
+        )?)
+        .item(&PredefinedMenuItem::hide_others(
+            app,
+            Some(t("menu.hide_others").as_str()),
+        )?)
+        .item(&PredefinedMenuItem::show_all(
+            app,
+            Some(t("menu.show_all").as_str()),
+        )?)
+        .separator()
+        .item(&PredefinedMenuItem::quit(
+            app,
+            Some(t("menu.quit").as_str()),
+        )?)
+        .build()?;
+
+    let edit_menu = SubmenuBuilder::new(app, t("menu.edit"))
+        .undo()
+        .redo()
+        .separator()
+        .cut()
+        .copy()
+        .paste()
+        .select_all()
+        .build()?;
+
+    let show_main = MenuItemBuilder::with_id(SHOW_MAIN_ID, t("menu.show_main")).build(app)?;
+    let window_menu = SubmenuBuilder::new(app, t("menu.window"))
+        .minimize()
+        .maximize()
+        .fullscreen()
+        .separator()
+        .item(&show_main)
+        .close_window()
+        .build()?;
+
+    MenuBuilder::new(app)
+        .item(&app_menu)
+        .item(&edit_menu)
+        .item(&window_menu)
+        .build()
+}
+
+fn on_menu_event(app: &tauri::AppHandle, id: &str) {
+    match id {
+        PREFERENCES_ID | CHECK_UPDATES_ID => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let event = if id == PREFERENCES_ID {
+                "menu-preferences"
+            } else {
+                "menu-check-updates"
+            };
+            let _ = app.emit(event, ());
+        }
+        SAFE_MODE_ID => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let enabled = !settings::current(&app).safe_mode;
+                if let Err(e) = capabilities::set_safe_mode(app.clone(), enabled).await {
+                    debug_log(&format!("[menu] 切换安全模式失败: {}", e));
+                    // 点击时系统已切换勾选状态，失败时按设置恢复
+                    refresh(&app);
+                }
+            });
+        }
+        ABOUT_ID => {
+            if let Err(e) = about::show(app) {
+                debug_log(&format!("[menu] 打开关于窗口失败: {}", e));
+            }
+        }
+        SHOW_MAIN_ID => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 设置应用菜单并监听菜单事件
+pub fn install(app: &tauri::AppHandle) -> tauri::Result<()> {
+    app.set_menu(build(app)?)?;
+    app.on_menu_event(|app, event| on_menu_event(app, event.id().as_ref()));
+    Ok(())
+}
+
+/// 重建菜单（语言或安全模式变化后调用）
+pub fn refresh(app: &tauri::AppHandle) {
+    if let Err(e) = build(app).and_then(|menu| app.set_menu(menu)) {
+        debug_log(&format!("[menu] 重建菜单失败: {}", e));
+    }
+}
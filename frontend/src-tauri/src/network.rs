@@ -0,0 +1,372 @@
+// ============================================================================
+// 网络信息：网卡、IP、默认网关、Wi-Fi，以及在线/离线检测
+// ============================================================================
+
+use crate::debug_log;
+use serde::{Deserialize, Serialize};
+use std::process::Command as SysCommand;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// 网络变化检查间隔（秒）
+const NETWORK_POLL_SECS: u64 = 10;
+
+/// 网络无变化时，连通性探测的最长间隔（秒）
+const CONNECTIVITY_PROBE_SECS: u64 = 60;
+
+/// 连通性探测目标（国内外各一组，任一可达即视为在线）
+const CONNECTIVITY_PROBE_HOSTS: [&str; 3] = ["223.5.5.5:53", "1.1.1.1:443", "8.8.8.8:53"];
+
+/// 在线状态（由网络监视线程维护）
+pub struct Connectivity {
+    online: AtomicBool,
+}
+
+impl Default for Connectivity {
+    fn default() -> Self {
+        // 首次探测前乐观地假定在线，避免启动瞬间误报离线
+        Connectivity {
+            online: AtomicBool::new(true),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceInfo {
+    pub name: String,
+    pub addresses: Vec<String>,
+    pub is_loopback: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WifiInfo {
+    /// 新版 macOS 未授予定位权限时 SSID 不可见
+    pub ssid: Option<String>,
+    /// 接入点 MAC 地址（同上，需要定位权限）
+    pub bssid: Option<String>,
+    /// 信号强度（百分比 0..100）
+    pub signal: Option<u8>,
+    /// 连接速率（Mbps；macOS 仅 get_wifi_info 返回）
+    pub link_speed_mbps: Option<u32>,
+    /// SSID / BSSID 被系统隐藏，需在"系统设置 → 隐私与安全性 → 定位服务"中
+    /// 授权本应用（open_system_preferences("location")）
+    pub location_permission_required: bool,
+}
+
+impl WifiInfo {
+    /// 是否连接到了不同的网络（忽略信号强度与速率的波动）
+    fn same_network(a: Option<&WifiInfo>, b: Option<&WifiInfo>) -> bool {
+        a.map(|w| (&w.ssid, &w.bssid)) == b.map(|w| (&w.ssid, &w.bssid))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkInfo {
+    pub interfaces: Vec<InterfaceInfo>,
+    pub default_gateway: Option<String>,
+    pub wifi: Option<WifiInfo>,
+}
+
+/// 运行命令并返回 stdout（失败返回 None）
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    let output = SysCommand::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 在 "key: value" / "key : value" 格式的输出中查找字段
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn find_field(text: &str, key: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k.trim() == key).then(|| v.trim().to_string())
+    })
+}
+
+/// 枚举网卡及其 IP 地址
+fn list_interfaces() -> Vec<InterfaceInfo> {
+    let mut interfaces: Vec<InterfaceInfo> = Vec::new();
+    for iface in if_addrs::get_if_addrs().unwrap_or_default() {
+        let addr = iface.ip().to_string();
+        match interfaces.iter_mut().find(|i| i.name == iface.name) {
+            Some(existing) => existing.addresses.push(addr),
+            None => interfaces.push(InterfaceInfo {
+                is_loopback: iface.is_loopback(),
+                name: iface.name,
+                addresses: vec![addr],
+            }),
+        }
+    }
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+/// 获取默认网关
+fn default_gateway() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        let out = command_stdout("route", &["-n", "get", "default"])?;
+        find_field(&out, "gateway")
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let out = command_stdout("ip", &["route", "show", "default"])?;
+        let mut parts = out.split_whitespace();
+        parts.find(|p| *p == "via")?;
+        parts.next().map(|s| s.to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let out = command_stdout(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "(Get-NetRoute -DestinationPrefix 0.0.0.0/0 | Sort-Object RouteMetric | Select-Object -First 1).NextHop",
+            ],
+        )?;
+        let gw = out.trim().to_string();
+        (!gw.is_empty()).then_some(gw)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+/// 获取当前 Wi-Fi 信息（未连接 Wi-Fi 时返回 None）
+pub fn wifi_info() -> Option<WifiInfo> {
+    #[cfg(target_os = "macos")]
+    {
+        // ipconfig getsummary 在 macOS 14+ 仍可用（airport 命令已移除）
+        let out = command_stdout("ipconfig", &["getsummary", "en0"])?;
+        let raw_ssid = find_field(&out, "SSID");
+        let location_permission_required = raw_ssid.as_deref() == Some("<redacted>");
+        let ssid = raw_ssid.filter(|s| s != "<redacted>");
+        let bssid = find_field(&out, "BSSID").filter(|s| s != "<redacted>");
+        let signal = find_field(&out, "RSSI")
+            .and_then(|r| r.parse::<i32>().ok())
+            .map(rssi_to_percent);
+        if ssid.is_none() && signal.is_none() && !location_permission_required {
+            return None;
+        }
+        Some(WifiInfo {
+            ssid,
+            bssid,
+            signal,
+            link_speed_mbps: None,
+            location_permission_required,
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let out = command_stdout(
+            "nmcli",
+            &["-t", "-f", "active,signal,rate,bssid,ssid", "dev", "wifi"],
+        )?;
+        let line = out.lines().find(|l| l.starts_with("yes:"))?;
+        let parts = split_nmcli_fields(line);
+        let field = |i: usize| parts.get(i).cloned().filter(|s| !s.is_empty());
+        let signal = field(1).and_then(|s| s.trim().parse::<u8>().ok());
+        // 速率形如 "270 Mbit/s"
+        let link_speed_mbps = field(2)
+            .and_then(|s| s.split_whitespace().next().map(str::to_string))
+            .and_then(|s| s.parse::<u32>().ok());
+        Some(WifiInfo {
+            ssid: field(4),
+            bssid: field(3),
+            signal,
+            link_speed_mbps,
+            location_permission_required: false,
+        })
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let out = command_stdout("netsh", &["wlan", "show", "interfaces"])?;
+        let ssid = find_field(&out, "SSID");
+        let signal = find_field(&out, "Signal")
+            .and_then(|s| s.trim_end_matches('%').trim().parse::<u8>().ok());
+        let link_speed_mbps = find_field(&out, "Receive rate (Mbps)")
+            .and_then(|s| s.parse::<f32>().ok())
+            .map(|r| r as u32);
+        if ssid.is_none() {
+            return None;
+        }
+        Some(WifiInfo {
+            ssid,
+            bssid: find_field(&out, "BSSID"),
+            signal,
+            link_speed_mbps,
+            location_permission_required: false,
+        })
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+/// 按未转义的 ':' 拆分 nmcli -t 输出（字段内的 ':' 转义为 '\:'）
+#[cfg(target_os = "linux")]
+fn split_nmcli_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    fields.last_mut().unwrap().push(next);
+                }
+            }
+            ':' => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// macOS 连接速率（system_profiler 较慢，仅按需查询）
+#[cfg(target_os = "macos")]
+fn macos_link_speed() -> Option<u32> {
+    let out = command_stdout("system_profiler", &["SPAirPortDataType", "-json"])?;
+    let json: serde_json::Value = serde_json::from_str(&out).ok()?;
+    json.get("SPAirPortDataType")?
+        .as_array()?
+        .iter()
+        .filter_map(|item| item.get("spairport_airport_interfaces")?.as_array())
+        .flatten()
+        .find_map(|iface| {
+            iface
+                .get("spairport_current_network_information")?
+                .get("spairport_network_rate")?
+                .as_u64()
+        })
+        .map(|rate| rate as u32)
+}
+
+/// RSSI（dBm）换算为百分比：-100dBm → 0%，-50dBm 及以上 → 100%
+#[cfg(target_os = "macos")]
+fn rssi_to_percent(rssi: i32) -> u8 {
+    (2 * (rssi + 100)).clamp(0, 100) as u8
+}
+
+/// 采集网络信息（同步，会调用外部命令）
+pub fn collect_network_info() -> NetworkInfo {
+    NetworkInfo {
+        interfaces: list_interfaces(),
+        default_gateway: default_gateway(),
+        wifi: wifi_info(),
+    }
+}
+
+/// 获取网络信息
+#[tauri::command]
+pub async fn get_network_info() -> Result<NetworkInfo, String> {
+    tauri::async_runtime::spawn_blocking(collect_network_info)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取当前 Wi-Fi 详情（未连接 Wi-Fi 时返回 None）
+///
+/// macOS 上 SSID / BSSID 需要定位权限，未授权时 `location_permission_required` 为 true。
+#[tauri::command]
+pub async fn get_wifi_info() -> Result<Option<WifiInfo>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        #[allow(unused_mut)]
+        let mut info = wifi_info();
+        #[cfg(target_os = "macos")]
+        if let Some(info) = info.as_mut() {
+            info.link_speed_mbps = macos_link_speed();
+        }
+        info
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 探测外网连通性（TCP 连接任一探测目标成功即视为在线）
+pub fn probe_connectivity() -> bool {
+    CONNECTIVITY_PROBE_HOSTS.iter().any(|host| {
+        host.parse::<std::net::SocketAddr>()
+            .map(|addr| {
+                std::net::TcpStream::connect_timeout(&addr, Duration::from_secs(3)).is_ok()
+            })
+            .unwrap_or(false)
+    })
+}
+
+/// 更新在线状态，变化时发出 `connectivity-changed`
+fn update_connectivity(app: &tauri::AppHandle, online: bool) {
+    let previous = app
+        .state::<Connectivity>()
+        .online
+        .swap(online, Ordering::SeqCst);
+    if previous != online {
+        debug_log(&format!(
+            "[network] 连通性变化: {}",
+            if online { "在线" } else { "离线" }
+        ));
+        let _ = app.emit("connectivity-changed", serde_json::json!({ "online": online }));
+    }
+}
+
+/// 启动网络监视线程
+///
+/// - 网卡/IP/网关/Wi-Fi 变化时发出 `network-changed`
+/// - 连接的 Wi-Fi 网络（SSID / BSSID）变化时发出 `wifi-changed`
+/// - 网络变化时立即探测连通性，否则每 CONNECTIVITY_PROBE_SECS 探测一次，
+///   在线状态变化时发出 `connectivity-changed`（前端据此降级，并通知后端暂停云端调用）
+pub fn start_network_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut last = collect_network_info();
+        update_connectivity(&app, probe_connectivity());
+        let mut since_probe = Duration::ZERO;
+
+        loop {
+            std::thread::sleep(Duration::from_secs(NETWORK_POLL_SECS));
+            since_probe += Duration::from_secs(NETWORK_POLL_SECS);
+
+            let current = collect_network_info();
+            let changed = current != last;
+            if changed {
+                debug_log(&format!(
+                    "[network] 网络变化 (gateway={:?})",
+                    current.default_gateway
+                ));
+                let _ = app.emit("network-changed", &current);
+                if !WifiInfo::same_network(last.wifi.as_ref(), current.wifi.as_ref()) {
+                    debug_log(&format!(
+                        "[network] Wi-Fi 变化: {:?}",
+                        current.wifi.as_ref().and_then(|w| w.ssid.as_deref())
+                    ));
+                    let _ = app.emit(
+                        "wifi-changed",
+                        serde_json::json!({ "previous": &last.wifi, "current": &current.wifi }),
+                    );
+                }
+                last = current;
+            }
+
+            if changed || since_probe >= Duration::from_secs(CONNECTIVITY_PROBE_SECS) {
+                update_connectivity(&app, probe_connectivity());
+                since_probe = Duration::ZERO;
+            }
+        }
+    });
+}
+
+/// 当前是否在线
+#[tauri::command]
+pub async fn is_online(app: tauri::AppHandle) -> Result<bool, String> {
+    Ok(app.state::<Connectivity>().online.load(Ordering::SeqCst))
+}
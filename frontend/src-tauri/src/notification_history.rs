@@ -0,0 +1,139 @@
+// ============================================================================
+// 通知历史
+// ============================================================================
+//
+// notifications::notify 发出的每条通知（包括因专注 / 暂停而延迟的）都记入历史，持久化到
+// notification-history.json，错过系统通知的用户可以在通知中心里找回 Agent 说过的话：
+// - get_notification_history(limit, unread_only)：按时间倒序返回
+// - mark_notifications_read(ids)：标记已读，ids 为空时全部标记
+// - clear_notification_history()：清空
+// 最多保留 MAX_HISTORY 条，超出时丢弃最旧的记录。变化时发出 `notification-history-changed`
+// 事件（负载为未读数量）。
+
+use crate::debug_log;
+use crate::store::{load_json, save_json};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Mutex, PoisonError};
+use tauri::{Emitter, Manager};
+
+/// 历史持久化文件
+const HISTORY_FILE: &str = "notification-history.json";
+
+/// 最多保留的记录数
+const MAX_HISTORY: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRecord {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub critical: bool,
+    /// 发送时因专注模式或手动暂停而延迟
+    #[serde(default)]
+    pub deferred: bool,
+    pub read: bool,
+    /// 发出时间（RFC 3339）
+    pub created_at: String,
+}
+
+/// 通知历史（按时间顺序，与磁盘文件保持同步）
+#[derive(Default)]
+pub struct NotificationHistory {
+    records: Mutex<Vec<NotificationRecord>>,
+}
+
+fn unread(records: &[NotificationRecord]) -> usize {
+    records.iter().filter(|r| !r.read).count()
+}
+
+/// 保存并通知前端
+fn changed(app: &tauri::AppHandle, records: &[NotificationRecord]) {
+    if let Err(e) = save_json(app, HISTORY_FILE, &records) {
+        debug_log(&format!("[notify] 保存通知历史失败: {}", e));
+    }
+    let _ = app.emit("notification-history-changed", unread(records));
+}
+
+/// 加载持久化的通知历史（启动过程中已记录的通知排在其后）
+pub fn load(app: &tauri::AppHandle) {
+    let saved: Vec<NotificationRecord> = load_json(app, HISTORY_FILE);
+    let state = app.state::<NotificationHistory>();
+    let mut records = state.records.lock().unwrap_or_else(PoisonError::into_inner);
+    records.splice(0..0, saved);
+    let excess = records.len().saturating_sub(MAX_HISTORY);
+    records.drain(..excess);
+}
+
+/// 记录一条通知
+pub fn record(app: &tauri::AppHandle, title: &str, body: &str, critical: bool, deferred: bool) {
+    let Some(state) = app.try_state::<NotificationHistory>() else {
+        return;
+    };
+    let mut records = state.records.lock().unwrap_or_else(PoisonError::into_inner);
+    records.push(NotificationRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: title.to_string(),
+        body: body.to_string(),
+        critical,
+        deferred,
+        read: false,
+        created_at: chrono::Local::now().to_rfc3339(),
+    });
+    let excess = records.len().saturating_sub(MAX_HISTORY);
+    records.drain(..excess);
+    changed(app, &records);
+}
+
+/// 获取通知历史（最新的在前）
+#[tauri::command]
+pub async fn get_notification_history(
+    app: tauri::AppHandle,
+    limit: Option<usize>,
+    unread_only: Option<bool>,
+) -> Result<Vec<NotificationRecord>, String> {
+    let state = app.state::<NotificationHistory>();
+    let records = state.records.lock().map_err(|e| e.to_string())?;
+    let unread_only = unread_only.unwrap_or(false);
+    Ok(records
+        .iter()
+        .rev()
+        .filter(|r| !unread_only || !r.read)
+        .take(limit.unwrap_or(MAX_HISTORY))
+        .cloned()
+        .collect())
+}
+
+/// 标记为已读（ids 为空时全部标记），返回剩余未读数量
+#[tauri::command]
+pub async fn mark_notifications_read(
+    app: tauri::AppHandle,
+    ids: Option<Vec<String>>,
+) -> Result<usize, String> {
+    let state = app.state::<NotificationHistory>();
+    let mut records = state.records.lock().map_err(|e| e.to_string())?;
+    let ids: Option<HashSet<String>> = ids.map(|ids| ids.into_iter().collect());
+    let mut marked = false;
+    for record in records.iter_mut().filter(|r| !r.read) {
+        if ids.as_ref().is_none_or(|ids| ids.contains(&record.id)) {
+            record.read = true;
+            marked = true;
+        }
+    }
+    if marked {
+        changed(&app, &records);
+    }
+    Ok(unread(&records))
+}
+
+/// 清空通知历史
+#[tauri::command]
+pub async fn clear_notification_history(app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<NotificationHistory>();
+    let mut records = state.records.lock().map_err(|e| e.to_string())?;
+    records.clear();
+    save_json(&app, HISTORY_FILE, &*records)?;
+    let _ = app.emit("notification-history-changed", 0);
+    debug_log("[notify] 已清空通知历史");
+    Ok(())
+}
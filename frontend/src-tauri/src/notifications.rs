@@ -0,0 +1,436 @@
+// ============================================================================
+// 本地通知：统一发送入口 + 专注模式延迟 + 手动暂停 + 定时提醒
+// ============================================================================
+//
+// 非关键通知在专注 / 勿扰模式开启或手动暂停（snooze_notifications）期间进入队列：
+// 专注结束后逐条补发，暂停结束后合并为一条汇总通知。暂停状态显示在托盘"暂停通知"子菜单中，
+// 也可以在那里暂停或恢复。
+
+use crate::i18n::{t, tf};
+use crate::store::{load_json, save_json};
+use crate::timeline::{self, TimelineKind};
+use crate::{appearance, debug_log, i18n, notification_history, settings, speech};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// 定时通知持久化文件
+const SCHEDULED_FILE: &str = "scheduled-notifications.json";
+
+/// 定时通知检查间隔（秒）
+const SCHEDULER_TICK_SECS: u64 = 10;
+
+/// 专注模式检查间隔（秒）
+const FOCUS_POLL_SECS: u64 = 30;
+
+/// 托盘"暂停通知"子菜单项 ID 前缀（后接分钟数或 "resume"）
+pub const SNOOZE_ITEM_PREFIX: &str = "snooze:";
+
+/// 托盘中可选的暂停时长（分钟）
+pub const SNOOZE_PRESETS: &[u64] = &[30, 60, 120];
+
+/// 单次暂停的最长时长（分钟）
+const MAX_SNOOZE_MINUTES: u64 = 7 * 24 * 60;
+
+/// 暂停结束的汇总通知中列出的标题数
+const SUMMARY_TITLES: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledNotification {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    /// 触发时间（RFC 3339）
+    pub at: String,
+}
+
+/// 待触发的定时通知（与磁盘文件保持同步）
+#[derive(Default)]
+pub struct NotificationScheduler {
+    pending: Vec<ScheduledNotification>,
+}
+
+/// 因专注/勿扰模式或手动暂停而延迟的通知
+#[derive(Default)]
+pub struct DeferredNotifications {
+    focus_active: bool,
+    focus_source: String,
+    queue: Vec<(String, String)>,
+    /// 手动暂停的结束时间
+    snoozed_until: Option<chrono::DateTime<chrono::Local>>,
+    /// 每次暂停 / 恢复时递增，旧的结束计时器据此失效
+    snooze_generation: u64,
+}
+
+impl DeferredNotifications {
+    fn deferring(&self) -> bool {
+        self.focus_active || self.snoozed_until.is_some()
+    }
+
+    fn snooze_state(&self) -> SnoozeState {
+        SnoozeState {
+            snoozed_until: self.snoozed_until.map(|t| t.to_rfc3339()),
+            deferred_notifications: self.queue.len(),
+        }
+    }
+}
+
+/// 手动暂停状态（`notifications-snoozed` 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct SnoozeState {
+    /// 暂停结束时间（RFC 3339），未暂停时为 None
+    pub snoozed_until: Option<String>,
+    /// 队列中等待补发的通知数量
+    pub deferred_notifications: usize,
+}
+
+/// 立即发送系统通知（不经过专注模式判断）
+///
+/// 屏幕阅读器开启且设置 speak_notifications 开启时同时朗读通知内容。
+pub fn show_notification(app: &tauri::AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        debug_log(&format!("[notify] 发送通知失败: {}", e));
+    }
+    if appearance::screen_reader_active() && settings::current(app).speak_notifications {
+        if let Err(e) = speech::say(app, &format!("{}。{}", title, body), None, None) {
+            debug_log(&format!("[notify] 朗读通知失败: {}", e));
+        }
+    }
+}
+
+/// 发送通知（Rust 侧所有通知的统一入口）
+///
+/// 非关键通知在专注模式开启或手动暂停期间进入队列，结束后统一补发。
+/// 每条通知都记入通知历史（见 notification_history.rs）与活动时间线。
+pub fn notify(app: &tauri::AppHandle, title: &str, body: &str, critical: bool) {
+    let deferred = !critical
+        && app
+            .state::<Mutex<DeferredNotifications>>()
+            .lock()
+            .is_ok_and(|mut guard| {
+                let deferring = guard.deferring();
+                if deferring {
+                    guard.queue.push((title.to_string(), body.to_string()));
+                }
+                deferring
+            });
+    notification_history::record(app, title, body, critical, deferred);
+    timeline::record(
+        app,
+        TimelineKind::Notification,
+        "notify",
+        true,
+        serde_json::json!({
+            "title": title,
+            "body": body,
+            "critical": critical,
+            "deferred": deferred,
+        }),
+    );
+    if !deferred {
+        show_notification(app, title, body);
+    }
+}
+
+/// 启动专注模式监视线程，专注结束时补发队列中的通知（仍在手动暂停时保留到暂停结束）
+pub fn start_focus_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        let (active, source) = crate::focus::detect_focus();
+
+        let (changed, flushed) = {
+            let state = app.state::<Mutex<DeferredNotifications>>();
+            let mut guard = state.lock().unwrap_or_else(PoisonError::into_inner);
+            let changed = guard.focus_active != active;
+            guard.focus_active = active;
+            guard.focus_source = source.to_string();
+            let flushed = if changed && !active && guard.snoozed_until.is_none() {
+                std::mem::take(&mut guard.queue)
+            } else {
+                vec![]
+            };
+            (changed, flushed)
+        };
+
+        if changed {
+            debug_log(&format!(
+                "[notify] 专注模式{} (source={})",
+                if active { "开启" } else { "结束" },
+                source
+            ));
+            let _ = app.emit("focus-changed", active);
+        }
+        if !flushed.is_empty() {
+            debug_log(&format!("[notify] 专注结束，补发 {} 条通知", flushed.len()));
+            for (title, body) in flushed {
+                show_notification(&app, &title, &body);
+            }
+        }
+
+        std::thread::sleep(Duration::from_secs(FOCUS_POLL_SECS));
+    });
+}
+
+/// 托盘菜单中显示的暂停结束时间
+pub fn snoozed_until<R: tauri::Runtime, M: Manager<R>>(
+    manager: &M,
+) -> Option<chrono::DateTime<chrono::Local>> {
+    let state = manager.try_state::<Mutex<DeferredNotifications>>()?;
+    let guard = state.lock().ok()?;
+    guard.snoozed_until
+}
+
+/// 补发暂停期间的通知：只有一条时原样发送，多条时合并为汇总
+fn deliver_summary(app: &tauri::AppHandle, queue: Vec<(String, String)>) {
+    match queue.len() {
+        0 => {}
+        1 => {
+            for (title, body) in queue {
+                show_notification(app, &title, &body);
+            }
+        }
+        count => {
+            let titles = queue
+                .iter()
+                .take(SUMMARY_TITLES)
+                .map(|(title, _)| title.as_str())
+                .collect::<Vec<_>>()
+                .join("、");
+            show_notification(
+                app,
+                &t("notify.snooze_summary.title"),
+                &tf("notify.snooze_summary.body", &[&count, &titles]),
+            );
+        }
+    }
+}
+
+/// 暂停状态变化后通知前端并刷新托盘菜单
+fn on_snooze_changed(app: &tauri::AppHandle, state: &SnoozeState) {
+    let _ = app.emit("notifications-snoozed", state);
+    if let Err(e) = i18n::refresh_tray_menu(app) {
+        debug_log(&format!("[notify] 刷新托盘菜单失败: {}", e));
+    }
+}
+
+/// 暂停非关键通知 minutes 分钟（重复调用时从现在重新计时）
+fn snooze(app: &tauri::AppHandle, minutes: u64) -> Result<SnoozeState, String> {
+    let until = chrono::Local::now() + chrono::Duration::minutes(minutes as i64);
+    let (generation, state) = {
+        let state = app.state::<Mutex<DeferredNotifications>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        guard.snoozed_until = Some(until);
+        guard.snooze_generation += 1;
+        (guard.snooze_generation, guard.snooze_state())
+    };
+    debug_log(&format!(
+        "[notify] 暂停通知至 {}",
+        until.format("%Y-%m-%d %H:%M")
+    ));
+
+    let timer_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(minutes * 60)).await;
+        if let Err(e) = end_snooze(&timer_app, Some(generation)) {
+            debug_log(&format!("[notify] 结束暂停失败: {}", e));
+        }
+    });
+    on_snooze_changed(app, &state);
+    Ok(state)
+}
+
+/// 结束暂停并汇总补发（专注模式仍开启时留到专注结束）；
+/// generation 与当前不一致（期间重新暂停或已恢复）时忽略
+fn end_snooze(app: &tauri::AppHandle, generation: Option<u64>) -> Result<SnoozeState, String> {
+    let (flushed, state) = {
+        let state = app.state::<Mutex<DeferredNotifications>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        if guard.snoozed_until.is_none() || generation.is_some_and(|g| g != guard.snooze_generation)
+        {
+            return Ok(guard.snooze_state());
+        }
+        guard.snoozed_until = None;
+        guard.snooze_generation += 1;
+        let flushed = if guard.focus_active {
+            vec![]
+        } else {
+            std::mem::take(&mut guard.queue)
+        };
+        (flushed, guard.snooze_state())
+    };
+    debug_log(&format!("[notify] 暂停结束，补发 {} 条通知", flushed.len()));
+    deliver_summary(app, flushed);
+    on_snooze_changed(app, &state);
+    Ok(state)
+}
+
+/// 托盘菜单中暂停 / 恢复通知
+pub fn snooze_from_tray(app: &tauri::AppHandle, menu_id: &str) {
+    let Some(action) = menu_id.strip_prefix(SNOOZE_ITEM_PREFIX) else {
+        return;
+    };
+    let result = match action {
+        "resume" => end_snooze(app, None),
+        minutes => match minutes.parse::<u64>() {
+            Ok(minutes) => snooze(app, minutes),
+            Err(_) => return,
+        },
+    };
+    if let Err(e) = result {
+        debug_log(&format!("[notify] {}", e));
+    }
+}
+
+fn persist(app: &tauri::AppHandle, pending: &[ScheduledNotification]) {
+    if let Err(e) = save_json(app, SCHEDULED_FILE, &pending) {
+        debug_log(&format!("[notify] 保存定时通知失败: {}", e));
+    }
+}
+
+/// 取出已到期的通知并同步磁盘
+fn take_due(app: &tauri::AppHandle) -> Vec<ScheduledNotification> {
+    let now = chrono::Utc::now();
+    let state = app.state::<Mutex<NotificationScheduler>>();
+    let mut guard = match state.lock() {
+        Ok(g) => g,
+        Err(_) => return vec![],
+    };
+
+    let (due, rest): (Vec<_>, Vec<_>) = guard.pending.drain(..).partition(|n| {
+        chrono::DateTime::parse_from_rfc3339(&n.at)
+            .map(|t| t <= now)
+            .unwrap_or(true)
+    });
+    guard.pending = rest;
+    if !due.is_empty() {
+        persist(app, &guard.pending);
+    }
+    due
+}
+
+/// 加载持久化的定时通知与通知历史，并启动调度循环
+///
+/// 应用关闭期间错过的提醒会在启动后立即补发。
+pub fn start_scheduler(app: tauri::AppHandle) {
+    notification_history::load(&app);
+    let saved: Vec<ScheduledNotification> = load_json(&app, SCHEDULED_FILE);
+    if !saved.is_empty() {
+        debug_log(&format!("[notify] 恢复 {} 条定时通知", saved.len()));
+    }
+    if let Ok(mut guard) = app.state::<Mutex<NotificationScheduler>>().lock() {
+        guard.pending = saved;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            for n in take_due(&app) {
+                debug_log(&format!("[notify] 触发定时通知 (id={})", n.id));
+                notify(&app, &n.title, &n.body, false);
+            }
+            tokio::time::sleep(Duration::from_secs(SCHEDULER_TICK_SECS)).await;
+        }
+    });
+}
+
+/// 创建定时通知，返回通知 ID（同 ID 会覆盖已有计划）
+#[tauri::command]
+pub async fn schedule_notification(
+    app: tauri::AppHandle,
+    at: String,
+    title: String,
+    body: String,
+    id: Option<String>,
+) -> Result<String, String> {
+    chrono::DateTime::parse_from_rfc3339(&at).map_err(|e| format!("无效的时间格式: {}", e))?;
+    let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let state = app.state::<Mutex<NotificationScheduler>>();
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    guard.pending.retain(|n| n.id != id);
+    guard.pending.push(ScheduledNotification {
+        id: id.clone(),
+        title,
+        body,
+        at,
+    });
+    save_json(&app, SCHEDULED_FILE, &guard.pending)?;
+    Ok(id)
+}
+
+/// 取消定时通知，返回是否存在
+#[tauri::command]
+pub async fn cancel_notification(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+    let state = app.state::<Mutex<NotificationScheduler>>();
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    let before = guard.pending.len();
+    guard.pending.retain(|n| n.id != id);
+    let removed = guard.pending.len() != before;
+    if removed {
+        save_json(&app, SCHEDULED_FILE, &guard.pending)?;
+    }
+    Ok(removed)
+}
+
+/// 列出尚未触发的定时通知
+#[tauri::command]
+pub async fn list_scheduled_notifications(
+    app: tauri::AppHandle,
+) -> Result<Vec<ScheduledNotification>, String> {
+    let state = app.state::<Mutex<NotificationScheduler>>();
+    let guard = state.lock().map_err(|e| e.to_string())?;
+    Ok(guard.pending.clone())
+}
+
+/// 发送通知
+///
+/// `critical` 为 true 时忽略专注模式立即发送；`subtitle` 会拼接到正文前。
+#[tauri::command]
+pub async fn send_notification(
+    app: tauri::AppHandle,
+    title: String,
+    body: String,
+    subtitle: Option<String>,
+    critical: Option<bool>,
+) -> Result<(), String> {
+    let body = match subtitle {
+        Some(sub) if !sub.is_empty() => format!("{}\n{}", sub, body),
+        _ => body,
+    };
+    notify(&app, &title, &body, critical.unwrap_or(false));
+    Ok(())
+}
+
+/// 获取专注/勿扰模式状态
+#[tauri::command]
+pub async fn get_focus_state(app: tauri::AppHandle) -> Result<crate::focus::FocusState, String> {
+    let state = app.state::<Mutex<DeferredNotifications>>();
+    let guard = state.lock().map_err(|e| e.to_string())?;
+    Ok(crate::focus::FocusState {
+        active: guard.focus_active,
+        source: guard.focus_source.clone(),
+        deferred_notifications: guard.queue.len(),
+        snoozed_until: guard.snoozed_until.map(|t| t.to_rfc3339()),
+    })
+}
+
+/// 暂停非关键通知 duration 分钟，结束后汇总补发（0 表示立即恢复）
+#[tauri::command]
+pub async fn snooze_notifications(
+    app: tauri::AppHandle,
+    duration: u64,
+) -> Result<SnoozeState, String> {
+    if duration == 0 {
+        return end_snooze(&app, None);
+    }
+    if duration > MAX_SNOOZE_MINUTES {
+        return Err(format!("Snooze duration too long: {} minutes", duration));
+    }
+    snooze(&app, duration)
+}
+
+/// 立即结束暂停并汇总补发
+#[tauri::command]
+pub async fn resume_notifications(app: tauri::AppHandle) -> Result<SnoozeState, String> {
+    end_snooze(&app, None)
+}
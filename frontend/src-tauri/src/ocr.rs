@@ -0,0 +1,172 @@
+// ============================================================================
+// 本地 OCR（截图文字识别）
+// ============================================================================
+//
+// - macOS: 通过 osascript (JXA) 调用 Vision 框架 VNRecognizeTextRequest
+// - Windows: 通过 PowerShell 调用 Windows.Media.Ocr（脚本经 -EncodedCommand 传入，不写临时文件，
+//   图片路径与语言通过环境变量传递）
+// 全部在本地完成，不依赖云端服务。
+// 边界框统一为归一化坐标（0..1，左上角为原点）。
+
+use serde::{Deserialize, Serialize};
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::Command as SysCommand;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrLine {
+    pub text: String,
+    pub confidence: f64,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrResult {
+    /// 全部识别文本（按行拼接）
+    pub text: String,
+    pub lines: Vec<OcrLine>,
+}
+
+#[cfg(target_os = "macos")]
+const VISION_OCR_SCRIPT: &str = r#"
+ObjC.import('Foundation');
+ObjC.import('Vision');
+function run(argv) {
+  const url = $.NSURL.fileURLWithPath(argv[0]);
+  const req = $.VNRecognizeTextRequest.alloc.init;
+  req.recognitionLevel = 0;
+  req.usesLanguageCorrection = true;
+  if (argv.length > 1 && argv[1].length > 0) {
+    req.recognitionLanguages = $(argv[1].split(','));
+  }
+  const handler = $.VNImageRequestHandler.alloc.initWithURLOptions(url, $({}));
+  if (!handler.performRequestsError($([req]), null)) {
+    throw new Error('Vision request failed');
+  }
+  const results = req.results;
+  const out = [];
+  for (let i = 0; i < results.count; i++) {
+    const obs = results.objectAtIndex(i);
+    const cand = obs.topCandidates(1).objectAtIndex(0);
+    const box = obs.boundingBox;
+    out.push({
+      text: cand.string.js,
+      confidence: cand.confidence,
+      x: box.origin.x,
+      y: 1 - box.origin.y - box.size.height,
+      width: box.size.width,
+      height: box.size.height
+    });
+  }
+  return JSON.stringify(out);
+}
+"#;
+
+#[cfg(target_os = "windows")]
+const WINDOWS_OCR_SCRIPT: &str = r#"
+$Path = $env:XIAODAZI_OCR_PATH
+$Langs = $env:XIAODAZI_OCR_LANGS
+Add-Type -AssemblyName System.Runtime.WindowsRuntime
+$asTask = ([System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object { $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1' })[0]
+function Await($op, [Type]$t) { $task = $asTask.MakeGenericMethod($t).Invoke($null, @($op)); $task.Wait(-1) | Out-Null; $task.Result }
+[Windows.Storage.StorageFile, Windows.Storage, ContentType = WindowsRuntime] | Out-Null
+[Windows.Media.Ocr.OcrEngine, Windows.Foundation, ContentType = WindowsRuntime] | Out-Null
+[Windows.Graphics.Imaging.BitmapDecoder, Windows.Graphics, ContentType = WindowsRuntime] | Out-Null
+$file = Await ([Windows.Storage.StorageFile]::GetFileFromPathAsync($Path)) ([Windows.Storage.StorageFile])
+$stream = Await ($file.OpenAsync([Windows.Storage.FileAccessMode]::Read)) ([Windows.Storage.Streams.IRandomAccessStream])
+$decoder = Await ([Windows.Graphics.Imaging.BitmapDecoder]::CreateAsync($stream)) ([Windows.Graphics.Imaging.BitmapDecoder])
+$bitmap = Await ($decoder.GetSoftwareBitmapAsync()) ([Windows.Graphics.Imaging.SoftwareBitmap])
+$engine = $null
+if ($Langs) { $engine = [Windows.Media.Ocr.OcrEngine]::TryCreateFromLanguage([Windows.Globalization.Language]::new($Langs.Split(',')[0])) }
+if (-not $engine) { $engine = [Windows.Media.Ocr.OcrEngine]::TryCreateFromUserProfileLanguages() }
+$result = Await ($engine.RecognizeAsync($bitmap)) ([Windows.Media.Ocr.OcrResult])
+$w = [double]$bitmap.PixelWidth; $h = [double]$bitmap.PixelHeight
+$out = @()
+foreach ($line in $result.Lines) {
+  $l = $line.Words | ForEach-Object { $_.BoundingRect }
+  $x0 = ($l | Measure-Object -Property X -Minimum).Minimum
+  $y0 = ($l | Measure-Object -Property Y -Minimum).Minimum
+  $x1 = ($l | ForEach-Object { $_.X + $_.Width } | Measure-Object -Maximum).Maximum
+  $y1 = ($l | ForEach-Object { $_.Y + $_.Height } | Measure-Object -Maximum).Maximum
+  $out += @{ text = $line.Text; confidence = 1.0; x = $x0 / $w; y = $y0 / $h; width = ($x1 - $x0) / $w; height = ($y1 - $y0) / $h }
+}
+ConvertTo-Json -InputObject @($out) -Compress
+"#;
+
+/// 调用平台 OCR，返回识别结果 JSON（OcrLine 数组）
+#[cfg(target_os = "macos")]
+fn run_platform_ocr(path: &str, languages: &str) -> Result<String, String> {
+    let output = SysCommand::new("osascript")
+        .args(["-l", "JavaScript", "-e", VISION_OCR_SCRIPT, path, languages])
+        .output()
+        .map_err(|e| format!("OCR 执行失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "OCR 执行失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn run_platform_ocr(path: &str, languages: &str) -> Result<String, String> {
+    use base64::Engine;
+    // -EncodedCommand 接受 UTF-16LE 的 Base64
+    let script: Vec<u8> = WINDOWS_OCR_SCRIPT
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let encoded = base64::engine::general_purpose::STANDARD.encode(script);
+    let output = SysCommand::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-EncodedCommand"])
+        .arg(&encoded)
+        // canonicalize 在 Windows 上返回 \\?\ 前缀，StorageFile 不接受
+        .env("XIAODAZI_OCR_PATH", path.trim_start_matches(r"\\?\"))
+        .env("XIAODAZI_OCR_LANGS", languages)
+        .output()
+        .map_err(|e| format!("OCR 执行失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "OCR 执行失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn run_platform_ocr(_path: &str, _languages: &str) -> Result<String, String> {
+    Err("OCR not supported on this platform".to_string())
+}
+
+/// 识别图片中的文字（本地 OCR）
+///
+/// `languages` 为 BCP-47 语言代码列表（如 ["zh-Hans", "en-US"]），为空时使用系统默认。
+#[tauri::command]
+pub async fn ocr_image(path: String, languages: Option<Vec<String>>) -> Result<OcrResult, String> {
+    crate::policy::check_path(std::path::Path::new(&path))?;
+    let abs = std::fs::canonicalize(&path).map_err(|e| format!("无法读取文件: {}", e))?;
+    let abs = abs.to_string_lossy().to_string();
+    let langs = languages.unwrap_or_default().join(",");
+
+    let raw = tauri::async_runtime::spawn_blocking(move || run_platform_ocr(&abs, &langs))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let raw = raw.trim();
+    let lines: Vec<OcrLine> = if raw.is_empty() {
+        vec![]
+    } else {
+        serde_json::from_str(raw).map_err(|e| format!("解析 OCR 结果失败: {}", e))?
+    };
+    let text = lines
+        .iter()
+        .map(|l| l.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(OcrResult { text, lines })
+}
@@ -0,0 +1,247 @@
+// ============================================================================
+// 首次运行引导
+// ============================================================================
+//
+// 设置向导的进度保存在设置 onboarding 中，页面刷新或应用重启后从未完成的步骤继续：
+// - permissions：已授予的系统权限（前端逐项请求后上报）
+// - data_dir：后端数据目录，None 表示默认的应用数据目录
+// - telemetry：是否上传崩溃报告（写入设置 crash_upload_consent）
+// - autostart：是否开机启动（完成该步骤时通过 tauri-plugin-autostart 注册或取消登录项）
+// 打包模式下首次运行不自动启动 sidecar，避免后端在用户选定数据目录、同意上传之前
+// 就在默认目录里初始化数据。向导完成 data_dir 与 telemetry 后可调用 start_backend
+// 提前启动（其余步骤进行时后端已在加载）；全部步骤完成时若仍未启动则自动启动。
+// 旧版本升级上来的设置文件没有 onboarding 字段，视为已完成。
+
+use crate::{debug_log, events, i18n, policy, settings};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+use tauri_plugin_autostart::ManagerExt;
+
+/// 引导步骤（按向导顺序）
+pub const ONBOARDING_STEPS: &[&str] = &["permissions", "data_dir", "telemetry", "autostart"];
+
+/// 启动后端前必须完成的步骤
+const BACKEND_REQUIRED_STEPS: &[&str] = &["data_dir", "telemetry"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OnboardingState {
+    /// 已完成的步骤（ONBOARDING_STEPS 中的名称）
+    pub completed_steps: Vec<String>,
+    /// 已授予的系统权限（如 "screen"、"accessibility"）
+    pub granted_permissions: Vec<String>,
+    /// 后端数据目录，None 表示默认目录
+    pub data_dir: Option<String>,
+    /// 是否开机启动，None 表示尚未选择
+    pub autostart: Option<bool>,
+    /// 完成全部步骤的时间
+    pub completed_at: Option<String>,
+}
+
+impl OnboardingState {
+    /// 已完成的状态（旧版本设置文件没有该字段时使用）
+    pub fn finished() -> Self {
+        Self {
+            completed_steps: ONBOARDING_STEPS.iter().map(|s| s.to_string()).collect(),
+            ..Self::default()
+        }
+    }
+
+    fn has_completed(&self, steps: &[&str]) -> bool {
+        steps
+            .iter()
+            .all(|step| self.completed_steps.iter().any(|s| s == step))
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.has_completed(ONBOARDING_STEPS)
+    }
+}
+
+/// 前端提交的步骤结果
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum OnboardingStep {
+    Permissions {
+        #[serde(default)]
+        granted: Vec<String>,
+    },
+    DataDir {
+        path: Option<String>,
+    },
+    Telemetry {
+        consent: settings::CrashUploadConsent,
+    },
+    Autostart {
+        enabled: bool,
+    },
+}
+
+impl OnboardingStep {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Permissions { .. } => "permissions",
+            Self::DataDir { .. } => "data_dir",
+            Self::Telemetry { .. } => "telemetry",
+            Self::Autostart { .. } => "autostart",
+        }
+    }
+}
+
+/// 引导完成前推迟执行的 sidecar 启动
+#[derive(Default)]
+pub struct OnboardingGate {
+    deferred: Mutex<Option<Box<dyn FnOnce() + Send>>>,
+}
+
+/// 后端数据目录（引导中选择的目录，否则为应用数据目录）
+pub fn data_dir_of(settings: &settings::AppSettings) -> PathBuf {
+    settings
+        .onboarding
+        .data_dir
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(settings::app_data_dir)
+}
+
+/// 传给 sidecar 的 --data-dir
+pub fn backend_data_dir(app: &tauri::AppHandle) -> String {
+    data_dir_of(&settings::current(app))
+        .to_string_lossy()
+        .to_string()
+}
+
+pub fn is_completed(app: &tauri::AppHandle) -> bool {
+    settings::current(app).onboarding.is_completed()
+}
+
+/// 引导完成（或调用 start_backend）后再启动后端
+pub fn defer_backend(app: &tauri::AppHandle, spawn: impl FnOnce() + Send + 'static) {
+    debug_log("[onboarding] 首次运行引导未完成，推迟启动后端");
+    if let Ok(mut deferred) = app.state::<OnboardingGate>().deferred.lock() {
+        *deferred = Some(Box::new(spawn));
+    }
+    crate::set_sidecar_status(app, &i18n::t("sidecar.onboarding"));
+}
+
+/// 启动推迟的后端，返回是否确实启动了（已启动或开发模式返回 false）
+fn run_deferred(app: &tauri::AppHandle) -> bool {
+    let deferred = app
+        .state::<OnboardingGate>()
+        .deferred
+        .lock()
+        .ok()
+        .and_then(|mut d| d.take());
+    match deferred {
+        Some(spawn) => {
+            debug_log("[onboarding] 启动推迟的后端");
+            spawn();
+            true
+        }
+        None => false,
+    }
+}
+
+/// 数据目录必须是绝对路径、可写，且在托管策略允许的范围内
+fn validate_data_dir(path: &str) -> Result<(), String> {
+    let dir = Path::new(path);
+    if !dir.is_absolute() {
+        return Err(format!("Data directory must be an absolute path: {}", path));
+    }
+    std::fs::create_dir_all(dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    let probe = dir.join(".xiaodazi-write-test");
+    std::fs::write(&probe, b"").map_err(|e| format!("数据目录不可写: {}", e))?;
+    let _ = std::fs::remove_file(&probe);
+    policy::check_path(dir)
+}
+
+/// 按引导中的选择注册或取消开机启动
+fn apply_autostart(app: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    if autolaunch.is_enabled().unwrap_or(!enabled) == enabled {
+        return Ok(());
+    }
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|e| format!("设置开机启动失败: {}", e))?;
+    debug_log(&format!(
+        "[onboarding] 开机启动已{}",
+        if enabled { "开启" } else { "关闭" }
+    ));
+    Ok(())
+}
+
+/// 获取引导进度
+#[tauri::command]
+pub async fn get_onboarding_state(app: tauri::AppHandle) -> Result<OnboardingState, String> {
+    Ok(settings::current(&app).onboarding)
+}
+
+/// 完成一个引导步骤，返回更新后的进度；完成最后一步时启动尚未启动的后端
+#[tauri::command]
+pub async fn complete_onboarding_step(
+    app: tauri::AppHandle,
+    step: OnboardingStep,
+) -> Result<OnboardingState, String> {
+    match &step {
+        OnboardingStep::DataDir { path: Some(path) } => validate_data_dir(path)?,
+        OnboardingStep::Autostart { enabled } => apply_autostart(&app, *enabled)?,
+        _ => {}
+    }
+    let name = step.name();
+
+    let (onboarding, just_completed) = {
+        let state = app.state::<Mutex<settings::AppSettings>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        // 托管策略锁定的设置保持策略值
+        if let OnboardingStep::Telemetry { consent } = &step {
+            if !policy::is_locked_setting("crash_upload_consent") {
+                guard.crash_upload_consent = *consent;
+            }
+        }
+        let onboarding = &mut guard.onboarding;
+        let was_completed = onboarding.is_completed();
+        match step {
+            OnboardingStep::Permissions { granted } => onboarding.granted_permissions = granted,
+            OnboardingStep::DataDir { path } => onboarding.data_dir = path,
+            OnboardingStep::Autostart { enabled } => onboarding.autostart = Some(enabled),
+            OnboardingStep::Telemetry { .. } => {}
+        }
+        if !onboarding.completed_steps.iter().any(|s| s == name) {
+            onboarding.completed_steps.push(name.to_string());
+        }
+        let just_completed = !was_completed && onboarding.is_completed();
+        if just_completed {
+            onboarding.completed_at = Some(chrono::Local::now().to_rfc3339());
+        }
+        let onboarding = onboarding.clone();
+        settings::save_settings(&guard)?;
+        (onboarding, just_completed)
+    };
+    debug_log(&format!("[onboarding] 已完成步骤: {}", name));
+
+    if just_completed {
+        debug_log("[onboarding] 首次运行引导已完成");
+        events::emit(&app, "onboarding-completed", &onboarding);
+        run_deferred(&app);
+    }
+    Ok(onboarding)
+}
+
+/// 启动推迟的后端（需先完成 data_dir 与 telemetry 步骤），返回是否由本次调用启动
+#[tauri::command]
+pub async fn start_backend(app: tauri::AppHandle) -> Result<bool, String> {
+    let onboarding = settings::current(&app).onboarding;
+    if let Some(step) = BACKEND_REQUIRED_STEPS
+        .iter()
+        .find(|&&step| !onboarding.has_completed(&[step]))
+    {
+        return Err(format!("Onboarding step not completed: {}", step));
+    }
+    Ok(run_deferred(&app))
+}
@@ -0,0 +1,169 @@
+// ============================================================================
+// 状态浮窗：置顶的迷你窗口，显示当前任务状态与进度
+// ============================================================================
+//
+// - show_overlay(click_through) 创建（或显示）浮窗，hide_overlay 销毁浮窗
+// - 主窗口调用 update_overlay(status) 更新内容，浮窗收到 `overlay-status` 事件；
+//   浮窗加载后调用 get_overlay_status 获取当前内容
+// - 浮窗无边框、不出现在任务栏；click_through 为 true 时鼠标事件穿透到下层窗口
+// - 拖动后的位置保存在设置 overlay_position 中，下次打开时恢复
+
+use crate::{debug_log, settings};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// 浮窗标签
+pub const OVERLAY_WINDOW_LABEL: &str = "overlay";
+
+/// 浮窗大小（逻辑像素）
+const OVERLAY_WIDTH: f64 = 280.0;
+const OVERLAY_HEIGHT: f64 = 64.0;
+
+/// 默认位置距屏幕右上角的边距
+const OVERLAY_MARGIN: f64 = 24.0;
+
+/// 拖动停止多久后保存位置
+const POSITION_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// 浮窗显示的内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OverlayStatus {
+    /// 主文本（如当前任务名称），为空时浮窗显示空闲状态
+    pub title: String,
+    pub detail: Option<String>,
+    /// 进度 0~1，None 表示不确定进度
+    pub progress: Option<f64>,
+}
+
+#[derive(Default)]
+pub struct OverlayState {
+    status: Mutex<OverlayStatus>,
+    /// 每次移动递增，用于合并拖动过程中的多次保存
+    move_generation: AtomicU64,
+}
+
+/// 默认位置：主显示器右上角
+fn default_position(app: &tauri::AppHandle) -> (f64, f64) {
+    app.primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| {
+            let size = monitor.size().to_logical::<f64>(monitor.scale_factor());
+            (
+                size.width - OVERLAY_WIDTH - OVERLAY_MARGIN,
+                OVERLAY_MARGIN * 2.0,
+            )
+        })
+        .unwrap_or((OVERLAY_MARGIN, OVERLAY_MARGIN))
+}
+
+/// 浮窗移动后延迟保存位置
+fn on_moved(app: &tauri::AppHandle, window: &tauri::WebviewWindow) {
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let position = position.to_logical::<f64>(scale);
+    let generation = app
+        .state::<OverlayState>()
+        .move_generation
+        .fetch_add(1, Ordering::SeqCst)
+        + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(POSITION_SAVE_DELAY).await;
+        if app
+            .state::<OverlayState>()
+            .move_generation
+            .load(Ordering::SeqCst)
+            == generation
+        {
+            settings::remember_overlay_position(&app, (position.x, position.y));
+        }
+    });
+}
+
+/// 显示状态浮窗
+#[tauri::command]
+pub async fn show_overlay(
+    app: tauri::AppHandle,
+    click_through: Option<bool>,
+) -> Result<(), String> {
+    let click_through = click_through.unwrap_or(false);
+    if let Some(window) = app.get_webview_window(OVERLAY_WINDOW_LABEL) {
+        window
+            .set_ignore_cursor_events(click_through)
+            .map_err(|e| e.to_string())?;
+        return window.show().map_err(|e| e.to_string());
+    }
+
+    let (x, y) = settings::current(&app)
+        .overlay_position
+        .unwrap_or_else(|| default_position(&app));
+    let window = tauri::WebviewWindowBuilder::new(
+        &app,
+        OVERLAY_WINDOW_LABEL,
+        tauri::WebviewUrl::App("overlay".into()),
+    )
+    .title("xiaodazi")
+    .inner_size(OVERLAY_WIDTH, OVERLAY_HEIGHT)
+    .position(x, y)
+    .decorations(false)
+    .resizable(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .focused(false)
+    .visible(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+    window
+        .set_ignore_cursor_events(click_through)
+        .map_err(|e| e.to_string())?;
+
+    let handle = app.clone();
+    let moved_window = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Moved(_) = event {
+            on_moved(&handle, &moved_window);
+        }
+    });
+    debug_log(&format!("[overlay] 已打开状态浮窗 ({}, {})", x, y));
+    Ok(())
+}
+
+/// 关闭状态浮窗
+#[tauri::command]
+pub async fn hide_overlay(app: tauri::AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(OVERLAY_WINDOW_LABEL) else {
+        return Ok(());
+    };
+    if let (Ok(position), Ok(scale)) = (window.outer_position(), window.scale_factor()) {
+        let position = position.to_logical::<f64>(scale);
+        settings::remember_overlay_position(&app, (position.x, position.y));
+    }
+    window.destroy().map_err(|e| e.to_string())
+}
+
+/// 更新浮窗内容
+#[tauri::command]
+pub async fn update_overlay(app: tauri::AppHandle, status: OverlayStatus) -> Result<(), String> {
+    *app.state::<OverlayState>()
+        .status
+        .lock()
+        .map_err(|e| e.to_string())? = status.clone();
+    if app.get_webview_window(OVERLAY_WINDOW_LABEL).is_some() {
+        let _ = app.emit_to(OVERLAY_WINDOW_LABEL, "overlay-status", status);
+    }
+    Ok(())
+}
+
+/// 获取浮窗当前内容
+#[tauri::command]
+pub async fn get_overlay_status(app: tauri::AppHandle) -> Result<OverlayStatus, String> {
+    let state = app.state::<OverlayState>();
+    let status = state.status.lock().map_err(|e| e.to_string())?;
+    Ok(status.clone())
+}
@@ -0,0 +1,438 @@
+// ============================================================================
+// 命令权限层
+// ============================================================================
+//
+// 每个敏感命令在 COMMAND_PERMISSIONS 中声明所需能力，由这里统一拦截：
+// - 前端调用：guard 包装 invoke handler，拒绝时返回结构化错误 PermissionDenied
+// - 远程调用等：调用 check_capability，拒绝时返回错误信息
+// - 计划任务与自动化规则：调用 check_unattended_capability，锁定期间仍可执行（见 lock.rs）
+// 检查顺序：调用窗口 → 应用锁定 → 能力开关（用户设置与托管策略）。
+// 不需要能力的命令（读取状态、设置等）在 CAPABILITY_FREE_COMMANDS 中显式列出；
+// 两张表都未声明的命令一律拒绝，新增命令必须先声明。
+// 只有主窗口可以调用全部命令；其他窗口（Canvas 等）只能调用 WINDOW_COMMANDS 中列出的命令，
+// 避免加载外部内容的窗口被利用后执行命令。
+
+use crate::{audit, capabilities, debug_log, lock};
+use serde::Serialize;
+use tauri::Manager;
+
+/// 命令与其所需的能力
+///
+/// schedule_task、add_automation 所需的能力取决于动作，由命令本身在登记时检查
+/// （见 scheduler::check_action_capability）。
+const COMMAND_PERMISSIONS: &[(&str, &str)] = &[
+    ("run_command", "system.run"),
+    ("run_command_at", "system.run"),
+    ("which_command", "system.which"),
+    ("send_notification", "system.notify"),
+    ("schedule_notification", "system.notify"),
+    ("speak", "system.speak"),
+    ("list_processes", "system.processes"),
+    ("get_process", "system.processes"),
+    ("kill_process", "system.processes"),
+    ("list_applications", "system.apps"),
+    ("launch_application", "system.apps"),
+    ("get_volume", "system.volume"),
+    ("set_volume", "system.volume"),
+    ("set_muted", "system.volume"),
+    ("list_printers", "system.print"),
+    ("print_file", "system.print"),
+    ("read_local_dir", "fs.read"),
+    ("read_local_file_text", "fs.read"),
+    ("read_local_file_binary", "fs.read"),
+    ("check_is_directory", "fs.read"),
+    ("backend_upload_file", "fs.read"),
+    ("open_file_transfer", "fs.read"),
+    ("make_thumbnail", "fs.read"),
+    ("downscale_image", "fs.read"),
+    ("trim_video", "fs.read"),
+    ("compress_video", "fs.read"),
+    ("move_local_file", "fs.write"),
+    ("delete_local_path", "fs.write"),
+    ("create_local_file", "fs.write"),
+    ("create_local_dir", "fs.write"),
+    ("git_status", "git.read"),
+    ("git_diff", "git.read"),
+    ("git_commit", "git.write"),
+    ("git_clone", "git.write"),
+    ("backend_download_file", "fs.write"),
+    ("ocr_image", "screen.ocr"),
+    ("capture_screen", "screen.record"),
+    ("preflight_screen_capture", "screen.record"),
+    ("request_calendar_access", "calendar.read"),
+    ("list_calendars", "calendar.read"),
+    ("list_events", "calendar.read"),
+    ("canvas_present", "canvas.present"),
+    ("canvas_hide", "canvas.hide"),
+    ("canvas_navigate", "canvas.navigate"),
+    ("canvas_eval", "canvas.eval"),
+    ("canvas_snapshot", "canvas.snapshot"),
+    ("cancel_notification", "system.notify"),
+    ("get_calendar_permission", "calendar.read"),
+    ("prevent_sleep", "system.power"),
+    ("set_brightness", "system.display"),
+    ("get_network_info", "system.network"),
+    ("get_wifi_info", "system.network"),
+    ("list_usb_devices", "system.usb"),
+    ("get_recent_items", "system.recent"),
+    ("annotate_image", "fs.read"),
+    ("export_history", "fs.write"),
+    ("export_diagnostics", "fs.write"),
+    ("enable_remote_control", "remote.control"),
+    ("connect_node", "remote.invoke"),
+    ("invoke_remote", "remote.invoke"),
+    ("start_webhook_server", "webhook.serve"),
+    ("backend_binary", "backend.proxy"),
+    ("subscribe_stream", "backend.proxy"),
+    ("replay_stream", "backend.proxy"),
+    ("ws_bridge_connect", "backend.proxy"),
+    ("ensure_tool", "tools.manage"),
+    ("remove_managed_tool", "tools.manage"),
+    ("set_tool_enabled", "tools.manage"),
+    ("cleanup_tools", "tools.manage"),
+    ("add_project", "projects.manage"),
+    ("remove_project", "projects.manage"),
+    ("set_default_project", "projects.manage"),
+];
+
+/// 不需要能力的命令（读取状态、应用自身的设置与界面、撤销/关闭类操作）
+///
+/// 这些命令有自己的保护（身份验证、审批、锁定检查），或只影响应用自身。
+const CAPABILITY_FREE_COMMANDS: &[&str] = &[
+    // 后端连接与启动状态
+    "get_backend_url",
+    "get_backend_ws_url",
+    "is_backend_ready",
+    "get_sidecar_status",
+    "get_app_state_snapshot",
+    "extend_backend_startup",
+    "get_port_selection",
+    "get_startup_timings",
+    "get_backend_health_history",
+    "get_recent_events",
+    "start_backend",
+    "ws_bridge_send",
+    "ws_bridge_close",
+    "unsubscribe_stream",
+    // 节点、能力与安全设置（开启能力、关闭安全模式等需要身份验证）
+    "get_node_info",
+    "set_display_name",
+    "list_capabilities",
+    "set_capability_enabled",
+    "set_safe_mode",
+    "authenticate",
+    "set_auth_grace_period",
+    "get_lock_state",
+    "lock_app",
+    "unlock_app",
+    "get_effective_policy",
+    "verify_audit_log",
+    "list_pending_approvals",
+    "approve",
+    "deny",
+    // 应用信息、外观与界面
+    "get_app_info",
+    "show_about",
+    "open_system_preferences",
+    "get_startup_paths",
+    "get_system_theme",
+    "get_system_appearance",
+    "is_screen_reader_active",
+    "get_language",
+    "set_language",
+    "log_from_frontend",
+    "get_settings",
+    "update_settings",
+    "update_security_settings",
+    "set_badge_count",
+    "set_progress",
+    "request_attention",
+    "show_overlay",
+    "hide_overlay",
+    "update_overlay",
+    "get_overlay_status",
+    "get_window_chrome",
+    "set_window_chrome",
+    "set_zoom",
+    "get_zoom",
+    "list_shortcuts",
+    "set_shortcut",
+    "get_displays",
+    "move_window_to_display",
+    "get_brightness",
+    "get_onboarding_state",
+    "complete_onboarding_step",
+    "run_self_test",
+    // 系统状态（不含个人数据）
+    "allow_sleep",
+    "get_power_state",
+    "stop_speaking",
+    "is_online",
+    "get_focus_state",
+    "get_storage_report",
+    "list_volumes",
+    "run_cleanup_now",
+    // 通知与时间线
+    "list_scheduled_notifications",
+    "snooze_notifications",
+    "resume_notifications",
+    "get_notification_history",
+    "mark_notifications_read",
+    "clear_notification_history",
+    "query_timeline",
+    // 计划任务与自动化（登记时按动作检查能力，见 scheduler::check_action_capability）
+    "schedule_task",
+    "list_schedules",
+    "delete_schedule",
+    "add_automation",
+    "list_automations",
+    "delete_automation",
+    "set_automation_enabled",
+    // 远程控制与 webhook 的状态查询和关闭
+    "discover_nodes",
+    "disable_remote_control",
+    "get_remote_control_status",
+    "disconnect_node",
+    "stop_webhook_server",
+    "get_webhook_status",
+    // 更新与崩溃报告
+    "check_for_update",
+    "download_update",
+    "install_update",
+    "get_update_history",
+    "rollback_update",
+    "check_backend_update",
+    "download_backend_update",
+    "get_backend_update_status",
+    "rollback_backend_update",
+    "list_crash_reports",
+    "send_crash_report",
+    "delete_crash_report",
+    // 已打开的传输、工具与项目查询
+    "read_chunk",
+    "close_transfer",
+    "list_managed_tools",
+    "get_tool_path",
+    "list_projects",
+];
+
+/// 不受窗口限制的主窗口
+const MAIN_WINDOW_LABEL: &str = "main";
+
+/// 所有窗口都可以调用的命令（只读、无副作用）
+const COMMON_WINDOW_COMMANDS: &[&str] = &[
+    "get_app_info",
+    "get_language",
+    "get_system_theme",
+    "log_from_frontend",
+];
+
+/// 非主窗口额外允许调用的命令（窗口标签 → 命令）
+const WINDOW_COMMANDS: &[(&str, &[&str])] = &[
+    (crate::CANVAS_WINDOW_LABEL, &["canvas_hide"]),
+    (
+        crate::overlay::OVERLAY_WINDOW_LABEL,
+        &["get_overlay_status", "hide_overlay"],
+    ),
+    (crate::about::ABOUT_WINDOW_LABEL, &["export_diagnostics"]),
+];
+
+/// 权限检查未通过时返回给调用方的错误
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionDenied {
+    /// command_not_declared / window_not_allowed / app_locked / capability_disabled
+    pub code: &'static str,
+    pub command: Option<String>,
+    pub capability: Option<String>,
+    pub message: String,
+}
+
+impl std::fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// 命令所需的能力（未声明时为 None）
+pub fn required_capability(command: &str) -> Option<&'static str> {
+    COMMAND_PERMISSIONS
+        .iter()
+        .find(|(c, _)| *c == command)
+        .map(|(_, capability)| *capability)
+}
+
+/// 命令是否已在 COMMAND_PERMISSIONS 或 CAPABILITY_FREE_COMMANDS 中声明
+fn is_declared(command: &str) -> bool {
+    required_capability(command).is_some() || CAPABILITY_FREE_COMMANDS.contains(&command)
+}
+
+fn capability_disabled(command: Option<&str>, capability: &str) -> PermissionDenied {
+    PermissionDenied {
+        code: "capability_disabled",
+        command: command.map(str::to_string),
+        capability: Some(capability.to_string()),
+        message: format!("Capability disabled: {}", capability),
+    }
+}
+
+/// 检查能力是否可用（远程调用等不经过 invoke handler 的入口）
+pub fn check_capability(app: &tauri::AppHandle, capability: &str) -> Result<(), PermissionDenied> {
+    if lock::is_locked(app) {
+        return Err(PermissionDenied {
+            code: "app_locked",
+            command: None,
+            capability: Some(capability.to_string()),
+            message: "App is locked".to_string(),
+        });
+    }
+    if !capabilities::is_enabled(app, capability) {
+        return Err(capability_disabled(None, capability));
+    }
+    Ok(())
+}
+
+/// 检查能力是否可用（计划任务、自动化规则等用户事先登记的无人值守动作，不受应用锁定影响）
+pub fn check_unattended_capability(
+    app: &tauri::AppHandle,
+    capability: &str,
+) -> Result<(), PermissionDenied> {
+    if !capabilities::is_enabled(app, capability) {
+        return Err(capability_disabled(None, capability));
+    }
+    Ok(())
+}
+
+/// 该窗口是否可以调用该命令
+fn window_allows(window: &str, command: &str) -> bool {
+    window == MAIN_WINDOW_LABEL
+        || COMMON_WINDOW_COMMANDS.contains(&command)
+        || WINDOW_COMMANDS
+            .iter()
+            .any(|(label, commands)| *label == window && commands.contains(&command))
+}
+
+/// 检查窗口是否可以调用该命令
+pub fn check_command(
+    app: &tauri::AppHandle,
+    window: &str,
+    command: &str,
+) -> Result<(), PermissionDenied> {
+    if !is_declared(command) {
+        return Err(PermissionDenied {
+            code: "command_not_declared",
+            command: Some(command.to_string()),
+            capability: None,
+            message: format!("Command {} is not declared", command),
+        });
+    }
+    let capability = required_capability(command);
+    if !window_allows(window, command) {
+        return Err(PermissionDenied {
+            code: "window_not_allowed",
+            command: Some(command.to_string()),
+            capability: capability.map(str::to_string),
+            message: format!("Command {} is not allowed from window {}", command, window),
+        });
+    }
+    if lock::is_blocked(app, command) {
+        return Err(PermissionDenied {
+            code: "app_locked",
+            command: Some(command.to_string()),
+            capability: capability.map(str::to_string),
+            message: "App is locked".to_string(),
+        });
+    }
+    match capability {
+        Some(capability) if !capabilities::is_enabled(app, capability) => {
+            Err(capability_disabled(Some(command), capability))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 包装 invoke handler：权限检查未通过时直接拒绝，不进入命令
+pub fn guard(
+    handler: impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let webview = invoke.message.webview();
+        let app = webview.app_handle().clone();
+        if let Err(denied) = check_command(&app, webview.label(), invoke.message.command()) {
+            debug_log(&format!(
+                "[permissions] 拒绝调用 {} (窗口 {}): {}",
+                invoke.message.command(),
+                webview.label(),
+                denied.message
+            ));
+            if denied.code == "window_not_allowed" || denied.code == "command_not_declared" {
+                audit::record(
+                    &app,
+                    "permissions.window_denied",
+                    false,
+                    serde_json::json!({
+                        "window": webview.label(),
+                        "command": invoke.message.command(),
+                    }),
+                );
+            }
+            invoke.resolver.reject(denied);
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// main.rs 中注册的全部命令
+    fn registered_commands() -> Vec<&'static str> {
+        let source = include_str!("main.rs");
+        let start = source
+            .find("generate_handler![")
+            .expect("generate_handler! not found")
+            + "generate_handler![".len();
+        let end = start
+            + source[start..]
+                .find(']')
+                .expect("unterminated handler list");
+        source[start..end]
+            .split(',')
+            .map(|c| c.trim())
+            .filter(|c| !c.is_empty())
+            .map(|c| c.rsplit("::").next().unwrap_or(c))
+            .collect()
+    }
+
+    #[test]
+    fn every_registered_command_is_declared() {
+        let commands = registered_commands();
+        assert!(commands.len() > 100);
+        for command in commands {
+            assert!(is_declared(command), "command {} is not declared", command);
+        }
+    }
+
+    #[test]
+    fn commands_are_declared_once() {
+        for command in CAPABILITY_FREE_COMMANDS {
+            assert!(
+                required_capability(command).is_none(),
+                "{} is declared in both tables",
+                command
+            );
+        }
+        let mut seen = std::collections::HashSet::new();
+        for (command, _) in COMMAND_PERMISSIONS {
+            assert!(seen.insert(*command), "{} is declared twice", command);
+        }
+    }
+
+    #[test]
+    fn undeclared_commands_are_unknown() {
+        assert!(!is_declared("plugin_command_that_does_not_exist"));
+        assert!(is_declared("run_command"));
+        assert!(is_declared("get_settings"));
+    }
+}
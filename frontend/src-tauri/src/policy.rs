@@ -0,0 +1,220 @@
+// ============================================================================
+// 企业托管策略
+// ============================================================================
+//
+// 管理员通过 MDM / 组策略下发的只读策略文件，可锁定部分设置：
+// - macOS: /Library/Managed Preferences/com.zenflux.agent.plist（MDM 配置描述文件），
+//   其次 /Library/Application Support/com.zenflux.agent/policy.json
+// - Windows: %ProgramData%\com.zenflux.agent\policy.json
+// - Linux: /etc/xiaodazi/policy.json
+// 策略在启动时读取一次，叠加在用户设置之上；被锁定的设置项不能在应用内修改。
+
+use crate::{debug_log, settings};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 安全模式下关闭的能力（可修改系统状态或执行任意代码）
+pub const SAFE_MODE_DISABLED_CAPABILITIES: &[&str] = &[
+    "system.run",
+    "system.processes",
+    "system.apps",
+    "canvas.eval",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ManagedPolicy {
+    /// 禁止执行 Shell 命令（system.run）
+    pub disable_run_command: bool,
+    /// 强制安全模式（关闭 SAFE_MODE_DISABLED_CAPABILITIES）
+    pub safe_mode: bool,
+    /// 固定更新清单地址（如内部镜像或指定渠道的 latest.json）
+    pub update_manifest_url: Option<String>,
+    /// 非空时，读取本地文件的命令（打印、OCR 等）只允许访问这些目录下的文件
+    pub allowed_paths: Vec<String>,
+    /// 强制的设置值（键为 AppSettings 字段名），这些设置项被锁定
+    pub settings: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectivePolicy {
+    /// 是否存在托管策略
+    pub managed: bool,
+    /// 策略文件路径
+    pub source: Option<String>,
+    pub policy: ManagedPolicy,
+    /// 被锁定、不能在应用内修改的设置项
+    pub locked_settings: Vec<String>,
+    /// 被策略关闭的能力
+    pub disabled_capabilities: Vec<String>,
+}
+
+#[cfg(target_os = "macos")]
+fn read_managed_plist(path: &Path) -> Option<String> {
+    let output = std::process::Command::new("plutil")
+        .args(["-convert", "json", "-o", "-"])
+        .arg(path)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 按平台查找并读取策略文件，返回 (路径, 内容)
+fn read_policy_file() -> Option<(PathBuf, String)> {
+    #[cfg(target_os = "macos")]
+    {
+        let plist = PathBuf::from("/Library/Managed Preferences")
+            .join(format!("{}.plist", settings::APP_IDENTIFIER));
+        if let Some(content) = plist
+            .is_file()
+            .then(|| read_managed_plist(&plist))
+            .flatten()
+        {
+            return Some((plist, content));
+        }
+    }
+
+    let path = if cfg!(target_os = "macos") {
+        PathBuf::from("/Library/Application Support")
+            .join(settings::APP_IDENTIFIER)
+            .join("policy.json")
+    } else if cfg!(target_os = "windows") {
+        PathBuf::from(
+            std::env::var("ProgramData").unwrap_or_else(|_| r"C:\ProgramData".to_string()),
+        )
+        .join(settings::APP_IDENTIFIER)
+        .join("policy.json")
+    } else {
+        PathBuf::from("/etc/xiaodazi/policy.json")
+    };
+    let content = std::fs::read_to_string(&path).ok()?;
+    Some((path, content))
+}
+
+fn load() -> Option<(PathBuf, ManagedPolicy)> {
+    let (path, content) = read_policy_file()?;
+    match serde_json::from_str::<ManagedPolicy>(&content) {
+        Ok(policy) => {
+            debug_log(&format!("[policy] 已加载托管策略: {}", path.display()));
+            Some((path, policy))
+        }
+        Err(e) => {
+            // 策略文件损坏时不静默忽略限制：按最严格的安全模式处理
+            debug_log(&format!(
+                "[policy] 解析托管策略失败，启用安全模式: {} ({})",
+                path.display(),
+                e
+            ));
+            Some((
+                path,
+                ManagedPolicy {
+                    disable_run_command: true,
+                    safe_mode: true,
+                    ..Default::default()
+                },
+            ))
+        }
+    }
+}
+
+/// 当前托管策略（启动后首次调用时读取）
+fn managed() -> Option<&'static (PathBuf, ManagedPolicy)> {
+    static POLICY: OnceLock<Option<(PathBuf, ManagedPolicy)>> = OnceLock::new();
+    POLICY.get_or_init(load).as_ref()
+}
+
+/// 当前策略（无托管策略时为默认值，不做任何限制）
+pub fn current() -> ManagedPolicy {
+    managed().map(|(_, p)| p.clone()).unwrap_or_default()
+}
+
+/// 被策略关闭的能力
+pub fn disabled_capabilities(policy: &ManagedPolicy) -> Vec<String> {
+    if policy.safe_mode {
+        SAFE_MODE_DISABLED_CAPABILITIES
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    } else if policy.disable_run_command {
+        vec!["system.run".to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// 设置项是否被策略锁定
+pub fn is_locked_setting(key: &str) -> bool {
+    managed()
+        .is_some_and(|(_, p)| p.settings.contains_key(key) || (key == "safe_mode" && p.safe_mode))
+}
+
+/// 把策略叠加到用户设置上
+pub fn apply(settings: &mut settings::AppSettings) {
+    let Some((_, policy)) = managed() else {
+        return;
+    };
+    if !policy.settings.is_empty() {
+        let forced = serde_json::to_value(&*settings)
+            .ok()
+            .and_then(|mut merged| {
+                merged.as_object_mut()?.extend(policy.settings.clone());
+                serde_json::from_value::<settings::AppSettings>(merged).ok()
+            });
+        match forced {
+            Some(forced) => *settings = forced,
+            None => debug_log("[policy] 托管策略中的设置值无效，已忽略"),
+        }
+    }
+    if policy.safe_mode {
+        settings.safe_mode = true;
+    }
+    for capability in disabled_capabilities(policy) {
+        if !settings.disabled_capabilities.contains(&capability) {
+            settings.disabled_capabilities.push(capability);
+        }
+    }
+}
+
+/// 检查路径是否在策略允许的目录内（未配置 allowed_paths 时不限制）
+pub fn check_path(path: &Path) -> Result<(), String> {
+    let policy = current();
+    if policy.allowed_paths.is_empty() {
+        return Ok(());
+    }
+    let target = std::fs::canonicalize(path).map_err(|e| format!("无法读取文件: {}", e))?;
+    let allowed = policy.allowed_paths.iter().any(|dir| {
+        let dir = match dir.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|h| h.join(rest)),
+            None => Some(PathBuf::from(dir)),
+        };
+        dir.and_then(|d| std::fs::canonicalize(d).ok())
+            .is_some_and(|d| target.starts_with(d))
+    });
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!("Path not allowed by policy: {}", path.display()))
+    }
+}
+
+/// 获取生效的托管策略（供设置界面标记被锁定的选项）
+#[tauri::command]
+pub async fn get_effective_policy() -> Result<EffectivePolicy, String> {
+    let policy = current();
+    Ok(EffectivePolicy {
+        managed: managed().is_some(),
+        source: managed().map(|(path, _)| path.display().to_string()),
+        locked_settings: policy
+            .settings
+            .keys()
+            .cloned()
+            .chain(policy.safe_mode.then(|| "safe_mode".to_string()))
+            .collect(),
+        disabled_capabilities: disabled_capabilities(&policy),
+        policy,
+    })
+}
@@ -0,0 +1,313 @@
+// ============================================================================
+// Sidecar 端口选择
+// ============================================================================
+//
+// 被占用的端口不一定是别的应用：上次异常退出残留的本应用后端也会占着端口。
+// 选择端口时探测占用者的 /identity：
+// - 同一数据目录的 ZenFlux 后端 → 直接接管，不再启动重复的 sidecar
+// - 其他进程 → 跳过，继续扫描
+// 首选范围全部不可用时，再在更大的备用范围内随机尝试；仍失败则报告端口冲突
+// （被占用的端口及占用进程），不再带着必然失败的端口去启动 sidecar。
+// 选择结果保存在 PortSelection 中，供前端通过 get_port_selection 展示。
+
+use crate::debug_log;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 探测占用者身份的超时
+const IDENTITY_TIMEOUT: Duration = Duration::from_millis(800);
+
+/// 首选范围用尽后的备用端口范围
+const FALLBACK_PORT_MIN: u16 = 20000;
+const FALLBACK_PORT_MAX: u16 = 40000;
+
+/// 备用范围内随机尝试的次数
+const FALLBACK_ATTEMPTS: usize = 20;
+
+/// 端口占用情况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PortOccupant {
+    /// 本应用残留的后端（同一数据目录）
+    OwnBackend { pid: Option<u32>, version: String },
+    /// 其他 ZenFlux 实例（不同数据目录）
+    OtherBackend { data_dir: String },
+    /// 其他应用
+    OtherApp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedPort {
+    pub port: u16,
+    pub occupant: PortOccupant,
+    /// 占用进程（仅在端口冲突时查询）
+    pub owner_pid: Option<u32>,
+    pub owner_name: Option<String>,
+}
+
+/// 所有候选端口都不可用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortConflict {
+    pub blocked: Vec<SkippedPort>,
+    /// 备用范围内尝试过的端口数
+    pub fallback_attempts: usize,
+}
+
+/// 端口选择结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortSelection {
+    pub port: u16,
+    /// 是否接管了残留的后端（无需启动新的 sidecar）
+    pub adopted: bool,
+    /// 接管的后端 PID
+    pub adopted_pid: Option<u32>,
+    /// 扫描时跳过的端口及占用者
+    pub skipped: Vec<SkippedPort>,
+    /// 端口冲突（Some 表示没有可用端口，sidecar 未启动）
+    pub conflict: Option<PortConflict>,
+}
+
+impl PortSelection {
+    pub fn fixed(port: u16) -> Self {
+        Self {
+            port,
+            adopted: false,
+            adopted_pid: None,
+            skipped: Vec::new(),
+            conflict: None,
+        }
+    }
+
+    /// 没有可用端口时的结果（sidecar 不会启动）
+    pub fn conflicted(port: u16, conflict: PortConflict) -> Self {
+        Self {
+            port,
+            adopted: false,
+            adopted_pid: None,
+            skipped: conflict.blocked.clone(),
+            conflict: Some(conflict),
+        }
+    }
+}
+
+/// 端口在 127.0.0.1 上是否可绑定
+pub fn port_is_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// 比较两个数据目录是否相同（尽量规范化路径）
+fn same_dir(a: &str, b: &Path) -> bool {
+    let a = Path::new(a);
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(x), Ok(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+/// 探测占用端口的进程身份
+pub async fn identify_occupant(port: u16, data_dir: &Path) -> PortOccupant {
+    let url = format!("http://127.0.0.1:{}/identity", port);
+    let resp = crate::http::client()
+        .get(&url)
+        .timeout(IDENTITY_TIMEOUT)
+        .send()
+        .await;
+    let body: serde_json::Value = match resp {
+        Ok(r) if r.status().is_success() => r.json().await.unwrap_or_default(),
+        _ => return PortOccupant::OtherApp,
+    };
+    if body.get("app").and_then(|v| v.as_str()) != Some("zenflux") {
+        return PortOccupant::OtherApp;
+    }
+
+    let their_dir = body
+        .get("data_dir")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    if same_dir(&their_dir, data_dir) {
+        PortOccupant::OwnBackend {
+            pid: body.get("pid").and_then(|v| v.as_u64()).map(|p| p as u32),
+            version: body
+                .get("version")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        }
+    } else {
+        PortOccupant::OtherBackend {
+            data_dir: their_dir,
+        }
+    }
+}
+
+/// 依次检查候选端口：空闲则使用；被本应用残留后端占用则接管；否则跳过
+async fn select_port_async(
+    candidates: Vec<u16>,
+    data_dir: &Path,
+) -> Result<PortSelection, Vec<SkippedPort>> {
+    let mut skipped = Vec::new();
+    for port in candidates {
+        if port_is_free(port) {
+            return Ok(PortSelection {
+                port,
+                adopted: false,
+                adopted_pid: None,
+                skipped,
+                conflict: None,
+            });
+        }
+        let occupant = identify_occupant(port, data_dir).await;
+        if let PortOccupant::OwnBackend { pid, version } = &occupant {
+            debug_log(&format!(
+                "[sidecar] 端口 {} 上是本应用残留的后端 (pid={:?}, version={})，直接接管",
+                port, pid, version
+            ));
+            return Ok(PortSelection {
+                port,
+                adopted: true,
+                adopted_pid: *pid,
+                skipped,
+                conflict: None,
+            });
+        }
+        debug_log(&format!("[sidecar] 端口 {} 被占用: {:?}", port, occupant));
+        skipped.push(SkippedPort {
+            port,
+            occupant,
+            owner_pid: None,
+            owner_name: None,
+        });
+    }
+    Err(skipped)
+}
+
+/// 备用范围内的随机端口
+fn random_fallback_ports(count: usize) -> Vec<u16> {
+    let mut seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(1)
+        | 1;
+    let span = (FALLBACK_PORT_MAX - FALLBACK_PORT_MIN) as u64;
+    (0..count)
+        .map(|_| {
+            // xorshift64
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            FALLBACK_PORT_MIN + (seed % span) as u16
+        })
+        .collect()
+}
+
+/// 查询监听该端口的进程 PID
+#[cfg(unix)]
+fn port_owner_pid(port: u16) -> Option<u32> {
+    let output = std::process::Command::new("lsof")
+        .args(["-nP", "-t", &format!("-iTCP:{}", port), "-sTCP:LISTEN"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .and_then(|l| l.trim().parse().ok())
+}
+
+#[cfg(windows)]
+fn port_owner_pid(port: u16) -> Option<u32> {
+    let output = std::process::Command::new("netstat")
+        .args(["-ano", "-p", "TCP"])
+        .output()
+        .ok()?;
+    let suffix = format!(":{}", port);
+    String::from_utf8_lossy(&output.stdout).lines().find_map(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() >= 5 && cols[1].ends_with(&suffix) && cols[3] == "LISTENING" {
+            cols[4].parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn port_owner_pid(_port: u16) -> Option<u32> {
+    None
+}
+
+/// 补全被占用端口的占用进程信息
+fn fill_owners(blocked: &mut [SkippedPort]) {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes();
+    for entry in blocked.iter_mut() {
+        entry.owner_pid = port_owner_pid(entry.port);
+        entry.owner_name = entry
+            .owner_pid
+            .and_then(|pid| sys.process(sysinfo::Pid::from_u32(pid)))
+            .map(|p| p.name().to_string());
+    }
+}
+
+/// 选择 sidecar 端口（同步入口，在创建 Tauri 应用之前调用）
+///
+/// 依次尝试 `last_port`、preferred 起的 range 个端口、备用范围内的随机端口。
+/// 全部不可用时返回 PortConflict（含被占用端口及占用进程）。
+pub fn select_sidecar_port(
+    last_port: Option<u16>,
+    preferred: u16,
+    range: u16,
+    data_dir: &Path,
+) -> Result<PortSelection, PortConflict> {
+    let mut candidates: Vec<u16> = last_port.into_iter().collect();
+    candidates.extend((preferred..preferred.saturating_add(range)).filter(|p| Some(*p) != last_port));
+
+    let mut blocked =
+        match tauri::async_runtime::block_on(select_port_async(candidates, data_dir)) {
+            Ok(selection) => return Ok(selection),
+            Err(blocked) => blocked,
+        };
+
+    debug_log(&format!(
+        "[sidecar] 端口 {}..{} 全部被占用，尝试备用范围 {}..{}",
+        preferred,
+        preferred.saturating_add(range),
+        FALLBACK_PORT_MIN,
+        FALLBACK_PORT_MAX
+    ));
+    if let Some(port) = random_fallback_ports(FALLBACK_ATTEMPTS)
+        .into_iter()
+        .find(|p| port_is_free(*p))
+    {
+        debug_log(&format!("[sidecar] 使用备用端口 {}", port));
+        return Ok(PortSelection {
+            port,
+            adopted: false,
+            adopted_pid: None,
+            skipped: blocked,
+            conflict: None,
+        });
+    }
+
+    fill_owners(&mut blocked);
+    debug_log(&format!(
+        "[sidecar] 没有可用端口: {:?}",
+        blocked
+            .iter()
+            .map(|b| (b.port, b.owner_name.clone()))
+            .collect::<Vec<_>>()
+    ));
+    Err(PortConflict {
+        blocked,
+        fallback_attempts: FALLBACK_ATTEMPTS,
+    })
+}
+
+/// 获取本次启动的端口选择结果（是否接管了残留后端、跳过了哪些端口）
+#[tauri::command]
+pub async fn get_port_selection(
+    state: tauri::State<'_, PortSelection>,
+) -> Result<PortSelection, String> {
+    Ok(state.inner().clone())
+}
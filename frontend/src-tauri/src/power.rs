@@ -0,0 +1,397 @@
+// ============================================================================
+// 电源管理：阻止系统休眠、休眠唤醒检测、供电状态
+// ============================================================================
+//
+// 每个 prevent_sleep 句柄对应一个持有电源断言的子进程：
+// - macOS: caffeinate（-w 绑定本进程 pid，应用崩溃时也会自动释放）
+// - Windows: PowerShell 调用 SetThreadExecutionState 并保持运行
+// - Linux: systemd-inhibit
+// 释放句柄即终止对应子进程。
+
+use crate::debug_log;
+use std::collections::HashMap;
+use std::process::{Child, Command as SysCommand, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+/// 单个阻止休眠断言
+struct SleepAssertion {
+    reason: String,
+    child: Child,
+}
+
+/// 当前持有的阻止休眠断言（句柄 → 断言）
+#[derive(Default)]
+pub struct SleepGuards {
+    assertions: HashMap<String, SleepAssertion>,
+}
+
+/// 构造持有电源断言的子进程命令
+#[cfg(target_os = "macos")]
+fn assertion_command(_reason: &str) -> Result<SysCommand, String> {
+    let mut cmd = SysCommand::new("caffeinate");
+    cmd.args(["-i", "-m", "-w", &std::process::id().to_string()]);
+    Ok(cmd)
+}
+
+#[cfg(target_os = "windows")]
+fn assertion_command(_reason: &str) -> Result<SysCommand, String> {
+    // ES_CONTINUOUS | ES_SYSTEM_REQUIRED
+    let script = "Add-Type -Namespace Z -Name P -MemberDefinition '[DllImport(\"kernel32.dll\")] public static extern uint SetThreadExecutionState(uint f);'; \
+                  [Z.P]::SetThreadExecutionState(0x80000001) | Out-Null; \
+                  while ($true) { Start-Sleep -Seconds 3600 }";
+    let mut cmd = SysCommand::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+    Ok(cmd)
+}
+
+#[cfg(target_os = "linux")]
+fn assertion_command(reason: &str) -> Result<SysCommand, String> {
+    let mut cmd = SysCommand::new("systemd-inhibit");
+    cmd.args([
+        "--what=idle:sleep",
+        "--who=xiaodazi",
+        &format!("--why={}", reason),
+        "sleep",
+        "infinity",
+    ]);
+    Ok(cmd)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn assertion_command(_reason: &str) -> Result<SysCommand, String> {
+    Err("Prevent sleep not supported on this platform".to_string())
+}
+
+/// 启动持有电源断言的子进程
+fn spawn_assertion_process(reason: &str) -> Result<Child, String> {
+    assertion_command(reason)?
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("阻止休眠失败: {}", e))
+}
+
+/// 释放指定句柄，返回是否存在
+fn release(app: &tauri::AppHandle, handle: &str) -> bool {
+    let state = app.state::<Mutex<SleepGuards>>();
+    let removed = match state.lock() {
+        Ok(mut guard) => guard.assertions.remove(handle),
+        Err(_) => None,
+    };
+    match removed {
+        Some(mut assertion) => {
+            let _ = assertion.child.kill();
+            let _ = assertion.child.wait();
+            debug_log(&format!(
+                "[power] 已释放阻止休眠 (handle={}, reason={})",
+                handle, assertion.reason
+            ));
+            true
+        }
+        None => false,
+    }
+}
+
+/// 释放全部阻止休眠断言（应用退出时调用）
+pub fn release_all(app: &tauri::AppHandle) {
+    let state = app.state::<Mutex<SleepGuards>>();
+    let drained: Vec<SleepAssertion> = match state.lock() {
+        Ok(mut guard) => guard.assertions.drain().map(|(_, a)| a).collect(),
+        Err(_) => return,
+    };
+    for mut assertion in drained {
+        let _ = assertion.child.kill();
+        let _ = assertion.child.wait();
+    }
+}
+
+/// 阻止系统休眠，返回句柄
+///
+/// `max_duration_secs` 到期后自动释放，避免任务异常结束后断言泄漏。
+#[tauri::command]
+pub async fn prevent_sleep(
+    app: tauri::AppHandle,
+    reason: String,
+    max_duration_secs: Option<u64>,
+) -> Result<String, String> {
+    let child = spawn_assertion_process(&reason)?;
+    let handle = uuid::Uuid::new_v4().to_string();
+
+    debug_log(&format!(
+        "[power] 阻止休眠 (handle={}, reason={})",
+        handle, reason
+    ));
+
+    app.state::<Mutex<SleepGuards>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .assertions
+        .insert(handle.clone(), SleepAssertion { reason, child });
+
+    if let Some(secs) = max_duration_secs {
+        let app_for_timer = app.clone();
+        let handle_for_timer = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(secs)).await;
+            release(&app_for_timer, &handle_for_timer);
+        });
+    }
+
+    Ok(handle)
+}
+
+/// 允许系统休眠（释放 prevent_sleep 返回的句柄）
+#[tauri::command]
+pub async fn allow_sleep(app: tauri::AppHandle, handle: String) -> Result<bool, String> {
+    Ok(release(&app, &handle))
+}
+
+// ============================================================================
+// 休眠/唤醒检测
+// ============================================================================
+//
+// 跨平台方案：后台线程周期性醒来，比较两次醒来之间的墙钟时间差。
+// 系统休眠期间线程不会被调度，唤醒后墙钟跳变远大于轮询间隔即视为发生过休眠。
+
+/// 休眠检测轮询间隔（秒）
+const WAKE_POLL_SECS: u64 = 5;
+
+/// 墙钟跳变超过该阈值（秒）视为系统曾休眠
+const WAKE_GAP_THRESHOLD_SECS: u64 = 30;
+
+/// 启动休眠/唤醒监视线程
+///
+/// 唤醒后重新检查后端健康状态，并发出 `system-resumed` 事件
+/// （前端据此重连 WebSocket 并刷新页面数据）。
+pub fn start_wake_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut last = std::time::SystemTime::now();
+        loop {
+            std::thread::sleep(Duration::from_secs(WAKE_POLL_SECS));
+            let now = std::time::SystemTime::now();
+            let gap = now.duration_since(last).unwrap_or_default();
+            last = now;
+
+            if gap.as_secs() < WAKE_GAP_THRESHOLD_SECS {
+                continue;
+            }
+
+            debug_log(&format!("[power] 检测到系统唤醒 (休眠约 {}s)", gap.as_secs()));
+            on_system_resumed(&app, gap.as_secs());
+        }
+    });
+}
+
+/// 系统唤醒后的处理：重新检查后端并通知前端
+fn on_system_resumed(app: &tauri::AppHandle, slept_secs: u64) {
+    use tauri::Emitter;
+
+    let info = app.state::<crate::BackendState>().info();
+
+    let healthy = tauri::async_runtime::block_on(crate::check_health(
+        info.port,
+        info.socket_path,
+        Duration::from_secs(3),
+    ));
+    debug_log(&format!(
+        "[power] 唤醒后健康检查: {}",
+        if healthy { "正常" } else { "失败" }
+    ));
+
+    crate::set_backend_ready(app, healthy);
+    let _ = app.emit(
+        "system-resumed",
+        serde_json::json!({
+            "slept_secs": slept_secs,
+            "backend_healthy": healthy,
+        }),
+    );
+}
+
+// ============================================================================
+// 供电状态：电池电量与供电来源
+// ============================================================================
+//
+// 周期性读取供电来源与电量（macOS: pmset，Windows: Win32_Battery，Linux: /sys/class/power_supply），
+// 供电来源变化时发出 `power-source-changed`，使用电池且电量低于阈值时发出一次 `battery-low`。
+// 设置 pause_heavy_tasks_on_battery 开启时，电池低电量期间 heavy_tasks_paused 为 true，
+// Agent 据此推迟录屏、本地模型推理等耗电任务。
+
+/// 供电状态轮询间隔（秒）
+const POWER_POLL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PowerState {
+    pub source: PowerSource,
+    /// 是否有电池（台式机为 false）
+    pub has_battery: bool,
+    /// 电量百分比（无电池时为 None）
+    pub battery_percent: Option<u8>,
+    pub charging: Option<bool>,
+    /// 低电量阈值（设置 battery_low_percent）
+    pub low_threshold_percent: u8,
+    /// 使用电池且电量不高于阈值
+    pub battery_low: bool,
+    /// 是否应推迟耗电任务（设置开启且电池低电量）
+    pub heavy_tasks_paused: bool,
+}
+
+/// 平台读取结果：(供电来源, 电量, 是否充电)
+type RawPowerState = (PowerSource, Option<u8>, Option<bool>);
+
+#[cfg(target_os = "macos")]
+fn read_power_source() -> RawPowerState {
+    // 输出示例：
+    // Now drawing from 'Battery Power'
+    //  -InternalBattery-0 (id=1234)	85%; discharging; 4:12 remaining present: true
+    let Ok(output) = SysCommand::new("pmset").args(["-g", "batt"]).output() else {
+        return (PowerSource::Unknown, None, None);
+    };
+    let text = String::from_utf8_lossy(&output.stdout);
+    let source = if text.contains("'AC Power'") {
+        PowerSource::Ac
+    } else if text.contains("'Battery Power'") {
+        PowerSource::Battery
+    } else {
+        PowerSource::Unknown
+    };
+    let battery_line = text.lines().find(|l| l.contains("InternalBattery"));
+    let percent = battery_line
+        .and_then(|l| l.split('%').next())
+        .and_then(|s| s.rsplit(|c: char| c.is_whitespace()).next())
+        .and_then(|s| s.parse().ok());
+    let charging = battery_line.map(|l| l.contains("; charging") || l.contains("; charged"));
+    (source, percent, charging)
+}
+
+#[cfg(target_os = "windows")]
+fn read_power_source() -> RawPowerState {
+    // BatteryStatus: 1/4/5 放电中，2 接通电源，3 已充满，6-9 充电中
+    let script = "$b = Get-CimInstance Win32_Battery | Select-Object -First 1; \
+                  if ($b) { \"$($b.EstimatedChargeRemaining),$($b.BatteryStatus)\" }";
+    let Ok(output) = SysCommand::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()
+    else {
+        return (PowerSource::Unknown, None, None);
+    };
+    let text = String::from_utf8_lossy(&output.stdout);
+    let Some((percent, status)) = text.trim().split_once(',') else {
+        // 没有电池：台式机始终接通电源
+        return (PowerSource::Ac, None, None);
+    };
+    let status: u32 = status.trim().parse().unwrap_or(0);
+    let source = match status {
+        1 | 4 | 5 => PowerSource::Battery,
+        0 => PowerSource::Unknown,
+        _ => PowerSource::Ac,
+    };
+    let charging = Some(matches!(status, 6..=9));
+    (source, percent.trim().parse().ok(), charging)
+}
+
+#[cfg(target_os = "linux")]
+fn read_power_source() -> RawPowerState {
+    let read = |path: &std::path::Path| {
+        std::fs::read_to_string(path)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return (PowerSource::Unknown, None, None);
+    };
+    let (mut mains_online, mut percent, mut charging) = (None, None, None);
+    for dir in entries.flatten().map(|e| e.path()) {
+        match read(&dir.join("type")).as_str() {
+            "Mains" => mains_online = Some(read(&dir.join("online")) == "1"),
+            "Battery" if percent.is_none() => {
+                percent = read(&dir.join("capacity")).parse().ok();
+                charging = Some(matches!(
+                    read(&dir.join("status")).as_str(),
+                    "Charging" | "Full"
+                ));
+            }
+            _ => {}
+        }
+    }
+    let source = match (mains_online, percent) {
+        (Some(true), _) | (None, None) => PowerSource::Ac,
+        (Some(false), _) => PowerSource::Battery,
+        (None, Some(_)) if charging == Some(true) => PowerSource::Ac,
+        (None, Some(_)) => PowerSource::Battery,
+    };
+    (source, percent, charging)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn read_power_source() -> RawPowerState {
+    (PowerSource::Unknown, None, None)
+}
+
+/// 读取当前供电状态并按设置计算是否低电量
+fn read_power_state(app: &tauri::AppHandle) -> PowerState {
+    let (source, battery_percent, charging) = read_power_source();
+    let settings = crate::settings::current(app);
+    let threshold = settings.battery_low_percent;
+    let battery_low =
+        source == PowerSource::Battery && battery_percent.is_some_and(|p| p <= threshold);
+    PowerState {
+        source,
+        has_battery: battery_percent.is_some(),
+        battery_percent,
+        charging,
+        low_threshold_percent: threshold,
+        battery_low,
+        heavy_tasks_paused: settings.pause_heavy_tasks_on_battery && battery_low,
+    }
+}
+
+/// 启动供电状态监视线程
+pub fn start_power_monitor(app: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    std::thread::spawn(move || {
+        let mut last: Option<PowerState> = None;
+        loop {
+            let state = read_power_state(&app);
+            if let Some(prev) = &last {
+                if prev.source != state.source {
+                    debug_log(&format!(
+                        "[power] 供电来源变化: {:?} -> {:?}",
+                        prev.source, state.source
+                    ));
+                    let _ = app.emit("power-source-changed", &state);
+                }
+            }
+            let was_low = last.as_ref().is_some_and(|s| s.battery_low);
+            if state.battery_low && !was_low {
+                debug_log(&format!(
+                    "[power] 电池电量低: {}%",
+                    state.battery_percent.unwrap_or_default()
+                ));
+                let _ = app.emit("battery-low", &state);
+            }
+            last = Some(state);
+            std::thread::sleep(Duration::from_secs(POWER_POLL_SECS));
+        }
+    });
+}
+
+/// 获取当前供电状态
+#[tauri::command]
+pub async fn get_power_state(app: tauri::AppHandle) -> Result<PowerState, String> {
+    tauri::async_runtime::spawn_blocking(move || read_power_state(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
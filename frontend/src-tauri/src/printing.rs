@@ -0,0 +1,332 @@
+// ============================================================================
+// 打印机列表与打印
+// ============================================================================
+//
+// - macOS / Linux: CUPS 命令行（lpstat 列出打印机，lp 提交任务）
+// - Windows: Win32_Printer 列出打印机，通过文件关联的 Print / PrintTo 动作打印
+//   （由关联程序处理，份数以外的选项不生效）
+// 预计页数或文件体积较大时，打印前弹出确认框；所有打印请求写入审计日志。
+
+use crate::{audit, debug_log, i18n, policy};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command as SysCommand;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+/// 预计纸张数（页数 × 份数）超过此值时需确认
+const LARGE_JOB_SHEETS: u64 = 50;
+
+/// 无法估算页数时，按文件体积（× 份数）判断
+const LARGE_JOB_BYTES: u64 = 20 * 1024 * 1024;
+
+/// 单次打印允许的最大份数
+const MAX_COPIES: u32 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrinterInfo {
+    pub name: String,
+    pub is_default: bool,
+    /// idle / printing / disabled / offline / unknown
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrintOptions {
+    pub copies: Option<u32>,
+    /// 双面打印（长边翻页）
+    pub duplex: Option<bool>,
+    pub landscape: Option<bool>,
+    /// 页码范围，如 "1-3,5"
+    pub page_ranges: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrintJob {
+    /// 打印任务 ID（CUPS request id；Windows 无）
+    pub job_id: Option<String>,
+    pub printer: Option<String>,
+    /// 估算页数（仅 PDF）
+    pub estimated_pages: Option<u64>,
+}
+
+fn command_output(cmd: &mut SysCommand) -> Result<String, String> {
+    let output = cmd.output().map_err(|e| format!("打印失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "打印失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(unix)]
+fn list() -> Result<Vec<PrinterInfo>, String> {
+    // 未配置默认打印机时 lpstat -d 返回非零，单独执行
+    let default = SysCommand::new("lpstat")
+        .arg("-d")
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .and_then(|s| s.split_once(':').map(|(_, name)| name.trim().to_string()));
+
+    // LC_ALL=C 保证输出为英文，便于解析：
+    //   printer Office is idle.  enabled since ...
+    //   printer Lab disabled since ... -
+    let out =
+        command_output(SysCommand::new("lpstat").arg("-p").env("LC_ALL", "C")).unwrap_or_default();
+    Ok(out
+        .lines()
+        .filter_map(|line| line.strip_prefix("printer "))
+        .filter_map(|rest| {
+            let (name, state) = rest.split_once(' ')?;
+            let status = if state.starts_with("disabled") {
+                "disabled"
+            } else if state.contains("now printing") {
+                "printing"
+            } else if state.starts_with("is idle") {
+                "idle"
+            } else {
+                "unknown"
+            };
+            Some(PrinterInfo {
+                is_default: default.as_deref() == Some(name),
+                name: name.to_string(),
+                status: status.to_string(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(unix)]
+fn submit(
+    path: &Path,
+    printer: Option<&str>,
+    options: &PrintOptions,
+) -> Result<Option<String>, String> {
+    let mut cmd = SysCommand::new("lp");
+    cmd.env("LC_ALL", "C");
+    if let Some(printer) = printer {
+        cmd.args(["-d", printer]);
+    }
+    if let Some(copies) = options.copies {
+        cmd.args(["-n", &copies.to_string()]);
+    }
+    if let Some(duplex) = options.duplex {
+        let sides = if duplex {
+            "sides=two-sided-long-edge"
+        } else {
+            "sides=one-sided"
+        };
+        cmd.args(["-o", sides]);
+    }
+    if options.landscape == Some(true) {
+        cmd.args(["-o", "landscape"]);
+    }
+    if let Some(ranges) = &options.page_ranges {
+        cmd.args(["-o", &format!("page-ranges={}", ranges)]);
+    }
+    // 输出示例：request id is Office-42 (1 file(s))
+    let out = command_output(cmd.arg("--").arg(path))?;
+    Ok(out
+        .strip_prefix("request id is ")
+        .and_then(|s| s.split_whitespace().next())
+        .map(str::to_string))
+}
+
+#[cfg(windows)]
+fn list() -> Result<Vec<PrinterInfo>, String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Win32Printer {
+        name: String,
+        default: Option<bool>,
+        work_offline: Option<bool>,
+        printer_status: Option<u16>,
+    }
+
+    let out = command_output(SysCommand::new("powershell").args([
+        "-NoProfile",
+        "-NonInteractive",
+        "-Command",
+        "@(Get-CimInstance Win32_Printer | Select-Object Name, Default, WorkOffline, PrinterStatus) | ConvertTo-Json -Compress",
+    ]))?;
+    if out.is_empty() {
+        return Ok(Vec::new());
+    }
+    let printers: Vec<Win32Printer> =
+        serde_json::from_str(&out).map_err(|e| format!("解析打印机列表失败: {}", e))?;
+    Ok(printers
+        .into_iter()
+        .map(|p| {
+            // PrinterStatus: 3 = Idle, 4 = Printing, 7 = Offline
+            let status = match (p.work_offline, p.printer_status) {
+                (Some(true), _) | (_, Some(7)) => "offline",
+                (_, Some(3)) => "idle",
+                (_, Some(4)) => "printing",
+                _ => "unknown",
+            };
+            PrinterInfo {
+                name: p.name,
+                is_default: p.default.unwrap_or(false),
+                status: status.to_string(),
+            }
+        })
+        .collect())
+}
+
+#[cfg(windows)]
+fn submit(
+    path: &Path,
+    printer: Option<&str>,
+    options: &PrintOptions,
+) -> Result<Option<String>, String> {
+    // 路径与打印机名通过环境变量传入，避免拼接到脚本中
+    let script = "$file = $env:XDZ_PRINT_FILE; $printer = $env:XDZ_PRINTER; \
+                  for ($i = 0; $i -lt [int]$env:XDZ_PRINT_COPIES; $i++) { \
+                    if ($printer) { Start-Process -FilePath $file -Verb PrintTo -ArgumentList ('\"' + $printer + '\"') -Wait } \
+                    else { Start-Process -FilePath $file -Verb Print -Wait } \
+                  }";
+    command_output(
+        SysCommand::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", script])
+            .env("XDZ_PRINT_FILE", path)
+            .env("XDZ_PRINTER", printer.unwrap_or_default())
+            .env("XDZ_PRINT_COPIES", options.copies.unwrap_or(1).to_string()),
+    )?;
+    Ok(None)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn list() -> Result<Vec<PrinterInfo>, String> {
+    Err("Printing not supported on this platform".to_string())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn submit(
+    _path: &Path,
+    _printer: Option<&str>,
+    _options: &PrintOptions,
+) -> Result<Option<String>, String> {
+    Err("Printing not supported on this platform".to_string())
+}
+
+/// 估算 PDF 页数（统计页面对象；对象流压缩的 PDF 可能无法识别）
+fn estimate_pdf_pages(path: &Path) -> Option<u64> {
+    if !path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+    {
+        return None;
+    }
+    let data = std::fs::read(path).ok()?;
+    let pages = [&b"/Type /Page"[..], &b"/Type/Page"[..]]
+        .iter()
+        .map(|pattern| {
+            data.windows(pattern.len() + 1)
+                .filter(|w| w.starts_with(pattern) && w[pattern.len()] != b's')
+                .count() as u64
+        })
+        .sum::<u64>();
+    (pages > 0).then_some(pages)
+}
+
+/// 判断是否为需要确认的大任务
+fn is_large_job(path: &Path, estimated_pages: Option<u64>, copies: u32) -> bool {
+    match estimated_pages {
+        Some(pages) => pages.saturating_mul(copies as u64) > LARGE_JOB_SHEETS,
+        None => std::fs::metadata(path)
+            .map(|m| m.len().saturating_mul(copies as u64) > LARGE_JOB_BYTES)
+            .unwrap_or(false),
+    }
+}
+
+/// 列出系统打印机
+#[tauri::command]
+pub async fn list_printers() -> Result<Vec<PrinterInfo>, String> {
+    tauri::async_runtime::spawn_blocking(list)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 打印文件
+///
+/// - `printer` 为空时使用系统默认打印机
+/// - 预计页数（PDF）或文件体积较大时需用户在对话框中确认
+/// - 所有请求（包括被拒绝的）都写入审计日志
+#[tauri::command]
+pub async fn print_file(
+    app: tauri::AppHandle,
+    path: String,
+    printer: Option<String>,
+    options: Option<PrintOptions>,
+) -> Result<PrintJob, String> {
+    let options = options.unwrap_or_default();
+    let copies = options.copies.unwrap_or(1);
+    if copies == 0 || copies > MAX_COPIES {
+        return Err(format!("Copies must be between 1 and {}", MAX_COPIES));
+    }
+    let file = Path::new(&path);
+    if !file.is_file() {
+        return Err(format!("文件不存在: {}", path));
+    }
+    policy::check_path(file)?;
+
+    let app_for_print = app.clone();
+    let path_for_print = path.clone();
+    let printer_for_print = printer.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<PrintJob, String> {
+        let file = Path::new(&path_for_print);
+        let estimated_pages = estimate_pdf_pages(file);
+
+        if is_large_job(file, estimated_pages, copies) {
+            let name = file
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let pages = estimated_pages
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "?".to_string());
+            let confirmed = app_for_print
+                .dialog()
+                .message(i18n::tf("dialog.print.body", &[&name, &pages, &copies]))
+                .title(i18n::t("dialog.print.title"))
+                .kind(MessageDialogKind::Warning)
+                .buttons(MessageDialogButtons::OkCancel)
+                .blocking_show();
+            if !confirmed {
+                return Err(format!("用户取消打印: {}", name));
+            }
+        }
+
+        let job_id = submit(file, printer_for_print.as_deref(), &options)?;
+        Ok(PrintJob {
+            job_id,
+            printer: printer_for_print,
+            estimated_pages,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    audit::record(
+        &app,
+        "print.file",
+        result.is_ok(),
+        serde_json::json!({
+            "path": &path,
+            "printer": &printer,
+            "copies": copies,
+            "error": result.as_ref().err(),
+        }),
+    );
+    match &result {
+        Ok(job) => debug_log(&format!(
+            "[printing] 已提交打印任务 {:?}: {}",
+            job.job_id, path
+        )),
+        Err(e) => debug_log(&format!("[printing] 打印失败: {}", e)),
+    }
+    result
+}
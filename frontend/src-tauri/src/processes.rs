@@ -0,0 +1,204 @@
+// ============================================================================
+// 进程列表、查询与终止
+// ============================================================================
+
+use crate::{audit, debug_log, i18n};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, Process, Signal, System};
+use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub parent_pid: Option<u32>,
+    pub name: String,
+    /// CPU 占用（百分比，多核可超过 100）
+    pub cpu_usage: f32,
+    /// 常驻内存（字节）
+    pub memory: u64,
+    pub exe: Option<String>,
+    pub cmd: Vec<String>,
+    pub status: String,
+    /// 启动时间（Unix 秒）
+    pub start_time: u64,
+}
+
+impl ProcessInfo {
+    fn from_process(pid: Pid, p: &Process) -> Self {
+        ProcessInfo {
+            pid: pid.as_u32(),
+            parent_pid: p.parent().map(|pp| pp.as_u32()),
+            name: p.name().to_string(),
+            cpu_usage: p.cpu_usage(),
+            memory: p.memory(),
+            exe: p.exe().map(|e| e.to_string_lossy().to_string()),
+            cmd: p.cmd().to_vec(),
+            status: p.status().to_string(),
+            start_time: p.start_time(),
+        }
+    }
+}
+
+/// 采样进程信息（CPU 占用需要两次刷新之间的差值）
+pub fn sample_system() -> System {
+    let mut sys = System::new_all();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    sys.refresh_processes();
+    sys
+}
+
+/// 列出进程（可按名称或命令行子串过滤，不区分大小写），按 CPU 占用降序
+#[tauri::command]
+pub async fn list_processes(filter: Option<String>) -> Result<Vec<ProcessInfo>, String> {
+    let sys = tauri::async_runtime::spawn_blocking(sample_system)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let needle = filter.map(|f| f.to_lowercase()).filter(|f| !f.is_empty());
+    let mut list: Vec<ProcessInfo> = sys
+        .processes()
+        .iter()
+        .filter(|(_, p)| match &needle {
+            Some(n) => {
+                p.name().to_lowercase().contains(n)
+                    || p.cmd().join(" ").to_lowercase().contains(n)
+            }
+            None => true,
+        })
+        .map(|(pid, p)| ProcessInfo::from_process(*pid, p))
+        .collect();
+
+    list.sort_by(|a, b| {
+        b.cpu_usage
+            .partial_cmp(&a.cpu_usage)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Ok(list)
+}
+
+/// 获取单个进程信息
+#[tauri::command]
+pub async fn get_process(pid: u32) -> Result<ProcessInfo, String> {
+    let sys = tauri::async_runtime::spawn_blocking(sample_system)
+        .await
+        .map_err(|e| e.to_string())?;
+    let pid = Pid::from_u32(pid);
+    sys.process(pid)
+        .map(|p| ProcessInfo::from_process(pid, p))
+        .ok_or_else(|| format!("进程不存在: {}", pid))
+}
+
+// ============================================================================
+// 终止进程
+// ============================================================================
+
+/// 解析信号名称（"TERM" / "KILL" / "INT" / "HUP"，可带 "SIG" 前缀）
+fn parse_signal(signal: &str) -> Result<Signal, String> {
+    match signal.trim().to_uppercase().trim_start_matches("SIG") {
+        "TERM" => Ok(Signal::Term),
+        "KILL" => Ok(Signal::Kill),
+        "INT" => Ok(Signal::Interrupt),
+        "HUP" => Ok(Signal::Hangup),
+        "QUIT" => Ok(Signal::Quit),
+        other => Err(format!("不支持的信号: {}", other)),
+    }
+}
+
+/// 判断进程是否由本应用（含 run_command 启动的命令）派生
+fn is_descendant_of(sys: &System, pid: Pid, ancestor: Pid) -> bool {
+    let mut current = sys.process(pid).and_then(|p| p.parent());
+    // 防御性上限，避免异常的父子关系成环
+    for _ in 0..64 {
+        match current {
+            Some(p) if p == ancestor => return true,
+            Some(p) => current = sys.process(p).and_then(|pp| pp.parent()),
+            None => return false,
+        }
+    }
+    false
+}
+
+/// 终止进程（带安全限制）
+///
+/// - 仅允许终止当前用户拥有的进程
+/// - 本应用自身与后端 sidecar 默认受保护，需显式传入 `allow_protected`
+/// - 非本应用派生的进程需用户在对话框中确认
+/// - 所有尝试（包括被拒绝的）都写入审计日志
+#[tauri::command]
+pub async fn kill_process(
+    app: tauri::AppHandle,
+    pid: u32,
+    signal: Option<String>,
+    allow_protected: Option<bool>,
+) -> Result<bool, String> {
+    let signal_name = signal.unwrap_or_else(|| "TERM".to_string());
+    let sig = parse_signal(&signal_name)?;
+    let sidecar_pid = app.state::<crate::BackendState>().info().pid;
+
+    let app_for_kill = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<(String, bool), String> {
+        let mut sys = System::new();
+        sys.refresh_processes();
+
+        let target_pid = Pid::from_u32(pid);
+        let own_pid = sysinfo::get_current_pid().map_err(|e| e.to_string())?;
+        let target = sys
+            .process(target_pid)
+            .ok_or_else(|| format!("进程不存在: {}", pid))?;
+        let name = target.name().to_string();
+
+        let own_user = sys.process(own_pid).and_then(|p| p.user_id().cloned());
+        if own_user.is_none() || target.user_id().cloned() != own_user {
+            return Err(format!("只能终止当前用户的进程: {} ({})", name, pid));
+        }
+
+        let is_protected = target_pid == own_pid || Some(pid) == sidecar_pid;
+        if is_protected && !allow_protected.unwrap_or(false) {
+            return Err(format!("受保护的进程，拒绝终止: {} ({})", name, pid));
+        }
+
+        if !is_descendant_of(&sys, target_pid, own_pid) {
+            let confirmed = app_for_kill
+                .dialog()
+                .message(i18n::tf("dialog.kill_process.body", &[&name, &pid]))
+                .title(i18n::t("dialog.kill_process.title"))
+                .kind(MessageDialogKind::Warning)
+                .buttons(MessageDialogButtons::OkCancel)
+                .blocking_show();
+            if !confirmed {
+                return Err(format!("用户取消终止进程: {} ({})", name, pid));
+            }
+        }
+
+        let killed = target.kill_with(sig).unwrap_or_else(|| target.kill());
+        Ok((name, killed))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    match result {
+        Ok((name, killed)) => {
+            audit::record(
+                &app,
+                "process.kill",
+                killed,
+                serde_json::json!({"pid": pid, "name": &name, "signal": &signal_name}),
+            );
+            debug_log(&format!(
+                "[process] 终止进程 {} ({}) signal={}: {}",
+                name, pid, signal_name, killed
+            ));
+            Ok(killed)
+        }
+        Err(e) => {
+            audit::record(
+                &app,
+                "process.kill",
+                false,
+                serde_json::json!({"pid": pid, "signal": &signal_name, "error": &e}),
+            );
+            Err(e)
+        }
+    }
+}
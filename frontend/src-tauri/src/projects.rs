@@ -0,0 +1,270 @@
+// ============================================================================
+// 项目（工作区）登记
+// ============================================================================
+//
+// 用户认可的项目目录持久化到 projects.json，给 Agent 一个明确、由用户控制的操作范围：
+// - 写入项目目录内的文件不再请求审批（见 approvals::approve_write）
+// - 监视项目目录的文件变化，批量发出 `project-files-changed` 事件（忽略 .git 等目录）
+// - run_command 未指定 cwd 且未传入 task_id 时，在默认项目目录中执行
+// 项目目录必须在托管策略允许的范围内（policy::check_path）。
+
+use crate::store::{load_json, save_json};
+use crate::{audit, debug_log, events, policy};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tauri::Manager;
+
+/// 项目持久化文件
+const PROJECTS_FILE: &str = "projects.json";
+
+/// 文件变化事件的合并间隔
+const CHANGE_BATCH_DELAY: Duration = Duration::from_millis(500);
+
+/// 不上报变化的目录名
+const IGNORED_DIRS: &[&str] = &[".git", "node_modules", "target", "__pycache__", ".venv"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    /// 规范化后的绝对路径
+    pub path: String,
+    /// 默认项目（未指定 cwd 的命令在此执行）
+    #[serde(default)]
+    pub default: bool,
+    pub added_at: String,
+}
+
+/// `project-files-changed` 事件负载
+#[derive(Debug, Clone, Serialize)]
+struct FilesChangedPayload {
+    project_id: String,
+    paths: Vec<String>,
+}
+
+/// 已登记的项目与其文件监视器
+#[derive(Default)]
+pub struct ProjectState {
+    projects: Mutex<Vec<Project>>,
+    watchers: Mutex<HashMap<String, notify::RecommendedWatcher>>,
+}
+
+fn save(app: &tauri::AppHandle, projects: &[Project]) -> Result<(), String> {
+    save_json(app, PROJECTS_FILE, &projects)
+}
+
+/// 已登记的项目目录
+pub fn roots(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    app.state::<ProjectState>()
+        .projects
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .map(|p| PathBuf::from(&p.path))
+        .collect()
+}
+
+/// 默认项目目录（未设置时为 None）
+pub fn default_cwd(app: &tauri::AppHandle) -> Option<String> {
+    app.state::<ProjectState>()
+        .projects
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|p| p.default)
+        .map(|p| p.path.clone())
+}
+
+fn is_ignored(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root).is_ok_and(|rel| {
+        rel.components()
+            .any(|c| IGNORED_DIRS.contains(&c.as_os_str().to_string_lossy().as_ref()))
+    })
+}
+
+fn watch_project(
+    app: &tauri::AppHandle,
+    project: &Project,
+) -> Result<notify::RecommendedWatcher, String> {
+    use notify::{RecursiveMode, Watcher};
+
+    let root = PathBuf::from(&project.path);
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<PathBuf>();
+    let watch_root = root.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        if event.kind.is_access() {
+            return;
+        }
+        for path in event.paths {
+            if !is_ignored(&watch_root, &path) {
+                let _ = tx.send(path);
+            }
+        }
+    })
+    .map_err(|e| format!("创建文件监视失败: {}", e))?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| format!("监视目录失败 {}: {}", root.display(), e))?;
+
+    // 监视器 drop 后发送端关闭，合并任务随之结束
+    let app = app.clone();
+    let project_id = project.id.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(first) = rx.recv().await {
+            tokio::time::sleep(CHANGE_BATCH_DELAY).await;
+            let mut paths = BTreeSet::from([first]);
+            while let Ok(path) = rx.try_recv() {
+                paths.insert(path);
+            }
+            events::emit(
+                &app,
+                "project-files-changed",
+                FilesChangedPayload {
+                    project_id: project_id.clone(),
+                    paths: paths
+                        .iter()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .collect(),
+                },
+            );
+        }
+    });
+    Ok(watcher)
+}
+
+fn activate(app: &tauri::AppHandle, project: &Project) {
+    match watch_project(app, project) {
+        Ok(watcher) => {
+            app.state::<ProjectState>()
+                .watchers
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(project.id.clone(), watcher);
+        }
+        Err(e) => debug_log(&format!("[projects] 项目 {} 监视失败: {}", project.id, e)),
+    }
+}
+
+/// 恢复已登记的项目并开始监视
+pub fn start(app: &tauri::AppHandle) {
+    let saved: Vec<Project> = load_json(app, PROJECTS_FILE);
+    if !saved.is_empty() {
+        debug_log(&format!("[projects] 恢复 {} 个项目", saved.len()));
+    }
+    for project in &saved {
+        activate(app, project);
+    }
+    *app.state::<ProjectState>()
+        .projects
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = saved;
+}
+
+/// 登记项目目录（已登记时返回原项目）；第一个项目自动成为默认项目
+#[tauri::command]
+pub async fn add_project(
+    app: tauri::AppHandle,
+    path: String,
+    name: Option<String>,
+) -> Result<Project, String> {
+    let dir = std::fs::canonicalize(&path).map_err(|e| format!("无法访问目录: {}", e))?;
+    if !dir.is_dir() {
+        return Err(format!("不是目录: {}", path));
+    }
+    policy::check_path(&dir)?;
+    let dir = dir.to_string_lossy().to_string();
+
+    let project = {
+        let state = app.state::<ProjectState>();
+        let mut projects = state.projects.lock().map_err(|e| e.to_string())?;
+        if let Some(existing) = projects.iter().find(|p| p.path == dir) {
+            return Ok(existing.clone());
+        }
+        let project = Project {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| {
+                Path::new(&dir)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| dir.clone())
+            }),
+            path: dir,
+            default: projects.is_empty(),
+            added_at: chrono::Local::now().to_rfc3339(),
+        };
+        projects.push(project.clone());
+        save(&app, &projects)?;
+        project
+    };
+    activate(&app, &project);
+    audit::record(
+        &app,
+        "project.add",
+        true,
+        serde_json::json!({ "id": &project.id, "path": &project.path }),
+    );
+    debug_log(&format!("[projects] 已登记项目: {}", project.path));
+    Ok(project)
+}
+
+/// 列出已登记的项目
+#[tauri::command]
+pub async fn list_projects(app: tauri::AppHandle) -> Result<Vec<Project>, String> {
+    let state = app.state::<ProjectState>();
+    let projects = state.projects.lock().map_err(|e| e.to_string())?;
+    Ok(projects.clone())
+}
+
+/// 移除项目（不删除目录）；移除默认项目时由剩下的第一个项目接替
+#[tauri::command]
+pub async fn remove_project(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let removed = {
+        let state = app.state::<ProjectState>();
+        let mut projects = state.projects.lock().map_err(|e| e.to_string())?;
+        let index = projects
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| format!("Project not found: {}", id))?;
+        let removed = projects.remove(index);
+        if removed.default {
+            if let Some(first) = projects.first_mut() {
+                first.default = true;
+            }
+        }
+        save(&app, &projects)?;
+        removed
+    };
+    app.state::<ProjectState>()
+        .watchers
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&id);
+    audit::record(
+        &app,
+        "project.remove",
+        true,
+        serde_json::json!({ "id": &id, "path": &removed.path }),
+    );
+    debug_log(&format!("[projects] 已移除项目: {}", removed.path));
+    Ok(())
+}
+
+/// 设置默认项目
+#[tauri::command]
+pub async fn set_default_project(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let state = app.state::<ProjectState>();
+    let mut projects = state.projects.lock().map_err(|e| e.to_string())?;
+    if !projects.iter().any(|p| p.id == id) {
+        return Err(format!("Project not found: {}", id));
+    }
+    for project in projects.iter_mut() {
+        project.default = project.id == id;
+    }
+    save(&app, &projects)
+}
@@ -0,0 +1,108 @@
+// ============================================================================
+// 后端重启期间的请求排队
+// ============================================================================
+//
+// sidecar 重启（崩溃恢复、后端更新）时，经 Rust 转发的请求（binary.rs、UDS 自定义协议）
+// 不立即失败，而是排队等后端恢复后重试，短暂的重启对前端不可见：
+// - 后端未就绪时等待 backend-ready，就绪后再发送
+// - 请求未到达后端（连接被拒绝、socket 不存在）时稍后重试；请求已发出后的失败不重试，
+//   非幂等请求不会被重复执行
+// - 最多 MAX_QUEUED 个请求同时排队，超出时直接失败；每个请求最多等待 QUEUE_TIMEOUT
+
+use crate::debug_log;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tauri::Manager;
+use tokio::time::Instant;
+
+/// 同时排队等待后端的请求上限
+const MAX_QUEUED: usize = 32;
+
+/// 单个请求最多等待后端恢复的时长
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 连接失败后重试的间隔
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// 单次发送的失败原因
+pub enum SendError {
+    /// 请求未到达后端，可以安全重试
+    Unreachable(String),
+    /// 其他错误（不重试）
+    Failed(String),
+}
+
+impl SendError {
+    pub fn from_reqwest(e: reqwest::Error) -> Self {
+        if e.is_connect() {
+            Self::Unreachable(e.to_string())
+        } else {
+            Self::Failed(e.to_string())
+        }
+    }
+
+    pub fn from_uds(e: String) -> Self {
+        if e.starts_with(crate::uds::CONNECT_FAILED) {
+            Self::Unreachable(e)
+        } else {
+            Self::Failed(e)
+        }
+    }
+}
+
+/// 正在排队的请求数
+#[derive(Default)]
+pub struct RequestQueue {
+    waiting: AtomicUsize,
+}
+
+/// 排队等待后端就绪（已就绪时立即返回）
+async fn wait_ready(app: &tauri::AppHandle, deadline: Instant) -> Result<(), String> {
+    let queue = app.state::<RequestQueue>();
+    if queue.waiting.fetch_add(1, Ordering::SeqCst) >= MAX_QUEUED {
+        queue.waiting.fetch_sub(1, Ordering::SeqCst);
+        return Err("Too many requests waiting for backend".to_string());
+    }
+    let mut rx = app.state::<crate::BackendState>().subscribe();
+    let result = tokio::time::timeout_at(deadline, rx.wait_for(|info| info.ready)).await;
+    queue.waiting.fetch_sub(1, Ordering::SeqCst);
+    match result {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!(
+            "Backend not ready after {}s",
+            QUEUE_TIMEOUT.as_secs()
+        )),
+    }
+}
+
+/// 发送请求；后端未就绪或暂时无法连接时排队，恢复后重试
+pub async fn send<T, F, Fut>(app: &tauri::AppHandle, mut attempt: F) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, SendError>>,
+{
+    let deadline = Instant::now() + QUEUE_TIMEOUT;
+    let mut retries = 0u32;
+    loop {
+        wait_ready(app, deadline).await?;
+        match attempt().await {
+            Ok(value) => {
+                if retries > 0 {
+                    debug_log(&format!("[queue] 后端恢复，请求重试 {} 次后成功", retries));
+                }
+                return Ok(value);
+            }
+            Err(SendError::Failed(e)) => return Err(e),
+            Err(SendError::Unreachable(e)) => {
+                // 就绪状态要等健康巡检发现后才会更新，先短暂等待再重新排队
+                if Instant::now() + RETRY_DELAY >= deadline {
+                    return Err(e);
+                }
+                retries += 1;
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+        }
+    }
+}
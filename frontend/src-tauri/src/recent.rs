@@ -0,0 +1,226 @@
+// ============================================================================
+// 最近使用的文件与应用
+// ============================================================================
+//
+// 让 Agent 结合上下文给出建议（如"继续编辑昨天的报告"）。需要用户在设置中开启
+// share_recent_items（默认关闭），否则 get_recent_items 直接拒绝。
+// - macOS: Spotlight 的 kMDItemLastUsedDate（mdfind / mdls），包含文件与应用
+// - Windows: 最近使用文件夹（%APPDATA%\Microsoft\Windows\Recent）中的快捷方式，指向 .exe 的视为应用
+// - Linux: ~/.local/share/recently-used.xbel（仅文件）
+// 结果按最近使用时间倒序，不在托管策略允许目录内的文件不返回。
+
+use crate::{debug_log, policy, settings};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// 默认返回条数
+const DEFAULT_LIMIT: usize = 20;
+
+/// 默认回溯天数
+const DEFAULT_DAYS: u32 = 7;
+
+/// 参与排序的候选条目上限
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const MAX_CANDIDATES: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentItem {
+    /// "file" / "app"
+    pub kind: String,
+    pub name: String,
+    pub path: String,
+    /// 最近使用时间（RFC 3339）
+    pub last_used: String,
+}
+
+fn item(path: PathBuf, last_used: chrono::DateTime<chrono::Local>, app: bool) -> RecentItem {
+    let name = if app {
+        path.file_stem()
+    } else {
+        path.file_name()
+    }
+    .map(|n| n.to_string_lossy().to_string())
+    .unwrap_or_default();
+    RecentItem {
+        kind: if app { "app" } else { "file" }.to_string(),
+        name,
+        path: path.to_string_lossy().to_string(),
+        last_used: last_used.to_rfc3339(),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn collect(days: u32) -> Result<Vec<RecentItem>, String> {
+    use std::process::Command as SysCommand;
+
+    fn mdfind(args: &[&str]) -> Result<Vec<String>, String> {
+        let output = SysCommand::new("mdfind")
+            .args(args)
+            .output()
+            .map_err(|e| format!("读取最近使用项目失败: {}", e))?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|l| !l.is_empty())
+            .take(MAX_CANDIDATES)
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// 批量读取 kMDItemLastUsedDate（mdls -raw 以 NUL 分隔）
+    fn last_used(paths: &[String]) -> Vec<Option<chrono::DateTime<chrono::Local>>> {
+        if paths.is_empty() {
+            return Vec::new();
+        }
+        let output = SysCommand::new("mdls")
+            .args(["-raw", "-name", "kMDItemLastUsedDate"])
+            .args(paths)
+            .output();
+        let Ok(output) = output else {
+            return vec![None; paths.len()];
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .map(|v| {
+                chrono::DateTime::parse_from_str(v.trim(), "%Y-%m-%d %H:%M:%S %z")
+                    .ok()
+                    .map(|d| d.with_timezone(&chrono::Local))
+            })
+            .collect()
+    }
+
+    let since = format!("kMDItemLastUsedDate >= $time.today(-{})", days);
+    let home = dirs::home_dir().ok_or("Home directory not found")?;
+    let home = home.to_string_lossy().to_string();
+    let files_query = format!(
+        "{} && kMDItemContentTypeTree != \"public.folder\" && kMDItemContentType != \"com.apple.application-bundle\"",
+        since
+    );
+    let apps_query = format!(
+        "{} && kMDItemContentType == \"com.apple.application-bundle\"",
+        since
+    );
+    let files = mdfind(&["-onlyin", home.as_str(), files_query.as_str()])?;
+    let apps = mdfind(&[apps_query.as_str()])?;
+
+    let mut items = Vec::new();
+    for (paths, app) in [(files, false), (apps, true)] {
+        for (path, used) in paths.iter().zip(last_used(&paths)) {
+            if let Some(used) = used {
+                items.push(item(PathBuf::from(path), used, app));
+            }
+        }
+    }
+    Ok(items)
+}
+
+#[cfg(target_os = "windows")]
+fn collect(days: u32) -> Result<Vec<RecentItem>, String> {
+    use std::process::Command as SysCommand;
+
+    #[derive(serde::Deserialize)]
+    struct Entry {
+        path: String,
+        last_used: String,
+    }
+
+    let script = format!(
+        "$shell = New-Object -ComObject WScript.Shell; \
+         $since = (Get-Date).AddDays(-{days}); \
+         $items = @(Get-ChildItem \"$env:APPDATA\\Microsoft\\Windows\\Recent\" -Filter *.lnk \
+           | Where-Object {{ $_.LastWriteTime -ge $since }} \
+           | Sort-Object LastWriteTime -Descending | Select-Object -First {max} \
+           | ForEach-Object {{ \
+               $t = $shell.CreateShortcut($_.FullName).TargetPath; \
+               if ($t -and (Test-Path -LiteralPath $t -PathType Leaf)) {{ \
+                 [pscustomobject]@{{ path = $t; last_used = $_.LastWriteTime.ToString('o') }} }} }}); \
+         ConvertTo-Json -InputObject $items -Compress",
+        days = days,
+        max = MAX_CANDIDATES
+    );
+    let output = SysCommand::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .map_err(|e| format!("读取最近使用项目失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "读取最近使用项目失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let entries: Vec<Entry> = if stdout.trim().is_empty() {
+        Vec::new()
+    } else {
+        serde_json::from_str(stdout.trim()).map_err(|e| format!("解析最近使用项目失败: {}", e))?
+    };
+    Ok(entries
+        .into_iter()
+        .filter_map(|e| {
+            let used = chrono::DateTime::parse_from_rfc3339(&e.last_used).ok()?;
+            let app = e.path.to_ascii_lowercase().ends_with(".exe");
+            Some(item(
+                PathBuf::from(e.path),
+                used.with_timezone(&chrono::Local),
+                app,
+            ))
+        })
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+fn collect(days: u32) -> Result<Vec<RecentItem>, String> {
+    let Some(path) = dirs::data_dir().map(|d| d.join("recently-used.xbel")) else {
+        return Ok(Vec::new());
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(Vec::new());
+    };
+    let regex = |pattern: &str| regex::Regex::new(pattern).map_err(|e| e.to_string());
+    let bookmark = regex(r"<bookmark\s[^>]*>")?;
+    let href_attr = regex(r#"\shref="([^"]*)""#)?;
+    let modified_attr = regex(r#"\smodified="([^"]*)""#)?;
+    let attr = |re: &regex::Regex, tag: &str| re.captures(tag).map(|c| c[1].to_string());
+    let since = chrono::Local::now() - chrono::Duration::days(days as i64);
+    Ok(bookmark
+        .find_iter(&content)
+        .filter_map(|tag| {
+            let href = attr(&href_attr, tag.as_str())?;
+            let modified = attr(&modified_attr, tag.as_str())?;
+            let path = url::Url::parse(&href).ok()?.to_file_path().ok()?;
+            let used = chrono::DateTime::parse_from_rfc3339(&modified)
+                .ok()?
+                .with_timezone(&chrono::Local);
+            (used >= since && path.is_file()).then(|| item(path, used, false))
+        })
+        .collect())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn collect(_days: u32) -> Result<Vec<RecentItem>, String> {
+    Err("Recent items not supported on this platform".to_string())
+}
+
+/// 最近使用的文件与应用（需要开启设置 share_recent_items）
+#[tauri::command]
+pub async fn get_recent_items(
+    app: tauri::AppHandle,
+    limit: Option<usize>,
+    days: Option<u32>,
+) -> Result<Vec<RecentItem>, String> {
+    if !settings::current(&app).share_recent_items {
+        return Err("Sharing recent items is disabled".to_string());
+    }
+    let days = days.unwrap_or(DEFAULT_DAYS).max(1);
+    let mut items = tauri::async_runtime::spawn_blocking(move || collect(days))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    items.retain(|item| policy::check_path(Path::new(&item.path)).is_ok());
+    items.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(item.path.clone()));
+    items.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+    debug_log(&format!("[recent] 返回 {} 个最近使用项目", items.len()));
+    Ok(items)
+}
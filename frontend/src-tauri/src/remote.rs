@@ -0,0 +1,837 @@
+// ============================================================================
+// 远程节点控制通道
+// ============================================================================
+//
+// 节点之间通过 TCP 交换换行分隔的 JSON 消息，握手阶段为明文：
+//
+//   客户端 → {"type":"hello","node_id":...,"nonce":客户端随机数}
+//   服务端 → {"type":"challenge","nonce":服务端随机数}
+//   客户端 → {"type":"auth","mac":HMAC-SHA256(配对码, "auth" | 两个随机数)}
+//   服务端 → {"type":"error","error":...}（认证失败，连接关闭）
+//
+// 认证通过后每一行都是 base64 编码的 ChaCha20-Poly1305 密文，两个方向的会话密钥
+// 由配对码与两个随机数经 HMAC 派生，nonce 为各方向的消息计数：
+//
+//   服务端 → {"type":"welcome","node":NodeInfo}（客户端能解密即确认服务端持有配对码）
+//   客户端 → {"type":"invoke","id":...,"capability":...,"params":{...}}
+//   服务端 → {"type":"result","id":...,"ok":...,"result":...,"error":...}
+//
+// 配对码为 100 位随机数（20 个 base32 字符），只参与 HMAC 计算，不在网络上明文传输，
+// 截获握手后也无法离线穷举。握手阶段的消息长度限制为 MAX_HANDSHAKE_LINE；
+// 同一来源 IP 累计 MAX_AUTH_FAILURES 次 MAC 校验失败后拒绝其后续连接，直到用户重新开启远程控制；
+// 超时、断开等未完成的握手不计入，避免空连接耗尽失败次数。
+// 被控端需由用户显式开启（enable_remote_control），关闭时已建立的连接一并断开。
+// 控制端收到的结果以 `node-result` 事件推送给前端。
+
+use crate::{audit, debug_log};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+/// 远程控制监听端口
+pub const NODE_CONTROL_PORT: u16 = 18950;
+
+/// 握手超时
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 握手阶段单条消息的最大长度（字节）
+const MAX_HANDSHAKE_LINE: u64 = 4 * 1024;
+
+/// 认证后单条消息的最大长度（字节，包含 canvas 截图等较大的结果）
+const MAX_MESSAGE_LINE: u64 = 64 * 1024 * 1024;
+
+/// 同一来源 IP 的 MAC 校验失败次数达到该值时拒绝其后续连接
+const MAX_AUTH_FAILURES: u32 = 10;
+
+/// 认证失败后回复前的延迟（减缓在线猜测）
+const AUTH_FAILURE_DELAY: Duration = Duration::from_secs(1);
+
+/// 配对码使用的字符（base32，去掉易混淆的 I / L / O / U）
+const PAIRING_ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// 配对码长度（每个字符 5 位，共 100 位）
+const PAIRING_CODE_LEN: usize = 20;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 被控端服务
+struct ControlServer {
+    /// 展示给用户的配对码（分组显示）
+    pairing_code: String,
+    /// 监听任务；结束时连同其持有的全部连接任务一起中止
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+/// 被控端连接共享的认证状态
+struct ServerAuth {
+    /// 规范化后的配对码（参与 HMAC 计算）
+    pairing_code: String,
+    /// 各来源 IP 的 MAC 校验失败次数
+    failures: Mutex<HashMap<IpAddr, u32>>,
+}
+
+impl ServerAuth {
+    /// 该来源是否已因认证失败过多被拒绝
+    fn is_blocked(&self, ip: IpAddr) -> bool {
+        self.failures
+            .lock()
+            .map(|f| f.get(&ip).is_some_and(|n| *n >= MAX_AUTH_FAILURES))
+            .unwrap_or(true)
+    }
+
+    /// 记录一次 MAC 校验失败，返回该来源的累计失败次数
+    fn record_failure(&self, ip: IpAddr) -> u32 {
+        let Ok(mut failures) = self.failures.lock() else {
+            return MAX_AUTH_FAILURES;
+        };
+        let count = failures.entry(ip).or_insert(0);
+        *count += 1;
+        *count
+    }
+}
+
+/// 被控端握手失败的原因
+enum HandshakeError {
+    /// 对方发送的 MAC 校验失败（计入认证失败次数）
+    BadMac,
+    /// 超时、断开或消息格式错误（不计入）
+    Aborted,
+}
+
+/// 远程控制状态：本机被控服务 + 到其他节点的连接
+#[derive(Default)]
+pub struct RemoteControl {
+    server: Mutex<Option<ControlServer>>,
+    connections: Mutex<HashMap<String, mpsc::UnboundedSender<String>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    Hello {
+        node_id: String,
+        nonce: String,
+    },
+    Challenge {
+        nonce: String,
+    },
+    Auth {
+        mac: String,
+    },
+    Welcome {
+        node: crate::NodeInfo,
+    },
+    Error {
+        error: String,
+    },
+    Invoke {
+        id: String,
+        capability: String,
+        #[serde(default)]
+        params: serde_json::Value,
+    },
+    Result {
+        id: String,
+        ok: bool,
+        #[serde(default)]
+        result: serde_json::Value,
+        #[serde(default)]
+        error: Option<String>,
+    },
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 == 1 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// 以配对码为密钥，对标签与两个随机数计算 HMAC
+fn session_hmac(
+    pairing_code: &str,
+    label: &str,
+    client_nonce: &str,
+    server_nonce: &str,
+) -> HmacSha256 {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(pairing_code.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    for part in [label, client_nonce, server_nonce] {
+        mac.update(part.as_bytes());
+        mac.update(b"\n");
+    }
+    mac
+}
+
+fn compute_mac(pairing_code: &str, client_nonce: &str, server_nonce: &str) -> String {
+    let mac = session_hmac(pairing_code, "auth", client_nonce, server_nonce);
+    hex_encode(&mac.finalize().into_bytes())
+}
+
+fn verify_mac(pairing_code: &str, client_nonce: &str, server_nonce: &str, mac_hex: &str) -> bool {
+    let Some(bytes) = hex_decode(mac_hex) else {
+        return false;
+    };
+    session_hmac(pairing_code, "auth", client_nonce, server_nonce)
+        .verify_slice(&bytes)
+        .is_ok()
+}
+
+/// 生成随机数（握手用）
+fn generate_nonce() -> String {
+    let bytes: Vec<u8> = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()]
+        .iter()
+        .flat_map(|id| *id.as_bytes())
+        .collect();
+    hex_encode(&bytes)
+}
+
+/// 生成配对码（20 个 base32 字符，每 4 个一组，如 "7K2M-..."）
+fn generate_pairing_code() -> String {
+    // 取每个字节的低 5 位；跳过 uuid v4 的版本字节（第 6 字节），变体位在高位不影响
+    let chars: Vec<char> = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()]
+        .iter()
+        .flat_map(|id| {
+            id.as_bytes()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != 6)
+                .map(|(_, b)| *b)
+                .collect::<Vec<_>>()
+        })
+        .take(PAIRING_CODE_LEN)
+        .map(|b| PAIRING_ALPHABET[(b & 0x1f) as usize] as char)
+        .collect();
+    chars
+        .chunks(4)
+        .map(|c| c.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// 规范化用户输入的配对码（忽略分隔符与大小写）
+fn normalize_pairing_code(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// 单方向的会话加密（ChaCha20-Poly1305，nonce 为消息计数）
+struct SessionCipher {
+    cipher: ChaCha20Poly1305,
+    counter: u64,
+}
+
+impl SessionCipher {
+    fn new(pairing_code: &str, direction: &str, client_nonce: &str, server_nonce: &str) -> Self {
+        let key = session_hmac(pairing_code, direction, client_nonce, server_nonce)
+            .finalize()
+            .into_bytes();
+        Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+            counter: 0,
+        }
+    }
+
+    fn next_nonce(&mut self) -> Nonce {
+        let mut nonce = [0u8; 12];
+        nonce[4..].copy_from_slice(&self.counter.to_le_bytes());
+        self.counter += 1;
+        *Nonce::from_slice(&nonce)
+    }
+
+    /// 加密一条消息，返回以换行结尾的文本行
+    fn seal(&mut self, plaintext: &str) -> Option<String> {
+        let nonce = self.next_nonce();
+        let ciphertext = self.cipher.encrypt(&nonce, plaintext.as_bytes()).ok()?;
+        let mut line = base64::engine::general_purpose::STANDARD.encode(ciphertext);
+        line.push('\n');
+        Some(line)
+    }
+
+    /// 解密一行（认证失败时返回 None，调用方应断开连接）
+    fn open(&mut self, line: &str) -> Option<Message> {
+        let ciphertext = base64::engine::general_purpose::STANDARD
+            .decode(line.trim())
+            .ok()?;
+        let nonce = self.next_nonce();
+        let plaintext = self.cipher.decrypt(&nonce, ciphertext.as_slice()).ok()?;
+        serde_json::from_slice(&plaintext).ok()
+    }
+}
+
+/// 客户端 → 服务端、服务端 → 客户端两个方向的加密器
+fn session_ciphers(
+    pairing_code: &str,
+    client_nonce: &str,
+    server_nonce: &str,
+) -> (SessionCipher, SessionCipher) {
+    (
+        SessionCipher::new(pairing_code, "client-to-server", client_nonce, server_nonce),
+        SessionCipher::new(pairing_code, "server-to-client", client_nonce, server_nonce),
+    )
+}
+
+async fn write_message<W: AsyncWriteExt + Unpin>(writer: &mut W, msg: &Message) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(msg).unwrap_or_default();
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await
+}
+
+async fn write_sealed<W: AsyncWriteExt + Unpin>(
+    writer: &mut W,
+    cipher: &mut SessionCipher,
+    msg: &Message,
+) -> std::io::Result<()> {
+    let plaintext = serde_json::to_string(msg).unwrap_or_default();
+    let line = cipher
+        .seal(&plaintext)
+        .ok_or_else(|| std::io::Error::other("encryption failed"))?;
+    writer.write_all(line.as_bytes()).await
+}
+
+/// 读取一行，超过 limit 字节或连接关闭时返回 None
+async fn read_line<R: AsyncBufReadExt + Unpin>(reader: &mut R, limit: u64) -> Option<String> {
+    let mut line = String::new();
+    match (&mut *reader).take(limit).read_line(&mut line).await {
+        Ok(0) | Err(_) => None,
+        // 读满 limit 仍没有换行：消息过长，丢弃连接
+        Ok(_) if !line.ends_with('\n') => None,
+        Ok(_) => Some(line),
+    }
+}
+
+async fn read_message<R: AsyncBufReadExt + Unpin>(reader: &mut R) -> Option<Message> {
+    let line = read_line(reader, MAX_HANDSHAKE_LINE).await?;
+    serde_json::from_str(line.trim()).ok()
+}
+
+async fn read_sealed<R: AsyncBufReadExt + Unpin>(
+    reader: &mut R,
+    cipher: &mut SessionCipher,
+) -> Option<Message> {
+    let line = read_line(reader, MAX_MESSAGE_LINE).await?;
+    cipher.open(&line)
+}
+
+// ============================================================================
+// 被控端
+// ============================================================================
+
+/// 执行远程调用的能力
+async fn handle_invoke(
+    app: &tauri::AppHandle,
+    peer: &str,
+    capability: &str,
+    params: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let result: Result<serde_json::Value, String> = async {
+        if capability != "node.info" {
+            crate::permissions::check_capability(app, capability).map_err(|e| e.to_string())?;
+        }
+        match capability {
+            "system.run" => {
+                let command: Vec<String> = serde_json::from_value(params["command"].clone())
+                    .map_err(|_| "params.command must be a string array".to_string())?;
+                let cwd = params["cwd"].as_str().map(|s| s.to_string());
+                let timeout_ms = params["timeout_ms"].as_u64();
+                crate::approvals::request_approval(
+                    app,
+                    "remote.system.run",
+                    crate::i18n::tf("approval.remote_run", &[&peer, &command.join(" ")]),
+                    serde_json::json!({ "peer": peer, "command": &command, "cwd": &cwd }),
+                )
+                .await?;
+                let source = format!("remote:{}", peer);
+                let r = crate::run_shell_command(
+                    app,
+                    Some(&source),
+                    command,
+                    cwd,
+                    None,
+                    timeout_ms,
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+                serde_json::to_value(r).map_err(|e| e.to_string())
+            }
+            "system.notify" => {
+                let title = params["title"].as_str().unwrap_or("xiaodazi");
+                let body = params["body"].as_str().unwrap_or("");
+                crate::notifications::notify(app, title, body, false);
+                Ok(serde_json::json!({"notified": true}))
+            }
+            "canvas.snapshot" => crate::canvas_snapshot(app.clone()).await,
+            "node.info" => serde_json::to_value(crate::collect_node_info()).map_err(|e| e.to_string()),
+            other => Err(format!("Unsupported remote capability: {}", other)),
+        }
+    }
+    .await;
+
+    audit::record(
+        app,
+        "remote.invoke",
+        result.is_ok(),
+        serde_json::json!({"peer": peer, "capability": capability, "params": params}),
+    );
+    result
+}
+
+/// 处理一个入站连接：握手认证后循环处理调用请求
+async fn serve_connection(app: tauri::AppHandle, stream: TcpStream, auth: Arc<ServerAuth>) {
+    let Ok(peer) = stream.peer_addr() else {
+        return;
+    };
+    let peer_addr = peer.to_string();
+    if auth.is_blocked(peer.ip()) {
+        debug_log(&format!("[remote] 拒绝已封禁来源的连接: {}", peer_addr));
+        return;
+    }
+    let (read_half, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(read_half);
+
+    let handshake = async {
+        let (peer_id, client_nonce) = match read_message(&mut reader).await {
+            Some(Message::Hello { node_id, nonce }) if !nonce.is_empty() => (node_id, nonce),
+            _ => return Err(HandshakeError::Aborted),
+        };
+        let server_nonce = generate_nonce();
+        write_message(
+            &mut writer,
+            &Message::Challenge {
+                nonce: server_nonce.clone(),
+            },
+        )
+        .await
+        .map_err(|_| HandshakeError::Aborted)?;
+        match read_message(&mut reader).await {
+            Some(Message::Auth { mac })
+                if verify_mac(&auth.pairing_code, &client_nonce, &server_nonce, &mac) =>
+            {
+                Ok((peer_id, client_nonce, server_nonce))
+            }
+            Some(Message::Auth { .. }) => Err(HandshakeError::BadMac),
+            _ => Err(HandshakeError::Aborted),
+        }
+    };
+
+    let handshake = tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake).await;
+    let (peer_id, client_nonce, server_nonce) = match handshake {
+        Ok(Ok(result)) => result,
+        Ok(Err(HandshakeError::Aborted)) | Err(_) => {
+            debug_log(&format!("[remote] 握手未完成，断开连接: {}", peer_addr));
+            return;
+        }
+        Ok(Err(HandshakeError::BadMac)) => {
+            let failures = auth.record_failure(peer.ip());
+            debug_log(&format!(
+                "[remote] 拒绝未认证连接: {} (该来源累计失败 {} 次)",
+                peer_addr, failures
+            ));
+            tokio::time::sleep(AUTH_FAILURE_DELAY).await;
+            let _ = write_message(
+                &mut writer,
+                &Message::Error {
+                    error: "authentication failed".to_string(),
+                },
+            )
+            .await;
+            audit::record(
+                &app,
+                "remote.auth",
+                false,
+                serde_json::json!({"peer_addr": peer_addr, "failures": failures}),
+            );
+            if failures == MAX_AUTH_FAILURES {
+                debug_log(&format!(
+                    "[remote] {} 认证失败次数过多，拒绝其后续连接",
+                    peer.ip()
+                ));
+                audit::record(
+                    &app,
+                    "remote.lockout",
+                    true,
+                    serde_json::json!({"peer_ip": peer.ip().to_string(), "failures": failures}),
+                );
+                let _ = app.emit(
+                    "remote-peer-blocked",
+                    serde_json::json!({"peer_ip": peer.ip().to_string(), "failures": failures}),
+                );
+            }
+            return;
+        }
+    };
+    let (mut inbound, mut outbound) =
+        session_ciphers(&auth.pairing_code, &client_nonce, &server_nonce);
+
+    debug_log(&format!("[remote] 节点已连接: {} ({})", peer_id, peer_addr));
+    audit::record(
+        &app,
+        "remote.auth",
+        true,
+        serde_json::json!({"peer": &peer_id, "peer_addr": &peer_addr}),
+    );
+    if write_sealed(
+        &mut writer,
+        &mut outbound,
+        &Message::Welcome {
+            node: crate::collect_node_info(),
+        },
+    )
+    .await
+    .is_err()
+    {
+        return;
+    }
+
+    while let Some(msg) = read_sealed(&mut reader, &mut inbound).await {
+        if let Message::Invoke {
+            id,
+            capability,
+            params,
+        } = msg
+        {
+            let reply = match handle_invoke(&app, &peer_id, &capability, params).await {
+                Ok(result) => Message::Result {
+                    id,
+                    ok: true,
+                    result,
+                    error: None,
+                },
+                Err(e) => Message::Result {
+                    id,
+                    ok: false,
+                    result: serde_json::Value::Null,
+                    error: Some(e),
+                },
+            };
+            if write_sealed(&mut writer, &mut outbound, &reply)
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+    debug_log(&format!("[remote] 节点已断开: {}", peer_id));
+}
+
+/// 开启本机远程控制，返回监听端口与配对码（每次开启生成新的配对码）
+#[tauri::command]
+pub async fn enable_remote_control(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    crate::auth::require_auth(&app, "remote_control").await?;
+    disable_remote_control(app.clone()).await?;
+
+    let listener = TcpListener::bind(("0.0.0.0", NODE_CONTROL_PORT))
+        .await
+        .map_err(|e| format!("远程控制端口 {} 监听失败: {}", NODE_CONTROL_PORT, e))?;
+    let pairing_code = generate_pairing_code();
+    let auth = Arc::new(ServerAuth {
+        pairing_code: normalize_pairing_code(&pairing_code),
+        failures: Mutex::new(HashMap::new()),
+    });
+
+    let app_for_server = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        // 连接任务由 JoinSet 持有：监听任务被中止时 JoinSet 随之释放，全部连接一并中止
+        let mut connections = JoinSet::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        connections.spawn(serve_connection(
+                            app_for_server.clone(),
+                            stream,
+                            auth.clone(),
+                        ));
+                    }
+                    Err(e) => {
+                        debug_log(&format!("[remote] 接受连接失败: {}", e));
+                        break;
+                    }
+                },
+                // 回收已结束的连接任务
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            }
+        }
+    });
+
+    *app.state::<RemoteControl>()
+        .server
+        .lock()
+        .map_err(|e| e.to_string())? = Some(ControlServer {
+        pairing_code: pairing_code.clone(),
+        task,
+    });
+
+    debug_log(&format!("[remote] 远程控制已开启 (port={})", NODE_CONTROL_PORT));
+    Ok(serde_json::json!({"port": NODE_CONTROL_PORT, "pairing_code": pairing_code}))
+}
+
+/// 关闭本机远程控制（不再接受新连接，已建立的连接一并断开）
+#[tauri::command]
+pub async fn disable_remote_control(app: tauri::AppHandle) -> Result<bool, String> {
+    let server = app
+        .state::<RemoteControl>()
+        .server
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take();
+    match server {
+        Some(s) => {
+            s.task.abort();
+            debug_log("[remote] 远程控制已关闭");
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// 本机远程控制服务是否已开启
+pub fn is_enabled(app: &tauri::AppHandle) -> bool {
+    app.state::<RemoteControl>()
+        .server
+        .lock()
+        .map(|g| g.is_some())
+        .unwrap_or(false)
+}
+
+/// 本机远程控制状态
+#[tauri::command]
+pub async fn get_remote_control_status(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    let state = app.state::<RemoteControl>();
+    let server = state.server.lock().map_err(|e| e.to_string())?;
+    let connections = state.connections.lock().map_err(|e| e.to_string())?;
+    Ok(serde_json::json!({
+        "enabled": server.is_some(),
+        "port": NODE_CONTROL_PORT,
+        "pairing_code": server.as_ref().map(|s| s.pairing_code.clone()),
+        "outgoing_connections": connections.keys().cloned().collect::<Vec<_>>(),
+    }))
+}
+
+// ============================================================================
+// 控制端
+// ============================================================================
+
+/// 连接远程节点，返回连接 ID 与对方节点信息
+///
+/// `address` 为 "host" 或 "host:port"，省略端口时使用 NODE_CONTROL_PORT。
+#[tauri::command]
+pub async fn connect_node(
+    app: tauri::AppHandle,
+    address: String,
+    pairing_code: String,
+) -> Result<serde_json::Value, String> {
+    let target = if address.contains(':') {
+        address.clone()
+    } else {
+        format!("{}:{}", address, NODE_CONTROL_PORT)
+    };
+
+    let stream = tokio::time::timeout(HANDSHAKE_TIMEOUT, TcpStream::connect(&target))
+        .await
+        .map_err(|_| format!("连接超时: {}", target))?
+        .map_err(|e| format!("连接失败: {}", e))?;
+    let (read_half, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(read_half);
+
+    let pairing_code = normalize_pairing_code(&pairing_code);
+    let client_nonce = generate_nonce();
+    let handshake = async {
+        write_message(
+            &mut writer,
+            &Message::Hello {
+                node_id: crate::node_id().to_string(),
+                nonce: client_nonce.clone(),
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        let server_nonce = match read_message(&mut reader).await {
+            Some(Message::Challenge { nonce }) => nonce,
+            _ => return Err("握手失败".to_string()),
+        };
+        write_message(
+            &mut writer,
+            &Message::Auth {
+                mac: compute_mac(&pairing_code, &client_nonce, &server_nonce),
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        let (outbound, mut inbound) = session_ciphers(&pairing_code, &client_nonce, &server_nonce);
+        // 认证失败时服务端回复明文错误；能解密 welcome 说明对方持有同一配对码
+        let line = read_line(&mut reader, MAX_HANDSHAKE_LINE)
+            .await
+            .ok_or("认证失败")?;
+        if let Ok(Message::Error { error }) = serde_json::from_str(line.trim()) {
+            return Err(format!("认证失败: {}", error));
+        }
+        match inbound.open(&line) {
+            Some(Message::Welcome { node }) => Ok((node, outbound, inbound)),
+            _ => Err("认证失败".to_string()),
+        }
+    };
+    let (node, mut outbound, mut inbound) = tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake)
+        .await
+        .map_err(|_| "握手超时".to_string())??;
+
+    let connection_id = uuid::Uuid::new_v4().to_string();
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    app.state::<RemoteControl>()
+        .connections
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(connection_id.clone(), tx);
+
+    // 写任务：把 invoke_remote 排队的请求加密后写入连接
+    tauri::async_runtime::spawn(async move {
+        while let Some(plaintext) = rx.recv().await {
+            let Some(line) = outbound.seal(&plaintext) else {
+                break;
+            };
+            if writer.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // 读任务：把结果以事件推送给前端
+    let app_for_reader = app.clone();
+    let id_for_reader = connection_id.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(msg) = read_sealed(&mut reader, &mut inbound).await {
+            if let Message::Result {
+                id,
+                ok,
+                result,
+                error,
+            } = msg
+            {
+                let _ = app_for_reader.emit(
+                    "node-result",
+                    serde_json::json!({
+                        "connection_id": id_for_reader,
+                        "request_id": id,
+                        "ok": ok,
+                        "result": result,
+                        "error": error,
+                    }),
+                );
+            }
+        }
+        if let Ok(mut conns) = app_for_reader.state::<RemoteControl>().connections.lock() {
+            conns.remove(&id_for_reader);
+        }
+        let _ = app_for_reader.emit(
+            "node-disconnected",
+            serde_json::json!({"connection_id": id_for_reader}),
+        );
+    });
+
+    debug_log(&format!("[remote] 已连接节点 {} ({})", node.node_id, target));
+    Ok(serde_json::json!({"connection_id": connection_id, "node": node}))
+}
+
+/// 在远程节点上调用能力，返回请求 ID（结果通过 `node-result` 事件返回）
+#[tauri::command]
+pub async fn invoke_remote(
+    app: tauri::AppHandle,
+    connection_id: String,
+    capability: String,
+    params: Option<serde_json::Value>,
+) -> Result<String, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let line = serde_json::to_string(&Message::Invoke {
+        id: id.clone(),
+        capability,
+        params: params.unwrap_or(serde_json::Value::Null),
+    })
+    .map_err(|e| e.to_string())?;
+
+    let state = app.state::<RemoteControl>();
+    let conns = state.connections.lock().map_err(|e| e.to_string())?;
+    let tx = conns
+        .get(&connection_id)
+        .ok_or_else(|| format!("连接不存在: {}", connection_id))?;
+    tx.send(line).map_err(|_| "连接已断开".to_string())?;
+    Ok(id)
+}
+
+/// 断开与远程节点的连接
+#[tauri::command]
+pub async fn disconnect_node(app: tauri::AppHandle, connection_id: String) -> Result<bool, String> {
+    let removed = app
+        .state::<RemoteControl>()
+        .connections
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&connection_id);
+    // 丢弃发送端后写任务结束，连接随之关闭
+    Ok(removed.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "7K2MAB3XQ9";
+
+    #[test]
+    fn verify_mac_accepts_matching_mac() {
+        let mac = compute_mac(CODE, "client", "server");
+        assert!(verify_mac(CODE, "client", "server", &mac));
+        assert!(verify_mac(CODE, "client", "server", &mac.to_uppercase()));
+    }
+
+    #[test]
+    fn verify_mac_rejects_wrong_code_nonces_or_encoding() {
+        let mac = compute_mac(CODE, "client", "server");
+        assert!(!verify_mac("7K2MAB3XQ8", "client", "server", &mac));
+        assert!(!verify_mac(CODE, "server", "client", &mac));
+        assert!(!verify_mac(CODE, "client", "other", &mac));
+        let truncated = &mac[..mac.len() - 2];
+        for mac in [truncated, "xyz", "éé", ""] {
+            assert!(!verify_mac(CODE, "client", "server", mac), "{}", mac);
+        }
+    }
+
+    #[test]
+    fn normalize_pairing_code_ignores_separators_and_case() {
+        assert_eq!(normalize_pairing_code("7k2m-ab3x q9"), CODE);
+        assert_eq!(normalize_pairing_code(" 7K2M_AB3X\n"), "7K2MAB3X");
+        // 全角字符不是 ASCII 字母数字
+        assert_eq!(normalize_pairing_code("７K2M"), "K2M");
+    }
+
+    #[test]
+    fn generated_pairing_code_survives_normalization() {
+        let code = generate_pairing_code();
+        let normalized = normalize_pairing_code(&code);
+        assert_eq!(normalized.len(), PAIRING_CODE_LEN);
+        assert!(normalized.bytes().all(|b| PAIRING_ALPHABET.contains(&b)));
+        assert_eq!(normalize_pairing_code(&code.to_lowercase()), normalized);
+    }
+}
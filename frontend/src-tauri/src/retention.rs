@@ -0,0 +1,186 @@
+// ============================================================================
+// 数据保留策略：清理旧日志、截图、录屏与临时工作区
+// ============================================================================
+//
+// 每个清理目标是数据目录下的一个目录，目录中的每一项（文件或子目录）作为一个单位：
+// 先删除超过 retention_max_age_days 的项，再按从旧到新删除，直到该目标
+// 总大小不超过 retention_max_size_mb。启动后与每天各执行一次。
+
+use crate::{debug_log, logging, settings};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::Emitter;
+
+/// 启动后首次清理的延迟（避开启动高峰）
+const FIRST_RUN_DELAY: Duration = Duration::from_secs(30);
+
+/// 定时清理间隔
+const RUN_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// 清理目标（相对数据目录）
+const TARGETS: &[&str] = &[
+    logging::LOG_DIR,
+    "recordings",
+    "screenshots",
+    "workspace/scratchpad",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TargetCleanup {
+    pub target: String,
+    pub removed: usize,
+    pub freed_bytes: u64,
+    /// 清理后剩余大小
+    pub remaining_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupReport {
+    pub started_at: String,
+    pub removed: usize,
+    pub freed_bytes: u64,
+    pub targets: Vec<TargetCleanup>,
+}
+
+struct Entry {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// 递归统计大小与最近修改时间（目录取其中最新的文件时间）
+fn scan(path: &Path) -> (u64, SystemTime) {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return (0, SystemTime::UNIX_EPOCH);
+    };
+    let own_mtime = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    if !meta.is_dir() {
+        return (meta.len(), own_mtime);
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| scan(&e.path()))
+                .fold((0, own_mtime), |(size, mtime), (s, m)| {
+                    (size + s, mtime.max(m))
+                })
+        })
+        .unwrap_or((0, own_mtime))
+}
+
+fn remove(path: &Path) -> bool {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            debug_log(&format!("[retention] 删除失败 {}: {}", path.display(), e));
+            false
+        }
+    }
+}
+
+fn clean_target(dir: &Path, name: &str, max_age: Duration, max_bytes: u64) -> TargetCleanup {
+    let mut report = TargetCleanup {
+        target: name.to_string(),
+        ..Default::default()
+    };
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return report;
+    };
+
+    // 正在写入的日志文件不参与清理
+    let current_log = logging::current_log_path();
+    let mut entries: Vec<Entry> = read
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| *p != current_log)
+        .map(|path| {
+            let (size, modified) = scan(&path);
+            Entry {
+                path,
+                size,
+                modified,
+            }
+        })
+        .collect();
+    entries.sort_by_key(|e| e.modified);
+
+    let now = SystemTime::now();
+    let mut total: u64 = entries.iter().map(|e| e.size).sum();
+    for entry in entries {
+        let expired = now
+            .duration_since(entry.modified)
+            .map(|age| age > max_age)
+            .unwrap_or(false);
+        if !expired && total <= max_bytes {
+            continue;
+        }
+        if remove(&entry.path) {
+            report.removed += 1;
+            report.freed_bytes += entry.size;
+            total = total.saturating_sub(entry.size);
+        }
+    }
+    report.remaining_bytes = total;
+    report
+}
+
+/// 按当前设置执行一次清理
+fn run_cleanup(s: &settings::AppSettings) -> CleanupReport {
+    let data_dir = settings::app_data_dir();
+    let max_age = Duration::from_secs(s.retention_max_age_days.saturating_mul(24 * 3600));
+    let max_bytes = s.retention_max_size_mb.saturating_mul(1024 * 1024);
+
+    let targets: Vec<TargetCleanup> = TARGETS
+        .iter()
+        .map(|t| clean_target(&data_dir.join(t), t, max_age, max_bytes))
+        .collect();
+    let report = CleanupReport {
+        started_at: chrono::Local::now().to_rfc3339(),
+        removed: targets.iter().map(|t| t.removed).sum(),
+        freed_bytes: targets.iter().map(|t| t.freed_bytes).sum(),
+        targets,
+    };
+    debug_log(&format!(
+        "[retention] 清理完成: 删除 {} 项，释放 {} KB",
+        report.removed,
+        report.freed_bytes / 1024
+    ));
+    report
+}
+
+async fn run_and_emit(app: &tauri::AppHandle) -> Result<CleanupReport, String> {
+    let s = settings::current(app);
+    let report = tauri::async_runtime::spawn_blocking(move || run_cleanup(&s))
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit("cleanup-finished", &report);
+    Ok(report)
+}
+
+/// 启动定时清理（启动后一次，之后每天一次；设置中关闭时跳过）
+pub fn start_scheduler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_RUN_DELAY).await;
+        loop {
+            if settings::current(&app).retention_enabled {
+                if let Err(e) = run_and_emit(&app).await {
+                    debug_log(&format!("[retention] {}", e));
+                }
+            }
+            tokio::time::sleep(RUN_INTERVAL).await;
+        }
+    });
+}
+
+/// 立即执行清理，返回释放的空间
+#[tauri::command]
+pub async fn run_cleanup_now(app: tauri::AppHandle) -> Result<CleanupReport, String> {
+    run_and_emit(&app).await
+}
@@ -0,0 +1,634 @@
+// ============================================================================
+// 定时任务调度（cron 表达式）
+// ============================================================================
+//
+// 任务由 Rust 壳层调度并持久化到 schedules.json，窗口隐藏到托盘或前端重新加载时
+// 照常执行。支持三种动作：调用后端接口、执行命令、向前端发送事件（事件名必须以
+// EVENT_PREFIX 开头，不能冒充应用内部事件）。
+// cron 表达式支持标准 5 段（分 时 日 月 周，星期 0-7，0 与 7 为周日）与 cron 库的 6/7 段写法
+// （带秒，星期 1-7，周日为 1）；
+// 应用关闭期间错过的执行不会补跑，启动后从当前时间起计算下一次执行。
+// 一次性任务（run_command_at）没有 cron，只在 run_at 执行一次，执行完成后移除；
+// 应用关闭期间错过的一次性任务在启动后立即执行。
+
+use crate::store::{load_json, save_json};
+use crate::{audit, debug_log, i18n, notifications};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// 定时任务持久化文件
+const SCHEDULES_FILE: &str = "schedules.json";
+
+/// 调度检查间隔（秒）
+const SCHEDULER_TICK_SECS: u64 = 5;
+
+/// 调用后端接口的超时
+const BACKEND_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 执行结果中保留的输出长度（字节）
+const MAX_RESULT_OUTPUT: usize = 4096;
+
+/// EmitEvent 动作的事件名前缀
+pub const EVENT_PREFIX: &str = "scheduled:";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskAction {
+    /// 调用后端接口（path 如 "/api/v1/agent/run"）
+    BackendRequest {
+        #[serde(default = "default_method")]
+        method: String,
+        path: String,
+        #[serde(default)]
+        body: Option<serde_json::Value>,
+    },
+    /// 执行命令（argv 形式，不经过 shell）
+    RunCommand {
+        command: Vec<String>,
+        #[serde(default)]
+        cwd: Option<String>,
+        #[serde(default)]
+        env: Option<HashMap<String, String>>,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+    /// 向前端发送事件（事件名以 EVENT_PREFIX 开头）
+    EmitEvent {
+        event: String,
+        #[serde(default)]
+        payload: serde_json::Value,
+    },
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRunResult {
+    /// 执行时间（RFC 3339）
+    pub at: String,
+    pub success: bool,
+    /// 输出摘要（接口响应体 / 命令输出 / 错误信息）
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    pub id: String,
+    /// cron 表达式（保存用户输入的原始写法；一次性任务为 None）
+    #[serde(default)]
+    pub cron: Option<String>,
+    /// 一次性任务的执行时间（RFC 3339）
+    #[serde(default)]
+    pub run_at: Option<String>,
+    /// 执行完成后发送系统通知
+    #[serde(default)]
+    pub notify: bool,
+    pub action: TaskAction,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub created_at: String,
+    /// 下一次执行时间（RFC 3339，无后续执行时为 None）
+    #[serde(default)]
+    pub next_run: Option<String>,
+    #[serde(default)]
+    pub last_run: Option<TaskRunResult>,
+}
+
+/// 已登记的定时任务（与磁盘文件保持同步）
+#[derive(Default)]
+pub struct TaskScheduler {
+    tasks: Vec<ScheduledTask>,
+}
+
+/// 星期缩写（下标为标准 cron 的 0-6，0 为周日）
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// 把标准 cron 的数字星期（0-7，0 与 7 为周日）转成英文缩写
+///
+/// cron 库的数字星期是 1-7 且周日为 1，直接使用会整体错开一天，因此数字项展开为缩写列表；
+/// 通配符与英文缩写原样保留。
+fn normalize_weekday(field: &str) -> Result<String, String> {
+    let number = |value: &str| -> Result<usize, String> {
+        match value.parse::<usize>() {
+            Ok(n) if n <= 7 => Ok(n),
+            _ => Err(format!("无效的星期: {}", value)),
+        }
+    };
+    let mut parts: Vec<String> = Vec::new();
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, Some(step)),
+            None => (item, None),
+        };
+        if !range.starts_with(|c: char| c.is_ascii_digit()) && !(range == "*" && step.is_some()) {
+            parts.push(item.to_string());
+            continue;
+        }
+        let step = match step {
+            Some(s) => s
+                .parse::<usize>()
+                .ok()
+                .filter(|s| *s > 0)
+                .ok_or_else(|| format!("无效的步长: {}", s))?,
+            None => 1,
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((start, end)) => (number(start)?, number(end)?),
+            // "n/step" 表示从 n 到最后一天
+            None if step > 1 => (number(range)?, 7),
+            None => (number(range)?, number(range)?),
+        };
+        if start > end {
+            return Err(format!("无效的星期范围: {}", range));
+        }
+        for day in (start..=end).step_by(step) {
+            let name = WEEKDAY_NAMES[day % 7].to_string();
+            if !parts.contains(&name) {
+                parts.push(name);
+            }
+        }
+    }
+    Ok(parts.join(","))
+}
+
+/// 解析 cron 表达式（5 段写法补上秒字段并转换星期字段）
+pub(crate) fn parse_cron(expr: &str) -> Result<cron::Schedule, String> {
+    let expr = expr.trim();
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let normalized = if let [minute, hour, day, month, weekday] = fields[..] {
+        format!(
+            "0 {} {} {} {} {}",
+            minute,
+            hour,
+            day,
+            month,
+            normalize_weekday(weekday)?
+        )
+    } else {
+        expr.to_string()
+    };
+    cron::Schedule::from_str(&normalized).map_err(|e| format!("无效的 cron 表达式: {}", e))
+}
+
+/// 从当前时间起的下一次执行时间
+pub(crate) fn next_run_after_now(expr: &str) -> Option<String> {
+    parse_cron(expr)
+        .ok()?
+        .after(&chrono::Local::now())
+        .next()
+        .map(|t| t.to_rfc3339())
+}
+
+fn persist(app: &tauri::AppHandle, tasks: &[ScheduledTask]) {
+    if let Err(e) = save_json(app, SCHEDULES_FILE, &tasks) {
+        debug_log(&format!("[scheduler] 保存定时任务失败: {}", e));
+    }
+}
+
+/// 截断输出（保证在字符边界上）
+pub(crate) fn truncate_output(mut text: String) -> String {
+    if text.len() > MAX_RESULT_OUTPUT {
+        let mut end = MAX_RESULT_OUTPUT;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("...(truncated)");
+    }
+    text
+}
+
+/// 动作的发起方
+///
+/// run_action 在锁定期间照常执行命令，只用于用户事先登记的无人值守动作；
+/// 其它入口（URL 快捷指令、远程调用等）需自行检查能力（含应用锁定）后直接调用对应实现。
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ActionSource<'a> {
+    /// 计划任务（任务 ID）
+    Task(&'a str),
+    /// 自动化规则（规则 ID）
+    Automation(&'a str),
+}
+
+impl ActionSource<'_> {
+    /// 写入审计日志的发起方标识，如 "task:<id>"
+    fn label(&self) -> String {
+        match self {
+            Self::Task(id) => format!("task:{}", id),
+            Self::Automation(id) => format!("automation:{}", id),
+        }
+    }
+}
+
+/// 正在执行的计划任务与自动化动作数（自动更新等待其归零后再安装）
+static ACTIVE_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// 执行期间计入 ACTIVE_RUNS
+struct ActiveRun;
+
+impl ActiveRun {
+    fn start() -> Self {
+        ACTIVE_RUNS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for ActiveRun {
+    fn drop(&mut self) {
+        ACTIVE_RUNS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// 是否有计划任务或自动化动作正在执行
+pub(crate) fn has_active_runs() -> bool {
+    ACTIVE_RUNS.load(Ordering::SeqCst) > 0
+}
+
+/// 执行一个计划任务或自动化规则的动作
+pub(crate) async fn run_action(
+    app: &tauri::AppHandle,
+    source: ActionSource<'_>,
+    action: &TaskAction,
+) -> TaskRunResult {
+    let _active = ActiveRun::start();
+    let source = source.label();
+    let at = chrono::Local::now().to_rfc3339();
+    let (success, output) = match action {
+        TaskAction::BackendRequest { method, path, body } => {
+            crate::http::call_backend(app, method, path, body.as_ref(), BACKEND_TIMEOUT).await
+        }
+        TaskAction::RunCommand {
+            command,
+            cwd,
+            env,
+            timeout_ms,
+        } => {
+            // 计划任务与自动化在锁定期间照常执行，只检查能力开关
+            let result = match crate::permissions::check_unattended_capability(app, "system.run") {
+                Ok(()) => crate::run_shell_command(
+                    app,
+                    Some(source.as_str()),
+                    command.clone(),
+                    cwd.clone(),
+                    env.clone(),
+                    *timeout_ms,
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string()),
+                Err(e) => {
+                    audit::record(
+                        app,
+                        "system.run",
+                        false,
+                        serde_json::json!({
+                            "source": source,
+                            "command": command,
+                            "error": e.message,
+                        }),
+                    );
+                    Err(e.to_string())
+                }
+            };
+            match result {
+                Ok(r) => (r.success, if r.success { r.stdout } else { r.stderr }),
+                Err(e) => (false, e),
+            }
+        }
+        // 旧版本保存的任务可能没有前缀，执行时同样检查
+        TaskAction::EmitEvent { event, .. } if !event.starts_with(EVENT_PREFIX) => (
+            false,
+            format!("Event name must start with '{}'", EVENT_PREFIX),
+        ),
+        TaskAction::EmitEvent { event, payload } => match app.emit(event, payload) {
+            Ok(()) => (true, String::new()),
+            Err(e) => (false, e.to_string()),
+        },
+    };
+    TaskRunResult {
+        at,
+        success,
+        output: truncate_output(output),
+    }
+}
+
+/// 取出已到期的任务，并把它们的下一次执行时间推进到当前时间之后
+///
+/// 一次性任务的 next_run 置为 None 并立即持久化，保证不会重复执行。
+fn take_due(app: &tauri::AppHandle) -> Vec<ScheduledTask> {
+    let now = chrono::Local::now();
+    let state = app.state::<Mutex<TaskScheduler>>();
+    let mut guard = match state.lock() {
+        Ok(g) => g,
+        Err(_) => return vec![],
+    };
+
+    let mut due = Vec::new();
+    for task in guard.tasks.iter_mut() {
+        let is_due = task
+            .next_run
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .is_some_and(|t| t <= now);
+        if is_due {
+            due.push(task.clone());
+            task.next_run = task.cron.as_deref().and_then(next_run_after_now);
+        }
+    }
+    if !due.is_empty() {
+        persist(app, &guard.tasks);
+    }
+    due
+}
+
+/// 记录执行结果（一次性任务执行完成后移除；任务在执行期间被删除时忽略）
+fn record_result(app: &tauri::AppHandle, id: &str, result: &TaskRunResult) {
+    let state = app.state::<Mutex<TaskScheduler>>();
+    let Ok(mut guard) = state.lock() else {
+        return;
+    };
+    let Some(index) = guard.tasks.iter().position(|t| t.id == id) else {
+        return;
+    };
+    if guard.tasks[index].cron.is_none() {
+        guard.tasks.remove(index);
+    } else {
+        guard.tasks[index].last_run = Some(result.clone());
+    }
+    persist(app, &guard.tasks);
+}
+
+/// 完成通知（仅 notify 为 true 的任务）
+fn notify_completion(app: &tauri::AppHandle, task: &ScheduledTask, result: &TaskRunResult) {
+    if !task.notify {
+        return;
+    }
+    let title = if result.success {
+        i18n::t("notify.scheduled_task.succeeded")
+    } else {
+        i18n::t("notify.scheduled_task.failed")
+    };
+    let body = match (&task.name, &task.action) {
+        (Some(name), _) => name.clone(),
+        (None, TaskAction::RunCommand { command, .. }) => command.join(" "),
+        (None, _) => task.id.clone(),
+    };
+    notifications::notify(app, &title, &body, !result.success);
+}
+
+/// 加载持久化的定时任务并启动调度循环
+pub fn start_scheduler(app: tauri::AppHandle) {
+    let mut saved: Vec<ScheduledTask> = load_json(&app, SCHEDULES_FILE);
+    // 一次性任务保留原执行时间（已过期的会在第一轮调度时执行）；
+    // 已开始但未记录结果的一次性任务说明上次执行被中断，不再重复执行
+    saved.retain(|task| {
+        let interrupted = task.cron.is_none() && task.next_run.is_none();
+        if interrupted {
+            debug_log(&format!(
+                "[scheduler] 一次性任务上次执行被中断，已移除 (id={})",
+                task.id
+            ));
+        }
+        !interrupted
+    });
+    for task in saved.iter_mut().filter(|t| t.cron.is_some()) {
+        task.next_run = task.cron.as_deref().and_then(next_run_after_now);
+    }
+    if !saved.is_empty() {
+        debug_log(&format!("[scheduler] 恢复 {} 个定时任务", saved.len()));
+    }
+    if let Ok(mut guard) = app.state::<Mutex<TaskScheduler>>().lock() {
+        guard.tasks = saved;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            for task in take_due(&app) {
+                let app = app.clone();
+                // 每个任务独立执行，耗时任务不阻塞其它任务的调度
+                tauri::async_runtime::spawn(async move {
+                    debug_log(&format!("[scheduler] 执行定时任务 (id={})", task.id));
+                    let result = run_action(&app, ActionSource::Task(&task.id), &task.action).await;
+                    if !result.success {
+                        debug_log(&format!(
+                            "[scheduler] 定时任务执行失败 (id={}): {}",
+                            task.id, result.output
+                        ));
+                    }
+                    record_result(&app, &task.id, &result);
+                    notify_completion(&app, &task, &result);
+                    let _ = app.emit(
+                        "scheduled-task-ran",
+                        serde_json::json!({ "id": task.id, "result": result }),
+                    );
+                });
+            }
+            tokio::time::sleep(Duration::from_secs(SCHEDULER_TICK_SECS)).await;
+        }
+    });
+}
+
+/// 校验动作参数
+pub(crate) fn validate_action(action: &TaskAction) -> Result<(), String> {
+    match action {
+        TaskAction::RunCommand { command, .. } if command.is_empty() => {
+            Err("Command cannot be empty".to_string())
+        }
+        TaskAction::BackendRequest { path, .. } if !path.starts_with('/') => {
+            Err("Backend path must start with '/'".to_string())
+        }
+        TaskAction::EmitEvent { event, .. } if event.len() <= EVENT_PREFIX.len() => {
+            match event.strip_prefix(EVENT_PREFIX) {
+                Some(_) => Err("Event name cannot be empty".to_string()),
+                None => Err(format!("Event name must start with '{}'", EVENT_PREFIX)),
+            }
+        }
+        TaskAction::EmitEvent { event, .. } if !event.starts_with(EVENT_PREFIX) => {
+            Err(format!("Event name must start with '{}'", EVENT_PREFIX))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 动作执行时需要的能力（登记时即检查，避免登记注定被拒绝的任务）
+pub(crate) fn check_action_capability(
+    app: &tauri::AppHandle,
+    action: &TaskAction,
+) -> Result<(), String> {
+    match action {
+        TaskAction::RunCommand { .. } => {
+            crate::permissions::check_capability(app, "system.run").map_err(|e| e.to_string())
+        }
+        TaskAction::BackendRequest { .. } | TaskAction::EmitEvent { .. } => Ok(()),
+    }
+}
+
+/// 创建定时任务，返回登记的任务（同 ID 会覆盖已有任务）
+#[tauri::command]
+pub async fn schedule_task(
+    app: tauri::AppHandle,
+    cron_expr: String,
+    action: TaskAction,
+    id: Option<String>,
+    name: Option<String>,
+) -> Result<ScheduledTask, String> {
+    parse_cron(&cron_expr)?;
+    validate_action(&action)?;
+    check_action_capability(&app, &action)?;
+
+    let task = ScheduledTask {
+        id: id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        next_run: next_run_after_now(&cron_expr),
+        cron: Some(cron_expr.clone()),
+        run_at: None,
+        notify: false,
+        action,
+        name,
+        created_at: chrono::Local::now().to_rfc3339(),
+        last_run: None,
+    };
+
+    let state = app.state::<Mutex<TaskScheduler>>();
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    guard.tasks.retain(|t| t.id != task.id);
+    guard.tasks.push(task.clone());
+    save_json(&app, SCHEDULES_FILE, &guard.tasks)?;
+    debug_log(&format!(
+        "[scheduler] 已登记定时任务 (id={}, cron={})",
+        task.id, cron_expr
+    ));
+    Ok(task)
+}
+
+/// 在指定时间执行一次命令（持久化，应用重启后仍会执行），完成后发送系统通知
+///
+/// `when` 为 RFC 3339 时间；已过去的时间会在下一轮调度时立即执行。
+#[tauri::command]
+pub async fn run_command_at(
+    app: tauri::AppHandle,
+    when: String,
+    command: Vec<String>,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+    timeout_ms: Option<u64>,
+    name: Option<String>,
+    notify: Option<bool>,
+) -> Result<ScheduledTask, String> {
+    let at = chrono::DateTime::parse_from_rfc3339(&when)
+        .map_err(|e| format!("无效的时间格式: {}", e))?
+        .to_rfc3339();
+    let action = TaskAction::RunCommand {
+        command,
+        cwd,
+        env,
+        timeout_ms,
+    };
+    validate_action(&action)?;
+
+    let task = ScheduledTask {
+        id: uuid::Uuid::new_v4().to_string(),
+        cron: None,
+        run_at: Some(at.clone()),
+        notify: notify.unwrap_or(true),
+        action,
+        name,
+        created_at: chrono::Local::now().to_rfc3339(),
+        next_run: Some(at),
+        last_run: None,
+    };
+
+    let state = app.state::<Mutex<TaskScheduler>>();
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    guard.tasks.push(task.clone());
+    save_json(&app, SCHEDULES_FILE, &guard.tasks)?;
+    debug_log(&format!(
+        "[scheduler] 已登记一次性命令 (id={}, at={})",
+        task.id, when
+    ));
+    Ok(task)
+}
+
+/// 列出全部定时任务
+#[tauri::command]
+pub async fn list_schedules(app: tauri::AppHandle) -> Result<Vec<ScheduledTask>, String> {
+    let state = app.state::<Mutex<TaskScheduler>>();
+    let guard = state.lock().map_err(|e| e.to_string())?;
+    Ok(guard.tasks.clone())
+}
+
+/// 删除定时任务，返回是否存在
+#[tauri::command]
+pub async fn delete_schedule(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+    let state = app.state::<Mutex<TaskScheduler>>();
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    let before = guard.tasks.len();
+    guard.tasks.retain(|t| t.id != id);
+    let removed = guard.tasks.len() != before;
+    if removed {
+        save_json(&app, SCHEDULES_FILE, &guard.tasks)?;
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone, Weekday};
+
+    /// 2024-01-01 00:00（周一）之后的前 n 次执行所在的星期
+    fn weekdays(expr: &str, n: usize) -> Vec<Weekday> {
+        let start = chrono::Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        parse_cron(expr)
+            .unwrap()
+            .after(&start)
+            .take(n)
+            .map(|t| t.weekday())
+            .collect()
+    }
+
+    #[test]
+    fn five_field_weekdays_use_standard_numbering() {
+        use Weekday::*;
+        assert_eq!(weekdays("0 9 * * 1-5", 5), [Mon, Tue, Wed, Thu, Fri]);
+        assert_eq!(weekdays("0 0 * * 0", 2), [Sun, Sun]);
+        assert_eq!(weekdays("0 0 * * 7", 2), [Sun, Sun]);
+        assert_eq!(weekdays("0 0 * * 5-7", 3), [Fri, Sat, Sun]);
+        assert_eq!(weekdays("0 0 * * 1,3", 3), [Wed, Mon, Wed]);
+        assert_eq!(weekdays("0 0 * * */2", 4), [Tue, Thu, Sat, Sun]);
+        assert_eq!(weekdays("0 0 * * MON-FRI", 2), [Tue, Wed]);
+        assert!(parse_cron("* * * * 0").is_ok());
+    }
+
+    #[test]
+    fn invalid_weekdays_are_rejected() {
+        assert!(parse_cron("0 0 * * 8").is_err());
+        assert!(parse_cron("0 0 * * 5-1").is_err());
+        assert!(parse_cron("0 0 * * */0").is_err());
+    }
+
+    #[test]
+    fn six_field_expressions_are_passed_through() {
+        // cron 库写法：星期 1-7，周日为 1
+        assert_eq!(weekdays("0 0 0 * * 1", 1), [Weekday::Sun]);
+    }
+
+    #[test]
+    fn emit_event_requires_prefix() {
+        let action = |event: &str| TaskAction::EmitEvent {
+            event: event.to_string(),
+            payload: serde_json::Value::Null,
+        };
+        assert!(validate_action(&action("scheduled:reminder")).is_ok());
+        assert!(validate_action(&action("backend-ready")).is_err());
+        assert!(validate_action(&action("scheduled:")).is_err());
+        assert!(validate_action(&action("")).is_err());
+    }
+}
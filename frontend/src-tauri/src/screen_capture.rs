@@ -0,0 +1,216 @@
+// ============================================================================
+// 屏幕录制权限预检
+// ============================================================================
+//
+// macOS 10.15 起未授予屏幕录制权限时，截图 / 录屏不会报错，只是其他应用的窗口变成
+// 空白（只剩桌面与本应用窗口）。开始截取前调用 preflight_screen_capture：
+// - CGPreflightScreenCaptureAccess 检查权限（request 为 true 时调用
+//   CGRequestScreenCaptureAccess，把应用登记到系统设置的列表并弹出系统提示）
+// - 列出每个显示器上将被遮挡的其他应用窗口（CGWindowListCopyWindowInfo，osascript JXA）
+// 前端据此给出具体提示，并通过 open_system_preferences("screen") 跳转授权。
+// Windows / Linux 没有对应的权限，始终返回已授权。
+
+use crate::debug_log;
+use crate::displays::{self, DisplayInfo};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureWindow {
+    /// 所属应用名称
+    pub app: String,
+    pub pid: i64,
+    /// 窗口标题（未授权时系统不返回其他应用的标题）
+    pub title: Option<String>,
+    /// 逻辑坐标（点）
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DisplayCaptureStatus {
+    pub display: DisplayInfo,
+    /// 截取该显示器时内容完整
+    pub capturable: bool,
+    /// 将变成空白的窗口
+    pub blocked_windows: Vec<CaptureWindow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenCapturePreflight {
+    pub granted: bool,
+    /// 当前平台需要单独授权屏幕录制
+    pub permission_required: bool,
+    pub displays: Vec<DisplayCaptureStatus>,
+    /// 未授权时的提示（列出受影响的显示器与应用）
+    pub message: Option<String>,
+}
+
+#[cfg(target_os = "macos")]
+mod access {
+    use super::CaptureWindow;
+    use std::process::Command as SysCommand;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+        fn CGRequestScreenCaptureAccess() -> bool;
+    }
+
+    /// 屏幕上的普通窗口（layer 0）；17 = kCGWindowListOptionOnScreenOnly |
+    /// kCGWindowListExcludeDesktopElements
+    const WINDOWS_SCRIPT: &str = r#"
+ObjC.import('CoreGraphics');
+const list = ObjC.deepUnwrap(ObjC.castRefToObject($.CGWindowListCopyWindowInfo(17, 0))) || [];
+JSON.stringify(list.filter(w => w.kCGWindowLayer === 0).map(w => ({
+  app: w.kCGWindowOwnerName || '',
+  pid: w.kCGWindowOwnerPID,
+  title: w.kCGWindowName || null,
+  x: w.kCGWindowBounds.X,
+  y: w.kCGWindowBounds.Y,
+  width: w.kCGWindowBounds.Width,
+  height: w.kCGWindowBounds.Height,
+})));
+"#;
+
+    pub fn granted() -> bool {
+        unsafe { CGPreflightScreenCaptureAccess() }
+    }
+
+    pub fn request() -> bool {
+        unsafe { CGRequestScreenCaptureAccess() }
+    }
+
+    pub fn other_windows() -> Result<Vec<CaptureWindow>, String> {
+        let output = SysCommand::new("osascript")
+            .args(["-l", "JavaScript", "-e", WINDOWS_SCRIPT])
+            .output()
+            .map_err(|e| format!("枚举窗口失败: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "枚举窗口失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        let windows: Vec<CaptureWindow> = serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("解析窗口列表失败: {}", e))?;
+        let own = std::process::id() as i64;
+        Ok(windows
+            .into_iter()
+            .filter(|w| w.pid != own && w.width > 0.0 && w.height > 0.0)
+            .collect())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod access {
+    use super::CaptureWindow;
+
+    pub fn granted() -> bool {
+        true
+    }
+
+    pub fn request() -> bool {
+        true
+    }
+
+    pub fn other_windows() -> Result<Vec<CaptureWindow>, String> {
+        Ok(Vec::new())
+    }
+}
+
+/// 是否已授予屏幕录制权限（无此权限概念的平台始终为 true）
+pub fn screen_capture_granted() -> bool {
+    access::granted()
+}
+
+/// 窗口中心点所在的显示器（显示器坐标为物理像素，窗口为逻辑坐标）
+fn contains(display: &DisplayInfo, window: &CaptureWindow) -> bool {
+    let scale = display.scale_factor.max(1.0);
+    let (left, top) = (display.x as f64 / scale, display.y as f64 / scale);
+    let (right, bottom) = (
+        left + display.width as f64 / scale,
+        top + display.height as f64 / scale,
+    );
+    let (cx, cy) = (
+        window.x + window.width / 2.0,
+        window.y + window.height / 2.0,
+    );
+    cx >= left && cx < right && cy >= top && cy < bottom
+}
+
+fn blocked_message(displays: &[DisplayCaptureStatus]) -> String {
+    let affected: Vec<String> = displays
+        .iter()
+        .filter(|d| !d.capturable)
+        .map(|d| {
+            let mut apps: Vec<&str> = d.blocked_windows.iter().map(|w| w.app.as_str()).collect();
+            apps.sort_unstable();
+            apps.dedup();
+            format!(
+                "{}（{}）",
+                d.display.name.as_deref().unwrap_or(&d.display.id),
+                apps.join("、")
+            )
+        })
+        .collect();
+    if affected.is_empty() {
+        "未授予屏幕录制权限，其他应用的窗口将显示为空白".to_string()
+    } else {
+        format!(
+            "未授予屏幕录制权限，以下显示器上的窗口将显示为空白：{}",
+            affected.join("；")
+        )
+    }
+}
+
+/// 截图 / 录屏前检查屏幕录制权限，列出将变成空白的显示器与窗口
+#[tauri::command]
+pub async fn preflight_screen_capture(
+    app: tauri::AppHandle,
+    request: Option<bool>,
+) -> Result<ScreenCapturePreflight, String> {
+    let display_list = displays::list_displays(&app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut granted = access::granted();
+        if !granted && request.unwrap_or(false) {
+            granted = access::request();
+        }
+        let windows = if granted {
+            Vec::new()
+        } else {
+            access::other_windows().unwrap_or_else(|e| {
+                debug_log(&format!("[screen_capture] {}", e));
+                Vec::new()
+            })
+        };
+        let displays: Vec<DisplayCaptureStatus> = display_list
+            .into_iter()
+            .map(|display| {
+                let blocked_windows: Vec<CaptureWindow> = windows
+                    .iter()
+                    .filter(|w| contains(&display, w))
+                    .cloned()
+                    .collect();
+                DisplayCaptureStatus {
+                    capturable: blocked_windows.is_empty(),
+                    display,
+                    blocked_windows,
+                }
+            })
+            .collect();
+        let message = (!granted).then(|| blocked_message(&displays));
+        if let Some(message) = &message {
+            debug_log(&format!("[screen_capture] {}", message));
+        }
+        ScreenCapturePreflight {
+            granted,
+            permission_required: cfg!(target_os = "macos"),
+            displays,
+            message,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,163 @@
+// ============================================================================
+// 自检报告
+// ============================================================================
+//
+// run_self_test 汇总各项运行时检查，供设置页/诊断页展示。
+// 端口暴露检查：从本机非回环地址尝试连接应用监听的端口，
+// 能连通即说明局域网内其他设备也可能访问到。
+
+use crate::{audit, debug_log, i18n, notifications};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::time::Duration;
+use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+/// 探测单个地址的连接超时
+const EXPOSURE_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestCheck {
+    /// 检查项标识（如 "backend.binding"）
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+    /// 检查项的附加数据
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub generated_at: String,
+    pub passed: bool,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+/// 本机所有非回环 IP 地址
+fn non_loopback_addrs() -> Vec<IpAddr> {
+    if_addrs::get_if_addrs()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|i| !i.is_loopback())
+        .map(|i| i.ip())
+        .filter(|ip| match ip {
+            // 链路本地 IPv6 需要 scope id，无法直接连接
+            IpAddr::V6(v6) => (v6.segments()[0] & 0xffc0) != 0xfe80,
+            IpAddr::V4(_) => true,
+        })
+        .collect()
+}
+
+/// 返回可以从非回环地址连通该端口的地址列表（为空表示仅本机可访问）
+pub fn exposed_addresses(port: u16) -> Vec<String> {
+    non_loopback_addrs()
+        .into_iter()
+        .map(|ip| SocketAddr::new(ip, port))
+        .filter(|addr| TcpStream::connect_timeout(addr, EXPOSURE_PROBE_TIMEOUT).is_ok())
+        .map(|addr| addr.to_string())
+        .collect()
+}
+
+/// 后端 sidecar 是否仅监听 127.0.0.1
+pub fn check_backend_binding(app: &tauri::AppHandle) -> SelfTestCheck {
+    let crate::BackendInfo {
+        port,
+        socket_path,
+        is_sidecar,
+        ..
+    } = app.state::<crate::BackendState>().info();
+
+    if let Some(socket) = socket_path {
+        return SelfTestCheck {
+            name: "backend.binding".to_string(),
+            passed: true,
+            detail: "后端使用 Unix socket，未监听网络端口".to_string(),
+            data: serde_json::json!({"socket": socket}),
+        };
+    }
+
+    let exposed = exposed_addresses(port);
+    let passed = exposed.is_empty();
+    let detail = if passed {
+        format!("后端仅监听本机 (127.0.0.1:{})", port)
+    } else if !is_sidecar {
+        format!("开发模式后端可从局域网访问: {}", exposed.join(", "))
+    } else {
+        format!("后端可从局域网访问: {}", exposed.join(", "))
+    };
+    SelfTestCheck {
+        name: "backend.binding".to_string(),
+        passed,
+        detail,
+        data: serde_json::json!({"port": port, "exposed": exposed, "is_sidecar": is_sidecar}),
+    }
+}
+
+/// 应用其他监听端口的暴露情况（远程控制按设计对局域网开放，仅报告不判失败）
+fn check_listeners(app: &tauri::AppHandle) -> Vec<SelfTestCheck> {
+    let mut checks = Vec::new();
+
+    if crate::remote::is_enabled(app) {
+        let port = crate::remote::NODE_CONTROL_PORT;
+        let exposed = exposed_addresses(port);
+        checks.push(SelfTestCheck {
+            name: "remote.binding".to_string(),
+            passed: true,
+            detail: format!("远程控制已开启，局域网可访问 (需配对码): {}", exposed.join(", ")),
+            data: serde_json::json!({"port": port, "exposed": exposed}),
+        });
+    }
+
+    if let Some(port) = crate::webhook::running_port(app) {
+        let exposed = exposed_addresses(port);
+        checks.push(SelfTestCheck {
+            name: "webhook.binding".to_string(),
+            passed: exposed.is_empty(),
+            detail: if exposed.is_empty() {
+                format!("Webhook 仅监听本机 (127.0.0.1:{})", port)
+            } else {
+                format!("Webhook 可从局域网访问: {}", exposed.join(", "))
+            },
+            data: serde_json::json!({"port": port, "exposed": exposed}),
+        });
+    }
+
+    checks
+}
+
+/// 启动时检查后端绑定，若可从外部访问则提醒用户并写入审计日志
+pub fn verify_backend_binding(app: &tauri::AppHandle) {
+    let check = check_backend_binding(app);
+    debug_log(&format!("[selftest] {}", check.detail));
+    if check.passed {
+        return;
+    }
+
+    audit::record(app, "security.port_exposure", false, check.data.clone());
+    notifications::notify(app, &i18n::t("notify.security.title"), &check.detail, true);
+    app.dialog()
+        .message(i18n::tf("dialog.port_exposed.body", &[&check.detail]))
+        .title(i18n::t("dialog.port_exposed.title"))
+        .kind(MessageDialogKind::Warning)
+        .show(|_| {});
+}
+
+/// 运行自检并返回报告
+pub fn run_checks(app: &tauri::AppHandle) -> SelfTestReport {
+    let mut checks = vec![check_backend_binding(app)];
+    checks.extend(check_listeners(app));
+
+    SelfTestReport {
+        generated_at: chrono::Local::now().to_rfc3339(),
+        passed: checks.iter().all(|c| c.passed),
+        checks,
+    }
+}
+
+/// 运行自检
+#[tauri::command]
+pub async fn run_self_test(app: tauri::AppHandle) -> Result<SelfTestReport, String> {
+    tauri::async_runtime::spawn_blocking(move || run_checks(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
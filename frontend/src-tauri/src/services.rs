@@ -0,0 +1,138 @@
+// ============================================================================
+// macOS 服务菜单（Services）
+// ============================================================================
+//
+// 在其它应用中选中文字后，可通过右键菜单或应用菜单的「服务」子菜单调用小搭子：
+// - 询问小搭子（askAgent）：把选中的文字交给 Agent
+// - 截屏识别并询问小搭子（ocrScreen）：截取屏幕识别文字，选中的文字作为提问（可为空）
+// 服务在 Info.plist 的 NSServices 中声明，这里把服务提供者注册到 NSApplication，
+// 收到请求后转成 intents.rs 的意图执行（确认、能力检查与 xiaodazi:// URL 相同）。
+// 系统共享菜单（share sheet）只能由单独打包的 App 扩展提供，Tauri 打包不支持，
+// 因此统一通过服务菜单接入。
+
+use crate::debug_log;
+use crate::intents::{self, Intent};
+use std::ffi::{c_char, c_void, CStr};
+use std::sync::OnceLock;
+
+type Id = *mut c_void;
+type Sel = *const c_void;
+
+#[link(name = "objc")]
+extern "C" {
+    fn objc_getClass(name: *const c_char) -> Id;
+    fn objc_allocateClassPair(superclass: Id, name: *const c_char, extra_bytes: usize) -> Id;
+    fn objc_registerClassPair(cls: Id);
+    fn class_addMethod(cls: Id, name: Sel, imp: *const c_void, types: *const c_char) -> bool;
+    fn sel_registerName(name: *const c_char) -> Sel;
+    fn objc_msgSend();
+}
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    static NSPasteboardTypeString: Id;
+    fn NSUpdateDynamicServices();
+}
+
+/// 服务方法的签名：- (void)xxx:(NSPasteboard *)pboard userData:(NSString *)data error:(NSString **)error
+const SERVICE_METHOD_TYPES: &CStr = c"v@:@@^@";
+
+/// 服务回调需要的应用句柄（回调由 AppKit 直接调用，无法携带上下文）
+static APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+unsafe fn send0(receiver: Id, selector: &CStr) -> Id {
+    let f: unsafe extern "C" fn(Id, Sel) -> Id = std::mem::transmute(objc_msgSend as *const ());
+    f(receiver, sel_registerName(selector.as_ptr()))
+}
+
+unsafe fn send1(receiver: Id, selector: &CStr, arg: Id) -> Id {
+    let f: unsafe extern "C" fn(Id, Sel, Id) -> Id = std::mem::transmute(objc_msgSend as *const ());
+    f(receiver, sel_registerName(selector.as_ptr()), arg)
+}
+
+/// 读取剪贴板中的文字（去除首尾空白，为空时返回 None）
+unsafe fn pasteboard_text(pboard: Id) -> Option<String> {
+    if pboard.is_null() {
+        return None;
+    }
+    let string = send1(pboard, c"stringForType:", NSPasteboardTypeString);
+    if string.is_null() {
+        return None;
+    }
+    let utf8 = send0(string, c"UTF8String") as *const c_char;
+    if utf8.is_null() {
+        return None;
+    }
+    let text = CStr::from_ptr(utf8).to_string_lossy().trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn dispatch(intent: Intent) {
+    let Some(app) = APP.get().cloned() else {
+        return;
+    };
+    tauri::async_runtime::spawn(async move {
+        let _ = intents::dispatch(&app, &intent).await;
+    });
+}
+
+extern "C" fn ask_agent(_this: Id, _cmd: Sel, pboard: Id, _data: Id, _error: *mut Id) {
+    match unsafe { pasteboard_text(pboard) } {
+        Some(text) => dispatch(Intent::Ask {
+            text,
+            conversation_id: None,
+        }),
+        None => debug_log("[services] 询问小搭子：没有选中的文字"),
+    }
+}
+
+extern "C" fn ocr_screen(_this: Id, _cmd: Sel, pboard: Id, _data: Id, _error: *mut Id) {
+    dispatch(Intent::OcrScreen {
+        ask: unsafe { pasteboard_text(pboard) },
+    });
+}
+
+/// 注册服务提供者（需在主线程调用 NSApplication）
+unsafe fn register() {
+    let cls = objc_allocateClassPair(
+        objc_getClass(c"NSObject".as_ptr()),
+        c"XiaodaziServiceProvider".as_ptr(),
+        0,
+    );
+    if cls.is_null() {
+        debug_log("[services] 服务提供者类已存在");
+        return;
+    }
+    let methods: [(&CStr, *const c_void); 2] = [
+        (c"askAgent:userData:error:", ask_agent as *const c_void),
+        (c"ocrScreen:userData:error:", ocr_screen as *const c_void),
+    ];
+    for (selector, imp) in methods {
+        class_addMethod(
+            cls,
+            sel_registerName(selector.as_ptr()),
+            imp,
+            SERVICE_METHOD_TYPES.as_ptr(),
+        );
+    }
+    objc_registerClassPair(cls);
+
+    let provider = send0(send0(cls, c"alloc"), c"init");
+    let ns_app = send0(
+        objc_getClass(c"NSApplication".as_ptr()),
+        c"sharedApplication",
+    );
+    send1(ns_app, c"setServicesProvider:", provider);
+    NSUpdateDynamicServices();
+    debug_log("[services] 已注册服务菜单");
+}
+
+/// 注册服务菜单（启动时调用）
+pub fn install(app: &tauri::AppHandle) {
+    if APP.set(app.clone()).is_err() {
+        return;
+    }
+    if let Err(e) = app.run_on_main_thread(|| unsafe { register() }) {
+        debug_log(&format!("[services] 注册服务菜单失败: {}", e));
+    }
+}
@@ -0,0 +1,397 @@
+// ============================================================================
+// 应用设置（Rust 壳层）
+// ============================================================================
+//
+// 持久化在应用数据目录下的 settings.json。
+// 部分设置（如端口、传输方式）在创建 Tauri 应用之前就要用到，
+// 因此数据目录按 Tauri 的规则（系统数据目录 + identifier）自行计算。
+
+use crate::debug_log;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+/// 应用标识（与 tauri.conf.json 中的 identifier 保持一致）
+pub const APP_IDENTIFIER: &str = "com.zenflux.agent";
+
+/// 设置文件名
+const SETTINGS_FILE: &str = "settings.json";
+
+/// 不能通过 update_settings 修改的设置项 → 负责修改它的专用命令（None 表示由应用自身维护）
+/// （专用命令负责校验、审计与副作用，绕过它们会留下不一致的状态）
+const PROTECTED_SETTINGS: &[(&str, Option<&str>)] = &[
+    // 延长时需要身份验证
+    ("auth_grace_period_secs", Some("set_auth_grace_period")),
+    // 关闭时需要身份验证，同步后端并刷新菜单
+    ("safe_mode", Some("set_safe_mode")),
+    // 开启时需要身份验证，同步后端
+    ("disabled_capabilities", Some("set_capability_enabled")),
+    // 校验长度与字符，同步后端并刷新局域网广播
+    ("node_display_name", Some("set_display_name")),
+    // 检查快捷键冲突并重新注册
+    ("shortcut_overrides", Some("set_shortcut")),
+    // 校验语言并刷新托盘与应用菜单
+    ("language", Some("set_language")),
+    // 校验窗口材质并应用到窗口
+    ("window_chrome", Some("set_window_chrome")),
+    // 限制缩放范围并应用到窗口
+    ("window_zoom", Some("set_zoom")),
+    ("disabled_tools", Some("set_tool_enabled")),
+    ("onboarding", Some("complete_onboarding_step")),
+    ("last_sidecar_port", None),
+    ("overlay_position", None),
+];
+
+/// 只能通过 update_security_settings 修改的安全相关设置项（需要身份验证）
+const SECURITY_SETTINGS: &[&str] = &[
+    "task_command_jail",
+    "blocked_env_keys",
+    "allowed_env_keys",
+    "auto_lock_minutes",
+    "crash_report_url",
+    "crash_upload_consent",
+    "dev_backend_command",
+    "dev_backend_cwd",
+    // 关闭后快捷指令无需确认即可向 Agent 提问
+    "confirm_url_intents",
+    // 开启后 Agent 可读取最近使用的文件与应用
+    "share_recent_items",
+    // 关闭后日志与诊断包中保留用户主目录与用户名
+    "redact_home_path",
+];
+
+/// 后端 sidecar 传输方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SidecarTransport {
+    /// 监听 127.0.0.1 上的 TCP 端口（默认）
+    #[default]
+    Tcp,
+    /// 监听数据目录内的 Unix domain socket（仅 macOS/Linux）
+    Uds,
+}
+
+/// 应用更新策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdatePolicy {
+    /// 只提示有新版本，由用户手动更新（默认）
+    #[default]
+    NotifyOnly,
+    /// 后台下载，退出应用时安装
+    InstallOnQuit,
+    /// 后台下载，在空闲时段自动安装并重启
+    Automatic,
+}
+
+/// 崩溃报告上传授权
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashUploadConsent {
+    /// 每次启动发现新报告时询问（默认）
+    #[default]
+    Ask,
+    /// 自动发送
+    Always,
+    /// 从不发送，仅保留在本地
+    Never,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    /// 后端传输方式（重启后生效）
+    pub sidecar_transport: SidecarTransport,
+    /// 后端启动超时（秒），首次启动需要解压模型的慢速机器可调大
+    pub backend_startup_timeout_secs: u64,
+    /// 启动阶段健康检查的最大轮询间隔（毫秒）
+    pub backend_health_poll_ms: u64,
+    /// 上次成功启动 sidecar 的端口（下次启动优先使用，保持 API 地址稳定）
+    pub last_sidecar_port: Option<u16>,
+    /// 界面语言（如 "zh-CN"、"en"），None 表示跟随系统
+    pub language: Option<String>,
+    /// 应用更新策略
+    pub update_policy: UpdatePolicy,
+    /// 自动更新允许的时段（本地时间，小时，[start, end)，可跨零点）
+    pub update_window_start_hour: u32,
+    pub update_window_end_hour: u32,
+    /// 崩溃报告是否上传
+    pub crash_upload_consent: CrashUploadConsent,
+    /// 崩溃报告上传地址（未配置时报告只保留在本地）
+    pub crash_report_url: Option<String>,
+    /// 日志中是否把用户主目录与用户名替换掉（凭据始终脱敏）
+    pub redact_home_path: bool,
+    /// 数据目录所在磁盘剩余空间低于该值（MB）时提醒
+    pub low_disk_space_mb: u64,
+    /// 是否自动清理旧日志、截图、录屏与临时工作区
+    pub retention_enabled: bool,
+    /// 超过该天数的数据会被清理
+    pub retention_max_age_days: u64,
+    /// 每类数据的总大小上限（MB），超出时从最旧的开始清理
+    pub retention_max_size_mb: u64,
+    /// 通过 xiaodazi:// 快捷指令向 Agent 提问前是否需要确认（运行命令始终需要确认）
+    pub confirm_url_intents: bool,
+    /// 使用电池且电量低时暂停耗电任务（录屏、本地模型推理等）
+    pub pause_heavy_tasks_on_battery: bool,
+    /// 电池低电量阈值（百分比）
+    pub battery_low_percent: u8,
+    /// 是否允许 Agent 调整系统音量与静音（system.volume）
+    pub volume_control_enabled: bool,
+    /// 节点显示名称（如 "Work MacBook"），None 表示使用主机名
+    pub node_display_name: Option<String>,
+    /// 关闭的节点能力（完整名称或分组前缀，如 "camera"）
+    pub disabled_capabilities: Vec<String>,
+    /// 系统身份验证（Touch ID / Windows Hello）通过后的免验证时长（秒），0 表示每次都验证
+    pub auth_grace_period_secs: u64,
+    /// 系统空闲多少分钟后自动锁定应用（解锁需系统身份验证），0 表示关闭
+    pub auto_lock_minutes: u64,
+    /// run_command 中禁止传入的环境变量（支持结尾通配符，如 "AWS_*"）
+    pub blocked_env_keys: Vec<String>,
+    /// 即使命中 blocked_env_keys 也允许传入的环境变量（同样支持结尾通配符）
+    pub allowed_env_keys: Vec<String>,
+    /// 传入 task_id 的 run_command 只能在该任务工作区内执行（见 jail.rs）
+    pub task_command_jail: bool,
+    /// 状态浮窗上次的位置（逻辑坐标），None 表示屏幕右上角
+    pub overlay_position: Option<(f64, f64)>,
+    /// 主窗口外观（毛玻璃材质、红绿灯按钮位置，见 chrome.rs）
+    pub window_chrome: crate::chrome::WindowChrome,
+    /// 各窗口的缩放比例（窗口标签 → 比例），未记录的窗口为 1.0
+    pub window_zoom: HashMap<String, f64>,
+    /// 安全模式：关闭执行命令、进程管理等高风险能力（托管策略可强制开启）
+    pub safe_mode: bool,
+    /// 后端运行中失去响应时自动重启 sidecar（见 health.rs）
+    pub backend_auto_restart: bool,
+    /// 开发模式下 8000 端口没有后端时执行的启动命令（如 "uvicorn main:app --port 8000"），
+    /// None 表示不自动启动、由开发者手动启动后端
+    pub dev_backend_command: Option<String>,
+    /// dev_backend_command 的工作目录，None 表示应用的当前目录
+    pub dev_backend_cwd: Option<String>,
+    /// 停用的托管工具（tools.lock.json 中的名称，见 tools.rs）
+    pub disabled_tools: Vec<String>,
+    /// 允许 Agent 读取最近使用的文件与应用（get_recent_items，见 recent.rs），默认关闭
+    pub share_recent_items: bool,
+    /// 屏幕阅读器开启时同时朗读系统通知（见 notifications.rs）
+    pub speak_notifications: bool,
+    /// 改绑的快捷键（快捷键 ID → 加速键），未记录的使用默认值（见 shortcuts.rs）
+    pub shortcut_overrides: HashMap<String, String>,
+    /// 首次运行引导进度（见 onboarding.rs）；旧版本的设置文件没有该字段，视为已完成
+    #[serde(default = "crate::onboarding::OnboardingState::finished")]
+    pub onboarding: crate::onboarding::OnboardingState,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            sidecar_transport: SidecarTransport::default(),
+            backend_startup_timeout_secs: crate::BACKEND_STARTUP_TIMEOUT_SECS,
+            backend_health_poll_ms: crate::BACKEND_HEALTH_POLL_MAX_MS,
+            last_sidecar_port: None,
+            language: None,
+            update_policy: UpdatePolicy::default(),
+            update_window_start_hour: 2,
+            update_window_end_hour: 5,
+            crash_upload_consent: CrashUploadConsent::default(),
+            crash_report_url: None,
+            redact_home_path: true,
+            low_disk_space_mb: 1024,
+            retention_enabled: true,
+            retention_max_age_days: 30,
+            retention_max_size_mb: 1024,
+            confirm_url_intents: true,
+            pause_heavy_tasks_on_battery: true,
+            battery_low_percent: 20,
+            volume_control_enabled: true,
+            node_display_name: None,
+            disabled_capabilities: Vec::new(),
+            auth_grace_period_secs: 300,
+            auto_lock_minutes: 0,
+            blocked_env_keys: crate::DEFAULT_BLOCKED_ENV_KEYS
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+            allowed_env_keys: Vec::new(),
+            task_command_jail: true,
+            overlay_position: None,
+            window_chrome: crate::chrome::WindowChrome::default(),
+            window_zoom: HashMap::new(),
+            safe_mode: false,
+            backend_auto_restart: true,
+            dev_backend_command: None,
+            dev_backend_cwd: None,
+            disabled_tools: Vec::new(),
+            share_recent_items: false,
+            speak_notifications: true,
+            shortcut_overrides: HashMap::new(),
+            onboarding: crate::onboarding::OnboardingState::default(),
+        }
+    }
+}
+
+/// 应用数据目录（无需 AppHandle，与 app.path().app_data_dir() 一致）
+pub fn app_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_IDENTIFIER)
+}
+
+fn settings_path() -> PathBuf {
+    app_data_dir().join(SETTINGS_FILE)
+}
+
+/// 读取设置（已叠加托管策略），文件不存在或损坏时返回默认值
+pub fn load_settings() -> AppSettings {
+    let mut settings = match std::fs::read_to_string(settings_path()) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            debug_log(&format!("[settings] 解析 settings.json 失败，使用默认值: {}", e));
+            AppSettings::default()
+        }),
+        Err(_) => AppSettings::default(),
+    };
+    crate::policy::apply(&mut settings);
+    settings
+}
+
+/// 写入设置
+pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
+    let path = settings_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("保存设置失败: {}", e))
+}
+
+/// 读取当前设置（内存中的副本）
+pub fn current(app: &tauri::AppHandle) -> AppSettings {
+    app.state::<Mutex<AppSettings>>()
+        .lock()
+        .map(|g| g.clone())
+        .unwrap_or_default()
+}
+
+/// 记录本次成功使用的 sidecar 端口（与已保存的相同时不写盘）
+pub fn remember_sidecar_port(app: &tauri::AppHandle, port: u16) {
+    let state = app.state::<Mutex<AppSettings>>();
+    let Ok(mut guard) = state.lock() else {
+        return;
+    };
+    if guard.last_sidecar_port == Some(port) {
+        return;
+    }
+    guard.last_sidecar_port = Some(port);
+    if let Err(e) = save_settings(&guard) {
+        debug_log(&format!("[settings] {}", e));
+    }
+}
+
+/// 记录状态浮窗位置
+pub fn remember_overlay_position(app: &tauri::AppHandle, position: (f64, f64)) {
+    let state = app.state::<Mutex<AppSettings>>();
+    let Ok(mut guard) = state.lock() else {
+        return;
+    };
+    if guard.overlay_position == Some(position) {
+        return;
+    }
+    guard.overlay_position = Some(position);
+    if let Err(e) = save_settings(&guard) {
+        debug_log(&format!("[settings] {}", e));
+    }
+}
+
+/// 获取设置
+#[tauri::command]
+pub async fn get_settings(app: tauri::AppHandle) -> Result<AppSettings, String> {
+    Ok(current(&app))
+}
+
+/// 更新设置（传入需要修改的字段，未传入的字段保持不变），返回更新后的设置
+///
+/// PROTECTED_SETTINGS 与 SECURITY_SETTINGS 中的字段被拒绝，需通过各自的专用命令修改。
+#[tauri::command]
+pub async fn update_settings(
+    app: tauri::AppHandle,
+    patch: serde_json::Value,
+) -> Result<AppSettings, String> {
+    let patch = patch.as_object().ok_or("patch must be an object")?.clone();
+    if let Some(key) = patch.keys().find(|k| SECURITY_SETTINGS.contains(&k.as_str())) {
+        return Err(format!(
+            "Setting must be changed through update_security_settings: {}",
+            key
+        ));
+    }
+    if let Some((key, command)) = patch
+        .keys()
+        .find_map(|k| PROTECTED_SETTINGS.iter().find(|(name, _)| *name == k.as_str()))
+    {
+        return Err(match command {
+            Some(command) => format!("Setting must be changed through {}: {}", command, key),
+            None => format!("Setting is managed by the app: {}", key),
+        });
+    }
+    let updated = apply_patch(&app, patch)?;
+
+    crate::logging::configure(&updated);
+    debug_log("[settings] 设置已更新");
+    let _ = app.emit("settings-changed", &updated);
+    Ok(updated)
+}
+
+/// 更新安全相关设置（命令执行限制、自动锁定、崩溃报告上传、开发后端命令、
+/// 快捷指令确认、最近项目共享、日志脱敏），需要系统身份验证
+#[tauri::command]
+pub async fn update_security_settings(
+    app: tauri::AppHandle,
+    patch: serde_json::Value,
+) -> Result<AppSettings, String> {
+    let patch = patch.as_object().ok_or("patch must be an object")?.clone();
+    if let Some(key) = patch
+        .keys()
+        .find(|k| !SECURITY_SETTINGS.contains(&k.as_str()))
+    {
+        return Err(format!("Not a security setting: {}", key));
+    }
+    crate::auth::require_auth(&app, "security_settings").await?;
+    let updated = apply_patch(&app, patch.clone())?;
+    crate::logging::configure(&updated);
+
+    crate::audit::record(
+        &app,
+        "settings.security",
+        true,
+        serde_json::json!({ "changes": patch }),
+    );
+    debug_log(&format!(
+        "[settings] 安全设置已更新: {}",
+        patch.keys().cloned().collect::<Vec<_>>().join(", ")
+    ));
+    let _ = app.emit("settings-changed", &updated);
+    Ok(updated)
+}
+
+/// 合并并保存设置（拒绝策略锁定的设置项）
+fn apply_patch(
+    app: &tauri::AppHandle,
+    patch: serde_json::Map<String, serde_json::Value>,
+) -> Result<AppSettings, String> {
+    if let Some(key) = patch.keys().find(|k| crate::policy::is_locked_setting(k)) {
+        return Err(format!("Setting locked by policy: {}", key));
+    }
+    let updated = {
+        let state = app.state::<Mutex<AppSettings>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        let mut merged = serde_json::to_value(&*guard).map_err(|e| e.to_string())?;
+        if let Some(obj) = merged.as_object_mut() {
+            obj.extend(patch);
+        }
+        let mut updated: AppSettings =
+            serde_json::from_value(merged).map_err(|e| format!("无效的设置: {}", e))?;
+        crate::policy::apply(&mut updated);
+        save_settings(&updated)?;
+        *guard = updated.clone();
+        updated
+    };
+    Ok(updated)
+}
@@ -0,0 +1,285 @@
+// ============================================================================
+// 快捷键登记
+// ============================================================================
+//
+// 应用的全部快捷键集中登记在这里，设置页通过 list_shortcuts 展示、set_shortcut 改绑：
+// - window：应用窗口获得焦点期间注册的快捷键（缩放，见 zoom.rs）
+// - menu：macOS 应用菜单加速键；编辑 / 窗口菜单中由系统处理的快捷键只参与冲突检测，不能改绑
+// - global：自动化规则的全局快捷键（ID 为 "automation:<规则 ID>"，改绑写回规则）
+// 改绑保存在设置 shortcut_overrides 中（恢复默认时删除）。注册前按解析后的按键组合检测冲突
+// （CmdOrCtrl 视为当前平台的 Cmd / Ctrl），冲突时拒绝并指出被哪个快捷键占用。
+
+use crate::i18n::t;
+use crate::{automation, debug_log, settings, zoom};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::Shortcut;
+
+/// 自动化规则快捷键的 ID 前缀
+pub const AUTOMATION_PREFIX: &str = "automation:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutKind {
+    Window,
+    Menu,
+    Global,
+}
+
+/// 可改绑的内置快捷键：(ID, 类型, 名称的 i18n key, 默认加速键)
+const BUILTIN_SHORTCUTS: &[(&str, ShortcutKind, &str, &str)] = &[
+    (
+        "zoom.in",
+        ShortcutKind::Window,
+        "shortcut.zoom_in",
+        "CommandOrControl+Equal",
+    ),
+    (
+        "zoom.out",
+        ShortcutKind::Window,
+        "shortcut.zoom_out",
+        "CommandOrControl+Minus",
+    ),
+    (
+        "zoom.reset",
+        ShortcutKind::Window,
+        "shortcut.zoom_reset",
+        "CommandOrControl+Digit0",
+    ),
+    (
+        "menu.preferences",
+        ShortcutKind::Menu,
+        "menu.preferences",
+        "CommandOrControl+Comma",
+    ),
+];
+
+/// 由系统处理的菜单快捷键（不可改绑）：(ID, 名称的 i18n key, 加速键)
+const SYSTEM_MENU_SHORTCUTS: &[(&str, &str, &str)] = &[
+    ("menu.undo", "shortcut.undo", "CommandOrControl+KeyZ"),
+    ("menu.redo", "shortcut.redo", "CommandOrControl+Shift+KeyZ"),
+    ("menu.cut", "shortcut.cut", "CommandOrControl+KeyX"),
+    ("menu.copy", "shortcut.copy", "CommandOrControl+KeyC"),
+    ("menu.paste", "shortcut.paste", "CommandOrControl+KeyV"),
+    (
+        "menu.select_all",
+        "shortcut.select_all",
+        "CommandOrControl+KeyA",
+    ),
+    ("menu.hide", "menu.hide", "CommandOrControl+KeyH"),
+    (
+        "menu.hide_others",
+        "menu.hide_others",
+        "CommandOrControl+Alt+KeyH",
+    ),
+    ("menu.quit", "menu.quit", "CommandOrControl+KeyQ"),
+    (
+        "menu.minimize",
+        "shortcut.minimize",
+        "CommandOrControl+KeyM",
+    ),
+    (
+        "menu.fullscreen",
+        "shortcut.fullscreen",
+        "Control+CommandOrControl+KeyF",
+    ),
+    (
+        "menu.close_window",
+        "shortcut.close_window",
+        "CommandOrControl+KeyW",
+    ),
+];
+
+/// 内置快捷键定义
+struct ShortcutDef {
+    id: &'static str,
+    kind: ShortcutKind,
+    /// 名称的 i18n key
+    label: &'static str,
+    default: &'static str,
+    rebindable: bool,
+}
+
+/// 当前平台的内置快捷键（只有 macOS 设置应用菜单，见 menu.rs）
+fn builtins() -> impl Iterator<Item = ShortcutDef> {
+    let rebindable = BUILTIN_SHORTCUTS
+        .iter()
+        .map(|&(id, kind, label, default)| ShortcutDef {
+            id,
+            kind,
+            label,
+            default,
+            rebindable: true,
+        });
+    let system = SYSTEM_MENU_SHORTCUTS
+        .iter()
+        .map(|&(id, label, default)| ShortcutDef {
+            id,
+            kind: ShortcutKind::Menu,
+            label,
+            default,
+            rebindable: false,
+        });
+    rebindable
+        .chain(system)
+        .filter(|d| d.kind != ShortcutKind::Menu || cfg!(target_os = "macos"))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutInfo {
+    pub id: String,
+    pub kind: ShortcutKind,
+    pub label: String,
+    /// 当前生效的加速键
+    pub accelerator: String,
+    /// 默认加速键（自动化规则为 None）
+    pub default_accelerator: Option<String>,
+    pub rebindable: bool,
+    /// 已改绑（不同于默认值）
+    pub customized: bool,
+    /// 已注册（停用的自动化规则为 false，不参与冲突检测）
+    pub enabled: bool,
+}
+
+fn builtin(id: &str) -> Option<ShortcutDef> {
+    builtins().find(|d| d.id == id)
+}
+
+/// 内置快捷键当前生效的加速键（改绑优先，未知 ID 返回空字符串）
+pub fn accelerator(app: &tauri::AppHandle, id: &str) -> String {
+    settings::current(app)
+        .shortcut_overrides
+        .get(id)
+        .cloned()
+        .or_else(|| builtin(id).map(|d| d.default.to_string()))
+        .unwrap_or_default()
+}
+
+/// 自动化规则对应的快捷键 ID
+pub fn automation_id(rule_id: &str) -> String {
+    format!("{}{}", AUTOMATION_PREFIX, rule_id)
+}
+
+fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("无效的快捷键 {}: {}", accelerator, e))
+}
+
+/// 全部快捷键
+fn entries(app: &tauri::AppHandle) -> Vec<ShortcutInfo> {
+    let overrides = settings::current(app).shortcut_overrides;
+    let mut entries: Vec<ShortcutInfo> = builtins()
+        .map(|d| {
+            let accelerator = overrides
+                .get(d.id)
+                .cloned()
+                .unwrap_or_else(|| d.default.to_string());
+            ShortcutInfo {
+                id: d.id.to_string(),
+                kind: d.kind,
+                label: t(d.label),
+                customized: accelerator != d.default,
+                accelerator,
+                default_accelerator: Some(d.default.to_string()),
+                rebindable: d.rebindable,
+                enabled: true,
+            }
+        })
+        .collect();
+    entries.extend(
+        automation::hotkeys(app)
+            .into_iter()
+            .map(|(id, label, shortcut, enabled)| ShortcutInfo {
+                id: automation_id(&id),
+                kind: ShortcutKind::Global,
+                label,
+                accelerator: shortcut,
+                default_accelerator: None,
+                rebindable: true,
+                customized: false,
+                enabled,
+            }),
+    );
+    entries
+}
+
+/// 检查 accelerator 是否与其它已注册的快捷键冲突（id 为将要使用它的快捷键）
+pub fn check_conflict(app: &tauri::AppHandle, id: &str, accelerator: &str) -> Result<(), String> {
+    let shortcut = parse(accelerator)?;
+    for entry in entries(app) {
+        if entry.id == id || !entry.enabled {
+            continue;
+        }
+        match parse(&entry.accelerator) {
+            Ok(other) if other == shortcut => {
+                return Err(format!(
+                    "快捷键 {} 已被「{}」占用",
+                    accelerator, entry.label
+                ));
+            }
+            Ok(_) => {}
+            Err(e) => debug_log(&format!("[shortcuts] {}: {}", entry.id, e)),
+        }
+    }
+    Ok(())
+}
+
+/// 列出全部快捷键（内置与自动化规则）
+#[tauri::command]
+pub async fn list_shortcuts(app: tauri::AppHandle) -> Result<Vec<ShortcutInfo>, String> {
+    Ok(entries(&app))
+}
+
+/// 改绑快捷键；accelerator 为 None 时恢复默认（自动化规则必须提供）
+#[tauri::command]
+pub async fn set_shortcut(
+    app: tauri::AppHandle,
+    id: String,
+    accelerator: Option<String>,
+) -> Result<ShortcutInfo, String> {
+    let accelerator = accelerator.map(|a| a.trim().to_string());
+    if accelerator.as_deref() == Some("") {
+        return Err("Shortcut cannot be empty".to_string());
+    }
+
+    if let Some(rule_id) = id.strip_prefix(AUTOMATION_PREFIX) {
+        let accelerator = accelerator.ok_or("Automation shortcuts have no default")?;
+        check_conflict(&app, &id, &accelerator)?;
+        automation::set_hotkey(&app, rule_id, &accelerator)?;
+    } else {
+        let def = builtin(&id).ok_or_else(|| format!("Shortcut not found: {}", id))?;
+        if !def.rebindable {
+            return Err(format!("Shortcut cannot be rebound: {}", id));
+        }
+        let accelerator = accelerator.unwrap_or_else(|| def.default.to_string());
+        check_conflict(&app, &id, &accelerator)?;
+        let previous = self::accelerator(&app, &id);
+        {
+            let state = app.state::<Mutex<settings::AppSettings>>();
+            let mut guard = state.lock().map_err(|e| e.to_string())?;
+            if accelerator == def.default {
+                guard.shortcut_overrides.remove(&id);
+            } else {
+                guard
+                    .shortcut_overrides
+                    .insert(id.clone(), accelerator.clone());
+            }
+            settings::save_settings(&guard)?;
+        }
+        match def.kind {
+            ShortcutKind::Window => zoom::rebind(&app, &previous),
+            #[cfg(target_os = "macos")]
+            ShortcutKind::Menu => crate::menu::refresh(&app),
+            _ => {}
+        }
+    }
+
+    debug_log(&format!("[shortcuts] 已改绑快捷键 {}", id));
+    let _ = app.emit("shortcuts-changed", ());
+    entries(&app)
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("Shortcut not found: {}", id))
+}
@@ -0,0 +1,544 @@
+// ============================================================================
+// 后端（sidecar）独立更新
+// ============================================================================
+//
+// 后端可以不随应用整体更新：从发布清单下载新版本的后端可执行文件，
+// 校验 SHA-256 与 minisign 签名（与应用更新共用 tauri.conf.json 中的公钥）后
+// 放到数据目录的 backend/<version>/ 下，下次启动 sidecar 时优先使用。
+//
+// 新版本首次启动处于"待验证"状态：健康检查通过后转正；
+// 启动失败（进程退出或超时）则回退到上一个可用版本（或内置版本），
+// 该版本记入失败列表不再自动安装，并重启应用。
+
+use crate::debug_log;
+use crate::store::{data_file_path, load_json, save_json};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// 后端发布清单
+const MANIFEST_URL: &str =
+    "https://github.com/malue-ai/dazee-small/releases/latest/download/backend-latest.json";
+
+/// 更新状态持久化文件
+const STATE_FILE: &str = "backend-update.json";
+
+/// 下载的后端存放目录（数据目录下）
+const BACKENDS_DIR: &str = "backend";
+
+/// 清单请求超时
+const MANIFEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 后端可执行文件下载超时
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// 已安装的后端版本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstalledBackend {
+    pub version: String,
+    pub path: String,
+    pub installed_at: String,
+}
+
+/// 后端更新状态（与 backend-update.json 同步）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendUpdateState {
+    /// 当前使用的下载版本（None 表示使用内置版本）
+    pub active: Option<InstalledBackend>,
+    /// 上一个可用的下载版本，回退时使用
+    pub previous: Option<InstalledBackend>,
+    /// 当前版本尚未通过健康检查
+    pub pending_verification: bool,
+    /// 待验证的版本已启动过（再次启动前仍未转正说明验证失败）
+    pub verifying: bool,
+    /// 启动失败过的版本，不再自动安装
+    pub failed_versions: Vec<String>,
+}
+
+/// 发布清单中某个平台的构建
+#[derive(Debug, Clone, Deserialize)]
+struct ManifestPlatform {
+    url: String,
+    sha256: String,
+    /// minisign 签名（base64，与应用更新包的 .sig 格式一致）
+    signature: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BackendManifest {
+    version: String,
+    #[serde(default)]
+    notes: Option<String>,
+    platforms: std::collections::HashMap<String, ManifestPlatform>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendUpdateInfo {
+    pub available: bool,
+    /// 当前使用的后端版本
+    pub current_version: String,
+    /// 当前是否为下载的版本
+    pub is_downloaded: bool,
+    pub version: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// `backend-update-progress` 事件负载
+#[derive(Debug, Clone, Serialize)]
+struct DownloadProgressPayload {
+    version: String,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+fn state_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn load_state(app: &tauri::AppHandle) -> BackendUpdateState {
+    load_json(app, STATE_FILE)
+}
+
+/// 读取-修改-写回更新状态
+fn modify_state<T>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&mut BackendUpdateState) -> T,
+) -> Result<T, String> {
+    let _guard = state_lock().lock().unwrap_or_else(PoisonError::into_inner);
+    let mut state = load_state(app);
+    let result = f(&mut state);
+    save_json(app, STATE_FILE, &state)?;
+    Ok(result)
+}
+
+/// 当前平台在发布清单中的 key（与应用更新的 latest.json 命名一致）
+pub fn platform_key() -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        other => other,
+    };
+    format!("{}-{}", os, std::env::consts::ARCH)
+}
+
+fn binary_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "xiaodazi-backend.exe"
+    } else {
+        "xiaodazi-backend"
+    }
+}
+
+/// 下载用的 HTTP 客户端（访问外网，走系统代理，不复用本机后端的客户端）
+pub fn download_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// 清单中的版本号必须是语义化版本（如 "1.4.0"、"1.5.0-beta.1"），
+/// 它会作为 backend/<version>/ 目录名，不能含路径分隔符或 ".."
+fn validate_version(version: &str) -> Result<(), String> {
+    semver::Version::parse(version)
+        .map(|_| ())
+        .map_err(|e| format!("Invalid backend version {:?}: {}", version, e))
+}
+
+fn parse_version(v: &str) -> Option<semver::Version> {
+    semver::Version::parse(v.trim_start_matches('v')).ok()
+}
+
+/// latest 是否比 current 新（无法按语义化版本解析时只要不同即视为新版本）
+fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => latest != current,
+    }
+}
+
+/// 当前使用的后端版本（未下载过时与应用版本一致）
+fn current_version(app: &tauri::AppHandle, state: &BackendUpdateState) -> String {
+    state
+        .active
+        .as_ref()
+        .map(|a| a.version.clone())
+        .unwrap_or_else(|| app.package_info().version.to_string())
+}
+
+async fn fetch_manifest() -> Result<BackendManifest, String> {
+    let manifest = download_client()
+        .get(MANIFEST_URL)
+        .timeout(MANIFEST_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("获取后端发布清单失败: {}", e))?
+        .json::<BackendManifest>()
+        .await
+        .map_err(|e| format!("解析后端发布清单失败: {}", e))?;
+    validate_version(&manifest.version)?;
+    Ok(manifest)
+}
+
+/// 用 tauri.conf.json 中 updater 的公钥校验 minisign 签名
+fn verify_signature(app: &tauri::AppHandle, data: &[u8], signature: &str) -> Result<(), String> {
+    use base64::Engine;
+    let decode = |s: &str| -> Result<String, String> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|e| e.to_string())?;
+        String::from_utf8(bytes).map_err(|e| e.to_string())
+    };
+
+    let pubkey = app
+        .config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|u| u.get("pubkey"))
+        .and_then(|k| k.as_str())
+        .ok_or("updater pubkey not configured")?;
+    let public_key = minisign_verify::PublicKey::decode(&decode(pubkey)?)
+        .map_err(|e| format!("无效的公钥: {}", e))?;
+    let signature = minisign_verify::Signature::decode(&decode(signature)?)
+        .map_err(|e| format!("无效的签名: {}", e))?;
+    public_key
+        .verify(data, &signature, true)
+        .map_err(|e| format!("签名校验失败: {}", e))
+}
+
+pub fn verify_checksum(data: &[u8], expected: &str) -> Result<(), String> {
+    let actual: String = Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!("校验和不匹配: 期望 {}，实际 {}", expected, actual))
+    }
+}
+
+/// 写入后端可执行文件（先写临时文件再重命名）
+fn write_binary(app: &tauri::AppHandle, version: &str, data: &[u8]) -> Result<PathBuf, String> {
+    validate_version(version)?;
+    let dir = data_file_path(app, BACKENDS_DIR).join(version);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建后端目录失败: {}", e))?;
+    let path = dir.join(binary_name());
+    let tmp = dir.join(format!("{}.download", binary_name()));
+    std::fs::write(&tmp, data).map_err(|e| format!("写入后端失败: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("设置可执行权限失败: {}", e))?;
+    }
+
+    std::fs::rename(&tmp, &path).map_err(|e| format!("写入后端失败: {}", e))?;
+    Ok(path)
+}
+
+/// 删除不再被引用的下载版本
+fn prune_installed(app: &tauri::AppHandle, state: &BackendUpdateState) {
+    let keep: Vec<&str> = [&state.active, &state.previous]
+        .into_iter()
+        .flatten()
+        .map(|b| b.version.as_str())
+        .collect();
+    let Ok(entries) = std::fs::read_dir(data_file_path(app, BACKENDS_DIR)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !keep.contains(&name.as_str()) {
+            let _ = std::fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// 启动 sidecar 前调用：返回应优先使用的下载版本路径（None 表示使用内置版本）
+///
+/// 上次待验证的版本没有转正（例如应用在验证期间崩溃）时视为失败并先回退。
+pub fn prepare_spawn(app: &tauri::AppHandle) -> Option<String> {
+    let state = load_state(app);
+    if state.pending_verification && state.verifying {
+        debug_log("[backend-update] 上次启动的新版本后端未通过验证，回退");
+        let _ = rollback(app, true);
+    }
+
+    let result = modify_state(app, |state| {
+        let active = state.active.clone()?;
+        if !std::path::Path::new(&active.path).is_file() {
+            debug_log(&format!(
+                "[backend-update] 后端文件缺失，使用内置版本: {}",
+                active.path
+            ));
+            state.active = state.previous.take();
+            state.pending_verification = false;
+            return None;
+        }
+        state.verifying = state.pending_verification;
+        Some(active)
+    });
+
+    match result {
+        Ok(Some(active)) => {
+            debug_log(&format!(
+                "[backend-update] 使用下载的后端 {}",
+                active.version
+            ));
+            Some(active.path)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            debug_log(&format!("[backend-update] {}", e));
+            None
+        }
+    }
+}
+
+/// 后端健康检查通过：待验证的新版本转正
+pub fn confirm_healthy(app: &tauri::AppHandle) {
+    if !load_state(app).verifying {
+        return;
+    }
+    match modify_state(app, |state| {
+        state.pending_verification = false;
+        state.verifying = false;
+        state.active.as_ref().map(|a| a.version.clone())
+    }) {
+        Ok(version) => {
+            debug_log(&format!(
+                "[backend-update] 新版本后端验证通过: {:?}",
+                version
+            ));
+            let _ = app.emit("backend-update-verified", version);
+        }
+        Err(e) => debug_log(&format!("[backend-update] {}", e)),
+    }
+}
+
+/// 后端启动失败：若正在验证新版本则回退并重启应用，返回是否已回退
+pub fn on_startup_failed(app: &tauri::AppHandle) -> bool {
+    if !load_state(app).verifying {
+        return false;
+    }
+    match rollback(app, true) {
+        Ok(_) => {
+            debug_log("[backend-update] 新版本后端启动失败，已回退，重启应用");
+            app.restart()
+        }
+        Err(e) => {
+            debug_log(&format!("[backend-update] 回退失败: {}", e));
+            false
+        }
+    }
+}
+
+/// 当前使用的下载版本（使用内置版本时为 None）
+pub fn active_backend(app: &tauri::AppHandle) -> Option<InstalledBackend> {
+    load_state(app).active
+}
+
+/// 回退到上一个可用版本（没有则回到内置版本），返回回退后的版本
+pub fn rollback(app: &tauri::AppHandle, mark_failed: bool) -> Result<Option<String>, String> {
+    let (failed, restored, state) = modify_state(app, |state| {
+        let failed = state.active.take();
+        if mark_failed {
+            if let Some(f) = &failed {
+                if !state.failed_versions.contains(&f.version) {
+                    state.failed_versions.push(f.version.clone());
+                }
+            }
+        }
+        state.active = state.previous.take();
+        state.pending_verification = false;
+        state.verifying = false;
+        (
+            failed.map(|f| f.version),
+            state.active.as_ref().map(|a| a.version.clone()),
+            state.clone(),
+        )
+    })?;
+    prune_installed(app, &state);
+
+    debug_log(&format!(
+        "[backend-update] 回退后端: {:?} → {:?}",
+        failed,
+        restored.as_deref().unwrap_or("内置版本")
+    ));
+    let _ = app.emit(
+        "backend-update-rolled-back",
+        serde_json::json!({ "from": failed, "to": restored }),
+    );
+    Ok(restored)
+}
+
+/// 检查是否有新版本后端
+#[tauri::command]
+pub async fn check_backend_update(app: tauri::AppHandle) -> Result<BackendUpdateInfo, String> {
+    let state = load_state(&app);
+    let current = current_version(&app, &state);
+    let manifest = fetch_manifest().await?;
+
+    let available = is_newer(&manifest.version, &current)
+        && !state.failed_versions.contains(&manifest.version)
+        && manifest.platforms.contains_key(&platform_key());
+
+    Ok(BackendUpdateInfo {
+        available,
+        current_version: current,
+        is_downloaded: state.active.is_some(),
+        version: available.then(|| manifest.version.clone()),
+        notes: available.then_some(manifest.notes).flatten(),
+    })
+}
+
+/// 下载、校验并安装新版本后端（下次启动 sidecar 时生效），返回安装的版本
+///
+/// 清单版本不高于当前版本时拒绝安装，除非 `allow_downgrade` 为 true；
+/// 验证失败过的版本（failed_versions）始终拒绝。
+#[tauri::command]
+pub async fn download_backend_update(
+    app: tauri::AppHandle,
+    allow_downgrade: Option<bool>,
+) -> Result<String, String> {
+    use futures_util::StreamExt;
+
+    let manifest = fetch_manifest().await?;
+    let state = load_state(&app);
+    let current = current_version(&app, &state);
+    if state.failed_versions.contains(&manifest.version) {
+        return Err(format!(
+            "Backend {} failed verification before",
+            manifest.version
+        ));
+    }
+    if !allow_downgrade.unwrap_or(false) && !is_newer(&manifest.version, &current) {
+        return Err(format!(
+            "Backend {} is not newer than current {}",
+            manifest.version, current
+        ));
+    }
+    let platform = manifest
+        .platforms
+        .get(&platform_key())
+        .ok_or_else(|| format!("No backend build for {}", platform_key()))?;
+
+    debug_log(&format!(
+        "[backend-update] 下载后端 {}: {}",
+        manifest.version, platform.url
+    ));
+    let resp = download_client()
+        .get(&platform.url)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("下载后端失败: {}", e))?;
+
+    let total = resp.content_length();
+    let mut data = Vec::with_capacity(total.unwrap_or(0) as usize);
+    let mut stream = resp.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("下载后端失败: {}", e))?;
+        data.extend_from_slice(&chunk);
+        let _ = app.emit(
+            "backend-update-progress",
+            DownloadProgressPayload {
+                version: manifest.version.clone(),
+                downloaded: data.len() as u64,
+                total,
+            },
+        );
+    }
+
+    verify_checksum(&data, &platform.sha256)?;
+    verify_signature(&app, &data, &platform.signature)?;
+
+    let version = manifest.version.clone();
+    let path = {
+        let app = app.clone();
+        let version = version.clone();
+        tauri::async_runtime::spawn_blocking(move || write_binary(&app, &version, &data))
+            .await
+            .map_err(|e| e.to_string())??
+    };
+
+    let state = modify_state(&app, |state| {
+        let installed = InstalledBackend {
+            version: version.clone(),
+            path: path.to_string_lossy().to_string(),
+            installed_at: chrono::Local::now().to_rfc3339(),
+        };
+        if state.active.as_ref().map(|a| &a.version) != Some(&version) {
+            state.previous = state.active.replace(installed);
+        } else {
+            state.active = Some(installed);
+        }
+        state.pending_verification = true;
+        state.verifying = false;
+        state.clone()
+    })?;
+    prune_installed(&app, &state);
+
+    debug_log(&format!(
+        "[backend-update] 后端 {} 已安装，重启后生效",
+        version
+    ));
+    let _ = app.emit("backend-update-installed", &version);
+    Ok(version)
+}
+
+/// 获取后端更新状态
+#[tauri::command]
+pub async fn get_backend_update_status(
+    app: tauri::AppHandle,
+) -> Result<BackendUpdateState, String> {
+    Ok(load_state(&app))
+}
+
+/// 手动回退到上一个后端版本（重启后生效），返回回退后的版本（None 表示内置版本）
+#[tauri::command]
+pub async fn rollback_backend_update(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    if load_state(&app).active.is_none() {
+        return Err("Already using the bundled backend".to_string());
+    }
+    rollback(&app, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_semver_versions() {
+        for version in ["1.4.0", "0.10.2-beta.1", "2.0.0+build.5"] {
+            assert!(validate_version(version).is_ok(), "{}", version);
+        }
+    }
+
+    #[test]
+    fn rejects_versions_that_are_not_plain_directory_names() {
+        for version in [
+            "../../bin",
+            "1.0.0/../../x",
+            "v1.0.0",
+            "1.0",
+            "",
+            "..",
+            "1.0.0\\..\\x",
+        ] {
+            assert!(validate_version(version).is_err(), "{}", version);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// ============================================================================
+// 语音播报（TTS）
+// ============================================================================
+//
+// - macOS: say
+// - Windows: PowerShell + System.Speech (SAPI)
+// - Linux: espeak
+// 文本统一通过 stdin 传入，避免命令行转义和以 "-" 开头的文本被当作参数。
+
+use crate::debug_log;
+use std::io::Write;
+use std::process::{Child, Command as SysCommand, Stdio};
+use std::sync::Mutex;
+use tauri::Manager;
+
+/// 当前正在播报的进程
+#[derive(Default)]
+pub struct SpeechState {
+    current: Option<Child>,
+}
+
+/// 默认语速（每分钟词数）
+const DEFAULT_RATE_WPM: u32 = 180;
+
+#[cfg(target_os = "macos")]
+fn speech_command(voice: Option<&str>, rate: u32) -> Result<SysCommand, String> {
+    let mut cmd = SysCommand::new("say");
+    cmd.args(["-r", &rate.to_string()]);
+    if let Some(v) = voice {
+        cmd.args(["-v", v]);
+    }
+    Ok(cmd)
+}
+
+#[cfg(target_os = "windows")]
+fn speech_command(voice: Option<&str>, rate: u32) -> Result<SysCommand, String> {
+    // SAPI 语速范围 -10..10，按 20 wpm 一档从默认语速换算
+    let sapi_rate = ((rate as i64 - DEFAULT_RATE_WPM as i64) / 20).clamp(-10, 10);
+    let select_voice = match voice {
+        Some(v) => format!("$s.SelectVoice('{}'); ", v.replace('\'', "''")),
+        None => String::new(),
+    };
+    let script = format!(
+        "Add-Type -AssemblyName System.Speech; \
+         $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+         $s.Rate = {}; {}$s.Speak([Console]::In.ReadToEnd())",
+        sapi_rate, select_voice
+    );
+    let mut cmd = SysCommand::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    Ok(cmd)
+}
+
+#[cfg(target_os = "linux")]
+fn speech_command(voice: Option<&str>, rate: u32) -> Result<SysCommand, String> {
+    let mut cmd = SysCommand::new("espeak");
+    cmd.args(["--stdin", "-s", &rate.to_string()]);
+    if let Some(v) = voice {
+        cmd.args(["-v", v]);
+    }
+    Ok(cmd)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn speech_command(_voice: Option<&str>, _rate: u32) -> Result<SysCommand, String> {
+    Err("Text-to-speech not supported on this platform".to_string())
+}
+
+/// 终止当前播报
+fn stop_current(state: &mut SpeechState) -> bool {
+    match state.current.take() {
+        Some(mut child) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            true
+        }
+        None => false,
+    }
+}
+
+/// 朗读文本，供通知等 Rust 侧调用（非阻塞，新的播报会打断正在进行的播报）
+pub fn say(
+    app: &tauri::AppHandle,
+    text: &str,
+    voice: Option<&str>,
+    rate: Option<u32>,
+) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("Text cannot be empty".to_string());
+    }
+
+    let rate = rate.unwrap_or(DEFAULT_RATE_WPM).clamp(80, 450);
+    let mut child = speech_command(voice, rate)?
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("语音播报失败: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("语音播报失败: {}", e))?;
+        // stdin 在此处关闭，播报程序读到 EOF 后开始朗读
+    }
+
+    debug_log(&format!(
+        "[speech] 开始播报 ({} 字符)",
+        text.chars().count()
+    ));
+
+    let state = app.state::<Mutex<SpeechState>>();
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    stop_current(&mut guard);
+    guard.current = Some(child);
+    Ok(())
+}
+
+/// 朗读文本（非阻塞，新的播报会打断正在进行的播报）
+///
+/// `rate` 为每分钟词数，默认 180。
+#[tauri::command]
+pub async fn speak(
+    app: tauri::AppHandle,
+    text: String,
+    voice: Option<String>,
+    rate: Option<u32>,
+) -> Result<(), String> {
+    say(&app, &text, voice.as_deref(), rate)
+}
+
+/// 停止播报，返回是否有正在进行的播报
+#[tauri::command]
+pub async fn stop_speaking(app: tauri::AppHandle) -> Result<bool, String> {
+    let state = app.state::<Mutex<SpeechState>>();
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    Ok(stop_current(&mut guard))
+}
@@ -0,0 +1,87 @@
+// ============================================================================
+// 启动阶段计时
+// ============================================================================
+//
+// 记录各启动阶段相对进程启动的耗时（端口扫描、sidecar 启动、首次健康检查通过、
+// webview 加载完成等），通过 get_startup_timings 查询，用于诊断启动慢的机器。
+// 部分阶段发生在 Tauri 应用创建之前，因此用进程级静态变量保存。
+
+use crate::debug_log;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Instant;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupPhase {
+    pub name: String,
+    /// 相对进程启动的毫秒数
+    pub at_ms: u64,
+    /// 距上一阶段的毫秒数
+    pub delta_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupTimings {
+    pub started_at: String,
+    pub phases: Vec<StartupPhase>,
+    /// 最后一个已记录阶段的时间
+    pub total_ms: u64,
+}
+
+struct Recorder {
+    start: Instant,
+    started_at: String,
+    phases: Mutex<Vec<StartupPhase>>,
+}
+
+fn recorder() -> &'static Recorder {
+    static RECORDER: OnceLock<Recorder> = OnceLock::new();
+    RECORDER.get_or_init(|| Recorder {
+        start: Instant::now(),
+        started_at: chrono::Local::now().to_rfc3339(),
+        phases: Mutex::new(Vec::new()),
+    })
+}
+
+/// 记录进程启动时刻（main 入口第一行调用）
+pub fn begin() {
+    mark("process_start");
+}
+
+/// 距进程启动的毫秒数
+pub fn elapsed_ms() -> u64 {
+    recorder().start.elapsed().as_millis() as u64
+}
+
+/// 记录一个启动阶段（同名阶段只记录第一次）
+pub fn mark(name: &str) {
+    let rec = recorder();
+    let at_ms = elapsed_ms();
+    let mut phases = rec.phases.lock().unwrap_or_else(PoisonError::into_inner);
+    if phases.iter().any(|p| p.name == name) {
+        return;
+    }
+    let delta_ms = at_ms - phases.last().map(|p| p.at_ms).unwrap_or(0);
+    debug_log(&format!("[startup] {} (+{}ms, 累计 {}ms)", name, delta_ms, at_ms));
+    phases.push(StartupPhase {
+        name: name.to_string(),
+        at_ms,
+        delta_ms,
+    });
+}
+
+/// 获取启动各阶段耗时
+#[tauri::command]
+pub async fn get_startup_timings() -> Result<StartupTimings, String> {
+    let rec = recorder();
+    let phases = rec
+        .phases
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    Ok(StartupTimings {
+        started_at: rec.started_at.clone(),
+        total_ms: phases.last().map(|p| p.at_ms).unwrap_or(0),
+        phases,
+    })
+}
@@ -0,0 +1,245 @@
+// ============================================================================
+// 磁盘空间监视与数据目录占用统计
+// ============================================================================
+//
+// 定时检查数据目录所在卷的剩余空间，低于阈值（设置 low_disk_space_mb）时
+// 发出 `low-disk-space` 事件并发送系统通知；恢复到阈值以上后重新计数，
+// 避免每次检查都重复提醒。
+// 同时跟踪已挂载的卷，外接磁盘 / SD 卡接入或移除时发出
+// `volume-mounted` / `volume-unmounted` 事件。
+
+use crate::{debug_log, i18n, notifications, settings};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::Emitter;
+
+/// 磁盘空间检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// 卷挂载检查间隔
+const VOLUME_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 数据目录下各类数据的位置（相对数据目录）
+const STORAGE_CATEGORIES: &[(&str, &[&str])] = &[
+    ("logs", &["logs", "crash-reports", "audit.log"]),
+    ("recordings", &["recordings", "screenshots"]),
+    ("workspace", &["workspace"]),
+    ("backend_data", &["data"]),
+    ("backend_updates", &["backend"]),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskSpace {
+    /// 数据目录所在卷的挂载点
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    /// 低空间阈值
+    pub threshold_bytes: u64,
+    pub low: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageCategory {
+    pub name: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageReport {
+    pub data_dir: String,
+    /// 数据目录总占用
+    pub total_bytes: u64,
+    /// 各类数据占用（未归类的计入 "other"）
+    pub categories: Vec<StorageCategory>,
+    /// 数据目录所在卷的空间（无法识别卷时为 None）
+    pub disk: Option<DiskSpace>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MountedVolume {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    /// 可移除设备（U 盘、SD 卡、外接硬盘等）
+    pub removable: bool,
+}
+
+/// 递归统计目录（或文件）大小，不跟随符号链接
+fn path_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| path_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// 数据目录所在卷的空间（取挂载点最长匹配的磁盘）
+fn disk_space(path: &Path, threshold_bytes: u64) -> Option<DiskSpace> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|d| path.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())?;
+    Some(DiskSpace {
+        mount_point: disk.mount_point().to_string_lossy().to_string(),
+        total_bytes: disk.total_space(),
+        available_bytes: disk.available_space(),
+        threshold_bytes,
+        low: disk.available_space() < threshold_bytes,
+    })
+}
+
+fn mounted_volumes() -> Vec<MountedVolume> {
+    sysinfo::Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .map(|d| MountedVolume {
+            name: d.name().to_string_lossy().to_string(),
+            mount_point: d.mount_point().to_string_lossy().to_string(),
+            file_system: d.file_system().to_string_lossy().to_string(),
+            total_bytes: d.total_space(),
+            available_bytes: d.available_space(),
+            removable: d.is_removable(),
+        })
+        .collect()
+}
+
+fn threshold_bytes(app: &tauri::AppHandle) -> u64 {
+    settings::current(app)
+        .low_disk_space_mb
+        .saturating_mul(1024 * 1024)
+}
+
+fn data_dir() -> PathBuf {
+    settings::app_data_dir()
+}
+
+/// 启动磁盘空间监视
+pub fn start_disk_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut warned = false;
+        loop {
+            let threshold = threshold_bytes(&app);
+            let space =
+                tauri::async_runtime::spawn_blocking(move || disk_space(&data_dir(), threshold))
+                    .await
+                    .ok()
+                    .flatten();
+
+            if let Some(space) = space {
+                if space.low && !warned {
+                    warned = true;
+                    debug_log(&format!(
+                        "[storage] 磁盘空间不足: 剩余 {} MB (阈值 {} MB)",
+                        space.available_bytes / 1024 / 1024,
+                        space.threshold_bytes / 1024 / 1024
+                    ));
+                    crate::events::emit(&app, "low-disk-space", &space);
+                    notifications::notify(
+                        &app,
+                        &i18n::t("notify.low_disk.title"),
+                        &i18n::tf(
+                            "notify.low_disk.body",
+                            &[&(space.available_bytes / 1024 / 1024)],
+                        ),
+                        true,
+                    );
+                } else if !space.low && warned {
+                    debug_log("[storage] 磁盘空间已恢复");
+                    warned = false;
+                }
+            }
+
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// 启动卷挂载监视（按挂载点比较前后两次结果）
+pub fn start_volume_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let snapshot = || async {
+            tauri::async_runtime::spawn_blocking(mounted_volumes)
+                .await
+                .ok()
+                .map(|volumes| {
+                    volumes
+                        .into_iter()
+                        .map(|v| (v.mount_point.clone(), v))
+                        .collect::<HashMap<_, _>>()
+                })
+        };
+        let mut last = snapshot().await.unwrap_or_default();
+        loop {
+            tokio::time::sleep(VOLUME_CHECK_INTERVAL).await;
+            let Some(current) = snapshot().await else {
+                continue;
+            };
+
+            for (mount_point, volume) in &current {
+                if !last.contains_key(mount_point) {
+                    debug_log(&format!("[storage] 卷已挂载: {}", mount_point));
+                    let _ = app.emit("volume-mounted", volume);
+                }
+            }
+            for (mount_point, volume) in &last {
+                if !current.contains_key(mount_point) {
+                    debug_log(&format!("[storage] 卷已卸载: {}", mount_point));
+                    let _ = app.emit("volume-unmounted", volume);
+                }
+            }
+            last = current;
+        }
+    });
+}
+
+/// 列出已挂载的卷
+#[tauri::command]
+pub async fn list_volumes() -> Result<Vec<MountedVolume>, String> {
+    tauri::async_runtime::spawn_blocking(mounted_volumes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取数据目录占用与磁盘空间
+#[tauri::command]
+pub async fn get_storage_report(app: tauri::AppHandle) -> Result<StorageReport, String> {
+    let threshold = threshold_bytes(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = data_dir();
+        let total_bytes = path_size(&dir);
+
+        let mut categories: Vec<StorageCategory> = STORAGE_CATEGORIES
+            .iter()
+            .map(|(name, paths)| StorageCategory {
+                name: name.to_string(),
+                bytes: paths.iter().map(|p| path_size(&dir.join(p))).sum(),
+            })
+            .collect();
+        let categorized: u64 = categories.iter().map(|c| c.bytes).sum();
+        categories.push(StorageCategory {
+            name: "other".to_string(),
+            bytes: total_bytes.saturating_sub(categorized),
+        });
+
+        StorageReport {
+            data_dir: dir.to_string_lossy().to_string(),
+            total_bytes,
+            categories,
+            disk: disk_space(&dir, threshold),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
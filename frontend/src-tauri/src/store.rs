@@ -0,0 +1,41 @@
+// ============================================================================
+// 本地持久化：应用数据目录下的 JSON 文件
+// ============================================================================
+
+use crate::{debug_log, get_app_data_dir};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// 数据目录下的文件路径
+pub fn data_file_path(app: &tauri::AppHandle, name: &str) -> PathBuf {
+    PathBuf::from(get_app_data_dir(app)).join(name)
+}
+
+/// 读取 JSON 文件，不存在或解析失败时返回默认值
+pub fn load_json<T: DeserializeOwned + Default>(app: &tauri::AppHandle, name: &str) -> T {
+    let path = data_file_path(app, name);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(_) => return T::default(),
+    };
+    match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            debug_log(&format!("[store] 解析 {} 失败，使用默认值: {}", name, e));
+            T::default()
+        }
+    }
+}
+
+/// 写入 JSON 文件（先写临时文件再重命名，避免写一半时崩溃导致文件损坏）
+pub fn save_json<T: Serialize>(app: &tauri::AppHandle, name: &str, value: &T) -> Result<(), String> {
+    let path = data_file_path(app, name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, content).map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| format!("写入 {} 失败: {}", name, e))
+}
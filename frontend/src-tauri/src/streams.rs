@@ -0,0 +1,568 @@
+// ============================================================================
+// 后端流式响应桥接（SSE / chunked）
+// ============================================================================
+//
+// subscribe_stream(path) 由 Rust 打开后端的流式响应，按订阅 ID 转发给前端：
+// - `stream-event`：{id, seq, event, data, event_id}（SSE 按事件解析；其它 chunked 响应每块作为 "chunk" 事件）
+// - `stream-reconnecting`：{id, attempt, error}
+// - `stream-closed`：{id, reason: ended / cancelled / error, error}
+// webview 刷新或卡顿不会中断流：每个订阅保留最近 REPLAY_BUFFER_EVENTS 个事件（seq 从 1 递增），
+// 前端重新监听后调用 replay_stream(id, since) 补齐错过的事件；订阅结束后缓冲保留 CLOSED_RETENTION。
+// GET 订阅在连接中断时带 Last-Event-ID 自动重连（其它方法重连会重复提交请求，不重连）。
+// 解析缓冲区有上限，超过时丢弃未完成的事件，避免一直不换行的响应占满内存。
+
+use crate::debug_log;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// 最多重连次数（每次成功连接后清零）
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+/// 重连间隔上限
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// 未完成事件的最大缓冲字节数
+const MAX_PENDING_BYTES: usize = 1024 * 1024;
+
+/// 每个订阅保留的最近事件数
+const REPLAY_BUFFER_EVENTS: usize = 256;
+
+/// 订阅结束后保留事件缓冲的时长
+const CLOSED_RETENTION: Duration = Duration::from_secs(60);
+
+/// 订阅（ID → 状态）
+#[derive(Default)]
+pub struct StreamSubscriptions {
+    subscriptions: Mutex<HashMap<String, Subscription>>,
+}
+
+#[derive(Default)]
+struct Subscription {
+    /// 读取任务（spawn 之前先登记订阅，此时为 None；结束后为 None）
+    task: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// 最近的事件
+    buffer: VecDeque<StreamEvent>,
+    next_seq: u64,
+    /// 订阅结束时 stream-closed 的内容
+    closed: Option<serde_json::Value>,
+}
+
+#[derive(Clone)]
+struct StreamRequest {
+    method: String,
+    path: String,
+    body: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct StreamEvent {
+    id: String,
+    seq: u64,
+    event: String,
+    data: String,
+    event_id: Option<String>,
+}
+
+/// SSE 解析器（https://html.spec.whatwg.org/multipage/server-sent-events.html）
+#[derive(Default)]
+struct SseParser {
+    pending: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+    /// 最近收到的事件 ID（重连时作为 Last-Event-ID）
+    last_event_id: Option<String>,
+    /// 服务端建议的重连间隔
+    retry: Option<Duration>,
+}
+
+impl SseParser {
+    /// 输入一块数据，返回其中完整的事件 (event, data, id)
+    fn feed(&mut self, chunk: &[u8]) -> Vec<(String, String, Option<String>)> {
+        self.pending.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(pos) = self.pending.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.pending.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                // 空行：分发当前事件
+                if !self.data.is_empty() {
+                    events.push((
+                        self.event.take().unwrap_or_else(|| "message".to_string()),
+                        self.data.join("\n"),
+                        self.last_event_id.clone(),
+                    ));
+                }
+                self.data.clear();
+                self.event = None;
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = match line.split_once(':') {
+                Some((f, v)) => (f, v.strip_prefix(' ').unwrap_or(v)),
+                None => (line, ""),
+            };
+            match field {
+                "event" => self.event = Some(value.to_string()),
+                "data" => self.data.push(value.to_string()),
+                "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+                "retry" => {
+                    if let Ok(ms) = value.parse::<u64>() {
+                        self.retry = Some(Duration::from_millis(ms));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if self.pending.len() > MAX_PENDING_BYTES {
+            debug_log("[streams] 单行数据过长，已丢弃");
+            self.pending.clear();
+            self.data.clear();
+        }
+        events
+    }
+}
+
+/// 打开的响应体
+struct Body {
+    content_type: String,
+    inner: BodyInner,
+}
+
+enum BodyInner {
+    Http(reqwest::Response),
+    #[cfg(unix)]
+    Uds {
+        reader: tokio::io::BufReader<tokio::net::UnixStream>,
+        chunked: bool,
+    },
+}
+
+impl Body {
+    /// 读取下一块数据，流结束时返回 None
+    async fn next_chunk(&mut self) -> Result<Option<Vec<u8>>, String> {
+        match &mut self.inner {
+            BodyInner::Http(resp) => resp
+                .chunk()
+                .await
+                .map(|c| c.map(|b| b.to_vec()))
+                .map_err(|e| e.to_string()),
+            #[cfg(unix)]
+            BodyInner::Uds { reader, chunked } => {
+                use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+                if !*chunked {
+                    let mut buf = vec![0u8; 8192];
+                    let n = reader.read(&mut buf).await.map_err(|e| e.to_string())?;
+                    buf.truncate(n);
+                    return Ok((n > 0).then_some(buf));
+                }
+                let mut size_line = String::new();
+                if reader
+                    .read_line(&mut size_line)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    == 0
+                {
+                    return Err("connection closed".to_string());
+                }
+                let size =
+                    usize::from_str_radix(size_line.split(';').next().unwrap_or("").trim(), 16)
+                        .map_err(|_| format!("无效的 chunk 长度: {}", size_line.trim()))?;
+                if size == 0 {
+                    return Ok(None);
+                }
+                let mut buf = vec![0u8; size + 2];
+                reader
+                    .read_exact(&mut buf)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                buf.truncate(size);
+                Ok(Some(buf))
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn open_uds(
+    socket: &str,
+    request: &StreamRequest,
+    last_event_id: Option<&str>,
+) -> Result<Body, String> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let mut stream = tokio::net::UnixStream::connect(socket)
+        .await
+        .map_err(|e| format!("连接后端 socket 失败: {}", e))?;
+    let body = request
+        .body
+        .as_ref()
+        .map(|b| b.to_string())
+        .unwrap_or_default();
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nAccept: text/event-stream\r\nConnection: close\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\n",
+        request.method,
+        request.path,
+        body.len()
+    );
+    if let Some(id) = last_event_id {
+        head.push_str(&format!("Last-Event-ID: {}\r\n", id));
+    }
+    head.push_str("\r\n");
+    head.push_str(&body);
+    stream
+        .write_all(head.as_bytes())
+        .await
+        .map_err(|e| format!("发送请求失败: {}", e))?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status_line = String::new();
+    reader
+        .read_line(&mut status_line)
+        .await
+        .map_err(|e| e.to_string())?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("无效的响应: {}", status_line.trim()))?;
+    let mut content_type = String::new();
+    let mut chunked = false;
+    loop {
+        let mut line = String::new();
+        if reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| e.to_string())?
+            == 0
+        {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-type" => content_type = value.trim().to_string(),
+                "transfer-encoding" => chunked = value.trim().eq_ignore_ascii_case("chunked"),
+                _ => {}
+            }
+        }
+    }
+    if !(200..300).contains(&status) {
+        return Err(format!("HTTP {}", status));
+    }
+    Ok(Body {
+        content_type,
+        inner: BodyInner::Uds { reader, chunked },
+    })
+}
+
+async fn open(
+    app: &tauri::AppHandle,
+    request: &StreamRequest,
+    last_event_id: Option<&str>,
+) -> Result<Body, String> {
+    let crate::BackendInfo {
+        port, socket_path, ..
+    } = app.state::<crate::BackendState>().info();
+
+    #[cfg(unix)]
+    if let Some(socket) = socket_path {
+        return open_uds(&socket, request, last_event_id).await;
+    }
+    #[cfg(not(unix))]
+    let _ = socket_path;
+
+    let method = reqwest::Method::from_bytes(request.method.as_bytes())
+        .map_err(|e| format!("无效的请求方法: {}", e))?;
+    let mut builder = crate::http::client()
+        .request(method, format!("http://127.0.0.1:{}{}", port, request.path))
+        .header("Accept", "text/event-stream");
+    if let Some(id) = last_event_id {
+        builder = builder.header("Last-Event-ID", id);
+    }
+    if let Some(body) = &request.body {
+        builder = builder.json(body);
+    }
+    let resp = builder.send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {}", resp.status().as_u16()));
+    }
+    let content_type = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    Ok(Body {
+        content_type,
+        inner: BodyInner::Http(resp),
+    })
+}
+
+/// 记入订阅的事件缓冲并发给前端（订阅已取消时丢弃）
+fn publish(
+    app: &tauri::AppHandle,
+    id: &str,
+    event: String,
+    data: String,
+    event_id: Option<String>,
+) {
+    let payload = {
+        let state = app.state::<StreamSubscriptions>();
+        let Ok(mut subscriptions) = state.subscriptions.lock() else {
+            return;
+        };
+        let Some(subscription) = subscriptions.get_mut(id) else {
+            return;
+        };
+        subscription.next_seq += 1;
+        let payload = StreamEvent {
+            id: id.to_string(),
+            seq: subscription.next_seq,
+            event,
+            data,
+            event_id,
+        };
+        if subscription.buffer.len() >= REPLAY_BUFFER_EVENTS {
+            subscription.buffer.pop_front();
+        }
+        subscription.buffer.push_back(payload.clone());
+        payload
+    };
+    let _ = app.emit("stream-event", payload);
+}
+
+/// 读取一次连接，正常结束返回 Ok，连接失败或中断返回 Err
+async fn read_stream(
+    app: &tauri::AppHandle,
+    id: &str,
+    request: &StreamRequest,
+    parser: &mut SseParser,
+    attempt: &mut u32,
+) -> Result<(), String> {
+    let mut body = open(app, request, parser.last_event_id.as_deref()).await?;
+    *attempt = 0;
+    let is_sse = body.content_type.starts_with("text/event-stream");
+    while let Some(chunk) = body.next_chunk().await? {
+        if !is_sse {
+            let data = String::from_utf8_lossy(&chunk).to_string();
+            publish(app, id, "chunk".to_string(), data, None);
+            continue;
+        }
+        for (event, data, event_id) in parser.feed(&chunk) {
+            publish(app, id, event, data, event_id);
+        }
+    }
+    Ok(())
+}
+
+async fn run(app: tauri::AppHandle, id: String, request: StreamRequest) {
+    let mut parser = SseParser::default();
+    let mut attempt = 0;
+    let error = loop {
+        let error = match read_stream(&app, &id, &request, &mut parser, &mut attempt).await {
+            Ok(()) => break None,
+            Err(e) => e,
+        };
+        if request.method != "GET" || attempt >= MAX_RECONNECT_ATTEMPTS {
+            break Some(error);
+        }
+        attempt += 1;
+        let delay = parser
+            .retry
+            .unwrap_or(Duration::from_secs(1))
+            .saturating_mul(1 << (attempt - 1).min(5))
+            .min(MAX_RECONNECT_DELAY);
+        debug_log(&format!(
+            "[streams] 流中断，{}ms 后重连 (id={}, 第 {} 次): {}",
+            delay.as_millis(),
+            id,
+            attempt,
+            error
+        ));
+        let _ = app.emit(
+            "stream-reconnecting",
+            serde_json::json!({ "id": &id, "attempt": attempt, "error": &error }),
+        );
+        tokio::time::sleep(delay).await;
+    };
+
+    debug_log(&format!("[streams] 订阅结束 (id={}): {:?}", id, error));
+    let closed = serde_json::json!({
+        "id": &id,
+        "reason": if error.is_some() { "error" } else { "ended" },
+        "error": error,
+    });
+    if let Ok(mut subscriptions) = app.state::<StreamSubscriptions>().subscriptions.lock() {
+        if let Some(subscription) = subscriptions.get_mut(&id) {
+            subscription.task = None;
+            subscription.closed = Some(closed.clone());
+        }
+    }
+    let _ = app.emit("stream-closed", closed);
+
+    // 保留一段时间的事件缓冲，供刷新后的 webview 补齐
+    tokio::time::sleep(CLOSED_RETENTION).await;
+    if let Ok(mut subscriptions) = app.state::<StreamSubscriptions>().subscriptions.lock() {
+        subscriptions.remove(&id);
+    }
+}
+
+/// 订阅后端流式接口，返回订阅 ID
+#[tauri::command]
+pub async fn subscribe_stream(
+    app: tauri::AppHandle,
+    path: String,
+    method: Option<String>,
+    body: Option<serde_json::Value>,
+) -> Result<String, String> {
+    if !path.starts_with('/') {
+        return Err("path must start with /".to_string());
+    }
+    let request = StreamRequest {
+        method: method
+            .unwrap_or_else(|| "GET".to_string())
+            .to_ascii_uppercase(),
+        path,
+        body,
+    };
+    let id = uuid::Uuid::new_v4().to_string();
+    debug_log(&format!(
+        "[streams] 订阅 {} {} (id={})",
+        request.method, request.path, id
+    ));
+
+    // 先登记订阅再启动读取任务，任务中的事件与结束状态都能找到订阅
+    let state = app.state::<StreamSubscriptions>();
+    state
+        .subscriptions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(id.clone(), Subscription::default());
+    let task = tauri::async_runtime::spawn(run(app.clone(), id.clone(), request));
+    let mut subscriptions = state.subscriptions.lock().map_err(|e| e.to_string())?;
+    match subscriptions.get_mut(&id) {
+        Some(subscription) if subscription.closed.is_none() => subscription.task = Some(task),
+        // 已结束：任务只剩保留缓冲的等待，照常运行
+        Some(_) => {}
+        // 已取消
+        None => task.abort(),
+    }
+    Ok(id)
+}
+
+/// replay_stream 的结果
+#[derive(Debug, serde::Serialize)]
+pub struct StreamReplay {
+    /// seq 大于 since 的缓冲事件
+    events: Vec<StreamEvent>,
+    /// since 之后有事件已被挤出缓冲区
+    truncated: bool,
+    /// 订阅已结束时为 stream-closed 的内容
+    closed: Option<serde_json::Value>,
+}
+
+/// 返回订阅中 seq 大于 since（默认 0）的缓冲事件，webview 刷新后补齐错过的事件
+#[tauri::command]
+pub async fn replay_stream(
+    app: tauri::AppHandle,
+    id: String,
+    since: Option<u64>,
+) -> Result<StreamReplay, String> {
+    let since = since.unwrap_or(0);
+    let state = app.state::<StreamSubscriptions>();
+    let subscriptions = state.subscriptions.lock().map_err(|e| e.to_string())?;
+    let subscription = subscriptions
+        .get(&id)
+        .ok_or_else(|| format!("Stream not found: {}", id))?;
+    let oldest = subscription
+        .buffer
+        .front()
+        .map_or(subscription.next_seq + 1, |e| e.seq);
+    Ok(StreamReplay {
+        events: subscription
+            .buffer
+            .iter()
+            .filter(|e| e.seq > since)
+            .cloned()
+            .collect(),
+        truncated: since + 1 < oldest,
+        closed: subscription.closed.clone(),
+    })
+}
+
+/// 取消订阅，返回订阅是否存在
+#[tauri::command]
+pub async fn unsubscribe_stream(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+    let subscription = app
+        .state::<StreamSubscriptions>()
+        .subscriptions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&id);
+    let Some(subscription) = subscription else {
+        return Ok(false);
+    };
+    if let Some(task) = subscription.task {
+        task.abort();
+    }
+    if subscription.closed.is_some() {
+        return Ok(false);
+    }
+    let _ = app.emit(
+        "stream-closed",
+        serde_json::json!({ "id": &id, "reason": "cancelled", "error": null }),
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event: &str, data: &str, id: Option<&str>) -> (String, String, Option<String>) {
+        (event.to_string(), data.to_string(), id.map(str::to_string))
+    }
+
+    #[test]
+    fn sse_parser_joins_lines_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.feed(b"event: update\ndata: hel").is_empty());
+        assert_eq!(
+            parser.feed(b"lo\ndata: world\nid: 7\n\n"),
+            [event("update", "hello\nworld", Some("7"))]
+        );
+        assert_eq!(parser.last_event_id.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn sse_parser_handles_crlf_comments_and_default_event() {
+        let mut parser = SseParser::default();
+        assert_eq!(
+            parser.feed(b": keep-alive\r\ndata:x\r\n\r\n\r\n"),
+            [event("message", "x", None)]
+        );
+    }
+
+    #[test]
+    fn sse_parser_keeps_last_event_id_and_retry() {
+        let mut parser = SseParser::default();
+        let events = parser.feed(b"id: 1\ndata: a\n\nretry: 1500\nid: bad\0id\ndata: b\n\n");
+        assert_eq!(
+            events,
+            [
+                event("message", "a", Some("1")),
+                event("message", "b", Some("1"))
+            ]
+        );
+        assert_eq!(parser.retry, Some(Duration::from_millis(1500)));
+    }
+}
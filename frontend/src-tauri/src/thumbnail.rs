@@ -0,0 +1,146 @@
+// ============================================================================
+// 图片缩略图与压缩
+// ============================================================================
+//
+// 大截图、相机照片交给后端 / 模型前先在 Rust 中缩小，节省 token 与带宽：
+// - make_thumbnail(path, max_px)：长边缩到 max_px 以内
+// - downscale_image(path, max_bytes)：逐步降低 JPEG 质量与尺寸，直到文件不超过 max_bytes
+// 结果写入临时 JPEG 并以分块传输返回（见 transfers.rs）。
+
+use crate::transfers::{self, TransferInfo};
+use crate::{debug_log, policy, timeline};
+use image::codecs::jpeg::JpegEncoder;
+use image::RgbImage;
+use std::path::{Path, PathBuf};
+
+/// 缩略图的 JPEG 质量
+const THUMBNAIL_QUALITY: u8 = 80;
+
+/// 压缩时依次尝试的 JPEG 质量；都超出大小时缩小尺寸再试
+const DOWNSCALE_QUALITIES: &[u8] = &[85, 75, 65, 55];
+
+/// 每轮缩小的比例与最小边长
+const DOWNSCALE_FACTOR: f32 = 0.75;
+const MIN_EDGE_PX: u32 = 64;
+
+fn open_image(path: &Path) -> Result<RgbImage, String> {
+    Ok(image::open(path)
+        .map_err(|e| format!("无法读取图片: {}", e))?
+        .into_rgb8())
+}
+
+fn encode_jpeg(img: &RgbImage, quality: u8) -> Result<Vec<u8>, String> {
+    let mut buf = Vec::new();
+    img.write_with_encoder(JpegEncoder::new_with_quality(&mut buf, quality))
+        .map_err(|e| format!("编码图片失败: {}", e))?;
+    Ok(buf)
+}
+
+fn resize(img: &RgbImage, width: u32, height: u32) -> RgbImage {
+    image::imageops::resize(img, width, height, image::imageops::FilterType::Lanczos3)
+}
+
+/// 长边缩到 max_px 以内（不放大）
+fn thumbnail(img: RgbImage, max_px: u32) -> RgbImage {
+    let (width, height) = img.dimensions();
+    let longest = width.max(height);
+    if longest <= max_px {
+        return img;
+    }
+    let scale = max_px as f32 / longest as f32;
+    resize(
+        &img,
+        ((width as f32 * scale).round() as u32).max(1),
+        ((height as f32 * scale).round() as u32).max(1),
+    )
+}
+
+/// 压缩到不超过 max_bytes，返回 JPEG 数据
+fn downscale(mut img: RgbImage, max_bytes: u64) -> Result<Vec<u8>, String> {
+    loop {
+        let mut smallest = Vec::new();
+        for quality in DOWNSCALE_QUALITIES {
+            smallest = encode_jpeg(&img, *quality)?;
+            if smallest.len() as u64 <= max_bytes {
+                return Ok(smallest);
+            }
+        }
+        let (width, height) = img.dimensions();
+        if width.min(height) <= MIN_EDGE_PX {
+            // 已缩到最小仍超出时返回最小的结果
+            return Ok(smallest);
+        }
+        img = resize(
+            &img,
+            ((width as f32 * DOWNSCALE_FACTOR) as u32).max(1),
+            ((height as f32 * DOWNSCALE_FACTOR) as u32).max(1),
+        );
+    }
+}
+
+/// 校验来源文件并在后台线程处理，结果登记为临时传输（action 记入时间线）
+async fn process(
+    app: &tauri::AppHandle,
+    action: &str,
+    path: String,
+    work: impl FnOnce(RgbImage) -> Result<Vec<u8>, String> + Send + 'static,
+) -> Result<TransferInfo, String> {
+    let source = PathBuf::from(&path);
+    if !source.is_file() {
+        return Err(format!("不是文件: {}", source.display()));
+    }
+    policy::check_path(&source)?;
+
+    let target = std::env::temp_dir().join(format!("xiaodazi-image-{}.jpg", uuid::Uuid::new_v4()));
+    let output = target.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let data = work(open_image(&source)?)?;
+        std::fs::write(&output, data).map_err(|e| format!("保存图片失败: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+    timeline::record(
+        app,
+        timeline::TimelineKind::Media,
+        action,
+        result.is_ok(),
+        serde_json::json!({ "path": path, "error": result.as_ref().err() }),
+    );
+    result?;
+    transfers::register(app, target, true)
+}
+
+/// 生成缩略图（长边不超过 max_px）
+#[tauri::command]
+pub async fn make_thumbnail(
+    app: tauri::AppHandle,
+    path: String,
+    max_px: u32,
+) -> Result<TransferInfo, String> {
+    if max_px == 0 {
+        return Err("max_px must be positive".to_string());
+    }
+    debug_log(&format!("[thumbnail] 缩略图 {} (max_px={})", path, max_px));
+    process(&app, "image.thumbnail", path, move |img| {
+        encode_jpeg(&thumbnail(img, max_px), THUMBNAIL_QUALITY)
+    })
+    .await
+}
+
+/// 压缩图片到不超过 max_bytes（转为 JPEG）
+#[tauri::command]
+pub async fn downscale_image(
+    app: tauri::AppHandle,
+    path: String,
+    max_bytes: u64,
+) -> Result<TransferInfo, String> {
+    debug_log(&format!(
+        "[thumbnail] 压缩 {} (max_bytes={})",
+        path, max_bytes
+    ));
+    process(&app, "image.downscale", path, move |img| {
+        downscale(img, max_bytes)
+    })
+    .await
+}
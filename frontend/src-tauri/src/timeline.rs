@@ -0,0 +1,230 @@
+// ============================================================================
+// Agent 活动时间线
+// ============================================================================
+//
+// 壳层可以观察到的 Agent 活动按时间记录到数据目录下的 timeline.db（SQLite），
+// 与后端数据分开存放，重置后端或重启 sidecar 后仍然保留：
+// - command：run_command、计划任务与远程调用执行的命令
+// - capture：屏幕截图
+// - file_write：创建 / 移动 / 删除文件与目录、git 提交与克隆
+// - notification：发出的通知
+// - media：图片缩略图 / 压缩、视频剪辑 / 压缩
+// query_timeline(range, kinds, limit) 按时间倒序查询，range 写法同 export_history。
+// 超过 MAX_AGE_DAYS 天的记录在启动时清理。写入失败只记调试日志，不影响调用方。
+
+use crate::audit::{self, HistoryRange};
+use crate::debug_log;
+use crate::store::data_file_path;
+use rusqlite::{params, params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, PoisonError};
+use tauri::Manager;
+
+/// 时间线数据库文件
+const TIMELINE_FILE: &str = "timeline.db";
+
+/// 记录保留天数
+const MAX_AGE_DAYS: i64 = 90;
+
+/// 单次查询默认 / 最多返回的记录数
+const DEFAULT_LIMIT: usize = 500;
+const MAX_LIMIT: usize = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    Command,
+    Capture,
+    FileWrite,
+    Notification,
+    Media,
+}
+
+impl TimelineKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Command => "command",
+            Self::Capture => "capture",
+            Self::FileWrite => "file_write",
+            Self::Notification => "notification",
+            Self::Media => "media",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "command" => Some(Self::Command),
+            "capture" => Some(Self::Capture),
+            "file_write" => Some(Self::FileWrite),
+            "notification" => Some(Self::Notification),
+            "media" => Some(Self::Media),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    pub id: i64,
+    /// 记录时间（RFC 3339）
+    pub timestamp: String,
+    pub kind: TimelineKind,
+    /// 具体操作（如 "system.run"、"fs.create"）
+    pub action: String,
+    pub success: bool,
+    pub details: serde_json::Value,
+}
+
+/// 时间线数据库连接（打开失败时为 None，记录被忽略）
+#[derive(Default)]
+pub struct TimelineState {
+    conn: Mutex<Option<Connection>>,
+}
+
+fn open_db(app: &tauri::AppHandle) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(data_file_path(app, TIMELINE_FILE))?;
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            action TEXT NOT NULL,
+            success INTEGER NOT NULL,
+            details TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS events_ts ON events (ts);",
+    )?;
+    let cutoff = chrono::Local::now() - chrono::Duration::days(MAX_AGE_DAYS);
+    let removed = conn.execute(
+        "DELETE FROM events WHERE ts < ?1",
+        params![cutoff.timestamp_millis()],
+    )?;
+    if removed > 0 {
+        debug_log(&format!("[timeline] 清理 {} 条过期记录", removed));
+    }
+    Ok(conn)
+}
+
+/// 打开时间线数据库（启动时调用）
+pub fn open(app: &tauri::AppHandle) {
+    match open_db(app) {
+        Ok(conn) => {
+            *app.state::<TimelineState>()
+                .conn
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Some(conn);
+        }
+        Err(e) => debug_log(&format!("[timeline] 打开数据库失败: {}", e)),
+    }
+}
+
+/// 记录一条活动
+pub fn record(
+    app: &tauri::AppHandle,
+    kind: TimelineKind,
+    action: &str,
+    success: bool,
+    details: serde_json::Value,
+) {
+    let Some(state) = app.try_state::<TimelineState>() else {
+        return;
+    };
+    let conn = state.conn.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(conn) = conn.as_ref() else {
+        return;
+    };
+    let result = conn.execute(
+        "INSERT INTO events (ts, kind, action, success, details) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            chrono::Local::now().timestamp_millis(),
+            kind.as_str(),
+            action,
+            success,
+            details.to_string(),
+        ],
+    );
+    if let Err(e) = result {
+        debug_log(&format!("[timeline] 写入失败: {}", e));
+    }
+}
+
+/// 按时间范围与类型查询活动（最新的在前）
+#[tauri::command]
+pub async fn query_timeline(
+    app: tauri::AppHandle,
+    range: Option<HistoryRange>,
+    kinds: Option<Vec<TimelineKind>>,
+    limit: Option<usize>,
+) -> Result<Vec<TimelineEvent>, String> {
+    let range = range.unwrap_or_default();
+    let from = range
+        .from
+        .as_deref()
+        .map(|v| audit::parse_bound(v, false))
+        .transpose()?;
+    let to = range
+        .to
+        .as_deref()
+        .map(|v| audit::parse_bound(v, true))
+        .transpose()?;
+
+    let mut sql =
+        String::from("SELECT id, ts, kind, action, success, details FROM events WHERE 1 = 1");
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    if let Some(from) = from {
+        sql.push_str(" AND ts >= ?");
+        values.push(from.timestamp_millis().into());
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND ts < ?");
+        values.push(to.timestamp_millis().into());
+    }
+    if let Some(kinds) = kinds.filter(|k| !k.is_empty()) {
+        sql.push_str(&format!(
+            " AND kind IN ({})",
+            vec!["?"; kinds.len()].join(", ")
+        ));
+        values.extend(kinds.iter().map(|k| k.as_str().to_string().into()));
+    }
+    sql.push_str(" ORDER BY ts DESC, id DESC LIMIT ?");
+    values.push((limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as i64).into());
+
+    let state = app.state::<TimelineState>();
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let conn = conn.as_ref().ok_or("Timeline is not available")?;
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params_from_iter(values), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, bool>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })
+        .map_err(|e| format!("查询时间线失败: {}", e))?;
+
+    let mut events = Vec::new();
+    for row in rows {
+        let (id, ts, kind, action, success, details) =
+            row.map_err(|e| format!("查询时间线失败: {}", e))?;
+        let (Some(kind), Some(time)) = (
+            TimelineKind::parse(&kind),
+            chrono::DateTime::from_timestamp_millis(ts),
+        ) else {
+            continue;
+        };
+        events.push(TimelineEvent {
+            id,
+            timestamp: time.with_timezone(&chrono::Local).to_rfc3339(),
+            kind,
+            action,
+            success,
+            details: serde_json::from_str(&details).unwrap_or(serde_json::Value::Null),
+        });
+    }
+    Ok(events)
+}
@@ -0,0 +1,520 @@
+// ============================================================================
+// 托管辅助工具（ffmpeg、ripgrep、yt-dlp 等）
+// ============================================================================
+//
+// Agent 与媒体功能依赖的命令行工具可以下载到数据目录的 tools/<name>/<version>/ 下，不依赖用户的 PATH：
+// - 可下载的工具、版本、各平台的下载地址与 SHA-256 固定在 tools.lock.json 中（编译时嵌入），
+//   下载后及使用前都按固定的校验和验证，不接受远程清单给出的校验和
+// - 下载内容边写入磁盘边计算校验和，不整体读入内存；构建设置了 archive_path 时下载的是压缩包
+//   （.zip / .tar.gz），校验通过后取出其中的可执行文件，按同样固定在 tools.lock.json 中的
+//   executable_sha256 验证（安装时与使用前都验证，不信任数据目录中的任何校验记录）
+// - ensure_tool(name)：已安装且校验通过时直接返回，否则下载安装（按需调用，不自动下载）
+// - 查找工具时依次使用：托管版本、随应用打包（与主程序同目录）、PATH
+// - run_command 的程序名与已安装的托管工具相同（如 "rg"）时使用托管版本
+// - 设置 disabled_tools 中的工具不使用、不下载托管版本；cleanup_tools 删除旧版本、
+//   已停用或不再固定的工具
+// 平台 key 与后端更新清单一致（如 darwin-aarch64、windows-x86_64）。
+// 新增平台或升级版本后运行 scripts/update_tools_lock.py 下载并写入 SHA-256；
+// 尚未固定校验和的构建视为不可用，不会下载。
+
+use crate::store::data_file_path;
+use crate::{debug_log, settings, sidecar_update};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, SystemTime};
+use tauri::{Emitter, Manager};
+
+/// 固定的工具版本与校验和
+const TOOLS_LOCK: &str = include_str!("../tools.lock.json");
+
+/// 托管工具存放目录（数据目录下）
+const TOOLS_DIR: &str = "tools";
+
+/// 工具下载超时
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Deserialize)]
+struct PinnedBuild {
+    url: String,
+    /// 下载文件（压缩包或可执行文件本身）的 SHA-256
+    sha256: String,
+    /// 可执行文件在压缩包中的路径，下载的不是压缩包时为 None
+    #[serde(default)]
+    archive_path: Option<String>,
+    /// 从压缩包取出的可执行文件的 SHA-256（设置了 archive_path 时必需）
+    #[serde(default)]
+    executable_sha256: Option<String>,
+}
+
+impl PinnedBuild {
+    /// 安装后可执行文件的固定校验和
+    fn executable_checksum(&self) -> Option<&str> {
+        let checksum = match self.archive_path {
+            Some(_) => self.executable_sha256.as_deref()?,
+            None => &self.sha256,
+        };
+        Some(checksum.trim()).filter(|c| !c.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PinnedTool {
+    version: String,
+    /// 可执行文件名（不含 .exe），默认与工具名相同
+    #[serde(default)]
+    binary: Option<String>,
+    platforms: HashMap<String, PinnedBuild>,
+}
+
+impl PinnedTool {
+    fn binary<'a>(&'a self, name: &'a str) -> &'a str {
+        self.binary.as_deref().unwrap_or(name)
+    }
+
+    fn build(&self) -> Option<&PinnedBuild> {
+        self.platforms
+            .get(&sidecar_update::platform_key())
+            .filter(|build| {
+                !build.sha256.trim().is_empty() && build.executable_checksum().is_some()
+            })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManagedTool {
+    pub name: String,
+    pub version: String,
+    /// 可执行文件名（run_command 中使用的程序名）
+    pub binary: String,
+    /// 未被设置 disabled_tools 停用
+    pub enabled: bool,
+    /// 当前平台有可下载的固定构建
+    pub available: bool,
+    /// 托管版本已安装（且校验通过）
+    pub installed: bool,
+    /// 实际使用的路径（托管、打包或 PATH），找不到时为 None
+    pub path: Option<String>,
+    /// "managed" / "bundled" / "system"
+    pub source: Option<String>,
+}
+
+/// `tool-download-progress` 事件负载
+#[derive(Debug, Clone, Serialize)]
+struct DownloadProgressPayload {
+    name: String,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+fn pinned_tools() -> &'static HashMap<String, PinnedTool> {
+    static TOOLS: OnceLock<HashMap<String, PinnedTool>> = OnceLock::new();
+    TOOLS.get_or_init(|| {
+        serde_json::from_str(TOOLS_LOCK).unwrap_or_else(|e| {
+            debug_log(&format!("[tools] 解析 tools.lock.json 失败: {}", e));
+            HashMap::new()
+        })
+    })
+}
+
+fn pinned(name: &str) -> Result<&'static PinnedTool, String> {
+    pinned_tools()
+        .get(name)
+        .ok_or_else(|| format!("Unknown tool: {}", name))
+}
+
+fn is_enabled(app: &tauri::AppHandle, name: &str) -> bool {
+    !settings::current(app)
+        .disabled_tools
+        .iter()
+        .any(|t| t == name)
+}
+
+fn executable(binary: &str) -> String {
+    format!("{}{}", binary, std::env::consts::EXE_SUFFIX)
+}
+
+fn tool_dir(app: &tauri::AppHandle, name: &str) -> PathBuf {
+    data_file_path(app, TOOLS_DIR).join(name)
+}
+
+fn managed_path(app: &tauri::AppHandle, name: &str, tool: &PinnedTool) -> PathBuf {
+    tool_dir(app, name)
+        .join(&tool.version)
+        .join(executable(tool.binary(name)))
+}
+
+/// 已校验过的文件（路径 → 修改时间），文件未变化时不再重复计算校验和
+fn verified_cache() -> &'static Mutex<HashMap<PathBuf, SystemTime>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, SystemTime>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 已启用、已安装且与固定校验和一致的托管版本
+fn verified_managed(app: &tauri::AppHandle, name: &str) -> Option<PathBuf> {
+    let tool = pinned(name).ok()?;
+    let build = tool.build()?;
+    if !is_enabled(app, name) {
+        return None;
+    }
+    let path = managed_path(app, name, tool);
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    let mut cache = verified_cache()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if cache.get(&path) == Some(&modified) {
+        return Some(path);
+    }
+    let expected = build.executable_checksum()?;
+    let data = std::fs::read(&path).ok()?;
+    match sidecar_update::verify_checksum(&data, expected) {
+        Ok(()) => {
+            cache.insert(path.clone(), modified);
+            Some(path)
+        }
+        Err(e) => {
+            debug_log(&format!("[tools] {} 校验失败，忽略: {}", path.display(), e));
+            None
+        }
+    }
+}
+
+/// 查找工具，返回路径与来源
+fn locate(app: &tauri::AppHandle, name: &str) -> Option<(PathBuf, &'static str)> {
+    if let Some(path) = verified_managed(app, name) {
+        return Some((path, "managed"));
+    }
+    let binary = executable(pinned(name).map(|t| t.binary(name)).unwrap_or(name));
+    let bundled = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(&binary)))
+        .filter(|path| path.is_file());
+    if let Some(path) = bundled {
+        return Some((path, "bundled"));
+    }
+    let search_path = std::env::var_os("PATH")?;
+    std::env::split_paths(&search_path)
+        .map(|dir| dir.join(&binary))
+        .find(|path| path.is_file())
+        .map(|path| (path, "system"))
+}
+
+/// 查找工具路径（托管 > 打包 > PATH）
+pub fn find_tool(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    locate(app, name)
+        .map(|(path, _)| path)
+        .ok_or_else(|| format!("{} not found", name))
+}
+
+/// run_command 的程序名为已安装的托管工具时替换为托管路径
+pub fn resolve_command(app: &tauri::AppHandle, mut command: Vec<String>) -> Vec<String> {
+    let Some(program) = command.first() else {
+        return command;
+    };
+    let managed = pinned_tools()
+        .iter()
+        .find(|(name, tool)| {
+            program == tool.binary(name) || *program == executable(tool.binary(name))
+        })
+        .and_then(|(name, _)| verified_managed(app, name));
+    if let Some(path) = managed {
+        debug_log(&format!(
+            "[tools] {} 使用托管版本: {}",
+            program,
+            path.display()
+        ));
+        command[0] = path.to_string_lossy().to_string();
+    }
+    command
+}
+
+fn describe(app: &tauri::AppHandle, name: &str, tool: &PinnedTool) -> ManagedTool {
+    let located = locate(app, name);
+    ManagedTool {
+        name: name.to_string(),
+        version: tool.version.clone(),
+        binary: tool.binary(name).to_string(),
+        enabled: is_enabled(app, name),
+        available: tool.build().is_some(),
+        installed: located.as_ref().is_some_and(|(_, s)| *s == "managed"),
+        path: located
+            .as_ref()
+            .map(|(path, _)| path.to_string_lossy().to_string()),
+        source: located.map(|(_, source)| source.to_string()),
+    }
+}
+
+fn hex_digest(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 从压缩包中取出一个文件写入 dest（按下载地址的扩展名识别 .zip / .tar.gz / .tgz）
+fn extract_entry(archive: &Path, url: &str, entry: &str, dest: &Path) -> Result<(), String> {
+    let open = || std::fs::File::open(archive).map_err(|e| format!("打开压缩包失败: {}", e));
+    let mut out = std::fs::File::create(dest).map_err(|e| format!("写入工具失败: {}", e))?;
+    let url = url.to_ascii_lowercase();
+    if url.ends_with(".zip") {
+        let mut zip =
+            zip::ZipArchive::new(open()?).map_err(|e| format!("读取压缩包失败: {}", e))?;
+        let mut file = zip
+            .by_name(entry)
+            .map_err(|e| format!("压缩包中没有 {}: {}", entry, e))?;
+        std::io::copy(&mut file, &mut out).map_err(|e| format!("解压失败: {}", e))?;
+    } else if url.ends_with(".tar.gz") || url.ends_with(".tgz") {
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(open()?));
+        let entries = tar
+            .entries()
+            .map_err(|e| format!("读取压缩包失败: {}", e))?;
+        let mut found = false;
+        for file in entries {
+            let mut file = file.map_err(|e| format!("读取压缩包失败: {}", e))?;
+            if file.path().is_ok_and(|p| p == Path::new(entry)) {
+                std::io::copy(&mut file, &mut out).map_err(|e| format!("解压失败: {}", e))?;
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            return Err(format!("压缩包中没有 {}", entry));
+        }
+    } else {
+        return Err(format!("不支持的压缩包格式: {}", url));
+    }
+    out.flush().map_err(|e| format!("写入工具失败: {}", e))
+}
+
+/// 安装已下载并校验的文件：需要时从压缩包取出可执行文件并按固定校验和验证，
+/// 设置可执行权限后重命名到 path
+fn install_download(download: &Path, build: &PinnedBuild, path: &Path) -> Result<(), String> {
+    let tmp = match &build.archive_path {
+        Some(entry) => {
+            let tmp = path.with_extension("extract");
+            let result = extract_entry(download, &build.url, entry, &tmp).and_then(|()| {
+                let data = std::fs::read(&tmp).map_err(|e| format!("读取工具失败: {}", e))?;
+                let expected = build
+                    .executable_checksum()
+                    .ok_or("executable_sha256 not pinned")?;
+                sidecar_update::verify_checksum(&data, expected)
+            });
+            let _ = std::fs::remove_file(download);
+            if let Err(e) = result {
+                let _ = std::fs::remove_file(&tmp);
+                return Err(e);
+            }
+            // 旧版本在数据目录记录的校验和已不再使用
+            let _ = std::fs::remove_file(path.with_extension("sha256"));
+            tmp
+        }
+        None => download.to_path_buf(),
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("设置可执行权限失败: {}", e))?;
+    }
+
+    std::fs::rename(&tmp, path).map_err(|e| format!("写入工具失败: {}", e))
+}
+
+/// 下载到 dest（边写入边计算校验和），返回下载内容的 SHA-256
+async fn download_to(
+    app: &tauri::AppHandle,
+    name: &str,
+    url: &str,
+    dest: &Path,
+) -> Result<String, String> {
+    let resp = sidecar_update::download_client()
+        .get(url)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("下载 {} 失败: {}", name, e))?;
+
+    let total = resp.content_length();
+    let mut file = tokio::fs::File::create(dest)
+        .await
+        .map_err(|e| format!("写入工具失败: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut downloaded = 0u64;
+    let mut stream = resp.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("下载 {} 失败: {}", name, e))?;
+        hasher.update(&chunk);
+        tokio::io::AsyncWriteExt::write_all(&mut file, &chunk)
+            .await
+            .map_err(|e| format!("写入工具失败: {}", e))?;
+        downloaded += chunk.len() as u64;
+        let _ = app.emit(
+            "tool-download-progress",
+            DownloadProgressPayload {
+                name: name.to_string(),
+                downloaded,
+                total,
+            },
+        );
+    }
+    tokio::io::AsyncWriteExt::flush(&mut file)
+        .await
+        .map_err(|e| format!("写入工具失败: {}", e))?;
+    Ok(hex_digest(hasher))
+}
+
+/// 删除托管工具的其他版本，keep 为 None 时删除整个工具目录；返回删除的目录
+fn prune_versions(app: &tauri::AppHandle, name: &str, keep: Option<&str>) -> Vec<String> {
+    let dir = tool_dir(app, name);
+    let mut removed = Vec::new();
+    let Some(keep) = keep else {
+        if dir.exists() && std::fs::remove_dir_all(&dir).is_ok() {
+            removed.push(dir.to_string_lossy().to_string());
+        }
+        return removed;
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return removed;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name() != keep && std::fs::remove_dir_all(entry.path()).is_ok() {
+            removed.push(entry.path().to_string_lossy().to_string());
+        }
+    }
+    removed
+}
+
+/// 确保托管工具已安装（需要时下载并校验），返回工具信息
+#[tauri::command]
+pub async fn ensure_tool(app: tauri::AppHandle, name: String) -> Result<ManagedTool, String> {
+    let tool = pinned(&name)?;
+    if !is_enabled(&app, &name) {
+        return Err(format!("Tool is disabled: {}", name));
+    }
+    if verified_managed(&app, &name).is_some() {
+        return Ok(describe(&app, &name, tool));
+    }
+    let build = tool.build().ok_or_else(|| {
+        format!(
+            "No pinned {} build for {}",
+            name,
+            sidecar_update::platform_key()
+        )
+    })?;
+
+    debug_log(&format!(
+        "[tools] 下载 {} {}: {}",
+        name, tool.version, build.url
+    ));
+    let path = managed_path(&app, &name, tool);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("创建工具目录失败: {}", e))?;
+    }
+    let download = path.with_extension("download");
+    let checksum = download_to(&app, &name, &build.url, &download)
+        .await
+        .and_then(|actual| {
+            if actual.eq_ignore_ascii_case(build.sha256.trim()) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "校验和不匹配: 期望 {}，实际 {}",
+                    build.sha256, actual
+                ))
+            }
+        });
+    if let Err(e) = checksum {
+        let _ = std::fs::remove_file(&download);
+        return Err(e);
+    }
+
+    let build = build.clone();
+    tauri::async_runtime::spawn_blocking(move || install_download(&download, &build, &path))
+        .await
+        .map_err(|e| e.to_string())??;
+    prune_versions(&app, &name, Some(&tool.version));
+
+    debug_log(&format!("[tools] {} {} 已安装", name, tool.version));
+    Ok(describe(&app, &name, tool))
+}
+
+/// 列出全部托管工具及当前使用的路径
+#[tauri::command]
+pub async fn list_managed_tools(app: tauri::AppHandle) -> Result<Vec<ManagedTool>, String> {
+    let mut tools: Vec<ManagedTool> = pinned_tools()
+        .iter()
+        .map(|(name, tool)| describe(&app, name, tool))
+        .collect();
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(tools)
+}
+
+/// 获取工具的可执行文件路径（托管 > 打包 > PATH），找不到时返回 None
+#[tauri::command]
+pub async fn get_tool_path(app: tauri::AppHandle, name: String) -> Result<Option<String>, String> {
+    pinned(&name)?;
+    Ok(locate(&app, &name).map(|(path, _)| path.to_string_lossy().to_string()))
+}
+
+/// 启用或停用托管工具（停用后不使用、不下载托管版本，已安装的文件由 cleanup_tools 删除）
+#[tauri::command]
+pub async fn set_tool_enabled(
+    app: tauri::AppHandle,
+    name: String,
+    enabled: bool,
+) -> Result<ManagedTool, String> {
+    let tool = pinned(&name)?;
+    {
+        let state = app.state::<Mutex<settings::AppSettings>>();
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        guard.disabled_tools.retain(|t| t != &name);
+        if !enabled {
+            guard.disabled_tools.push(name.clone());
+        }
+        settings::save_settings(&guard)?;
+    }
+    debug_log(&format!(
+        "[tools] {} 已{}",
+        name,
+        if enabled { "启用" } else { "停用" }
+    ));
+    Ok(describe(&app, &name, tool))
+}
+
+/// 删除托管工具（之后回退到打包版本或 PATH）
+#[tauri::command]
+pub async fn remove_managed_tool(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    pinned(&name)?;
+    let dir = tool_dir(&app, &name);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("删除工具失败: {}", e))?;
+    }
+    debug_log(&format!("[tools] 已删除 {}", name));
+    Ok(())
+}
+
+/// 清理托管工具目录：旧版本、已停用的工具、tools.lock.json 中已不存在的工具；返回删除的目录
+#[tauri::command]
+pub async fn cleanup_tools(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let Ok(entries) = std::fs::read_dir(data_file_path(&app, TOOLS_DIR)) else {
+        return Ok(Vec::new());
+    };
+    let mut removed = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let keep = pinned_tools()
+            .get(&name)
+            .filter(|_| is_enabled(&app, &name))
+            .map(|tool| tool.version.as_str());
+        removed.extend(prune_versions(&app, &name, keep));
+    }
+    debug_log(&format!("[tools] 清理完成，删除 {} 个目录", removed.len()));
+    Ok(removed)
+}
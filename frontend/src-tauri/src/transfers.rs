@@ -0,0 +1,204 @@
+// ============================================================================
+// 分块传输：大文件 / 截图分块交给 webview
+// ============================================================================
+//
+// 一次 invoke 返回几十 MB 的数据（尤其是 base64）会让内存峰值翻倍，也可能超过 IPC 限制。
+// read_local_file_binary 只适合小文件，大文件改为句柄方式：
+// - open_file_transfer(path) 或 Rust 内部调用 register(...) 登记文件，返回 {id, size, chunk_size, chunks}
+// - 前端按序调用 read_chunk(id, index)，每块以原始字节（ArrayBuffer）返回
+// - 读完调用 close_transfer(id)；临时文件（截图等）在关闭或闲置超时后删除
+
+use crate::{debug_log, policy};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+/// 每块大小
+const CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// 闲置超过该时长的传输自动关闭
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferInfo {
+    pub id: String,
+    pub size: u64,
+    pub chunk_size: u64,
+    pub chunks: u64,
+}
+
+struct Transfer {
+    path: PathBuf,
+    size: u64,
+    /// 关闭时删除文件
+    temporary: bool,
+    last_access: Instant,
+}
+
+impl Transfer {
+    fn cleanup(&self) {
+        if self.temporary {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// 进行中的传输
+#[derive(Default)]
+pub struct Transfers {
+    inner: Mutex<HashMap<String, Transfer>>,
+}
+
+/// 关闭闲置超时的传输
+fn prune(transfers: &mut HashMap<String, Transfer>) {
+    transfers.retain(|id, transfer| {
+        let alive = transfer.last_access.elapsed() < IDLE_TIMEOUT;
+        if !alive {
+            debug_log(&format!("[transfers] 传输闲置超时，已关闭: {}", id));
+            transfer.cleanup();
+        }
+        alive
+    });
+}
+
+/// 登记一个待分块读取的文件（temporary 为 true 时关闭后删除）
+pub fn register(
+    app: &tauri::AppHandle,
+    path: PathBuf,
+    temporary: bool,
+) -> Result<TransferInfo, String> {
+    let size = std::fs::metadata(&path)
+        .map_err(|e| format!("无法读取文件信息: {}", e))?
+        .len();
+    let info = TransferInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        size,
+        chunk_size: CHUNK_SIZE,
+        chunks: size.div_ceil(CHUNK_SIZE),
+    };
+    let state = app.state::<Transfers>();
+    let mut transfers = state.inner.lock().map_err(|e| e.to_string())?;
+    prune(&mut transfers);
+    transfers.insert(
+        info.id.clone(),
+        Transfer {
+            path,
+            size,
+            temporary,
+            last_access: Instant::now(),
+        },
+    );
+    Ok(info)
+}
+
+/// 开始分块读取本地文件
+#[tauri::command]
+pub async fn open_file_transfer(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<TransferInfo, String> {
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err(format!("不是文件: {}", path.display()));
+    }
+    policy::check_path(&path)?;
+    register(&app, path, false)
+}
+
+/// 截取整个屏幕，截图以临时文件分块读取（关闭传输后删除）
+///
+/// 每次截图前都需用户审批。
+#[tauri::command]
+pub async fn capture_screen(app: tauri::AppHandle) -> Result<TransferInfo, String> {
+    crate::approvals::request_approval(
+        &app,
+        "screen.capture",
+        crate::i18n::t("approval.capture_screen"),
+        serde_json::json!({}),
+    )
+    .await?;
+    let path = std::env::temp_dir().join(format!("xiaodazi-capture-{}.png", uuid::Uuid::new_v4()));
+    let target = path.clone();
+    let result =
+        tauri::async_runtime::spawn_blocking(move || crate::intents::capture_screen(&target))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+    crate::timeline::record(
+        &app,
+        crate::timeline::TimelineKind::Capture,
+        "screen.capture",
+        result.is_ok(),
+        serde_json::json!({ "error": result.as_ref().err() }),
+    );
+    result?;
+    register(&app, path, true)
+}
+
+/// 读取第 index 块（从 0 开始）
+#[tauri::command]
+pub async fn read_chunk(
+    app: tauri::AppHandle,
+    id: String,
+    index: u64,
+) -> Result<tauri::ipc::Response, String> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let (path, size) = {
+        let state = app.state::<Transfers>();
+        let mut transfers = state.inner.lock().map_err(|e| e.to_string())?;
+        let transfer = transfers
+            .get_mut(&id)
+            .ok_or_else(|| format!("Transfer not found: {}", id))?;
+        transfer.last_access = Instant::now();
+        (transfer.path.clone(), transfer.size)
+    };
+    let offset = index.saturating_mul(CHUNK_SIZE);
+    if offset >= size {
+        return Err(format!("Chunk index out of range: {}", index));
+    }
+    let len = CHUNK_SIZE.min(size - offset) as usize;
+
+    let mut file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    file.seek(std::io::SeekFrom::Start(offset))
+        .await
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    let mut buf = vec![0u8; len];
+    file.read_exact(&mut buf)
+        .await
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    Ok(tauri::ipc::Response::new(buf))
+}
+
+/// 传输对应的文件路径
+pub fn path_of(app: &tauri::AppHandle, id: &str) -> Result<PathBuf, String> {
+    let state = app.state::<Transfers>();
+    let transfers = state.inner.lock().map_err(|e| e.to_string())?;
+    transfers
+        .get(id)
+        .map(|t| t.path.clone())
+        .ok_or_else(|| format!("Transfer not found: {}", id))
+}
+
+/// 结束传输（临时文件随之删除），返回传输是否存在
+pub fn close(app: &tauri::AppHandle, id: &str) -> Result<bool, String> {
+    let state = app.state::<Transfers>();
+    let mut transfers = state.inner.lock().map_err(|e| e.to_string())?;
+    let removed = transfers.remove(id);
+    if let Some(transfer) = &removed {
+        transfer.cleanup();
+    }
+    prune(&mut transfers);
+    Ok(removed.is_some())
+}
+
+/// 结束传输，返回传输是否存在
+#[tauri::command]
+pub async fn close_transfer(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+    close(&app, &id)
+}
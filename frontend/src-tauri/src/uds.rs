@@ -0,0 +1,393 @@
+// ============================================================================
+// Unix domain socket 传输（sidecar ↔ Rust ↔ WebView）
+// ============================================================================
+//
+// UDS 模式下后端只监听数据目录内的 backend.sock，不占用任何 TCP 端口：
+// - HTTP：WebView 通过自定义协议 zenflux://localhost/... 发起请求，由 Rust 转发到 socket
+// - 流式响应（SSE）：自定义协议只能整块返回，改由 subscribe_stream（streams.rs）逐块转发
+// - WebSocket：自定义协议无法承载 WebSocket，改由 ws_bridge_* 命令 + 事件桥接
+// Windows 暂不支持（uvicorn 不支持命名管道），该平台始终使用 TCP。
+
+use crate::debug_log;
+use std::time::Duration;
+
+/// 自定义协议名（WebView 内访问 zenflux://localhost/api/...）
+pub const URI_SCHEME: &str = "zenflux";
+
+/// socket 文件名（位于应用数据目录）
+pub const SOCKET_FILE: &str = "backend.sock";
+
+/// 连接 socket 失败时的错误前缀（请求未发出，可以重试，见 queue.rs）
+pub const CONNECT_FAILED: &str = "连接后端 socket 失败";
+
+/// 是否支持 UDS 传输
+pub fn is_supported() -> bool {
+    cfg!(unix)
+}
+
+pub struct UdsResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// 解码 chunked 传输编码
+#[cfg(unix)]
+fn decode_chunked(mut data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    while let Some(line_end) = data.windows(2).position(|w| w == b"\r\n") {
+        let size_str = String::from_utf8_lossy(&data[..line_end]);
+        let size = usize::from_str_radix(size_str.split(';').next().unwrap_or("").trim(), 16)
+            .unwrap_or(0);
+        data = &data[line_end + 2..];
+        if size == 0 || data.len() < size {
+            break;
+        }
+        out.extend_from_slice(&data[..size]);
+        data = data.get(size + 2..).unwrap_or(&[]);
+    }
+    out
+}
+
+/// 拼出请求头（Connection: close，响应读到 EOF 为止）
+#[cfg(unix)]
+fn request_head(
+    method: &str,
+    path_and_query: &str,
+    headers: &[(String, String)],
+    body_len: usize,
+) -> String {
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n",
+        method, path_and_query, body_len
+    );
+    for (k, v) in headers {
+        let lower = k.to_ascii_lowercase();
+        if lower == "host" || lower == "connection" || lower == "content-length" {
+            continue;
+        }
+        head.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    head.push_str("\r\n");
+    head
+}
+
+/// 解析完整的 HTTP 响应
+#[cfg(unix)]
+fn parse_response(raw: &[u8]) -> Result<UdsResponse, String> {
+    let header_end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("响应格式错误")?;
+    let head_text = String::from_utf8_lossy(&raw[..header_end]).to_string();
+    let mut lines = head_text.lines();
+    let status = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or("响应状态行错误")?;
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+
+    let raw_body = &raw[header_end + 4..];
+    let chunked = headers.iter().any(|(k, v)| {
+        k.eq_ignore_ascii_case("transfer-encoding") && v.eq_ignore_ascii_case("chunked")
+    });
+    let body = if chunked {
+        decode_chunked(raw_body)
+    } else {
+        raw_body.to_vec()
+    };
+
+    Ok(UdsResponse {
+        status,
+        headers: headers
+            .into_iter()
+            .filter(|(k, _)| !k.eq_ignore_ascii_case("transfer-encoding"))
+            .collect(),
+        body,
+    })
+}
+
+/// 通过 Unix socket 发送一个 HTTP/1.1 请求（异步，等待后端时不占用线程）
+#[cfg(unix)]
+pub async fn http_request(
+    socket_path: &str,
+    method: &str,
+    path_and_query: &str,
+    headers: &[(String, String)],
+    body: &[u8],
+    timeout: Duration,
+) -> Result<UdsResponse, String> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let exchange = async {
+        let mut stream = tokio::net::UnixStream::connect(socket_path)
+            .await
+            .map_err(|e| format!("{}: {}", CONNECT_FAILED, e))?;
+        let head = request_head(method, path_and_query, headers, body.len());
+        stream
+            .write_all(head.as_bytes())
+            .await
+            .map_err(|e| format!("写入请求失败: {}", e))?;
+        stream
+            .write_all(body)
+            .await
+            .map_err(|e| format!("写入请求失败: {}", e))?;
+
+        let mut raw = Vec::new();
+        stream
+            .read_to_end(&mut raw)
+            .await
+            .map_err(|e| format!("读取响应失败: {}", e))?;
+        Ok::<_, String>(raw)
+    };
+    let raw = tokio::time::timeout(timeout, exchange)
+        .await
+        .map_err(|_| "读取响应超时".to_string())??;
+    parse_response(&raw)
+}
+
+#[cfg(not(unix))]
+pub async fn http_request(
+    _socket_path: &str,
+    _method: &str,
+    _path_and_query: &str,
+    _headers: &[(String, String)],
+    _body: &[u8],
+    _timeout: Duration,
+) -> Result<UdsResponse, String> {
+    Err("Unix socket transport not supported on this platform".to_string())
+}
+
+/// 自定义协议处理：把 WebView 的请求转发到后端 socket（后端重启期间排队重试）
+///
+/// 自定义协议只能返回完整响应体，流式接口（SSE）需改用 subscribe_stream。
+pub async fn handle_scheme_request(
+    app: &tauri::AppHandle,
+    request: tauri::http::Request<Vec<u8>>,
+) -> tauri::http::Response<Vec<u8>> {
+    use tauri::Manager;
+
+    let build = |status: u16, headers: Vec<(String, String)>, body: Vec<u8>| {
+        let mut builder = tauri::http::Response::builder()
+            .status(status)
+            .header("Access-Control-Allow-Origin", "*")
+            .header("Access-Control-Allow-Headers", "*")
+            .header("Access-Control-Allow-Methods", "GET, POST, PUT, PATCH, DELETE, OPTIONS");
+        for (k, v) in headers {
+            if !k.to_ascii_lowercase().starts_with("access-control-") {
+                builder = builder.header(k, v);
+            }
+        }
+        builder.body(body).unwrap_or_default()
+    };
+
+    // CORS 预检由 Rust 直接应答
+    if request.method() == tauri::http::Method::OPTIONS {
+        return build(204, vec![], vec![]);
+    }
+
+    let Some(socket) = app.state::<crate::BackendState>().info().socket_path else {
+        return build(503, vec![], b"{\"error\":\"backend not running in uds mode\"}".to_vec());
+    };
+
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "/".to_string());
+    let headers: Vec<(String, String)> = request
+        .headers()
+        .iter()
+        .filter_map(|(k, v)| Some((k.as_str().to_string(), v.to_str().ok()?.to_string())))
+        .collect();
+
+    let result = crate::queue::send(app, || async {
+        http_request(
+            &socket,
+            request.method().as_str(),
+            &path_and_query,
+            &headers,
+            request.body(),
+            Duration::from_secs(300),
+        )
+        .await
+        .map_err(crate::queue::SendError::from_uds)
+    })
+    .await;
+    match result {
+        Ok(resp) => build(resp.status, resp.headers, resp.body),
+        Err(e) => {
+            debug_log(&format!("[uds] 转发 {} 失败: {}", path_and_query, e));
+            build(
+                502,
+                vec![],
+                serde_json::json!({"error": e}).to_string().into_bytes(),
+            )
+        }
+    }
+}
+
+// ============================================================================
+// WebSocket 桥接（UDS 模式）
+// ============================================================================
+
+#[cfg(unix)]
+mod ws_bridge {
+    use crate::debug_log;
+    use futures_util::{SinkExt, StreamExt};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use tauri::{Emitter, Manager};
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// 活动的 WebSocket 桥接（ID → 发送队列）
+    #[derive(Default)]
+    pub struct WsBridges {
+        senders: Mutex<HashMap<String, mpsc::UnboundedSender<Message>>>,
+    }
+
+    /// 通过后端 socket 建立 WebSocket 连接，返回桥接 ID
+    ///
+    /// 收到的消息以 `ws-bridge-message` 事件推送，连接关闭时发出 `ws-bridge-closed`。
+    #[tauri::command]
+    pub async fn ws_bridge_connect(app: tauri::AppHandle, path: String) -> Result<String, String> {
+        let socket = app
+            .state::<crate::BackendState>()
+            .info()
+            .socket_path
+            .ok_or("backend not running in uds mode")?;
+
+        let stream = tokio::net::UnixStream::connect(&socket)
+            .await
+            .map_err(|e| format!("连接后端 socket 失败: {}", e))?;
+        let url = format!("ws://localhost{}", path);
+        let (ws, _) = tokio_tungstenite::client_async(url.as_str(), stream)
+            .await
+            .map_err(|e| format!("WebSocket 握手失败: {}", e))?;
+        let (mut sink, mut source) = ws.split();
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
+        app.state::<WsBridges>()
+            .senders
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(id.clone(), tx);
+
+        tauri::async_runtime::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                let closing = matches!(msg, Message::Close(_));
+                if sink.send(msg).await.is_err() || closing {
+                    break;
+                }
+            }
+        });
+
+        let app_for_reader = app.clone();
+        let id_for_reader = id.clone();
+        tauri::async_runtime::spawn(async move {
+            while let Some(Ok(msg)) = source.next().await {
+                let data = match msg {
+                    Message::Text(t) => t,
+                    Message::Binary(b) => String::from_utf8_lossy(&b).to_string(),
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                let _ = app_for_reader.emit(
+                    "ws-bridge-message",
+                    serde_json::json!({"id": id_for_reader, "data": data}),
+                );
+            }
+            if let Ok(mut senders) = app_for_reader.state::<WsBridges>().senders.lock() {
+                senders.remove(&id_for_reader);
+            }
+            debug_log(&format!("[uds] WebSocket 桥接已关闭 (id={})", id_for_reader));
+            let _ = app_for_reader.emit("ws-bridge-closed", serde_json::json!({"id": id_for_reader}));
+        });
+
+        Ok(id)
+    }
+
+    /// 通过桥接发送文本消息
+    #[tauri::command]
+    pub async fn ws_bridge_send(app: tauri::AppHandle, id: String, data: String) -> Result<(), String> {
+        let state = app.state::<WsBridges>();
+        let senders = state.senders.lock().map_err(|e| e.to_string())?;
+        senders
+            .get(&id)
+            .ok_or_else(|| format!("WebSocket 桥接不存在: {}", id))?
+            .send(Message::Text(data))
+            .map_err(|_| "WebSocket 桥接已关闭".to_string())
+    }
+
+    /// 关闭桥接
+    #[tauri::command]
+    pub async fn ws_bridge_close(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+        let sender = app
+            .state::<WsBridges>()
+            .senders
+            .lock()
+            .map_err(|e| e.to_string())?
+            .remove(&id);
+        match sender {
+            Some(tx) => {
+                let _ = tx.send(Message::Close(None));
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// 非 Unix 平台不支持 UDS 传输，桥接命令直接报错
+#[cfg(not(unix))]
+mod ws_bridge {
+    #[derive(Default)]
+    pub struct WsBridges;
+
+    #[tauri::command]
+    pub async fn ws_bridge_connect(_path: String) -> Result<String, String> {
+        Err("Unix socket transport not supported on this platform".to_string())
+    }
+
+    #[tauri::command]
+    pub async fn ws_bridge_send(_id: String, _data: String) -> Result<(), String> {
+        Err("Unix socket transport not supported on this platform".to_string())
+    }
+
+    #[tauri::command]
+    pub async fn ws_bridge_close(_id: String) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+pub use ws_bridge::{ws_bridge_close, ws_bridge_connect, ws_bridge_send, WsBridges};
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_chunked_joins_chunks() {
+        assert_eq!(
+            decode_chunked(b"5\r\nhello\r\n6;ext=1\r\n world\r\n0\r\n\r\n"),
+            b"hello world"
+        );
+        assert_eq!(
+            decode_chunked(b"A\r\n0123456789\r\n0\r\n\r\n"),
+            b"0123456789"
+        );
+    }
+
+    #[test]
+    fn decode_chunked_stops_at_incomplete_chunk() {
+        assert_eq!(decode_chunked(b"5\r\nhello\r\n5\r\nwor"), b"hello");
+        assert_eq!(decode_chunked(b"zz\r\nhello\r\n"), b"");
+        assert_eq!(decode_chunked(b""), b"");
+    }
+}
@@ -0,0 +1,617 @@
+// ============================================================================
+// 应用更新：检查 / 下载 / 安装
+// ============================================================================
+//
+// 封装 updater 插件，分三步暴露给前端：
+// - check_for_update：检查新版本，返回解析后的更新说明
+// - download_update：下载安装包，期间发出 `update-download-progress`
+// - install_update：终止 sidecar 后安装并重启
+// 下载好的安装包暂存在内存中，直到安装或重新检查。
+//
+// 另有后台定时检查，按设置中的更新策略处理新版本：
+// 仅提示 / 下载后在退出时安装 / 下载后在空闲时段自动安装并重启。
+
+use crate::settings::{self, UpdatePolicy};
+use crate::store::{load_json, save_json};
+use crate::{debug_log, i18n, notifications, policy};
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+/// 启动后首次后台检查的延迟
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(60);
+
+/// 后台检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+
+/// 自动安装模式下，等待进入空闲时段的检查间隔
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// 系统无输入多久后视为用户已离开（主窗口仍显示时）
+const USER_AWAY_SECS: u64 = 10 * 60;
+
+/// 后端活跃会话列表（有会话时说明 Agent 任务仍在进行）
+const ACTIVE_SESSIONS_PATH: &str = "/api/v1/sessions";
+
+/// 更新历史持久化文件（记录上一个版本，供回退使用）
+const HISTORY_FILE: &str = "update-history.json";
+
+/// 指定版本的 updater 清单（每个 release 都附带 latest.json）
+const RELEASE_MANIFEST_URL: &str =
+    "https://github.com/malue-ai/dazee-small/releases/download/v{version}/latest.json";
+
+/// 更新说明中的一节（如 "新功能"、"修复"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseNoteSection {
+    /// 标题（说明开头没有标题的条目归入空标题）
+    pub title: String,
+    pub items: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseNotes {
+    /// 原始 Markdown
+    pub raw: String,
+    pub sections: Vec<ReleaseNoteSection>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub available: bool,
+    pub current_version: String,
+    /// 新版本号（无更新时为 None）
+    pub version: Option<String>,
+    /// 发布时间
+    pub date: Option<String>,
+    pub notes: Option<ReleaseNotes>,
+}
+
+/// `update-download-progress` 事件负载
+#[derive(Debug, Clone, Serialize)]
+struct DownloadProgressPayload {
+    downloaded: u64,
+    /// 安装包总大小（服务器未返回时为 None）
+    total: Option<u64>,
+    /// 下载进度（0-100，总大小未知时为 None）
+    percent: Option<u8>,
+}
+
+/// 待安装的更新
+#[derive(Default)]
+pub struct UpdaterState {
+    pending: tokio::sync::Mutex<Option<PendingUpdate>>,
+}
+
+/// 应用更新历史
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateHistory {
+    /// 更新前的版本（可回退到的版本）
+    pub previous_version: Option<String>,
+    /// 最近一次安装的版本
+    pub installed_version: Option<String>,
+    pub installed_at: Option<String>,
+}
+
+/// 回退结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollbackResult {
+    /// 回退的对象："app" 或 "backend"
+    pub target: String,
+    pub from_version: String,
+    /// 回退后的版本（后端回到内置版本时为 None）
+    pub to_version: Option<String>,
+}
+
+struct PendingUpdate {
+    update: Update,
+    /// 已下载的安装包
+    bytes: Option<Vec<u8>>,
+}
+
+/// 解析 Markdown 更新说明：`#` 标题分节，`-` / `*` / `1.` 开头的行为条目
+pub fn parse_release_notes(raw: &str) -> ReleaseNotes {
+    let mut sections: Vec<ReleaseNoteSection> = Vec::new();
+
+    for line in raw.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.starts_with('#') {
+            sections.push(ReleaseNoteSection {
+                title: line.trim_start_matches('#').trim().to_string(),
+                items: Vec::new(),
+            });
+            continue;
+        }
+
+        let item = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))
+            .or_else(|| {
+                let (num, rest) = line.split_once(". ")?;
+                num.chars().all(|c| c.is_ascii_digit()).then_some(rest)
+            })
+            .unwrap_or(line)
+            .trim();
+
+        if sections.is_empty() {
+            sections.push(ReleaseNoteSection {
+                title: String::new(),
+                items: Vec::new(),
+            });
+        }
+        if let Some(section) = sections.last_mut() {
+            section.items.push(item.to_string());
+        }
+    }
+
+    sections.retain(|s| !s.items.is_empty() || !s.title.is_empty());
+    ReleaseNotes {
+        raw: raw.to_string(),
+        sections,
+    }
+}
+
+fn percent_of(downloaded: u64, total: Option<u64>) -> Option<u8> {
+    total
+        .filter(|t| *t > 0)
+        .map(|t| (downloaded.saturating_mul(100) / t).min(100) as u8)
+}
+
+/// 检查更新，有新版本时暂存待下载
+async fn check(app: &tauri::AppHandle) -> Result<UpdateInfo, String> {
+    let current_version = app.package_info().version.to_string();
+    // 托管策略固定了更新清单地址时，不使用 tauri.conf.json 中的 endpoints
+    let updater = match policy::current().update_manifest_url {
+        Some(manifest) => {
+            let endpoint = url::Url::parse(&manifest).map_err(|e| e.to_string())?;
+            app.updater_builder()
+                .endpoints(vec![endpoint])
+                .map_err(|e| e.to_string())?
+                .build()
+        }
+        None => app.updater(),
+    };
+    let update = updater
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| format!("检查更新失败: {}", e))?;
+
+    let state = app.state::<UpdaterState>();
+    let mut pending = state.pending.lock().await;
+
+    let Some(update) = update else {
+        debug_log("[updater] 已是最新版本");
+        *pending = None;
+        return Ok(UpdateInfo {
+            available: false,
+            current_version,
+            version: None,
+            date: None,
+            notes: None,
+        });
+    };
+
+    debug_log(&format!("[updater] 发现新版本: {}", update.version));
+    let info = UpdateInfo {
+        available: true,
+        current_version,
+        version: Some(update.version.clone()),
+        date: update.date.map(|d| d.to_string()),
+        notes: update.body.as_deref().map(parse_release_notes),
+    };
+    *pending = Some(PendingUpdate {
+        update,
+        bytes: None,
+    });
+    Ok(info)
+}
+
+/// 下载已检查到的更新，返回安装包大小
+async fn download(app: &tauri::AppHandle) -> Result<u64, String> {
+    let state = app.state::<UpdaterState>();
+    let mut pending = state.pending.lock().await;
+    let pending = pending
+        .as_mut()
+        .ok_or("No update available, call check_for_update first")?;
+
+    if let Some(bytes) = &pending.bytes {
+        return Ok(bytes.len() as u64);
+    }
+
+    debug_log(&format!("[updater] 开始下载 {}", pending.update.version));
+    let progress_app = app.clone();
+    let mut downloaded: u64 = 0;
+    let bytes = pending
+        .update
+        .download(
+            move |chunk_length, content_length| {
+                downloaded += chunk_length as u64;
+                let _ = progress_app.emit(
+                    "update-download-progress",
+                    DownloadProgressPayload {
+                        downloaded,
+                        total: content_length,
+                        percent: percent_of(downloaded, content_length),
+                    },
+                );
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| format!("下载更新失败: {}", e))?;
+
+    let size = bytes.len() as u64;
+    debug_log(&format!("[updater] 下载完成 ({} bytes)", size));
+    crate::events::emit(app, "update-downloaded", &pending.update.version);
+    pending.bytes = Some(bytes);
+    Ok(size)
+}
+
+/// 终止 sidecar、安装已下载的更新并重启应用
+async fn install_and_relaunch(app: &tauri::AppHandle) -> Result<(), String> {
+    let (update, bytes) = {
+        let state = app.state::<UpdaterState>();
+        let mut pending = state.pending.lock().await;
+        match pending.take() {
+            Some(PendingUpdate {
+                update,
+                bytes: Some(bytes),
+            }) => (update, bytes),
+            other => {
+                *pending = other;
+                return Err("Update not downloaded, call download_update first".to_string());
+            }
+        }
+    };
+
+    debug_log(&format!("[updater] 安装 {}，先终止后端", update.version));
+    // Windows 上 sidecar 运行中会导致安装程序无法覆盖文件
+    crate::shutdown_sidecar(app.clone()).await;
+
+    let install_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || install(&install_app, &update, &bytes))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    debug_log("[updater] 安装完成，重启应用");
+    app.restart()
+}
+
+/// 安装更新包，并记录更新前的版本以便回退
+fn install(app: &tauri::AppHandle, update: &Update, bytes: &[u8]) -> Result<(), String> {
+    update
+        .install(bytes)
+        .map_err(|e| format!("安装更新失败: {}", e))?;
+    let history = UpdateHistory {
+        previous_version: Some(update.current_version.clone()),
+        installed_version: Some(update.version.clone()),
+        installed_at: Some(chrono::Local::now().to_rfc3339()),
+    };
+    if let Err(e) = save_json(app, HISTORY_FILE, &history) {
+        debug_log(&format!("[updater] {}", e));
+    }
+    Ok(())
+}
+
+/// 当前时间是否处于允许自动更新的时段
+fn in_update_window(start_hour: u32, end_hour: u32) -> bool {
+    let hour = chrono::Local::now().hour();
+    if start_hour <= end_hour {
+        (start_hour..end_hour).contains(&hour)
+    } else {
+        hour >= start_hour || hour < end_hour
+    }
+}
+
+/// 用户是否未在使用应用：主窗口隐藏 / 最小化，或系统长时间无输入
+/// （窗口只是不在前台不算空闲，用户可能正在对照其它窗口查看任务）
+async fn is_user_idle(app: &tauri::AppHandle) -> bool {
+    let window_hidden = app
+        .get_webview_window("main")
+        .map(|w| !w.is_visible().unwrap_or(false) || w.is_minimized().unwrap_or(false))
+        .unwrap_or(true);
+    if window_hidden {
+        return true;
+    }
+    tauri::async_runtime::spawn_blocking(crate::lock::system_idle_secs)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|idle| idle >= USER_AWAY_SECS)
+}
+
+/// 是否有进行中的工作：计划任务 / 自动化动作正在执行，或后端有活跃会话
+async fn has_work_in_progress(app: &tauri::AppHandle) -> bool {
+    if crate::scheduler::has_active_runs() {
+        return true;
+    }
+    let (ok, body) = crate::http::call_backend(
+        app,
+        "GET",
+        ACTIVE_SESSIONS_PATH,
+        None,
+        crate::http::BACKEND_TIMEOUT,
+    )
+    .await;
+    if !ok {
+        // 后端未运行或无响应时没有可打断的会话
+        debug_log(&format!("[updater] 无法获取后端活跃会话: {}", body));
+        return false;
+    }
+    serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v["data"]["total"].as_u64())
+        .is_some_and(|total| total > 0)
+}
+
+/// 按更新策略处理一次后台检查
+async fn run_policy(app: &tauri::AppHandle) -> Result<(), String> {
+    let info = check(app).await?;
+    let Some(version) = info.version.clone() else {
+        return Ok(());
+    };
+    let policy = settings::current(app).update_policy;
+    crate::events::emit(app, "update-available", &info);
+
+    match policy {
+        UpdatePolicy::NotifyOnly => {
+            notifications::notify(
+                app,
+                &i18n::t("notify.update_available.title"),
+                &i18n::tf("notify.update_available.body", &[&version]),
+                false,
+            );
+        }
+        UpdatePolicy::InstallOnQuit => {
+            download(app).await?;
+            notifications::notify(
+                app,
+                &i18n::t("notify.update_on_quit.title"),
+                &i18n::tf("notify.update_on_quit.body", &[&version]),
+                false,
+            );
+        }
+        UpdatePolicy::Automatic => {
+            download(app).await?;
+            // 等到空闲时段、用户未在使用应用且没有进行中的任务时再安装
+            loop {
+                let s = settings::current(app);
+                if s.update_policy != UpdatePolicy::Automatic {
+                    debug_log("[updater] 更新策略已变更，取消自动安装");
+                    return Ok(());
+                }
+                if in_update_window(s.update_window_start_hour, s.update_window_end_hour)
+                    && is_user_idle(app).await
+                    && !has_work_in_progress(app).await
+                {
+                    break;
+                }
+                tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+            }
+            debug_log(&format!("[updater] 空闲时段自动安装 {}", version));
+            install_and_relaunch(app).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 启动后台更新检查
+pub fn start_scheduler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        loop {
+            if let Err(e) = run_policy(&app).await {
+                debug_log(&format!("[updater] 后台更新失败: {}", e));
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// 应用退出时安装已下载的更新（仅"退出时安装"策略，sidecar 已在此之前终止）
+pub fn install_on_quit(app: &tauri::AppHandle) {
+    if settings::current(app).update_policy != UpdatePolicy::InstallOnQuit {
+        return;
+    }
+    let state = app.state::<UpdaterState>();
+    // 不等待锁：下载仍在进行时直接跳过，下次退出再装
+    let Ok(mut pending) = state.pending.try_lock() else {
+        return;
+    };
+    let Some(PendingUpdate {
+        update,
+        bytes: Some(bytes),
+    }) = pending.take()
+    else {
+        return;
+    };
+
+    debug_log(&format!("[updater] 退出时安装 {}", update.version));
+    match install(app, &update, &bytes) {
+        Ok(()) => debug_log("[updater] 安装完成"),
+        Err(e) => debug_log(&format!("[updater] 退出时{}", e)),
+    }
+}
+
+/// 回退应用到更新前的版本：重新下载该版本已签名的安装包并安装（允许降级）
+async fn rollback_app(app: &tauri::AppHandle, version: &str) -> Result<(), String> {
+    let endpoint = url::Url::parse(&RELEASE_MANIFEST_URL.replace("{version}", version))
+        .map_err(|e| e.to_string())?;
+    let update = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .map_err(|e| e.to_string())?
+        .version_comparator(|current, release| release.version != current)
+        .build()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| format!("获取版本 {} 失败: {}", version, e))?
+        .ok_or_else(|| format!("Version {} is not available", version))?;
+
+    debug_log(&format!("[updater] 下载回退版本 {}", update.version));
+    let bytes = update
+        .download(|_, _| {}, || {})
+        .await
+        .map_err(|e| format!("下载回退版本失败: {}", e))?;
+
+    crate::shutdown_sidecar(app.clone()).await;
+    tauri::async_runtime::spawn_blocking(move || update.install(bytes))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("安装回退版本失败: {}", e))?;
+
+    // 已回到旧版本，不能再"回退"到出问题的新版本
+    let history = UpdateHistory {
+        previous_version: None,
+        installed_version: Some(version.to_string()),
+        installed_at: Some(chrono::Local::now().to_rfc3339()),
+    };
+    save_json(app, HISTORY_FILE, &history)
+}
+
+/// 回退最近一次更新（应用或后端，取较晚安装的一个）并重启应用
+pub async fn rollback(app: &tauri::AppHandle) -> Result<RollbackResult, String> {
+    let history: UpdateHistory = load_json(app, HISTORY_FILE);
+    let backend = crate::sidecar_update::active_backend(app);
+
+    let app_rollback = history
+        .previous_version
+        .clone()
+        .map(|v| (v, history.installed_at.clone().unwrap_or_default()));
+    let backend_is_newer = match (&backend, &app_rollback) {
+        (Some(b), Some((_, app_at))) => b.installed_at > *app_at,
+        (Some(_), None) => true,
+        _ => false,
+    };
+
+    let result = if backend_is_newer {
+        let from = backend.map(|b| b.version).unwrap_or_default();
+        // 用户主动回退不代表该版本有问题，不记入 failed_versions（否则以后无法再升级到该版本）
+        let to = crate::sidecar_update::rollback(app, false)?;
+        RollbackResult {
+            target: "backend".to_string(),
+            from_version: from,
+            to_version: to,
+        }
+    } else if let Some((version, _)) = app_rollback {
+        let from = app.package_info().version.to_string();
+        rollback_app(app, &version).await?;
+        RollbackResult {
+            target: "app".to_string(),
+            from_version: from,
+            to_version: Some(version),
+        }
+    } else {
+        return Err("No previous version to roll back to".to_string());
+    };
+
+    debug_log(&format!(
+        "[updater] 已回退 {}: {} → {:?}，重启应用",
+        result.target, result.from_version, result.to_version
+    ));
+    let _ = app.emit("update-rolled-back", &result);
+    Ok(result)
+}
+
+/// 托盘菜单"回退到上一版本"：确认后回退并重启，失败时提示原因
+pub fn rollback_from_tray(app: &tauri::AppHandle) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let app = app.clone();
+    app.dialog()
+        .message(i18n::t("dialog.rollback.body"))
+        .title(i18n::t("dialog.rollback.title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancel)
+        .show(move |confirmed| {
+            if !confirmed {
+                return;
+            }
+            tauri::async_runtime::spawn(async move {
+                match rollback(&app).await {
+                    Ok(_) => app.restart(),
+                    Err(e) => {
+                        debug_log(&format!("[updater] 回退失败: {}", e));
+                        app.dialog()
+                            .message(i18n::tf("dialog.rollback_failed.body", &[&e]))
+                            .title(i18n::t("dialog.rollback.title"))
+                            .kind(MessageDialogKind::Error)
+                            .show(|_| {});
+                    }
+                }
+            });
+        });
+}
+
+/// 检查更新
+#[tauri::command]
+pub async fn check_for_update(app: tauri::AppHandle) -> Result<UpdateInfo, String> {
+    check(&app).await
+}
+
+/// 下载已检查到的更新，返回安装包大小
+#[tauri::command]
+pub async fn download_update(app: tauri::AppHandle) -> Result<u64, String> {
+    download(&app).await
+}
+
+/// 安装已下载的更新并重启应用
+#[tauri::command]
+pub async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
+    install_and_relaunch(&app).await
+}
+
+/// 获取更新历史
+#[tauri::command]
+pub async fn get_update_history(app: tauri::AppHandle) -> Result<UpdateHistory, String> {
+    Ok(load_json(&app, HISTORY_FILE))
+}
+
+/// 回退最近一次更新并重启应用
+#[tauri::command]
+pub async fn rollback_update(app: tauri::AppHandle) -> Result<(), String> {
+    rollback(&app).await?;
+    app.restart()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_release_notes_splits_sections_and_items() {
+        let raw = "Intro line\n\n## Features\n- Added A\n* Added B\n10. Numbered\n\n### Fixes\n   - Fixed C  \n## Empty\n";
+        let notes = parse_release_notes(raw);
+        assert_eq!(notes.raw, raw);
+        let sections: Vec<(&str, Vec<&str>)> = notes
+            .sections
+            .iter()
+            .map(|s| {
+                (
+                    s.title.as_str(),
+                    s.items.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                ("", vec!["Intro line"]),
+                ("Features", vec!["Added A", "Added B", "Numbered"]),
+                ("Fixes", vec!["Fixed C"]),
+                ("Empty", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_release_notes_keeps_unlisted_lines_as_items() {
+        let notes = parse_release_notes("# 1.2.0\nv1.2. not a list\n-no space");
+        assert_eq!(notes.sections.len(), 1);
+        assert_eq!(notes.sections[0].items, ["v1.2. not a list", "-no space"]);
+        assert!(parse_release_notes("  \n\n").sections.is_empty());
+    }
+}
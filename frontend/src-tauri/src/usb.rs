@@ -0,0 +1,234 @@
+// ============================================================================
+// USB 设备枚举与热插拔事件
+// ============================================================================
+//
+// - macOS: system_profiler SPUSBDataType（新版系统为 SPUSBHostDataType）
+// - Windows: Win32_PnPEntity 中 USB\VID_xxxx&PID_xxxx 设备
+// - Linux: /sys/bus/usb/devices
+// 后台线程定期枚举并与上次结果比较，发出 `usb-device-attached` / `usb-device-detached`。
+
+use crate::debug_log;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::Command as SysCommand;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// 热插拔检查间隔（秒）
+const USB_POLL_SECS: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsbDevice {
+    /// 设备标识（同一设备插在同一端口时保持不变）
+    pub id: String,
+    pub name: String,
+    /// 十六进制，如 "05ac"
+    pub vendor_id: Option<String>,
+    pub product_id: Option<String>,
+    pub manufacturer: Option<String>,
+    pub serial: Option<String>,
+}
+
+/// 规范化十六进制 ID（"0x05ac (Apple Inc.)" → "05ac"）
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn normalize_hex_id(raw: &str) -> Option<String> {
+    let hex = raw.trim().trim_start_matches("0x");
+    let hex: String = hex.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+    (!hex.is_empty()).then(|| format!("{:0>4}", hex.to_ascii_lowercase()))
+}
+
+#[cfg(target_os = "macos")]
+fn collect_macos(item: &serde_json::Value, out: &mut Vec<UsbDevice>) {
+    let field = |key: &str| {
+        item.get(key)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .filter(|s| !s.is_empty())
+    };
+    if let Some(product_id) = field("product_id") {
+        let vendor_id = field("vendor_id");
+        let location = field("location_id").unwrap_or_default();
+        out.push(UsbDevice {
+            id: format!(
+                "{}:{}@{}",
+                vendor_id.as_deref().unwrap_or_default(),
+                product_id,
+                location
+            ),
+            name: field("_name").unwrap_or_default(),
+            manufacturer: field("manufacturer").or_else(|| {
+                // vendor_id 形如 "0x05ac (Apple Inc.)"
+                vendor_id
+                    .as_deref()
+                    .and_then(|v| v.split_once('('))
+                    .map(|(_, rest)| rest.trim_end_matches(')').to_string())
+            }),
+            vendor_id: vendor_id.as_deref().and_then(normalize_hex_id),
+            product_id: normalize_hex_id(&product_id),
+            serial: field("serial_num"),
+        });
+    }
+    if let Some(children) = item.get("_items").and_then(|v| v.as_array()) {
+        for child in children {
+            collect_macos(child, out);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn list_devices() -> Vec<UsbDevice> {
+    let mut devices = Vec::new();
+    for data_type in ["SPUSBDataType", "SPUSBHostDataType"] {
+        let Ok(output) = SysCommand::new("system_profiler")
+            .args([data_type, "-json"])
+            .output()
+        else {
+            continue;
+        };
+        let Ok(json) = serde_json::from_slice::<serde_json::Value>(&output.stdout) else {
+            continue;
+        };
+        if let Some(buses) = json.get(data_type).and_then(|v| v.as_array()) {
+            for bus in buses {
+                collect_macos(bus, &mut devices);
+            }
+        }
+        if !devices.is_empty() {
+            break;
+        }
+    }
+    devices
+}
+
+#[cfg(target_os = "windows")]
+fn list_devices() -> Vec<UsbDevice> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct PnpEntity {
+        name: Option<String>,
+        #[serde(rename = "PNPDeviceID")]
+        pnp_device_id: String,
+        manufacturer: Option<String>,
+    }
+
+    let script =
+        "Get-CimInstance Win32_PnPEntity | Where-Object { $_.PNPDeviceID -like 'USB\\VID_*' } \
+                  | Select-Object Name, PNPDeviceID, Manufacturer | ConvertTo-Json -Compress";
+    let Ok(output) = SysCommand::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()
+    else {
+        return Vec::new();
+    };
+    // 只有一个设备时 ConvertTo-Json 输出对象而不是数组
+    let entities: Vec<PnpEntity> = match serde_json::from_slice::<serde_json::Value>(&output.stdout)
+    {
+        Ok(v @ serde_json::Value::Array(_)) => serde_json::from_value(v).unwrap_or_default(),
+        Ok(v @ serde_json::Value::Object(_)) => serde_json::from_value(v)
+            .map(|e| vec![e])
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    entities
+        .into_iter()
+        // USB\VID_046D&PID_C52B\5&2A1E3F0&0&1
+        .map(|e| {
+            let mut parts = e.pnp_device_id.split('\\');
+            let ids = parts.nth(1).unwrap_or_default().to_string();
+            let instance = parts.next().unwrap_or_default();
+            let id_field = |prefix: &str| {
+                ids.split('&')
+                    .find_map(|p| p.strip_prefix(prefix))
+                    .and_then(normalize_hex_id)
+            };
+            UsbDevice {
+                name: e.name.unwrap_or_default(),
+                vendor_id: id_field("VID_"),
+                product_id: id_field("PID_"),
+                manufacturer: e.manufacturer,
+                // 含 & 的实例 ID 是系统生成的，不是设备序列号
+                serial: (!instance.contains('&') && !instance.is_empty())
+                    .then(|| instance.to_string()),
+                id: e.pnp_device_id,
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn list_devices() -> Vec<UsbDevice> {
+    let Ok(entries) = std::fs::read_dir("/sys/bus/usb/devices") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        // 1-1.2:1.0 这类为接口，不是设备
+        .filter(|p| {
+            p.file_name()
+                .is_some_and(|n| !n.to_string_lossy().contains(':'))
+        })
+        .filter_map(|dir| {
+            let read = |name: &str| {
+                std::fs::read_to_string(dir.join(name))
+                    .ok()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+            };
+            let vendor_id = read("idVendor")?;
+            let product_id = read("idProduct")?;
+            let manufacturer = read("manufacturer");
+            Some(UsbDevice {
+                id: dir.file_name()?.to_string_lossy().to_string(),
+                name: read("product").unwrap_or_else(|| format!("{}:{}", vendor_id, product_id)),
+                vendor_id: Some(vendor_id),
+                product_id: Some(product_id),
+                manufacturer,
+                serial: read("serial"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn list_devices() -> Vec<UsbDevice> {
+    Vec::new()
+}
+
+/// 启动 USB 热插拔监视线程
+pub fn start_usb_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let index = |devices: Vec<UsbDevice>| -> HashMap<String, UsbDevice> {
+            devices.into_iter().map(|d| (d.id.clone(), d)).collect()
+        };
+        let mut last = index(list_devices());
+        loop {
+            std::thread::sleep(Duration::from_secs(USB_POLL_SECS));
+            let current = index(list_devices());
+
+            for (id, device) in &current {
+                if !last.contains_key(id) {
+                    debug_log(&format!("[usb] 设备接入: {} ({})", device.name, id));
+                    let _ = app.emit("usb-device-attached", device);
+                }
+            }
+            for (id, device) in &last {
+                if !current.contains_key(id) {
+                    debug_log(&format!("[usb] 设备移除: {} ({})", device.name, id));
+                    let _ = app.emit("usb-device-detached", device);
+                }
+            }
+            last = current;
+        }
+    });
+}
+
+/// 列出当前连接的 USB 设备
+#[tauri::command]
+pub async fn list_usb_devices() -> Result<Vec<UsbDevice>, String> {
+    tauri::async_runtime::spawn_blocking(list_devices)
+        .await
+        .map_err(|e| e.to_string())
+}
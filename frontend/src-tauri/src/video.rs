@@ -0,0 +1,141 @@
+// ============================================================================
+// 录屏视频剪辑与压缩
+// ============================================================================
+//
+// 长录屏在分析或上传前先裁掉无关片段、降低码率：
+// - trim_video(path, start, end)：截取 [start, end) 秒，直接复制音视频流（不重新编码，
+//   起点对齐到之前最近的关键帧）
+// - compress_video(path, target_bitrate)：H.264 重新编码到目标码率（kbps），音频 AAC 96k
+// 通过 ffmpeg 完成，查找顺序见 tools.rs（托管版本 > 随应用打包 > PATH）。
+// 结果写入临时 MP4 并以分块传输返回（见 transfers.rs）。
+
+use crate::transfers::{self, TransferInfo};
+use crate::{debug_log, policy, timeline, tools};
+use std::path::PathBuf;
+
+/// 音频码率（kbps）
+const AUDIO_BITRATE_KBPS: u32 = 96;
+
+/// 校验来源视频并返回临时输出路径
+fn prepare(path: &str) -> Result<(PathBuf, PathBuf), String> {
+    let source = PathBuf::from(path);
+    if !source.is_file() {
+        return Err(format!("不是文件: {}", source.display()));
+    }
+    policy::check_path(&source)?;
+    let target = std::env::temp_dir().join(format!("xiaodazi-video-{}.mp4", uuid::Uuid::new_v4()));
+    Ok((source, target))
+}
+
+/// 运行 ffmpeg 并记入时间线（action 如 "video.trim"）
+async fn run_ffmpeg(
+    app: &tauri::AppHandle,
+    action: &str,
+    source: &str,
+    args: Vec<String>,
+    target: PathBuf,
+) -> Result<TransferInfo, String> {
+    let result = spawn_ffmpeg(app, args, target).await;
+    timeline::record(
+        app,
+        timeline::TimelineKind::Media,
+        action,
+        result.is_ok(),
+        serde_json::json!({ "path": source, "error": result.as_ref().err() }),
+    );
+    result
+}
+
+/// 运行 ffmpeg，成功后把输出登记为临时传输
+async fn spawn_ffmpeg(
+    app: &tauri::AppHandle,
+    args: Vec<String>,
+    target: PathBuf,
+) -> Result<TransferInfo, String> {
+    let ffmpeg = tools::find_tool(app, "ffmpeg")?;
+    let output = tokio::process::Command::new(&ffmpeg)
+        .args(["-hide_banner", "-loglevel", "error", "-y"])
+        .args(&args)
+        .arg(&target)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("启动 ffmpeg 失败: {}", e))?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&target);
+        return Err(format!(
+            "处理视频失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    debug_log(&format!("[video] 已生成视频: {}", target.display()));
+    transfers::register(app, target, true)
+}
+
+/// 截取视频片段（单位秒），结果以临时文件分块读取
+#[tauri::command]
+pub async fn trim_video(
+    app: tauri::AppHandle,
+    path: String,
+    start: f64,
+    end: f64,
+) -> Result<TransferInfo, String> {
+    if !start.is_finite() || !end.is_finite() || start < 0.0 || end <= start {
+        return Err(format!("Invalid time range: {} - {}", start, end));
+    }
+    let (source, target) = prepare(&path)?;
+    debug_log(&format!("[video] 截取 {} ({}s - {}s)", path, start, end));
+    let args = vec![
+        "-ss".to_string(),
+        format!("{:.3}", start),
+        "-i".to_string(),
+        source.to_string_lossy().to_string(),
+        "-t".to_string(),
+        format!("{:.3}", end - start),
+        "-c".to_string(),
+        "copy".to_string(),
+        "-avoid_negative_ts".to_string(),
+        "make_zero".to_string(),
+    ];
+    run_ffmpeg(&app, "video.trim", &path, args, target).await
+}
+
+/// 压缩视频到目标码率（kbps），结果以临时文件分块读取
+#[tauri::command]
+pub async fn compress_video(
+    app: tauri::AppHandle,
+    path: String,
+    target_bitrate: u32,
+) -> Result<TransferInfo, String> {
+    if target_bitrate == 0 {
+        return Err("target_bitrate must be positive".to_string());
+    }
+    let (source, target) = prepare(&path)?;
+    debug_log(&format!("[video] 压缩 {} ({}kbps)", path, target_bitrate));
+    let args = vec![
+        "-i".to_string(),
+        source.to_string_lossy().to_string(),
+        "-c:v".to_string(),
+        "libx264".to_string(),
+        "-preset".to_string(),
+        "veryfast".to_string(),
+        "-b:v".to_string(),
+        format!("{}k", target_bitrate),
+        "-maxrate".to_string(),
+        format!("{}k", target_bitrate),
+        "-bufsize".to_string(),
+        format!("{}k", target_bitrate * 2),
+        // 部分播放器不支持奇数宽高
+        "-vf".to_string(),
+        "scale=trunc(iw/2)*2:trunc(ih/2)*2".to_string(),
+        "-pix_fmt".to_string(),
+        "yuv420p".to_string(),
+        "-c:a".to_string(),
+        "aac".to_string(),
+        "-b:a".to_string(),
+        format!("{}k", AUDIO_BITRATE_KBPS),
+        "-movflags".to_string(),
+        "+faststart".to_string(),
+    ];
+    run_ffmpeg(&app, "video.compress", &path, args, target).await
+}
@@ -0,0 +1,247 @@
+// ============================================================================
+// 系统音量与静音控制（system.volume）
+// ============================================================================
+//
+// - macOS: osascript 读写 volume settings（CoreAudio 默认输出设备）
+// - Windows: PowerShell 调用 Core Audio IAudioEndpointVolume
+//   （waveOutSetVolume 自 Vista 起只影响调用进程自身，无法调整系统音量）
+// - Linux: pactl（PulseAudio / PipeWire 默认输出）
+// 设置 volume_control_enabled 关闭时拒绝修改音量（读取不受影响）。
+
+use crate::{debug_log, settings};
+use serde::{Deserialize, Serialize};
+use std::process::Command as SysCommand;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeState {
+    /// 输出音量（0-100）
+    pub level: u8,
+    pub muted: bool,
+}
+
+fn command_output(cmd: &mut SysCommand) -> Result<String, String> {
+    let output = cmd.output().map_err(|e| format!("音量控制失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "音量控制失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn read_volume() -> Result<VolumeState, String> {
+    let out = command_output(SysCommand::new("osascript").args([
+        "-e",
+        "set s to get volume settings",
+        "-e",
+        "return ((output volume of s) as text) & \",\" & ((output muted of s) as text)",
+    ]))?;
+    // 部分外接设备不支持音量调节，此时 output volume 为 missing value
+    let (level, muted) = out.split_once(',').ok_or("无法读取系统音量")?;
+    Ok(VolumeState {
+        level: level.trim().parse().unwrap_or(0),
+        muted: muted.trim() == "true",
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn write_volume(level: Option<u8>, muted: Option<bool>) -> Result<(), String> {
+    let mut script = Vec::new();
+    if let Some(level) = level {
+        script.push(format!("set volume output volume {}", level));
+    }
+    if let Some(muted) = muted {
+        script.push(format!("set volume output muted {}", muted));
+    }
+    let mut cmd = SysCommand::new("osascript");
+    for line in &script {
+        cmd.args(["-e", line]);
+    }
+    command_output(&mut cmd).map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+const WINDOWS_AUDIO_TYPE: &str = r#"
+Add-Type -TypeDefinition @'
+using System.Runtime.InteropServices;
+[Guid("5CDF2C82-841E-4546-9722-0CF74078229A"), InterfaceType(ComInterfaceType.InterfaceIsIUnknown)]
+interface IAudioEndpointVolume {
+  int f(); int g(); int h(); int i();
+  int SetMasterVolumeLevelScalar(float fLevel, System.Guid pguidEventContext);
+  int j();
+  int GetMasterVolumeLevelScalar(out float pfLevel);
+  int k(); int l(); int m(); int n();
+  int SetMute([MarshalAs(UnmanagedType.Bool)] bool bMute, System.Guid pguidEventContext);
+  int GetMute(out bool pbMute);
+}
+[Guid("D666063F-1587-4E43-81F1-B948E807363F"), InterfaceType(ComInterfaceType.InterfaceIsIUnknown)]
+interface IMMDevice {
+  int Activate(ref System.Guid id, int clsCtx, int activationParams, out IAudioEndpointVolume aev);
+}
+[Guid("A95664D2-9614-4F35-A746-DE8DB63617E6"), InterfaceType(ComInterfaceType.InterfaceIsIUnknown)]
+interface IMMDeviceEnumerator {
+  int f();
+  int GetDefaultAudioEndpoint(int dataFlow, int role, out IMMDevice endpoint);
+}
+[ComImport, Guid("BCDE0395-E52F-467C-8E3D-C4579291692E")] class MMDeviceEnumeratorComObject { }
+public class ZAudio {
+  static IAudioEndpointVolume Vol() {
+    var enumerator = new MMDeviceEnumeratorComObject() as IMMDeviceEnumerator;
+    IMMDevice dev = null;
+    Marshal.ThrowExceptionForHR(enumerator.GetDefaultAudioEndpoint(0, 1, out dev));
+    IAudioEndpointVolume epv = null;
+    var epvid = typeof(IAudioEndpointVolume).GUID;
+    Marshal.ThrowExceptionForHR(dev.Activate(ref epvid, 23, 0, out epv));
+    return epv;
+  }
+  public static float Volume {
+    get { float v = -1; Marshal.ThrowExceptionForHR(Vol().GetMasterVolumeLevelScalar(out v)); return v; }
+    set { Marshal.ThrowExceptionForHR(Vol().SetMasterVolumeLevelScalar(value, System.Guid.Empty)); }
+  }
+  public static bool Mute {
+    get { bool m; Marshal.ThrowExceptionForHR(Vol().GetMute(out m)); return m; }
+    set { Marshal.ThrowExceptionForHR(Vol().SetMute(value, System.Guid.Empty)); }
+  }
+}
+'@
+"#;
+
+#[cfg(target_os = "windows")]
+fn run_windows_audio(statements: &str) -> Result<String, String> {
+    let script = format!(
+        "{}{}\n\"$([math]::Round([ZAudio]::Volume * 100)),$([ZAudio]::Mute)\"",
+        WINDOWS_AUDIO_TYPE, statements
+    );
+    command_output(SysCommand::new("powershell").args([
+        "-NoProfile",
+        "-NonInteractive",
+        "-Command",
+        &script,
+    ]))
+}
+
+#[cfg(target_os = "windows")]
+fn parse_windows_state(out: &str) -> Result<VolumeState, String> {
+    let (level, muted) = out
+        .lines()
+        .last()
+        .and_then(|l| l.split_once(','))
+        .ok_or("无法读取系统音量")?;
+    Ok(VolumeState {
+        level: level.trim().parse().unwrap_or(0),
+        muted: muted.trim().eq_ignore_ascii_case("true"),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn read_volume() -> Result<VolumeState, String> {
+    parse_windows_state(&run_windows_audio("")?)
+}
+
+#[cfg(target_os = "windows")]
+fn write_volume(level: Option<u8>, muted: Option<bool>) -> Result<(), String> {
+    let mut statements = String::new();
+    if let Some(level) = level {
+        statements.push_str(&format!("[ZAudio]::Volume = {}\n", level as f32 / 100.0));
+    }
+    if let Some(muted) = muted {
+        statements.push_str(&format!(
+            "[ZAudio]::Mute = ${}\n",
+            if muted { "true" } else { "false" }
+        ));
+    }
+    run_windows_audio(&statements).map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+fn read_volume() -> Result<VolumeState, String> {
+    // 输出示例：Volume: front-left: 42597 /  65% / -11.23 dB,   front-right: ...
+    let volume =
+        command_output(SysCommand::new("pactl").args(["get-sink-volume", "@DEFAULT_SINK@"]))?;
+    let level = volume
+        .split('%')
+        .next()
+        .and_then(|s| s.rsplit(|c: char| c.is_whitespace() || c == '/').next())
+        .and_then(|s| s.parse().ok())
+        .ok_or("无法读取系统音量")?;
+    let mute = command_output(SysCommand::new("pactl").args(["get-sink-mute", "@DEFAULT_SINK@"]))?;
+    Ok(VolumeState {
+        level,
+        muted: mute.ends_with("yes"),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn write_volume(level: Option<u8>, muted: Option<bool>) -> Result<(), String> {
+    if let Some(level) = level {
+        command_output(SysCommand::new("pactl").args([
+            "set-sink-volume",
+            "@DEFAULT_SINK@",
+            &format!("{}%", level),
+        ]))?;
+    }
+    if let Some(muted) = muted {
+        command_output(SysCommand::new("pactl").args([
+            "set-sink-mute",
+            "@DEFAULT_SINK@",
+            if muted { "1" } else { "0" },
+        ]))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn read_volume() -> Result<VolumeState, String> {
+    Err("Volume control not supported on this platform".to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn write_volume(_level: Option<u8>, _muted: Option<bool>) -> Result<(), String> {
+    Err("Volume control not supported on this platform".to_string())
+}
+
+/// 修改音量后返回最新状态
+async fn update(
+    app: &tauri::AppHandle,
+    level: Option<u8>,
+    muted: Option<bool>,
+) -> Result<VolumeState, String> {
+    if !settings::current(app).volume_control_enabled {
+        return Err("Volume control is disabled in settings".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        write_volume(level, muted)?;
+        debug_log(&format!(
+            "[volume] 调整音量 (level={:?}, muted={:?})",
+            level, muted
+        ));
+        read_volume()
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 获取系统输出音量与静音状态
+#[tauri::command]
+pub async fn get_volume() -> Result<VolumeState, String> {
+    tauri::async_runtime::spawn_blocking(read_volume)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 设置系统输出音量（0-100）
+#[tauri::command]
+pub async fn set_volume(app: tauri::AppHandle, level: u8) -> Result<VolumeState, String> {
+    if level > 100 {
+        return Err("Volume level must be between 0 and 100".to_string());
+    }
+    update(&app, Some(level), None).await
+}
+
+/// 设置系统静音
+#[tauri::command]
+pub async fn set_muted(app: tauri::AppHandle, muted: bool) -> Result<VolumeState, String> {
+    update(&app, None, Some(muted)).await
+}
@@ -0,0 +1,341 @@
+// ============================================================================
+// 本地 Webhook 接收服务（可选开启）
+// ============================================================================
+//
+// 外部工具（CI、家庭自动化等）通过 HTTP POST 触发本机 Agent 动作：
+//
+//   POST /hook/<name>
+//   Authorization: Bearer <token>
+//   <JSON body>
+//
+// 认证通过的请求以 `webhook-received` 事件转发给前端；
+// 路径在 FORWARD_ALLOWED_PATHS 中的请求转发到后端 API 并返回后端的响应，其它 /api/ 路径一律拒绝。
+// 应用锁定或关闭 webhook.serve 能力期间拒绝所有请求。
+// 仅实现够用的 HTTP/1.1 子集（单请求、Content-Length 正文），不支持 keep-alive。
+// 连接任务由监听任务的 JoinSet 持有，停止服务时一并中止。
+
+use crate::{audit, debug_log, permissions};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+
+/// 请求体大小上限（1MB）
+const MAX_BODY_BYTES: usize = 1_000_000;
+
+/// 请求行与单个请求头的长度上限（字节）
+const MAX_LINE_BYTES: u64 = 8 * 1024;
+
+/// 允许转发到后端的 API 路径（不含查询参数）
+const FORWARD_ALLOWED_PATHS: &[&str] = &["/api/v1/chat"];
+
+/// 读取请求的超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 转发到后端的超时
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct WebhookServer {
+    port: u16,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+/// Webhook 服务状态
+#[derive(Default)]
+pub struct WebhookState {
+    server: Mutex<Option<WebhookServer>>,
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// 读取一行，超过 MAX_LINE_BYTES 时报错（连接关闭时返回空字符串）
+async fn read_line<R: AsyncBufReadExt + Unpin>(reader: &mut R) -> Result<String, String> {
+    let mut line = String::new();
+    let n = (&mut *reader)
+        .take(MAX_LINE_BYTES)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| e.to_string())?;
+    // 读满上限仍没有换行：行过长
+    if n as u64 == MAX_LINE_BYTES && !line.ends_with('\n') {
+        return Err("line too long".to_string());
+    }
+    Ok(line)
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest, String> {
+    let mut reader = BufReader::new(stream);
+
+    let request_line = read_line(&mut reader).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or("bad request line")?.to_string();
+    let path = parts.next().ok_or("bad request line")?.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(&mut reader).await?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = line.split_once(':') {
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+        if headers.len() > 100 {
+            return Err("too many headers".to_string());
+        }
+    }
+
+    let len = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    if len > MAX_BODY_BYTES {
+        return Err("body too large".to_string());
+    }
+    let mut body = vec![0u8; len];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(HttpRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+async fn write_response(stream: &mut TcpStream, status: u16, body: &str) {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// 常量时间比较，避免通过响应时间猜测 token
+fn token_matches(expected: &str, provided: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), provided.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 该路径是否允许转发到后端
+fn forward_allowed(path: &str) -> bool {
+    let path = path.split('?').next().unwrap_or(path);
+    FORWARD_ALLOWED_PATHS.contains(&path)
+}
+
+/// 转发到后端 API，返回 (状态码, 响应体)
+async fn forward_to_backend(app: &tauri::AppHandle, path: &str, body: Vec<u8>) -> (u16, String) {
+    match crate::http::backend_request(app, "POST", path, body, FORWARD_TIMEOUT).await {
+        Ok(resp) => resp,
+        Err(e) => (502, serde_json::json!({"error": e}).to_string()),
+    }
+}
+
+async fn handle_connection(app: tauri::AppHandle, mut stream: TcpStream, token: String) {
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(r)) => r,
+        Ok(Err(e)) => {
+            write_response(&mut stream, 400, &serde_json::json!({"error": e}).to_string()).await;
+            return;
+        }
+        Err(_) => return,
+    };
+
+    if request.method != "POST" {
+        write_response(&mut stream, 405, r#"{"error":"method not allowed"}"#).await;
+        return;
+    }
+
+    let provided = request
+        .header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if !token_matches(&token, provided) {
+        audit::record(
+            &app,
+            "webhook.receive",
+            false,
+            serde_json::json!({"path": &request.path, "error": "unauthorized"}),
+        );
+        write_response(&mut stream, 401, r#"{"error":"unauthorized"}"#).await;
+        return;
+    }
+
+    // 锁定或关闭能力期间不接受外部触发
+    if let Err(denied) = permissions::check_capability(&app, "webhook.serve") {
+        audit::record(
+            &app,
+            "webhook.receive",
+            false,
+            serde_json::json!({"path": &request.path, "error": denied.code}),
+        );
+        write_response(&mut stream, 403, &serde_json::json!({"error": denied.code}).to_string()).await;
+        return;
+    }
+    if request.path.starts_with("/api/") && !forward_allowed(&request.path) {
+        audit::record(
+            &app,
+            "webhook.receive",
+            false,
+            serde_json::json!({"path": &request.path, "error": "path not allowed"}),
+        );
+        write_response(&mut stream, 403, r#"{"error":"path not allowed"}"#).await;
+        return;
+    }
+
+    audit::record(
+        &app,
+        "webhook.receive",
+        true,
+        serde_json::json!({"path": &request.path, "bytes": request.body.len()}),
+    );
+    debug_log(&format!("[webhook] 收到请求: {}", request.path));
+
+    if request.path.starts_with("/api/") {
+        let (status, resp_body) = forward_to_backend(&app, &request.path, request.body).await;
+        write_response(&mut stream, status, &resp_body).await;
+        return;
+    }
+
+    let Some(name) = request.path.strip_prefix("/hook/") else {
+        write_response(&mut stream, 404, r#"{"error":"not found"}"#).await;
+        return;
+    };
+    let payload: serde_json::Value = serde_json::from_slice(&request.body)
+        .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&request.body).to_string()));
+    let _ = app.emit(
+        "webhook-received",
+        serde_json::json!({"name": name, "payload": payload}),
+    );
+    write_response(&mut stream, 202, r#"{"accepted":true}"#).await;
+}
+
+/// 启动 Webhook 服务（仅监听 127.0.0.1）
+///
+/// `token` 为调用方必须在 `Authorization: Bearer` 中携带的密钥，至少 16 个字符。
+#[tauri::command]
+pub async fn start_webhook_server(
+    app: tauri::AppHandle,
+    port: u16,
+    token: String,
+) -> Result<serde_json::Value, String> {
+    if token.len() < 16 {
+        return Err("Webhook token 至少需要 16 个字符".to_string());
+    }
+    stop_webhook_server(app.clone()).await?;
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Webhook 端口 {} 监听失败: {}", port, e))?;
+    let actual_port = listener.local_addr().map(|a| a.port()).unwrap_or(port);
+
+    let app_for_server = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        // 连接任务由 JoinSet 持有：停止服务中止监听任务时 JoinSet 随之释放，全部连接一并中止
+        let mut connections = JoinSet::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        connections.spawn(handle_connection(
+                            app_for_server.clone(),
+                            stream,
+                            token.clone(),
+                        ));
+                    }
+                    Err(e) => {
+                        debug_log(&format!("[webhook] 接受连接失败: {}", e));
+                        break;
+                    }
+                },
+                // 回收已结束的连接任务
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            }
+        }
+    });
+
+    *app.state::<WebhookState>()
+        .server
+        .lock()
+        .map_err(|e| e.to_string())? = Some(WebhookServer {
+        port: actual_port,
+        task,
+    });
+
+    debug_log(&format!("[webhook] Webhook 服务已启动 (port={})", actual_port));
+    Ok(serde_json::json!({"running": true, "port": actual_port}))
+}
+
+/// 停止 Webhook 服务（进行中的请求一并中止）
+#[tauri::command]
+pub async fn stop_webhook_server(app: tauri::AppHandle) -> Result<bool, String> {
+    let server = app
+        .state::<WebhookState>()
+        .server
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take();
+    match server {
+        Some(s) => {
+            s.task.abort();
+            debug_log(&format!("[webhook] Webhook 服务已停止 (port={})", s.port));
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Webhook 服务当前监听的端口（未启动返回 None）
+pub fn running_port(app: &tauri::AppHandle) -> Option<u16> {
+    app.state::<WebhookState>()
+        .server
+        .lock()
+        .ok()
+        .and_then(|g| g.as_ref().map(|s| s.port))
+}
+
+/// Webhook 服务状态
+#[tauri::command]
+pub async fn get_webhook_status(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    let state = app.state::<WebhookState>();
+    let server = state.server.lock().map_err(|e| e.to_string())?;
+    Ok(serde_json::json!({
+        "running": server.is_some(),
+        "port": server.as_ref().map(|s| s.port),
+    }))
+}
@@ -0,0 +1,146 @@
+// ============================================================================
+// 窗口缩放
+// ============================================================================
+//
+// 整体缩放 webview 内容（无障碍需求），不依赖页面 CSS：
+// - set_zoom(factor) / get_zoom 作用于调用命令的窗口，按窗口标签保存在设置 window_zoom 中
+// - 页面加载完成时恢复该窗口上次的缩放
+// - 应用窗口获得焦点期间注册 Cmd/Ctrl + = / - / 0（放大 / 缩小 / 还原），失去焦点时注销，
+//   不会占用其它应用的快捷键；按键可在设置中改绑（见 shortcuts.rs）
+
+use crate::{debug_log, settings, shortcuts};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+const ZOOM_MIN: f64 = 0.5;
+const ZOOM_MAX: f64 = 3.0;
+const ZOOM_STEP: f64 = 0.1;
+
+/// 缩放快捷键 ID（见 shortcuts.rs）与对应的操作（None 表示还原）
+const ZOOM_SHORTCUTS: &[(&str, Option<f64>)] = &[
+    ("zoom.in", Some(ZOOM_STEP)),
+    ("zoom.out", Some(-ZOOM_STEP)),
+    ("zoom.reset", None),
+];
+
+/// 窗口当前的缩放比例
+fn zoom_of(app: &tauri::AppHandle, label: &str) -> f64 {
+    settings::current(app)
+        .window_zoom
+        .get(label)
+        .copied()
+        .unwrap_or(1.0)
+}
+
+/// 设置并保存窗口缩放，返回实际生效的比例
+fn apply(app: &tauri::AppHandle, label: &str, factor: f64) -> Result<f64, String> {
+    if !factor.is_finite() {
+        return Err(format!("Invalid zoom factor: {}", factor));
+    }
+    // 保留两位小数，避免步进累积误差
+    let factor = (factor.clamp(ZOOM_MIN, ZOOM_MAX) * 100.0).round() / 100.0;
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window not found: {}", label))?;
+    window.set_zoom(factor).map_err(|e| e.to_string())?;
+
+    let state = app.state::<Mutex<settings::AppSettings>>();
+    let mut guard = state.lock().map_err(|e| e.to_string())?;
+    if (factor - 1.0).abs() < f64::EPSILON {
+        guard.window_zoom.remove(label);
+    } else {
+        guard.window_zoom.insert(label.to_string(), factor);
+    }
+    settings::save_settings(&guard)?;
+    drop(guard);
+
+    let _ = app.emit(
+        "zoom-changed",
+        serde_json::json!({ "label": label, "factor": factor }),
+    );
+    Ok(factor)
+}
+
+/// 页面加载完成后恢复缩放
+pub fn restore(webview: &tauri::Webview) {
+    let factor = zoom_of(webview.app_handle(), webview.label());
+    if (factor - 1.0).abs() >= f64::EPSILON {
+        if let Err(e) = webview.set_zoom(factor) {
+            debug_log(&format!("[zoom] 恢复缩放失败 {}: {}", webview.label(), e));
+        }
+    }
+}
+
+/// 应用窗口获得 / 失去焦点时注册 / 注销缩放快捷键
+pub fn on_focus_changed(app: &tauri::AppHandle, focused: bool) {
+    // 焦点在应用的两个窗口之间切换时保留快捷键
+    if !focused
+        && app
+            .webview_windows()
+            .values()
+            .any(|w| w.is_focused().unwrap_or(false))
+    {
+        return;
+    }
+    for (id, step) in ZOOM_SHORTCUTS {
+        let shortcut = shortcuts::accelerator(app, id);
+        let shortcut_manager = app.global_shortcut();
+        if !focused {
+            let _ = shortcut_manager.unregister(shortcut.as_str());
+            continue;
+        }
+        if shortcut_manager.is_registered(shortcut.as_str()) {
+            continue;
+        }
+        let step = *step;
+        let result =
+            shortcut_manager.on_shortcut(shortcut.as_str(), move |app, _shortcut, event| {
+                if event.state() != ShortcutState::Pressed {
+                    return;
+                }
+                let Some(label) = app
+                    .webview_windows()
+                    .into_iter()
+                    .find(|(_, w)| w.is_focused().unwrap_or(false))
+                    .map(|(label, _)| label)
+                else {
+                    return;
+                };
+                let factor = match step {
+                    Some(step) => zoom_of(app, &label) + step,
+                    None => 1.0,
+                };
+                if let Err(e) = apply(app, &label, factor) {
+                    debug_log(&format!("[zoom] {}", e));
+                }
+            });
+        if let Err(e) = result {
+            debug_log(&format!("[zoom] 注册快捷键失败 {}: {}", shortcut, e));
+        }
+    }
+}
+
+/// 缩放快捷键改绑后注销旧按键，应用窗口有焦点时立即注册新按键
+pub fn rebind(app: &tauri::AppHandle, previous: &str) {
+    let _ = app.global_shortcut().unregister(previous);
+    let focused = app
+        .webview_windows()
+        .values()
+        .any(|w| w.is_focused().unwrap_or(false));
+    if focused {
+        on_focus_changed(app, true);
+    }
+}
+
+/// 设置调用窗口的缩放比例（0.5 ~ 3.0），返回实际生效的比例
+#[tauri::command]
+pub async fn set_zoom(window: tauri::WebviewWindow, factor: f64) -> Result<f64, String> {
+    apply(window.app_handle(), window.label(), factor)
+}
+
+/// 获取调用窗口的缩放比例
+#[tauri::command]
+pub async fn get_zoom(window: tauri::WebviewWindow) -> Result<f64, String> {
+    Ok(zoom_of(window.app_handle(), window.label()))
+}
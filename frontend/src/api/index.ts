@@ -9,11 +9,44 @@
 import axios from 'axios'
 import { invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
-import { isTauriEnv } from './tauri'
+import { isTauriEnv, BridgeSocket, type BackendSocket } from './tauri'
 import { apiLog, tauriLog } from '@/utils/logger'
 
+/** Rust 侧 backend-ready 事件负载 */
+export interface BackendReadyPayload {
+  ready: boolean
+  port: number
+  base_url: string
+  ws_url: string | null
+  pid: number | null
+  started_at: string | null
+  reason: string | null
+}
+
+/** Rust 侧 sidecar-status 事件负载 */
+export interface SidecarStatusPayload {
+  message: string
+  /** 后端上报的启动阶段（壳层自身的状态为 null） */
+  stage: string | null
+  /** 启动进度 0-100（未知时为 null） */
+  percent: number | null
+  elapsed_ms: number
+}
+
+/** Rust 侧缓冲的状态事件（get_recent_events） */
+export interface BufferedEvent {
+  seq: number
+  event: string
+  payload: unknown
+  timestamp: string
+}
+
 // 后端基础 URL（运行时初始化）
 let _baseUrl: string = '/api'
+// 后端 WebSocket 基础 URL（Tauri 模式下由 Rust 提供）
+let _wsBaseUrl: string | null = null
+// UDS 传输：后端不监听 TCP，WebSocket / 流式响应需经 Rust 桥接
+let _bridged = false
 let _initialized = false
 
 // 后端就绪状态（供其他模块查询）
@@ -50,8 +83,9 @@ function startBackendReadyWatcher(): void {
 
   let resolved = false
 
+  // 启动失败后后端仍可能出现（如开发模式下稍后手动启动），此时仍更新为就绪
   const onReady = () => {
-    if (resolved) return
+    if (_backendReady) return
     resolved = true
     _backendReady = true
     _backendReadyResolve?.()
@@ -66,16 +100,24 @@ function startBackendReadyWatcher(): void {
     tauriLog.error(`后端启动失败: ${reason}`)
   }
 
-  // 方式 1: 监听 Rust 侧发出的 backend-ready 事件
-  listen<boolean>('backend-ready', (event) => {
-    if (event.payload) {
+  const onBackendReady = (payload: BackendReadyPayload) => {
+    if (payload.ready) {
       onReady()
     } else {
-      onFailed('后端启动失败（可能端口被占用或进程崩溃），请关闭后重试')
+      onFailed(payload.reason || '后端启动失败（可能端口被占用或进程崩溃），请关闭后重试')
     }
-  }).catch((err) => {
-    tauriLog.error('监听 backend-ready 事件失败', err)
-  })
+  }
+
+  // 方式 1: 监听 Rust 侧发出的 backend-ready 事件，并补取监听前已发出的事件
+  listen<BackendReadyPayload>('backend-ready', (event) => onBackendReady(event.payload))
+    .then(() => invoke<BufferedEvent[]>('get_recent_events', { since: null }))
+    .then((events) => {
+      const last = events.filter(e => e.event === 'backend-ready').pop()
+      if (last) onBackendReady(last.payload as BackendReadyPayload)
+    })
+    .catch((err) => {
+      tauriLog.error('监听 backend-ready 事件失败', err)
+    })
 
   // 方式 2: 轮询健康检查（兜底，防止事件在监听前已发出）
   let attempts = 0
@@ -135,6 +177,14 @@ export async function initApiBaseUrl(): Promise<string> {
       _baseUrl = 'http://localhost:18900/api'
     }
 
+    try {
+      _wsBaseUrl = await invoke<string>('get_backend_ws_url')
+    } catch {
+      // ws_url 为 null：后端走 UDS 传输，没有可直连的 WebSocket 地址
+      _bridged = _baseUrl.startsWith('zenflux:')
+      tauriLog.info(`WebSocket 经 Rust 桥接: ${_bridged}`)
+    }
+
     // 在后台等待后端 sidecar 就绪（不阻塞 UI 渲染）
     startBackendReadyWatcher()
   } else {
@@ -159,6 +209,39 @@ export function getApiBaseUrl(): string {
   return _baseUrl
 }
 
+/**
+ * 后端是否经 Rust 桥接访问（UDS 传输）
+ *
+ * 此时 WebView 无法直连 WebSocket，自定义协议也只能整块返回响应，
+ * WebSocket 需用 openBackendSocket，流式响应需用 subscribeStream。
+ */
+export function isBackendBridged(): boolean {
+  return _bridged
+}
+
+/**
+ * 打开后端 WebSocket（UDS 传输下自动改用 Rust 桥接）
+ *
+ * @param path API 路径（不含 /api 前缀），如 /v1/ws/chat
+ */
+export function openBackendSocket(path: string): BackendSocket {
+  if (_bridged) {
+    return new BridgeSocket(`/api${path}`)
+  }
+  if (_wsBaseUrl) {
+    return new WebSocket(`${_wsBaseUrl}${path}`)
+  }
+
+  // 如果 baseUrl 是绝对地址（http://...），转换为 ws://...
+  if (_baseUrl.startsWith('http')) {
+    return new WebSocket(`${_baseUrl.replace(/^http/, 'ws')}${path}`)
+  }
+
+  // 相对地址（/api）：使用当前页面地址构造 WebSocket URL
+  const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:'
+  return new WebSocket(`${protocol}//${window.location.host}${_baseUrl}${path}`)
+}
+
 /**
  * 获取完整的 API URL（供 fetch/SSE 使用）
  */
@@ -22,6 +22,8 @@ export interface ShellResult {
 
 export interface NodeInfo {
   node_id: string
+  /** 稳定的机器标识（加盐哈希），数据目录重建后保持不变 */
+  machine_id: string | null
   display_name: string
   platform: string
   version: string
@@ -70,8 +72,8 @@ export function isTauriEnv(): boolean {
  * 执行 Shell 命令
  * 
  * @param command 命令数组，如 ['ls', '-la']
- * @param options 可选参数
- * @returns 执行结果
+ * @param options 可选参数（传入 task_id 时命令被限制在该任务的工作区内）
+ * @returns 执行结果；越出任务工作区时抛出 JailViolation
  */
 export async function runCommand(
   command: string[],
@@ -79,6 +81,7 @@ export async function runCommand(
     cwd?: string
     env?: Record<string, string>
     timeout_ms?: number
+    task_id?: string
   }
 ): Promise<ShellResult> {
   if (!isTauriEnv()) {
@@ -90,9 +93,21 @@ export async function runCommand(
     cwd: options?.cwd ?? null,
     env: options?.env ?? null,
     timeout_ms: options?.timeout_ms ?? null,
+    task_id: options?.task_id ?? null,
   })
 }
 
+/**
+ * run_command 越出任务工作区时的结构化错误
+ */
+export interface JailViolation {
+  code: 'invalid_task_id' | 'cwd_outside_workspace' | 'path_outside_workspace'
+  task_id: string
+  workspace: string
+  path: string | null
+  message: string
+}
+
 /**
  * 检查可执行文件是否存在
  * 
@@ -157,6 +172,7 @@ export async function getNodeInfo(): Promise<NodeInfo> {
     // 在浏览器中返回模拟数据
     return {
       node_id: 'browser-node',
+      machine_id: null,
       display_name: 'Browser',
       platform: 'web',
       version: '1.0.0',
@@ -167,6 +183,1426 @@ export async function getNodeInfo(): Promise<NodeInfo> {
   return await invoke<NodeInfo>('get_node_info')
 }
 
+/**
+ * 设置节点显示名称（空字符串恢复为主机名）
+ */
+export async function setDisplayName(name: string): Promise<NodeInfo> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<NodeInfo>('set_display_name', { name })
+}
+
+export interface CapabilityState {
+  capability: string
+  enabled: boolean
+  /** 被托管策略关闭，不能在应用内开启 */
+  locked: boolean
+}
+
+/**
+ * 列出本平台支持的能力及其开关状态
+ */
+export async function listCapabilities(): Promise<CapabilityState[]> {
+  if (!isTauriEnv()) {
+    return []
+  }
+
+  return await invoke<CapabilityState[]>('list_capabilities')
+}
+
+/**
+ * 开启或关闭能力（可传分组前缀，如 'camera'），返回关闭的能力列表
+ */
+export async function setCapabilityEnabled(capability: string, enabled: boolean): Promise<string[]> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<string[]>('set_capability_enabled', { capability, enabled })
+}
+
+/**
+ * 权限检查未通过时 invoke 返回的结构化错误
+ */
+export interface PermissionDenied {
+  code: 'command_not_declared' | 'window_not_allowed' | 'app_locked' | 'capability_disabled'
+  command: string | null
+  capability: string | null
+  message: string
+}
+
+export function isPermissionDenied(error: unknown): error is PermissionDenied {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error
+}
+
+export interface AuditVerification {
+  valid: boolean
+  /** 参与校验的记录数 */
+  entries: number
+  /** 启用哈希链之前的旧记录数 */
+  legacy_entries: number
+  /** 第一条校验失败的记录行号（链末端不一致时为 null） */
+  invalid_line: number | null
+  error: string | null
+  /** 是否对照了链末端（audit.head），能发现末尾记录被删除 */
+  anchored: boolean
+}
+
+/**
+ * 校验审计日志哈希链与链末端（检测记录是否被篡改、插入或截断）
+ */
+export async function verifyAuditLog(): Promise<AuditVerification> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<AuditVerification>('verify_audit_log')
+}
+
+export interface ApprovalRequest {
+  id: string
+  /** 操作类型（如 remote.system.run） */
+  action: string
+  summary: string
+  details: Record<string, unknown>
+  requested_at: string
+  expires_at: string
+}
+
+/**
+ * 列出待审批的 Agent 操作
+ */
+export async function listPendingApprovals(): Promise<ApprovalRequest[]> {
+  if (!isTauriEnv()) {
+    return []
+  }
+
+  return await invoke<ApprovalRequest[]>('list_pending_approvals')
+}
+
+/**
+ * 批准 Agent 操作
+ */
+export async function approveRequest(id: string): Promise<void> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  await invoke('approve', { id })
+}
+
+/**
+ * 拒绝 Agent 操作
+ */
+export async function denyRequest(id: string): Promise<void> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  await invoke('deny', { id })
+}
+
+export interface AppStateSnapshot {
+  backend: {
+    port: number
+    is_sidecar: boolean
+    socket_path: string | null
+    pid: number | null
+    ready: boolean
+    /** 最近一次 sidecar 启动进度 */
+    status: string
+    startup_timeout_secs: number
+    adopted: boolean
+    started_at: string | null
+  }
+  base_url: string
+  ws_url: string | null
+  pending_approvals: ApprovalRequest[]
+  scheduled_tasks: Record<string, unknown>[]
+  /** 主窗口是否已隐藏到托盘 */
+  in_tray: boolean
+  locked: boolean
+  settings: Record<string, unknown>
+  /** 最新事件序号，之后可用 get_recent_events(since) 增量补取 */
+  last_event_seq: number
+}
+
+/**
+ * 获取应用状态快照（前端加载或刷新时调用，无需依赖可能错过的事件）
+ */
+export async function getAppStateSnapshot(): Promise<AppStateSnapshot | null> {
+  if (!isTauriEnv()) {
+    return null
+  }
+
+  return await invoke<AppStateSnapshot>('get_app_state_snapshot')
+}
+
+export interface StreamEvent {
+  id: string
+  /** 订阅内的事件序号（从 1 递增），用于 replayStream 补齐 */
+  seq: number
+  /** SSE 事件名；非 SSE 的 chunked 响应为 "chunk" */
+  event: string
+  data: string
+  event_id: string | null
+}
+
+export interface StreamHandlers {
+  onEvent: (event: StreamEvent) => void
+  onReconnecting?: (attempt: number, error: string) => void
+  /** reason: ended / cancelled / error */
+  onClosed?: (reason: string, error: string | null) => void
+}
+
+export interface StreamReplay {
+  /** seq 大于 since 的缓冲事件 */
+  events: StreamEvent[]
+  /** since 之后有事件已被挤出缓冲区（Rust 每个订阅只保留最近的事件） */
+  truncated: boolean
+  /** 订阅已结束时为 stream-closed 的内容 */
+  closed: { id: string; reason: string; error: string | null } | null
+}
+
+/**
+ * 监听某个订阅的事件：start(id) 之前到达的事件先暂存，按 seq 去重，结束回调只触发一次
+ */
+async function listenStream(handlers: StreamHandlers, since = 0) {
+  const { listen } = await import('@tauri-apps/api/event')
+  let id: string | null = null
+  let lastSeq = since
+  let finished = false
+  const early: Array<() => void> = []
+  const dispatch = (eventId: string, handle: () => void) => {
+    if (id === null) early.push(() => eventId === id && handle())
+    else if (eventId === id) handle()
+  }
+  const deliver = (event: StreamEvent) => {
+    if (finished || event.seq <= lastSeq) return
+    lastSeq = event.seq
+    handlers.onEvent(event)
+  }
+  const finish = (reason: string, error: string | null) => {
+    if (finished) return
+    finished = true
+    stop()
+    handlers.onClosed?.(reason, error)
+  }
+
+  const unlisteners = await Promise.all([
+    listen<StreamEvent>('stream-event', (e) => dispatch(e.payload.id, () => deliver(e.payload))),
+    listen<{ id: string; attempt: number; error: string }>('stream-reconnecting', (e) =>
+      dispatch(e.payload.id, () => handlers.onReconnecting?.(e.payload.attempt, e.payload.error))
+    ),
+    listen<{ id: string; reason: string; error: string | null }>('stream-closed', (e) =>
+      dispatch(e.payload.id, () => finish(e.payload.reason, e.payload.error))
+    ),
+  ])
+  const stop = () => unlisteners.forEach((unlisten) => unlisten())
+
+  return {
+    deliver,
+    finish,
+    stop,
+    start(streamId: string) {
+      id = streamId
+      early.splice(0).forEach((handle) => handle())
+    },
+  }
+}
+
+/**
+ * 订阅后端流式接口（由 Rust 读取 SSE / chunked 响应并转发，webview 刷新不会中断）
+ *
+ * GET 订阅在连接中断时自动重连。返回取消订阅的函数。
+ */
+export async function subscribeStream(
+  path: string,
+  handlers: StreamHandlers,
+  options?: { method?: string; body?: unknown }
+): Promise<() => Promise<void>> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  const stream = await listenStream(handlers)
+  let id: string
+  try {
+    id = await invoke<string>('subscribe_stream', {
+      path,
+      method: options?.method,
+      body: options?.body,
+    })
+  } catch (e) {
+    stream.stop()
+    throw e
+  }
+  stream.start(id)
+
+  return async () => {
+    await invoke('unsubscribe_stream', { id })
+  }
+}
+
+/**
+ * 获取订阅中 seq 大于 since 的缓冲事件（webview 刷新后补齐错过的事件）
+ */
+export async function replayStream(id: string, since?: number): Promise<StreamReplay> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<StreamReplay>('replay_stream', { id, since })
+}
+
+/**
+ * 重新接上已有的订阅（如 webview 刷新后）：先补发 seq 大于 since 的缓冲事件，再继续接收新事件
+ *
+ * 返回取消订阅的函数。
+ */
+export async function resumeStream(
+  id: string,
+  handlers: StreamHandlers,
+  since = 0
+): Promise<() => Promise<void>> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  const stream = await listenStream(handlers, since)
+  let replay: StreamReplay
+  try {
+    replay = await replayStream(id, since)
+  } catch (e) {
+    stream.stop()
+    throw e
+  }
+  if (replay.truncated) {
+    console.warn(`[streams] 订阅 ${id} 的部分事件已不在缓冲区中`)
+  }
+  replay.events.forEach(stream.deliver)
+  stream.start(id)
+  if (replay.closed) {
+    stream.finish(replay.closed.reason, replay.closed.error)
+  }
+
+  return async () => {
+    await invoke('unsubscribe_stream', { id })
+  }
+}
+
+/** 后端 WebSocket 连接（浏览器 WebSocket 或 BridgeSocket，常用子集） */
+export interface BackendSocket {
+  readonly readyState: number
+  onopen: ((event: Event) => void) | null
+  onmessage: ((event: MessageEvent) => void) | null
+  onerror: ((event: Event) => void) | null
+  onclose: ((event: CloseEvent) => void) | null
+  send(data: string): void
+  close(code?: number, reason?: string): void
+}
+
+/**
+ * 经 Rust 桥接的 WebSocket（UDS 传输下后端不监听 TCP，WebView 无法直连）
+ *
+ * 由 ws_bridge_connect / ws_bridge_send / ws_bridge_close 实现，接口与浏览器 WebSocket 一致，
+ * readyState 取值同 WebSocket.CONNECTING / OPEN / CLOSING / CLOSED。
+ */
+export class BridgeSocket implements BackendSocket {
+  readyState: number = WebSocket.CONNECTING
+  onopen: ((event: Event) => void) | null = null
+  onmessage: ((event: MessageEvent) => void) | null = null
+  onerror: ((event: Event) => void) | null = null
+  onclose: ((event: CloseEvent) => void) | null = null
+
+  private id: string | null = null
+  private unlisteners: Array<() => void> = []
+  private closeCode = 1000
+  private closeReason = ''
+
+  /**
+   * @param path 后端路径，如 /api/v1/ws/chat
+   */
+  constructor(path: string) {
+    void this.open(path)
+  }
+
+  send(data: string): void {
+    if (this.readyState !== WebSocket.OPEN || this.id === null) {
+      throw new Error('WebSocket 桥接未连接')
+    }
+    invoke('ws_bridge_send', { id: this.id, data }).catch(() => this.fail())
+  }
+
+  close(code = 1000, reason = ''): void {
+    if (this.readyState === WebSocket.CLOSING || this.readyState === WebSocket.CLOSED) return
+    this.closeCode = code
+    this.closeReason = reason
+    const connecting = this.readyState === WebSocket.CONNECTING
+    this.readyState = WebSocket.CLOSING
+    // 仍在连接中时由 open() 在拿到桥接 ID 后关闭
+    if (connecting) return
+    invoke<boolean>('ws_bridge_close', { id: this.id })
+      .then((existed) => {
+        if (!existed) this.finish()
+      })
+      .catch(() => this.finish())
+  }
+
+  private async open(path: string): Promise<void> {
+    const { listen } = await import('@tauri-apps/api/event')
+    // 桥接 ID 返回前到达的事件先暂存
+    const early: Array<{ id: string; handle: () => void }> = []
+    const dispatch = (id: string, handle: () => void) => {
+      if (this.id === null) early.push({ id, handle })
+      else if (id === this.id) handle()
+    }
+
+    try {
+      this.unlisteners = await Promise.all([
+        listen<{ id: string; data: string }>('ws-bridge-message', (e) =>
+          dispatch(e.payload.id, () =>
+            this.onmessage?.(new MessageEvent('message', { data: e.payload.data }))
+          )
+        ),
+        listen<{ id: string }>('ws-bridge-closed', (e) =>
+          dispatch(e.payload.id, () => this.finish())
+        ),
+      ])
+      this.id = await invoke<string>('ws_bridge_connect', { path })
+    } catch {
+      this.fail()
+      return
+    }
+
+    if (this.readyState === WebSocket.CLOSING) {
+      this.readyState = WebSocket.OPEN
+      this.close(this.closeCode, this.closeReason)
+      return
+    }
+    this.readyState = WebSocket.OPEN
+    this.onopen?.(new Event('open'))
+    early
+      .splice(0)
+      .filter((e) => e.id === this.id)
+      .forEach((e) => e.handle())
+  }
+
+  private fail(): void {
+    if (this.readyState === WebSocket.CLOSED) return
+    this.onerror?.(new Event('error'))
+    this.closeCode = 1006
+    this.finish()
+  }
+
+  private finish(): void {
+    if (this.readyState === WebSocket.CLOSED) return
+    this.readyState = WebSocket.CLOSED
+    this.unlisteners.splice(0).forEach((unlisten) => unlisten())
+    this.onclose?.(
+      new CloseEvent('close', {
+        code: this.closeCode,
+        reason: this.closeReason,
+        wasClean: this.closeCode !== 1006,
+      })
+    )
+  }
+}
+
+/**
+ * 向后端发送原始字节请求（不经过 JSON / base64），返回响应体
+ *
+ * @param path 后端路径，如 /api/v1/files/upload
+ */
+export async function backendBinary(
+  path: string,
+  data: ArrayBuffer | Uint8Array = new Uint8Array(),
+  options?: { method?: string; contentType?: string }
+): Promise<ArrayBuffer> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<ArrayBuffer>('backend_binary', data, {
+    headers: {
+      'x-backend-path': path,
+      'x-backend-method': options?.method ?? 'POST',
+      'content-type': options?.contentType ?? 'application/octet-stream',
+    },
+  })
+}
+
+/**
+ * 把本地文件直接发送给后端（文件内容不经过 webview），返回响应文本
+ */
+export async function backendUploadFile(
+  path: string,
+  filePath: string,
+  method?: string
+): Promise<string> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<string>('backend_upload_file', { path, filePath, method })
+}
+
+/**
+ * 把后端响应直接保存为本地文件，返回写入的字节数
+ */
+export async function backendDownloadFile(path: string, filePath: string): Promise<number> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<number>('backend_download_file', { path, filePath })
+}
+
+export interface TransferInfo {
+  id: string
+  size: number
+  chunk_size: number
+  chunks: number
+}
+
+/**
+ * 开始分块读取本地文件（大文件不要使用 read_local_file_binary）
+ */
+export async function openFileTransfer(path: string): Promise<TransferInfo> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<TransferInfo>('open_file_transfer', { path })
+}
+
+/**
+ * 截取整个屏幕（仅 macOS），截图通过 readTransfer 分块读取
+ */
+export async function captureScreen(): Promise<TransferInfo> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<TransferInfo>('capture_screen')
+}
+
+/**
+ * 按序读取传输的全部分块并关闭传输
+ *
+ * @param onChunk 每收到一块回调一次；不传时拼接为 Blob 返回
+ */
+export async function readTransfer(
+  transfer: TransferInfo,
+  onChunk?: (chunk: ArrayBuffer, index: number) => void | Promise<void>,
+  type = 'application/octet-stream'
+): Promise<Blob | null> {
+  const parts: ArrayBuffer[] = []
+  try {
+    for (let index = 0; index < transfer.chunks; index++) {
+      const chunk = await invoke<ArrayBuffer>('read_chunk', { id: transfer.id, index })
+      if (onChunk) await onChunk(chunk, index)
+      else parts.push(chunk)
+    }
+  } finally {
+    await invoke('close_transfer', { id: transfer.id })
+  }
+  return onChunk ? null : new Blob(parts, { type })
+}
+
+/**
+ * 设置徽标计数（macOS Dock 徽标；Windows 任务栏显示红点），0 时清除
+ */
+export async function setBadgeCount(count: number): Promise<void> {
+  if (!isTauriEnv()) {
+    return
+  }
+
+  await invoke('set_badge_count', { count })
+}
+
+/**
+ * 设置任务栏 / Dock 进度条
+ *
+ * @param value 0~1，null 时清除
+ */
+export async function setProgress(
+  value: number | null,
+  state?: 'normal' | 'indeterminate' | 'paused' | 'error'
+): Promise<void> {
+  if (!isTauriEnv()) {
+    return
+  }
+
+  await invoke('set_progress', { value, state })
+}
+
+/**
+ * 窗口不在前台时请求用户注意（macOS Dock 图标跳动，Windows 任务栏闪烁）
+ *
+ * @param critical 持续提醒直到窗口获得焦点
+ */
+export async function requestAttention(critical = false): Promise<void> {
+  if (!isTauriEnv()) {
+    return
+  }
+
+  await invoke('request_attention', { critical })
+}
+
+export interface OverlayStatus {
+  /** 主文本（如当前任务名称），为空时显示空闲状态 */
+  title: string
+  detail?: string | null
+  /** 进度 0~1，null 表示不确定进度 */
+  progress?: number | null
+}
+
+/**
+ * 显示置顶的状态浮窗
+ *
+ * @param clickThrough 鼠标事件穿透到下层窗口
+ */
+export async function showOverlay(clickThrough = false): Promise<void> {
+  if (!isTauriEnv()) {
+    return
+  }
+
+  await invoke('show_overlay', { clickThrough })
+}
+
+/**
+ * 关闭状态浮窗
+ */
+export async function hideOverlay(): Promise<void> {
+  if (!isTauriEnv()) {
+    return
+  }
+
+  await invoke('hide_overlay')
+}
+
+/**
+ * 更新状态浮窗显示的内容
+ */
+export async function updateOverlay(status: OverlayStatus): Promise<void> {
+  if (!isTauriEnv()) {
+    return
+  }
+
+  await invoke('update_overlay', { status })
+}
+
+export interface WindowChrome {
+  /** macOS: sidebar / under_window / hud / popover / menu / titlebar / content / window；Windows: mica / acrylic / blur / tabbed */
+  vibrancy: string | null
+  /** 毛玻璃背景圆角（仅 macOS） */
+  corner_radius: number | null
+  /** 红绿灯按钮位置 [x, y]（仅 macOS），null 为系统默认标题栏 */
+  traffic_light_inset: [number, number] | null
+}
+
+/**
+ * 获取主窗口外观设置
+ */
+export async function getWindowChrome(): Promise<WindowChrome | null> {
+  if (!isTauriEnv()) {
+    return null
+  }
+
+  return await invoke<WindowChrome>('get_window_chrome')
+}
+
+/**
+ * 修改窗口外观，返回是否需要重启应用才能完全生效
+ *
+ * @param label 窗口标签，默认主窗口（只有主窗口的外观会保存）
+ */
+export async function setWindowChrome(chrome: WindowChrome, label?: string): Promise<boolean> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  const restartRequired = await invoke<boolean>('set_window_chrome', { chrome, label })
+  if (!label || label === 'main') {
+    document.documentElement.classList.toggle('window-vibrancy', !!chrome.vibrancy)
+  }
+  return restartRequired
+}
+
+/**
+ * 设置当前窗口的缩放比例（0.5 ~ 3.0，按窗口保存），返回实际生效的比例
+ */
+export async function setZoom(factor: number): Promise<number> {
+  if (!isTauriEnv()) {
+    return 1
+  }
+
+  return await invoke<number>('set_zoom', { factor })
+}
+
+/**
+ * 获取当前窗口的缩放比例
+ */
+export async function getZoom(): Promise<number> {
+  if (!isTauriEnv()) {
+    return 1
+  }
+
+  return await invoke<number>('get_zoom')
+}
+
+/**
+ * 更新安全相关设置（task_command_jail、blocked_env_keys、auto_lock_minutes、crash_report_url、
+ * confirm_url_intents、share_recent_items、redact_home_path 等），
+ * 需要先通过系统身份验证；普通设置的更新接口会拒绝这些字段
+ */
+export async function updateSecuritySettings(
+  patch: Record<string, unknown>,
+): Promise<Record<string, unknown>> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<Record<string, unknown>>('update_security_settings', { patch })
+}
+
+/**
+ * 修改身份验证宽限期（秒，0 表示每次都验证），延长时需要先通过系统身份验证
+ */
+export async function setAuthGracePeriod(secs: number): Promise<number> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<number>('set_auth_grace_period', { secs })
+}
+
+/**
+ * 开启或关闭安全模式（关闭执行命令、进程管理等高风险能力），返回当前状态
+ */
+export async function setSafeMode(enabled: boolean): Promise<boolean> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<boolean>('set_safe_mode', { enabled })
+}
+
+export interface AppInfo {
+  name: string
+  version: string
+  git_commit: string | null
+  build_date: string | null
+  build_profile: string
+  tauri_version: string
+  webview_version: string | null
+  os: string
+  os_version: string | null
+  arch: string
+  /** sidecar（打包模式）/ dev */
+  backend_mode: string
+}
+
+/**
+ * 获取应用版本与构建信息
+ */
+export async function getAppInfo(): Promise<AppInfo | null> {
+  if (!isTauriEnv()) {
+    return null
+  }
+
+  return await invoke<AppInfo>('get_app_info')
+}
+
+/**
+ * 打开"关于"窗口
+ */
+export async function showAbout(): Promise<void> {
+  if (!isTauriEnv()) {
+    return
+  }
+
+  await invoke('show_about')
+}
+
+/**
+ * 导出诊断信息（版本、设置、自检、日志等），返回导出目录
+ *
+ * @param dir 父目录，默认下载目录
+ */
+export async function exportDiagnostics(dir?: string): Promise<string> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<string>('export_diagnostics', { dir })
+}
+
+export interface OnboardingState {
+  /** 已完成的步骤：permissions / data_dir / telemetry / autostart */
+  completed_steps: string[]
+  granted_permissions: string[]
+  /** 后端数据目录，null 表示默认目录 */
+  data_dir: string | null
+  autostart: boolean | null
+  completed_at: string | null
+}
+
+export type OnboardingStep =
+  | { step: 'permissions'; granted: string[] }
+  | { step: 'data_dir'; path: string | null }
+  | { step: 'telemetry'; consent: 'ask' | 'always' | 'never' }
+  | { step: 'autostart'; enabled: boolean }
+
+/**
+ * 获取首次运行引导进度（刷新页面后从未完成的步骤继续）
+ */
+export async function getOnboardingState(): Promise<OnboardingState | null> {
+  if (!isTauriEnv()) {
+    return null
+  }
+
+  return await invoke<OnboardingState>('get_onboarding_state')
+}
+
+/**
+ * 完成一个引导步骤；全部完成后才会启动后端（打包模式）
+ */
+export async function completeOnboardingStep(step: OnboardingStep): Promise<OnboardingState> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<OnboardingState>('complete_onboarding_step', { step })
+}
+
+/**
+ * 提前启动推迟的后端（需先完成 data_dir 与 telemetry 步骤），返回是否由本次调用启动
+ */
+export async function startBackend(): Promise<boolean> {
+  if (!isTauriEnv()) {
+    return false
+  }
+
+  return await invoke<boolean>('start_backend')
+}
+
+export interface HistoryExport {
+  path: string
+  entries: number
+}
+
+/**
+ * 导出审计日志（含命令执行历史）为 CSV / JSON
+ *
+ * @param range 时间范围（RFC 3339 或 YYYY-MM-DD，含两端）
+ * @param path 导出文件路径，默认下载目录
+ */
+export async function exportHistory(
+  format: 'csv' | 'json',
+  range?: { from?: string; to?: string },
+  path?: string
+): Promise<HistoryExport> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<HistoryExport>('export_history', { format, range, path })
+}
+
+export interface AnnotateRect {
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+/** 图片标注操作（坐标为图片像素） */
+export type AnnotateOp =
+  | ({ type: 'crop' } & AnnotateRect)
+  | ({ type: 'redact'; mode?: 'pixelate' | 'fill' } & AnnotateRect)
+  | { type: 'arrow'; from: [number, number]; to: [number, number]; color?: string; width?: number }
+
+/**
+ * 标注图片（裁剪、遮盖敏感区域、画箭头），结果以分块传输返回，用 readTransfer 读取
+ *
+ * 来源为 captureScreen 的传输 ID 时，原始截图随之删除
+ */
+export async function annotateImage(
+  source: { path: string } | { transferId: string },
+  ops: AnnotateOp[]
+): Promise<TransferInfo> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<TransferInfo>('annotate_image', {
+    path: 'path' in source ? source.path : null,
+    transferId: 'transferId' in source ? source.transferId : null,
+    ops,
+  })
+}
+
+/**
+ * 生成缩略图（长边不超过 maxPx，JPEG），结果以分块传输返回，用 readTransfer 读取
+ */
+export async function makeThumbnail(path: string, maxPx: number): Promise<TransferInfo> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<TransferInfo>('make_thumbnail', { path, maxPx })
+}
+
+/**
+ * 压缩图片到不超过 maxBytes（JPEG），发送给后端前缩小大截图、照片
+ */
+export async function downscaleImage(path: string, maxBytes: number): Promise<TransferInfo> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<TransferInfo>('downscale_image', { path, maxBytes })
+}
+
+/**
+ * 截取视频片段（单位秒，按关键帧对齐，不重新编码），结果以分块传输返回
+ */
+export async function trimVideo(path: string, start: number, end: number): Promise<TransferInfo> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<TransferInfo>('trim_video', { path, start, end })
+}
+
+/**
+ * 压缩视频到目标码率（kbps，H.264），结果以分块传输返回
+ */
+export async function compressVideo(path: string, targetBitrate: number): Promise<TransferInfo> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<TransferInfo>('compress_video', { path, targetBitrate })
+}
+
+export interface ManagedTool {
+  name: string
+  version: string
+  /** 可执行文件名（runCommand 中使用的程序名，如 rg） */
+  binary: string
+  /** 未被停用 */
+  enabled: boolean
+  /** 当前平台有可下载的固定构建 */
+  available: boolean
+  /** 托管版本已安装且校验通过 */
+  installed: boolean
+  /** 实际使用的路径，找不到时为 null */
+  path: string | null
+  source: 'managed' | 'bundled' | 'system' | null
+}
+
+/**
+ * 确保托管工具（如 ffmpeg）已安装，需要时下载并按固定校验和验证
+ *
+ * 下载进度通过 tool-download-progress 事件通知
+ */
+export async function ensureTool(name: string): Promise<ManagedTool> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<ManagedTool>('ensure_tool', { name })
+}
+
+/**
+ * 列出托管工具及当前使用的路径
+ */
+export async function listManagedTools(): Promise<ManagedTool[]> {
+  if (!isTauriEnv()) {
+    return []
+  }
+
+  return await invoke<ManagedTool[]>('list_managed_tools')
+}
+
+/**
+ * 获取工具的可执行文件路径（托管 > 打包 > PATH），找不到时返回 null
+ */
+export async function getToolPath(name: string): Promise<string | null> {
+  if (!isTauriEnv()) {
+    return null
+  }
+
+  return await invoke<string | null>('get_tool_path', { name })
+}
+
+/**
+ * 启用或停用托管工具（停用后不使用、不下载托管版本）
+ */
+export async function setToolEnabled(name: string, enabled: boolean): Promise<ManagedTool> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<ManagedTool>('set_tool_enabled', { name, enabled })
+}
+
+/**
+ * 删除托管工具（之后回退到打包版本或 PATH）
+ */
+export async function removeManagedTool(name: string): Promise<void> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  await invoke('remove_managed_tool', { name })
+}
+
+/**
+ * 清理托管工具的旧版本与已停用的工具，返回删除的目录
+ */
+export async function cleanupTools(): Promise<string[]> {
+  if (!isTauriEnv()) {
+    return []
+  }
+
+  return await invoke<string[]>('cleanup_tools')
+}
+
+export interface GitFileStatus {
+  path: string
+  orig_path: string | null
+  /** 暂存区状态（porcelain 的 X），未跟踪为 "?" */
+  index: string
+  /** 工作区状态（porcelain 的 Y） */
+  worktree: string
+  conflicted: boolean
+}
+
+export interface GitStatus {
+  branch: string | null
+  upstream: string | null
+  ahead: number
+  behind: number
+  files: GitFileStatus[]
+  clean: boolean
+}
+
+export interface GitDiff {
+  files: { path: string; additions: number | null; deletions: number | null }[]
+  patch: string
+  truncated: boolean
+}
+
+/**
+ * 获取仓库状态
+ */
+export async function gitStatus(repo: string): Promise<GitStatus> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<GitStatus>('git_status', { repo })
+}
+
+/**
+ * 获取变更（staged 为 true 时为已暂存的变更），可限定路径
+ */
+export async function gitDiff(
+  repo: string,
+  options: { staged?: boolean; paths?: string[] } = {}
+): Promise<GitDiff> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<GitDiff>('git_diff', {
+    repo,
+    staged: options.staged ?? null,
+    paths: options.paths ?? null,
+  })
+}
+
+/**
+ * 提交变更：paths 非空时先暂存这些路径，all 为 true 时提交全部已跟踪文件的修改
+ */
+export async function gitCommit(
+  repo: string,
+  message: string,
+  options: { paths?: string[]; all?: boolean } = {}
+): Promise<{ commit: string; summary: string }> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke('git_commit', {
+    repo,
+    message,
+    paths: options.paths ?? null,
+    all: options.all ?? null,
+  })
+}
+
+/**
+ * 克隆仓库到 dest
+ */
+export async function gitClone(
+  url: string,
+  dest: string,
+  options: { branch?: string; depth?: number } = {}
+): Promise<{ path: string; commit: string }> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke('git_clone', {
+    url,
+    dest,
+    branch: options.branch ?? null,
+    depth: options.depth ?? null,
+  })
+}
+
+export interface Project {
+  id: string
+  name: string
+  path: string
+  /** 默认项目（未指定 cwd 的 runCommand 在此执行） */
+  default: boolean
+  added_at: string
+}
+
+/**
+ * 登记项目目录（已登记时返回原项目）
+ *
+ * 项目内的写入不再请求审批；文件变化通过 project-files-changed 事件通知
+ */
+export async function addProject(path: string, name?: string): Promise<Project> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<Project>('add_project', { path, name: name ?? null })
+}
+
+/**
+ * 列出已登记的项目
+ */
+export async function listProjects(): Promise<Project[]> {
+  if (!isTauriEnv()) {
+    return []
+  }
+
+  return await invoke<Project[]>('list_projects')
+}
+
+/**
+ * 移除项目（不删除目录）
+ */
+export async function removeProject(id: string): Promise<void> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  await invoke('remove_project', { id })
+}
+
+/**
+ * 设置默认项目
+ */
+export async function setDefaultProject(id: string): Promise<void> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  await invoke('set_default_project', { id })
+}
+
+export interface RecentItem {
+  kind: 'file' | 'app'
+  name: string
+  path: string
+  /** 最近使用时间（RFC 3339） */
+  last_used: string
+}
+
+/**
+ * 最近使用的文件与应用（需要在设置中开启 share_recent_items）
+ */
+export async function getRecentItems(
+  options: { limit?: number; days?: number } = {}
+): Promise<RecentItem[]> {
+  if (!isTauriEnv()) {
+    return []
+  }
+
+  return await invoke<RecentItem[]>('get_recent_items', {
+    limit: options.limit ?? null,
+    days: options.days ?? null,
+  })
+}
+
+export interface SystemAppearance {
+  theme: 'light' | 'dark'
+  /** 系统强调色（#RRGGBB），无法获取时为 null */
+  accent_color: string | null
+  reduce_motion: boolean
+  reduce_transparency: boolean
+  /** 系统文字缩放比例（1.0 为默认大小） */
+  font_scale: number
+  /** 屏幕阅读器（VoiceOver / 讲述人等）已开启 */
+  screen_reader: boolean
+}
+
+/**
+ * 获取系统外观（主题、强调色、辅助功能偏好与文字缩放），变化时发出 appearance-changed 事件
+ */
+export async function getSystemAppearance(): Promise<SystemAppearance | null> {
+  if (!isTauriEnv()) {
+    return null
+  }
+
+  return await invoke<SystemAppearance>('get_system_appearance')
+}
+
+/**
+ * 屏幕阅读器是否开启，开关变化时发出 screen-reader-changed 事件
+ */
+export async function isScreenReaderActive(): Promise<boolean> {
+  if (!isTauriEnv()) {
+    return false
+  }
+
+  return await invoke<boolean>('is_screen_reader_active')
+}
+
+export interface DisplayInfo {
+  id: string
+  name: string | null
+  /** 物理像素坐标 */
+  x: number
+  y: number
+  width: number
+  height: number
+  scale_factor: number
+  is_primary: boolean
+}
+
+export interface CaptureWindow {
+  app: string
+  pid: number
+  /** 窗口标题（未授权时系统不返回其他应用的标题） */
+  title: string | null
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+export interface ScreenCapturePreflight {
+  granted: boolean
+  /** 当前平台需要单独授权屏幕录制（macOS） */
+  permission_required: boolean
+  displays: {
+    display: DisplayInfo
+    capturable: boolean
+    /** 未授权时将变成空白的窗口 */
+    blocked_windows: CaptureWindow[]
+  }[]
+  /** 未授权时的提示 */
+  message: string | null
+}
+
+/**
+ * 截图 / 录屏前检查屏幕录制权限，列出将变成空白的显示器与窗口
+ * request 为 true 时弹出系统授权提示
+ */
+export async function preflightScreenCapture(
+  request?: boolean
+): Promise<ScreenCapturePreflight | null> {
+  if (!isTauriEnv()) {
+    return null
+  }
+
+  return await invoke<ScreenCapturePreflight>('preflight_screen_capture', { request })
+}
+
+export interface ShortcutInfo {
+  id: string
+  /** window：窗口获得焦点时生效；menu：macOS 菜单；global：自动化规则的全局快捷键 */
+  kind: 'window' | 'menu' | 'global'
+  label: string
+  accelerator: string
+  /** 默认加速键（自动化规则为 null） */
+  default_accelerator: string | null
+  rebindable: boolean
+  customized: boolean
+  /** 已注册（停用的自动化规则为 false） */
+  enabled: boolean
+}
+
+/**
+ * 列出全部快捷键（内置与自动化规则），改绑后发出 shortcuts-changed 事件
+ */
+export async function listShortcuts(): Promise<ShortcutInfo[]> {
+  if (!isTauriEnv()) {
+    return []
+  }
+
+  return await invoke<ShortcutInfo[]>('list_shortcuts')
+}
+
+/**
+ * 改绑快捷键，accelerator 为空时恢复默认；与其它快捷键冲突时拒绝
+ */
+export async function setShortcut(id: string, accelerator?: string): Promise<ShortcutInfo> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<ShortcutInfo>('set_shortcut', { id, accelerator })
+}
+
+export interface SnoozeState {
+  /** 暂停结束时间（RFC 3339），未暂停时为 null */
+  snoozed_until: string | null
+  deferred_notifications: number
+}
+
+/**
+ * 暂停非关键通知 duration 分钟，结束后汇总补发（0 表示立即恢复）
+ * 状态变化时发出 notifications-snoozed 事件
+ */
+export async function snoozeNotifications(duration: number): Promise<SnoozeState> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<SnoozeState>('snooze_notifications', { duration })
+}
+
+/**
+ * 立即结束暂停并汇总补发
+ */
+export async function resumeNotifications(): Promise<SnoozeState> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  return await invoke<SnoozeState>('resume_notifications')
+}
+
+export interface NotificationRecord {
+  id: string
+  title: string
+  body: string
+  critical: boolean
+  /** 发送时因专注模式或手动暂停而延迟 */
+  deferred: boolean
+  read: boolean
+  created_at: string
+}
+
+/**
+ * 获取应用发出的通知历史（最新的在前），变化时发出 notification-history-changed 事件
+ */
+export async function getNotificationHistory(
+  limit?: number,
+  unreadOnly?: boolean
+): Promise<NotificationRecord[]> {
+  if (!isTauriEnv()) {
+    return []
+  }
+
+  return await invoke<NotificationRecord[]>('get_notification_history', {
+    limit,
+    unreadOnly,
+  })
+}
+
+/**
+ * 标记通知为已读（不传 ids 时全部标记），返回剩余未读数量
+ */
+export async function markNotificationsRead(ids?: string[]): Promise<number> {
+  if (!isTauriEnv()) {
+    return 0
+  }
+
+  return await invoke<number>('mark_notifications_read', { ids })
+}
+
+/**
+ * 清空通知历史
+ */
+export async function clearNotificationHistory(): Promise<void> {
+  if (!isTauriEnv()) {
+    throw new Error('Not running in Tauri environment')
+  }
+
+  await invoke('clear_notification_history')
+}
+
+export type TimelineKind = 'command' | 'capture' | 'file_write' | 'notification' | 'media'
+
+export interface TimelineEvent {
+  id: number
+  timestamp: string
+  kind: TimelineKind
+  /** 具体操作，如 system.run、fs.create */
+  action: string
+  success: boolean
+  details: Record<string, unknown> | null
+}
+
+/**
+ * 查询 Agent 活动时间线（最新的在前），range 写法同 exportHistory
+ */
+export async function queryTimeline(
+  range?: { from?: string; to?: string },
+  kinds?: TimelineKind[],
+  limit?: number
+): Promise<TimelineEvent[]> {
+  if (!isTauriEnv()) {
+    return []
+  }
+
+  return await invoke<TimelineEvent[]>('query_timeline', { range, kinds, limit })
+}
+
+export interface ManagedPolicy {
+  disable_run_command: boolean
+  safe_mode: boolean
+  update_manifest_url: string | null
+  allowed_paths: string[]
+  settings: Record<string, unknown>
+}
+
+export interface EffectivePolicy {
+  managed: boolean
+  source: string | null
+  policy: ManagedPolicy
+  /** 被锁定、不能在应用内修改的设置项 */
+  locked_settings: string[]
+  /** 被策略关闭的能力 */
+  disabled_capabilities: string[]
+}
+
+/**
+ * 获取生效的企业托管策略
+ */
+export async function getEffectivePolicy(): Promise<EffectivePolicy | null> {
+  if (!isTauriEnv()) {
+    return null
+  }
+
+  return await invoke<EffectivePolicy>('get_effective_policy')
+}
+
 /**
  * 获取连接状态
  */
@@ -262,6 +1698,73 @@ export default {
   sendNotification,
   openExternalUrl,
   getNodeInfo,
+  setDisplayName,
+  listCapabilities,
+  setCapabilityEnabled,
+  getEffectivePolicy,
+  verifyAuditLog,
+  listPendingApprovals,
+  approveRequest,
+  denyRequest,
+  getAppStateSnapshot,
+  subscribeStream,
+  backendBinary,
+  backendUploadFile,
+  backendDownloadFile,
+  openFileTransfer,
+  captureScreen,
+  readTransfer,
+  setBadgeCount,
+  setProgress,
+  requestAttention,
+  showOverlay,
+  hideOverlay,
+  updateOverlay,
+  getWindowChrome,
+  setWindowChrome,
+  setZoom,
+  getZoom,
+  setSafeMode,
+  setAuthGracePeriod,
+  updateSecuritySettings,
+  getAppInfo,
+  showAbout,
+  exportDiagnostics,
+  getOnboardingState,
+  completeOnboardingStep,
+  startBackend,
+  exportHistory,
+  annotateImage,
+  makeThumbnail,
+  downscaleImage,
+  trimVideo,
+  compressVideo,
+  ensureTool,
+  listManagedTools,
+  getToolPath,
+  setToolEnabled,
+  removeManagedTool,
+  cleanupTools,
+  gitStatus,
+  gitDiff,
+  gitCommit,
+  gitClone,
+  addProject,
+  listProjects,
+  removeProject,
+  setDefaultProject,
+  getRecentItems,
+  getSystemAppearance,
+  isScreenReaderActive,
+  preflightScreenCapture,
+  listShortcuts,
+  setShortcut,
+  snoozeNotifications,
+  resumeNotifications,
+  getNotificationHistory,
+  markNotificationsRead,
+  clearNotificationHistory,
+  queryTimeline,
   getConnectionStatus,
   setConnectionStatus,
   openSystemPreferences,
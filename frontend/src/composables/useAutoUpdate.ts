@@ -3,11 +3,31 @@
  *
  * 启动时静默检查 Tauri updater endpoint，发现新版本后
  * 通过响应式状态驱动 UI 弹窗，用户确认即下载安装并重启。
+ * 检查 / 下载 / 安装均由 Rust 壳层的命令完成（安装前会先终止后端）。
  */
 
 import { ref, readonly } from 'vue'
-import { check, type Update } from '@tauri-apps/plugin-updater'
-import { relaunch } from '@tauri-apps/plugin-process'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+interface ReleaseNoteSection {
+  title: string
+  items: string[]
+}
+
+interface UpdateInfo {
+  available: boolean
+  current_version: string
+  version: string | null
+  date: string | null
+  notes: { raw: string; sections: ReleaseNoteSection[] } | null
+}
+
+interface DownloadProgressPayload {
+  downloaded: number
+  total: number | null
+  percent: number | null
+}
 
 export type UpdatePhase = 'idle' | 'checking' | 'found' | 'downloading' | 'installing' | 'error'
 
@@ -17,8 +37,9 @@ export function useAutoUpdate() {
   const changelog = ref('')
   const downloadProgress = ref(0) // 0–100
   const errorMessage = ref('')
+  const releaseNotes = ref<ReleaseNoteSection[]>([])
 
-  let pendingUpdate: Update | null = null
+  let hasPendingUpdate = false
 
   /**
    * 检查更新（静默模式不弹错误，手动模式会暴露错误）
@@ -30,12 +51,13 @@ export function useAutoUpdate() {
     errorMessage.value = ''
 
     try {
-      const update = await check()
+      const info = await invoke<UpdateInfo>('check_for_update')
 
-      if (update) {
-        pendingUpdate = update
-        newVersion.value = update.version
-        changelog.value = update.body ?? ''
+      if (info.available) {
+        hasPendingUpdate = true
+        newVersion.value = info.version ?? ''
+        changelog.value = info.notes?.raw ?? ''
+        releaseNotes.value = info.notes?.sections ?? []
         phase.value = 'found'
         return true
       }
@@ -54,51 +76,42 @@ export function useAutoUpdate() {
    * 用户确认后执行下载 + 安装 + 重启
    */
   async function downloadAndInstall() {
-    if (!pendingUpdate) return
+    if (!hasPendingUpdate) return
 
     phase.value = 'downloading'
     downloadProgress.value = 0
 
+    const unlisten = await listen<DownloadProgressPayload>('update-download-progress', (event) => {
+      downloadProgress.value = event.payload.percent ?? 0
+    })
+
     try {
-      let totalBytes = 0
-      let downloadedBytes = 0
-
-      await pendingUpdate.downloadAndInstall((event) => {
-        switch (event.event) {
-          case 'Started':
-            totalBytes = event.data.contentLength ?? 0
-            break
-          case 'Progress':
-            downloadedBytes += event.data.chunkLength
-            downloadProgress.value = totalBytes > 0
-              ? Math.min(Math.round((downloadedBytes / totalBytes) * 100), 100)
-              : 0
-            break
-          case 'Finished':
-            downloadProgress.value = 100
-            break
-        }
-      })
+      await invoke<number>('download_update')
+      downloadProgress.value = 100
 
       phase.value = 'installing'
-      await relaunch()
+      // 安装完成后由 Rust 壳层重启应用
+      await invoke('install_update')
     } catch (err) {
       console.error('[auto-update] download/install failed:', err)
       errorMessage.value = err instanceof Error ? err.message : String(err)
       phase.value = 'error'
+    } finally {
+      unlisten()
     }
   }
 
   function dismiss() {
     if (phase.value === 'downloading' || phase.value === 'installing') return
     phase.value = 'idle'
-    pendingUpdate = null
+    hasPendingUpdate = false
   }
 
   return {
     phase: readonly(phase),
     newVersion: readonly(newVersion),
     changelog: readonly(changelog),
+    releaseNotes: readonly(releaseNotes),
     downloadProgress: readonly(downloadProgress),
     errorMessage: readonly(errorMessage),
 
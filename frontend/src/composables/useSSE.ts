@@ -5,7 +5,8 @@
 
 import { ref } from 'vue'
 import { useSessionStore } from '@/stores/session'
-import { getFullApiUrl } from '@/api'
+import { getFullApiUrl, isBackendBridged } from '@/api'
+import { subscribeStream } from '@/api/tauri'
 import { sseLog } from '@/utils/logger'
 import type { SSEEvent, ChatRequest } from '@/types'
 
@@ -25,6 +26,8 @@ export function useSSE() {
   const lastEventId = ref(0)
 
   let abortController: AbortController | null = null
+  // UDS 传输下经 Rust 转发的订阅（取消函数）
+  let unsubscribe: (() => Promise<void>) | null = null
 
   /**
    * 创建 SSE 连接（POST 请求）
@@ -48,6 +51,22 @@ export function useSSE() {
           : '(非文本)',
       })
 
+      if (isBackendBridged()) {
+        isConnected.value = true
+        sessionStore.setConnected(true)
+        sseLog.info('SSE 经 Rust 桥接')
+        onConnected?.()
+
+        const fullResponse = await readBridgedStream(requestBody, onEvent, abortController.signal)
+
+        isConnected.value = false
+        sessionStore.setConnected(false)
+        sseLog.info('SSE 流结束', { responseLength: fullResponse.length })
+        onDisconnected?.()
+
+        return fullResponse
+      }
+
       const response = await fetch(url, {
         method: 'POST',
         headers: {
@@ -105,6 +124,10 @@ export function useSSE() {
       abortController.abort()
       abortController = null
     }
+    if (unsubscribe) {
+      unsubscribe().catch(() => {})
+      unsubscribe = null
+    }
     isConnected.value = false
     sessionStore.setConnected(false)
   }
@@ -117,6 +140,123 @@ export function useSSE() {
     lastEventId.value = 0
   }
 
+  /**
+   * 处理一个完整的 SSE 事件，返回累积的回复文本及流是否结束
+   */
+  function handleEvent(
+    event: { id: string | null; event: string | null; data: string },
+    onEvent: SSEEventHandler,
+    fullResponse: string
+  ): { fullResponse: string; done: boolean } {
+    try {
+      const data = JSON.parse(event.data)
+      const eventType = data.type || event.event
+
+      if (!data.type && eventType) {
+        data.type = eventType
+      }
+
+      if (event.id) {
+        lastEventId.value = parseInt(event.id, 10) || lastEventId.value
+        sessionStore.setLastEventId(lastEventId.value)
+      }
+
+      // 记录非 content_delta 事件（content_delta 太频繁，只记录关键事件）
+      if (eventType !== 'content_delta') {
+        sseLog.debug(`← 事件: ${eventType}`, data.data)
+      }
+
+      onEvent(data)
+
+      if (eventType === 'content_delta' && data.data?.delta) {
+        const delta = data.data.delta
+        if (typeof delta === 'string') {
+          fullResponse += delta
+        } else if (delta?.text) {
+          fullResponse += delta.text
+        }
+      }
+
+      if (eventType === 'complete' && data.data?.final_result && !fullResponse) {
+        fullResponse = data.data.final_result
+      }
+
+      const done =
+        event.event === 'done' ||
+        eventType === 'message_stop' ||
+        eventType === 'session_end'
+      return { fullResponse, done }
+    } catch (e) {
+      sseLog.error('解析 SSE 数据失败', { error: e, raw: event.data })
+      return { fullResponse, done: false }
+    }
+  }
+
+  /**
+   * 经 Rust 读取 SSE 流（UDS 传输下自定义协议只能整块返回响应，改用 subscribeStream 逐事件转发）
+   */
+  function readBridgedStream(
+    requestBody: ChatRequest,
+    onEvent: SSEEventHandler,
+    signal: AbortSignal
+  ): Promise<string> {
+    return new Promise((resolve, reject) => {
+      let fullResponse = ''
+      let settled = false
+
+      const finish = (error?: Error) => {
+        if (settled) return
+        settled = true
+        if (unsubscribe) {
+          unsubscribe().catch(() => {})
+          unsubscribe = null
+        }
+        if (error) {
+          reject(error)
+        } else {
+          resolve(fullResponse)
+        }
+      }
+
+      signal.addEventListener('abort', () => finish(new DOMException('SSE 已中断', 'AbortError')))
+
+      subscribeStream(
+        '/api/v1/chat?format=zenflux',
+        {
+          onEvent: (e) => {
+            if (settled) return
+            const result = handleEvent(
+              { id: e.event_id, event: e.event, data: e.data },
+              onEvent,
+              fullResponse
+            )
+            fullResponse = result.fullResponse
+            if (result.done) {
+              finish()
+            }
+          },
+          onClosed: (reason, error) => {
+            if (reason === 'error') {
+              sseLog.error('SSE 桥接中断', error)
+              finish(new Error(error || 'SSE 流中断'))
+            } else {
+              finish()
+            }
+          },
+        },
+        { method: 'POST', body: requestBody }
+      )
+        .then((cancel) => {
+          if (settled) {
+            cancel().catch(() => {})
+          } else {
+            unsubscribe = cancel
+          }
+        })
+        .catch((e) => finish(e instanceof Error ? e : new Error(String(e))))
+    })
+  }
+
   /**
    * 读取 SSE 流
    */
@@ -153,48 +293,14 @@ export function useSSE() {
         for (const line of lines) {
           if (line === '') {
             if (currentEvent.data) {
-              try {
-                const data = JSON.parse(currentEvent.data)
-                const eventType = data.type || currentEvent.event
-
-                if (!data.type && eventType) {
-                  data.type = eventType
-                }
-
-                if (currentEvent.id) {
-                  lastEventId.value = parseInt(currentEvent.id, 10) || lastEventId.value
-                  sessionStore.setLastEventId(lastEventId.value)
-                }
-
-                // 记录非 content_delta 事件（content_delta 太频繁，只记录关键事件）
-                if (eventType !== 'content_delta') {
-                  sseLog.debug(`← 事件: ${eventType}`, data.data)
-                }
-
-                onEvent(data)
-
-                if (eventType === 'content_delta' && data.data?.delta) {
-                  const delta = data.data.delta
-                  if (typeof delta === 'string') {
-                    fullResponse += delta
-                  } else if (delta?.text) {
-                    fullResponse += delta.text
-                  }
-                }
-
-                if (eventType === 'complete' && data.data?.final_result && !fullResponse) {
-                  fullResponse = data.data.final_result
-                }
-
-                if (
-                  currentEvent.event === 'done' ||
-                  eventType === 'message_stop' ||
-                  eventType === 'session_end'
-                ) {
-                  return fullResponse
-                }
-              } catch (e) {
-                sseLog.error('解析 SSE 数据失败', { error: e, raw: currentEvent.data })
+              const result = handleEvent(
+                { ...currentEvent, data: currentEvent.data },
+                onEvent,
+                fullResponse
+              )
+              fullResponse = result.fullResponse
+              if (result.done) {
+                return fullResponse
               }
             }
 
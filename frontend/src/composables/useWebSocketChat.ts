@@ -19,8 +19,8 @@
 import { ref } from 'vue'
 import { useSessionStore } from '@/stores/session'
 import { useNotificationStore } from '@/stores/notification'
-import { getApiBaseUrl } from '@/api'
-import { isTauriEnv } from '@/api/tauri'
+import { openBackendSocket } from '@/api'
+import { isTauriEnv, type BackendSocket } from '@/api/tauri'
 import { wsLog } from '@/utils/logger'
 import type { SSEEvent, ChatRequest } from '@/types'
 
@@ -41,6 +41,9 @@ export interface WsConnectOptions {
 
 // ==================== 常量 ====================
 
+/** 聊天 WebSocket 路径（UDS 传输下经 Rust 桥接，见 openBackendSocket） */
+const WS_CHAT_PATH = '/v1/ws/chat'
+
 /** 心跳间隔（秒），与后端 HEARTBEAT_INTERVAL_S 一致 */
 const HEARTBEAT_INTERVAL_S = 30
 
@@ -97,7 +100,7 @@ export function useWebSocketChat(wsOptions?: UseWebSocketChatOptions) {
 
   // ==================== 内部状态 ====================
 
-  let ws: WebSocket | null = null
+  let ws: BackendSocket | null = null
   let connectionPromise: Promise<void> | null = null
   let closed = false
 
@@ -125,22 +128,6 @@ export function useWebSocketChat(wsOptions?: UseWebSocketChatOptions) {
   // Playbook suggestion handler (persistent, survives stream end)
   let playbookSuggestionHandler: ((data: any) => void) | null = null
 
-  // ==================== WebSocket URL ====================
-
-  function getWsUrl(): string {
-    const baseUrl = getApiBaseUrl()
-
-    // 如果 baseUrl 是绝对地址（http://...），转换为 ws://...
-    if (baseUrl.startsWith('http')) {
-      const wsBase = baseUrl.replace(/^http/, 'ws')
-      return `${wsBase}/v1/ws/chat`
-    }
-
-    // 相对地址（/api）：使用当前页面地址构造 WebSocket URL
-    const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:'
-    return `${protocol}//${window.location.host}${baseUrl}/v1/ws/chat`
-  }
-
   // ==================== 连接管理 ====================
 
   /**
@@ -153,10 +140,9 @@ export function useWebSocketChat(wsOptions?: UseWebSocketChatOptions) {
     connectionPromise = new Promise<void>((resolve, reject) => {
       connectionStatus.value = 'connecting'
 
-      const url = getWsUrl()
-      wsLog.info(`WebSocket 连接中: ${url}`)
+      wsLog.info(`WebSocket 连接中: ${WS_CHAT_PATH}`)
 
-      ws = new WebSocket(url)
+      ws = openBackendSocket(WS_CHAT_PATH)
 
       ws.onopen = () => {
         wsLog.info('WebSocket 已连接')
@@ -3,11 +3,15 @@ import { createPinia } from 'pinia'
 import App from './App.vue'
 import router from './router'
 import { initApiBaseUrl } from './api'
-import { appLog } from './utils/logger'
+import { getWindowChrome } from './api/tauri'
+import { appLog, forwardToRustLog, installGlobalErrorForwarding } from './utils/logger'
 import './style.css'
 import 'markstream-vue/index.css'
 import { enableMermaid } from 'markstream-vue'
 
+// 未捕获的异常写入 Rust 壳层日志，与后端日志形成统一时间线
+installGlobalErrorForwarding()
+
 // 启用 Mermaid 图表渲染
 enableMermaid()
 
@@ -25,10 +29,25 @@ const app = createApp(App)
 app.use(createPinia())
 app.use(router)
 
+app.config.errorHandler = (err, _instance, info) => {
+  console.error(err)
+  forwardToRustLog('error', err instanceof Error ? err.message : String(err), {
+    info,
+    stack: err instanceof Error ? err.stack : undefined,
+  })
+}
+
 // 立即挂载（让 App.vue 内置的 SplashScreen 尽快显示，避免白屏）
 app.mount('#app')
 appLog.info('应用挂载完成')
 
+// 窗口开启毛玻璃材质时页面背景透明
+getWindowChrome()
+  .then((chrome) => {
+    document.documentElement.classList.toggle('window-vibrancy', !!chrome?.vibrancy)
+  })
+  .catch(() => {})
+
 // API 初始化在后台进行（SplashScreen 会通过 waitForBackendReady() 等待后端就绪）
 initApiBaseUrl().catch((err) => {
   appLog.error('API 初始化失败', err)
@@ -80,6 +80,22 @@ const routes = [
     meta: { layout: 'none' }
   },
 
+  // ==================== 状态浮窗（独立窗口） ====================
+  {
+    path: '/overlay',
+    name: 'overlay',
+    component: () => import('@/views/overlay/OverlayView.vue'),
+    meta: { layout: 'none' }
+  },
+
+  // ==================== 关于（独立窗口） ====================
+  {
+    path: '/about',
+    name: 'about',
+    component: () => import('@/views/about/AboutView.vue'),
+    meta: { layout: 'none' }
+  },
+
   // 引导教程由 GuideOverlay + guideStore 在 ChatView 中自动触发，无需独立路由
 ]
 
@@ -10,7 +10,8 @@
 
 import { defineStore } from 'pinia'
 import { reactive, computed } from 'vue'
-import { getApiBaseUrl } from '@/api'
+import { openBackendSocket } from '@/api'
+import type { BackendSocket } from '@/api/tauri'
 import { useAgentStore } from './agent'
 import { useNotificationStore } from './notification'
 import type { AgentCreationEvent } from '@/types'
@@ -41,7 +42,7 @@ export const useAgentCreationStore = defineStore('agentCreation', () => {
   const tasks = reactive<Map<string, CreationTask>>(new Map())
 
   /** WebSocket instances (internal, not reactive) */
-  const _wsMap = new Map<string, WebSocket>()
+  const _wsMap = new Map<string, BackendSocket>()
   const _reconnectCount = new Map<string, number>()
 
   // ==================== 计算属性 ====================
@@ -50,18 +51,6 @@ export const useAgentCreationStore = defineStore('agentCreation', () => {
     Array.from(tasks.values()).some(t => t.status === 'creating')
   )
 
-  // ==================== WebSocket URL ====================
-
-  function getWsUrl(agentId: string): string {
-    const baseUrl = getApiBaseUrl()
-    if (baseUrl.startsWith('http')) {
-      const wsBase = baseUrl.replace(/^http/, 'ws')
-      return `${wsBase}/v1/agents/ws/create/${agentId}`
-    }
-    const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:'
-    return `${protocol}//${window.location.host}${baseUrl}/v1/agents/ws/create/${agentId}`
-  }
-
   // ==================== WebSocket 连接管理 ====================
 
   function connectWs(agentId: string) {
@@ -70,8 +59,8 @@ export const useAgentCreationStore = defineStore('agentCreation', () => {
       existing.close()
     }
 
-    const url = getWsUrl(agentId)
-    const ws = new WebSocket(url)
+    // UDS 传输下经 Rust 桥接
+    const ws = openBackendSocket(`/v1/agents/ws/create/${agentId}`)
     _wsMap.set(agentId, ws)
 
     ws.onopen = () => {
@@ -6,9 +6,12 @@
  * - 按模块分类（API / SSE / WS / APP）
  * - 按级别过滤（DEBUG / INFO / WARN / ERROR）
  * - 自动截断旧日志（防止内存泄漏）
+ * - WARN / ERROR 转发到 Rust 壳层的统一日志文件（Tauri 环境）
  */
 
 import { reactive } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
+import { isTauriEnv } from '@/api/tauri'
 
 // ==================== 类型定义 ====================
 
@@ -45,6 +48,40 @@ export const logStore = reactive({
   hasError: false,
 })
 
+// ==================== 转发到 Rust 日志 ====================
+
+/** 写入 Rust 壳层日志文件（失败时静默，避免日志本身产生错误循环） */
+export function forwardToRustLog(level: string, message: string, context?: unknown): void {
+  if (!isTauriEnv()) return
+  let ctx: unknown = null
+  if (context !== undefined) {
+    try {
+      ctx = JSON.parse(JSON.stringify(context))
+    } catch {
+      ctx = String(context)
+    }
+  }
+  invoke('log_from_frontend', { level, message, context: ctx }).catch(() => {})
+}
+
+/** 捕获未处理的异常与 Promise 拒绝，写入 Rust 日志 */
+export function installGlobalErrorForwarding(): void {
+  window.addEventListener('error', (e) => {
+    forwardToRustLog('error', e.message, {
+      source: e.filename,
+      line: e.lineno,
+      column: e.colno,
+      stack: e.error instanceof Error ? e.error.stack : undefined,
+    })
+  })
+  window.addEventListener('unhandledrejection', (e) => {
+    const reason = e.reason
+    forwardToRustLog('error', `Unhandled rejection: ${reason instanceof Error ? reason.message : String(reason)}`, {
+      stack: reason instanceof Error ? reason.stack : undefined,
+    })
+  })
+}
+
 // ==================== 核心函数 ====================
 
 function addLog(level: LogLevel, module: LogModule, message: string, data?: unknown): void {
@@ -74,6 +111,11 @@ function addLog(level: LogLevel, module: LogModule, message: string, data?: unkn
     logStore.hasError = true
   }
 
+  // 警告和错误写入统一日志文件
+  if (level === 'ERROR' || level === 'WARN') {
+    forwardToRustLog(level.toLowerCase(), `[${module}] ${message}`, data)
+  }
+
   // 同时输出到浏览器控制台（开发模式）
   if (IS_DEV) {
     const prefix = `[${module}]`
@@ -45,10 +45,26 @@ class UnicodeJSONResponse(JSONResponse):
             separators=(",", ":"),
         ).encode("utf-8")
 
+# ==================== 启动进度上报 ====================
+# Tauri 壳层解析 stdout 中的进度行，转发为 sidecar-status 事件（启动画面显示真实进度）
+
+_PROGRESS_MARKER = "[xiaodazi:progress]"
+
+
+def _report_progress(stage: str, percent: int, message: str) -> None:
+    """输出一行启动进度标记"""
+    payload = json.dumps(
+        {"stage": stage, "percent": percent, "message": message},
+        ensure_ascii=False,
+    )
+    print(f"{_PROGRESS_MARKER} {payload}", flush=True)
+
+
 # ==================== 启动 heartbeat ====================
 # 在所有 local import 之前输出，确保进程存活可见。
 # 若此行出现但后续无输出，说明 import 阶段卡死或报错。
 print(f"[xiaodazi] Python {sys.version.split()[0]}, importing modules...", flush=True)
+_report_progress("import", 5, "正在加载模块...")
 
 # 加载配置（统一从 config.yaml）
 from services.settings_service import load_config_to_env
@@ -76,6 +92,7 @@ from infra.local_store import close_all_workspaces
 from infra.local_store.engine import close_local_engine
 
 print("[xiaodazi] All modules imported.", flush=True)
+_report_progress("imported", 30, "模块加载完成")
 
 # ==================== 常量定义 ====================
 
@@ -429,16 +446,25 @@ async def lifespan(app: FastAPI):
     except Exception as e:
         print(f"⚠️ 依赖检查跳过: {e}")
 
+    _report_progress("resilience", 35, "正在加载配置...")
     await _init_resilience_config()
+    _report_progress("local_store", 45, "正在初始化数据...")
     await _init_local_store()
+    _report_progress("capabilities", 55, "正在加载工具...")
     await _preload_capability_registry()  # 加载工具注册表（必须在 Agent 之前）
+    _report_progress("agents", 65, "正在加载 Agent...")
     await _preload_agent_registry()  # 加载 Agent 配置
+    _report_progress("chat_service", 75, "正在预热对话服务...")
     await _init_chat_service()  # 预热 ChatService（避免首次请求冷启动）
+    _report_progress("knowledge", 85, "正在索引知识库...")
     await _init_knowledge_index()  # 知识库：索引配置的目录
+    _report_progress("embedding", 90, "正在预热模型...")
     await _warmup_embedding_model()  # Embedding 模型预热（非阻塞）
+    _report_progress("schedulers", 95, "即将就绪...")
     scheduler = await _start_scheduler()
     user_task_scheduler = await _start_user_task_scheduler()  # 用户定时任务调度器
     gateway_manager = await _start_gateway()  # 多渠道网关（可选）
+    _report_progress("ready", 100, "服务已启动")
     
     yield
     
@@ -564,11 +590,29 @@ async def health_check() -> Dict[str, Any]:
     }
 
 
+@app.get("/identity")
+async def identity() -> Dict[str, Any]:
+    """
+    Identity endpoint used by the Tauri shell when a sidecar port is occupied.
+
+    Lets main.rs tell a stale backend of its own (same data dir) apart from
+    an unrelated process, so it can adopt the former instead of spawning a duplicate.
+    """
+    from utils.app_paths import get_user_data_dir
+
+    return {
+        "app": "zenflux",
+        "version": APP_VERSION,
+        "data_dir": str(get_user_data_dir()),
+        "pid": os.getpid(),
+    }
+
+
 # ==================== 启动入口 ====================
 
 if __name__ == "__main__":
     import uvicorn
-    from utils.app_paths import get_cli_port, is_frozen
+    from utils.app_paths import get_cli_port, get_cli_uds, is_frozen
 
     # Windows: ProactorEventLoop 支持 asyncio.create_subprocess_exec
     # SelectorEventLoop（默认）不支持子进程，会导致 NotImplementedError
@@ -576,6 +620,7 @@ if __name__ == "__main__":
         asyncio.set_event_loop_policy(asyncio.WindowsProactorEventLoopPolicy())
     
     port = get_cli_port() if is_frozen() else 8000
+    uds = get_cli_uds() if is_frozen() else None
     host = "127.0.0.1" if is_frozen() else "0.0.0.0"
     
     print("\n" + "=" * 60)
@@ -586,7 +631,15 @@ if __name__ == "__main__":
     print(f"📖 ReDoc: http://localhost:{port}/redoc")
     print("=" * 60 + "\n")
     
-    if is_frozen():
+    if uds:
+        # UDS 传输模式：仅监听数据目录内的 socket，不占用 TCP 端口
+        print(f"🔌 Unix socket: {uds}")
+        uvicorn.run(
+            app,
+            uds=uds,
+            log_level="info",
+        )
+    elif is_frozen():
         # PyInstaller 打包模式：必须直接传 app 对象
         # 字符串导入 "main:app" 在 PyInstaller 中会导致 ModuleNotFoundError
         uvicorn.run(
@@ -0,0 +1,160 @@
+"""
+Tray icon generator
+
+Builds the monochrome tray icons from the app icon
+(frontend/src-tauri/icons/128x128@2x.png): the dark outline of the mascot is
+kept as the glyph and the coloured background is dropped, then the glyph is
+cropped and scaled to TRAY_SIZE.
+
+    icons/tray-light.png  black glyph, for light taskbars / menu bars
+                          (also used as the macOS template icon)
+    icons/tray-dark.png   white glyph, for dark taskbars
+
+The desktop app picks one by the taskbar theme (see src-tauri/src/health.rs).
+Run this after changing the app icon and commit the result. Only the standard
+library is used, so no image toolkit is needed.
+
+Usage:
+    python scripts/generate_tray_icons.py
+"""
+
+import struct
+import zlib
+from pathlib import Path
+
+PROJECT_ROOT = Path(__file__).parent.parent
+ICONS_DIR = PROJECT_ROOT / "frontend" / "src-tauri" / "icons"
+SOURCE = ICONS_DIR / "128x128@2x.png"
+
+# Output edge length (32 pt tray icon at 2x)
+TRAY_SIZE = 64
+# Empty border around the glyph, as a fraction of the output size
+MARGIN = 0.04
+# Pixels darker than DARK_FULL are fully part of the glyph, lighter than
+# DARK_NONE are background; in between is the anti-aliased edge.
+DARK_FULL = 0.25
+DARK_NONE = 0.55
+
+
+def read_png(path: Path):
+    """Decode an 8-bit RGBA, non-interlaced PNG into (width, height, rows)."""
+    data = path.read_bytes()
+    if data[:8] != b"\x89PNG\r\n\x1a\n":
+        raise ValueError(f"{path} is not a PNG file")
+    pos, idat, header = 8, b"", None
+    while pos < len(data):
+        length, kind = struct.unpack(">I4s", data[pos : pos + 8])
+        chunk = data[pos + 8 : pos + 8 + length]
+        if kind == b"IHDR":
+            header = struct.unpack(">IIBBBBB", chunk)
+        elif kind == b"IDAT":
+            idat += chunk
+        pos += 12 + length
+    width, height, depth, color, _, _, interlace = header
+    if (depth, color, interlace) != (8, 6, 0):
+        raise ValueError(f"{path} must be 8-bit RGBA without interlacing")
+
+    raw = zlib.decompress(idat)
+    stride = width * 4
+    rows, prev = [], bytearray(stride)
+    for y in range(height):
+        start = y * (stride + 1)
+        kind, line = raw[start], bytearray(raw[start + 1 : start + 1 + stride])
+        for i in range(stride):
+            a = line[i - 4] if i >= 4 else 0
+            b = prev[i]
+            c = prev[i - 4] if i >= 4 else 0
+            if kind == 1:
+                line[i] = (line[i] + a) & 0xFF
+            elif kind == 2:
+                line[i] = (line[i] + b) & 0xFF
+            elif kind == 3:
+                line[i] = (line[i] + (a + b) // 2) & 0xFF
+            elif kind == 4:
+                p = a + b - c
+                pa, pb, pc = abs(p - a), abs(p - b), abs(p - c)
+                pred = a if pa <= pb and pa <= pc else (b if pb <= pc else c)
+                line[i] = (line[i] + pred) & 0xFF
+        rows.append(line)
+        prev = line
+    return width, height, rows
+
+
+def write_png(path: Path, size: int, alpha, value: int):
+    """Write a size x size RGBA PNG with a single colour and the given alpha."""
+    raw = bytearray()
+    for y in range(size):
+        raw.append(0)
+        for x in range(size):
+            raw += bytes((value, value, value, alpha[y][x]))
+
+    def chunk(kind: bytes, body: bytes) -> bytes:
+        crc = zlib.crc32(kind + body) & 0xFFFFFFFF
+        return struct.pack(">I", len(body)) + kind + body + struct.pack(">I", crc)
+
+    header = struct.pack(">IIBBBBB", size, size, 8, 6, 0, 0, 0)
+    path.write_bytes(
+        b"\x89PNG\r\n\x1a\n"
+        + chunk(b"IHDR", header)
+        + chunk(b"IDAT", zlib.compress(bytes(raw), 9))
+        + chunk(b"IEND", b"")
+    )
+
+
+def glyph_mask(width: int, height: int, rows):
+    """Coverage (0..1) of the dark glyph for every source pixel."""
+    mask = []
+    for y in range(height):
+        line = rows[y]
+        out = []
+        for x in range(width):
+            r, g, b, a = line[x * 4 : x * 4 + 4]
+            darkness = 1 - (0.299 * r + 0.587 * g + 0.114 * b) / 255
+            coverage = (darkness - (1 - DARK_NONE)) / (DARK_NONE - DARK_FULL)
+            out.append(min(max(coverage, 0.0), 1.0) * a / 255)
+        mask.append(out)
+    return mask
+
+
+def crop_and_scale(mask, size: int):
+    """Crop the mask to the glyph, centre it in a square and box-filter to size."""
+    height, width = len(mask), len(mask[0])
+    points = [(x, y) for y in range(height) for x in range(width) if mask[y][x] > 0.05]
+    left = min(x for x, _ in points)
+    right = max(x for x, _ in points) + 1
+    top = min(y for _, y in points)
+    bottom = max(y for _, y in points) + 1
+    side = max(right - left, bottom - top) / (1 - 2 * MARGIN)
+    cx, cy = (left + right) / 2, (top + bottom) / 2
+    x0, y0 = cx - side / 2, cy - side / 2
+
+    def sample(x: int, y: int) -> float:
+        return mask[y][x] if 0 <= x < width and 0 <= y < height else 0.0
+
+    scale = side / size
+    out = []
+    for oy in range(size):
+        line = []
+        for ox in range(size):
+            sx0, sy0 = x0 + ox * scale, y0 + oy * scale
+            total, count = 0.0, 0
+            for sy in range(int(sy0), int(sy0 + scale + 0.999)):
+                for sx in range(int(sx0), int(sx0 + scale + 0.999)):
+                    total += sample(sx, sy)
+                    count += 1
+            line.append(round(255 * total / max(count, 1)))
+        out.append(line)
+    return out
+
+
+def main():
+    width, height, rows = read_png(SOURCE)
+    alpha = crop_and_scale(glyph_mask(width, height, rows), TRAY_SIZE)
+    for name, value in (("tray-light.png", 0x00), ("tray-dark.png", 0xFF)):
+        target = ICONS_DIR / name
+        write_png(target, TRAY_SIZE, alpha, value)
+        print(f"  WROTE {target.relative_to(PROJECT_ROOT)}")
+
+
+if __name__ == "__main__":
+    main()
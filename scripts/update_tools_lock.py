@@ -0,0 +1,131 @@
+"""
+Managed tools lock updater
+
+Downloads every pinned build listed in frontend/src-tauri/tools.lock.json and
+records its SHA-256. The desktop app only downloads builds whose checksum is
+pinned here (see frontend/src-tauri/src/tools.rs), so run this after adding
+a platform or bumping a tool version and commit the result.
+
+For archives ("archive_path" set) "sha256" covers the downloaded archive and
+"executable_sha256" covers the executable extracted from it; the app verifies
+both, so neither may be left empty.
+
+Usage:
+    python scripts/update_tools_lock.py            # fill in missing checksums
+    python scripts/update_tools_lock.py --refresh  # recompute all checksums
+    python scripts/update_tools_lock.py --check    # verify pinned checksums, exit 1 on mismatch
+"""
+
+import argparse
+import hashlib
+import json
+import sys
+import tarfile
+import tempfile
+import urllib.request
+import zipfile
+from pathlib import Path
+
+PROJECT_ROOT = Path(__file__).parent.parent
+LOCK_FILE = PROJECT_ROOT / "frontend" / "src-tauri" / "tools.lock.json"
+
+CHUNK_SIZE = 1 << 20
+
+
+def sha256_stream(stream) -> str:
+    """Return the hex SHA-256 of a binary stream."""
+    digest = hashlib.sha256()
+    while chunk := stream.read(CHUNK_SIZE):
+        digest.update(chunk)
+    return digest.hexdigest()
+
+
+def extracted_sha256(archive: Path, url: str, entry: str) -> str:
+    """Return the hex SHA-256 of one file inside a .zip / .tar.gz / .tgz archive."""
+    lower = url.lower()
+    if lower.endswith(".zip"):
+        with zipfile.ZipFile(archive) as zf, zf.open(entry) as member:
+            return sha256_stream(member)
+    if lower.endswith((".tar.gz", ".tgz")):
+        with tarfile.open(archive, "r:gz") as tf:
+            member = tf.extractfile(entry)
+            if member is None:
+                raise KeyError(f"{entry} is not a regular file")
+            return sha256_stream(member)
+    raise ValueError(f"unsupported archive format: {url}")
+
+
+def checksums_of(build: dict) -> dict:
+    """Download a build and return the checksum fields it should pin."""
+    request = urllib.request.Request(build["url"], headers={"User-Agent": "xiaodazi-tools-lock"})
+    with tempfile.TemporaryDirectory() as tmp:
+        download = Path(tmp) / "download"
+        digest = hashlib.sha256()
+        with urllib.request.urlopen(request, timeout=600) as resp, download.open("wb") as out:
+            while chunk := resp.read(CHUNK_SIZE):
+                digest.update(chunk)
+                out.write(chunk)
+        checksums = {"sha256": digest.hexdigest()}
+        if build.get("archive_path"):
+            checksums["executable_sha256"] = extracted_sha256(
+                download, build["url"], build["archive_path"]
+            )
+    return checksums
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Pin SHA-256 checksums in tools.lock.json")
+    mode = parser.add_mutually_exclusive_group()
+    mode.add_argument("--refresh", action="store_true", help="Recompute all checksums")
+    mode.add_argument(
+        "--check",
+        action="store_true",
+        help="Verify pinned checksums without writing (exit 1 on mismatch)",
+    )
+    args = parser.parse_args()
+
+    lock = json.loads(LOCK_FILE.read_text())
+    all_ok = True
+    changed = False
+
+    for name, tool in lock.items():
+        for platform, build in tool.get("platforms", {}).items():
+            label = f"{name} {tool['version']} {platform}"
+            fields = ["sha256"] + (["executable_sha256"] if build.get("archive_path") else [])
+            pinned = {field: build.get(field, "") for field in fields}
+            if all(pinned.values()) and not (args.refresh or args.check):
+                print(f"  OK   {label}")
+                continue
+
+            try:
+                actual = checksums_of(build)
+            except Exception as e:
+                print(f"  FAIL {label}: {e}")
+                all_ok = False
+                continue
+
+            if args.check:
+                if pinned == actual:
+                    print(f"  OK   {label}")
+                else:
+                    for field in fields:
+                        if pinned[field] != actual[field]:
+                            print(f"  DIFF {label} {field}: {pinned[field] or '(missing)'} -> {actual[field]}")
+                    all_ok = False
+                continue
+
+            if pinned != actual:
+                build.update(actual)
+                changed = True
+            print(f"  PIN  {label}: {', '.join(f'{k}={v}' for k, v in actual.items())}")
+
+    if changed:
+        LOCK_FILE.write_text(json.dumps(lock, indent=2) + "\n")
+        print(f"\nUpdated {LOCK_FILE.relative_to(PROJECT_ROOT)}")
+
+    if not all_ok:
+        sys.exit(1)
+
+
+if __name__ == "__main__":
+    main()
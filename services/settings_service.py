@@ -115,6 +115,14 @@ SETTINGS_SCHEMA = {
         "LOG_LEVEL": {"label": "日志级别", "required": False, "secret": False,
                       "default": "INFO"},
     },
+    "node": {
+        # 由桌面端 set_display_name 同步，为空时使用主机名
+        "NODE_DISPLAY_NAME": {"label": "设备名称", "required": False, "secret": False,
+                              "default": ""},
+        # 由桌面端 set_capability_enabled 同步，逗号分隔（可为分组前缀，如 "camera"）
+        "NODE_DISABLED_CAPABILITIES": {"label": "已关闭的能力", "required": False,
+                                       "secret": False, "default": ""},
+    },
 }
 
 # 从 Schema 自动提取所有 LLM API Key 名称（secret=True 且以 _API_KEY 结尾）
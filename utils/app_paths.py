@@ -32,6 +32,7 @@ _LEGACY_APP_NAMES = ["xiaodazi"]
 # 命令行参数键（Tauri sidecar 传入）
 _CLI_DATA_DIR_KEY = "--data-dir"
 _CLI_PORT_KEY = "--port"
+_CLI_UDS_KEY = "--uds"
 
 # 缓存（避免重复计算）
 _user_data_dir: Optional[Path] = None
@@ -126,6 +127,18 @@ def get_cli_port() -> int:
     return int(os.getenv("XIAODAZI_PORT") or os.getenv("ZENFLUX_PORT", "18900"))
 
 
+def get_cli_uds() -> Optional[str]:
+    """
+    获取命令行指定的 Unix domain socket 路径
+
+    Tauri 以 UDS 传输模式启动 sidecar 时传入，此时后端不监听 TCP 端口。
+
+    Returns:
+        socket 路径，未指定时返回 None
+    """
+    return _get_cli_arg(_CLI_UDS_KEY) or None
+
+
 # ==================== 全局共享路径 ====================
 
 